@@ -29,6 +29,7 @@ mod model;
 mod optimize_check;
 mod params;
 mod profile;
+mod quantize;
 mod run;
 #[cfg(feature = "pulse")]
 mod stream_check;
@@ -312,6 +313,31 @@ fn main() -> tract_core::anyhow::Result<()> {
         .long_about("Compare output of streamed and regular exec");
     app = app.subcommand(output_options(stream_check));
 
+    let quantize = clap::SubCommand::with_name("quantize")
+        .long_about(
+            "Post-training static quantization: calibrate activation ranges, quantize matrix \
+            products and convolutions to i8, and report errors against the float model",
+        )
+        .arg(
+            Arg::with_name("calibration")
+                .takes_value(true)
+                .required(true)
+                .help("Calibration data: npz file, or directory of npz files or onnx pb dirs"),
+        )
+        .arg(
+            Arg::with_name("percentile")
+                .takes_value(true)
+                .long("percentile")
+                .help("Clip activations at this percentile (like 99.99) instead of min/max"),
+        )
+        .arg(
+            Arg::with_name("nnef-tar")
+                .takes_value(true)
+                .long("nnef-tar")
+                .help("Dump the quantized network in NNEF format (as a tar file)"),
+        );
+    app = app.subcommand(output_options(quantize));
+
     let matches = app.get_matches();
 
     let probe = if matches.is_present("readings") {
@@ -516,6 +542,8 @@ fn handle(matches: clap::ArgMatches, probe: Option<&Probe>) -> CliResult<()> {
             stream_check::handle(&params, &display_params_from_clap(&matches, m)?)
         }
 
+        ("quantize", Some(m)) => quantize::handle(&params, m),
+
         ("", None) => dump::handle(
             &params,
             &display_params_from_clap(&matches, &clap::ArgMatches::default())?,
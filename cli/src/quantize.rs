@@ -0,0 +1,136 @@
+#[cfg(feature = "onnx")]
+use std::convert::TryInto;
+use std::fs;
+use std::path::Path;
+
+use tract_core::ops::quant::calibration::{Calibration, CalibrationMethod};
+use tract_hir::internal::*;
+
+use crate::tensor;
+use crate::{CliResult, Parameters};
+
+pub fn handle(params: &Parameters, matches: &clap::ArgMatches) -> CliResult<()> {
+    let model = params
+        .decluttered_model
+        .as_ref()
+        .context("Decluttered model not generated. (using --pass ?)")?;
+    let samples = load_samples(model, Path::new(matches.value_of("calibration").unwrap()))?;
+    if samples.is_empty() {
+        bail!("No calibration sample found");
+    }
+    let method = if let Some(p) = matches.value_of("percentile") {
+        CalibrationMethod::Percentile(p.parse()?)
+    } else {
+        CalibrationMethod::MinMax
+    };
+    let calibration = Calibration::collect(model, samples.iter().cloned())?;
+    let quantized = calibration.quantize(model, method)?;
+
+    let float_plan = SimplePlan::new(&**model)?;
+    let quantized_plan = SimplePlan::new(&quantized)?;
+    let mut max_error = vec![0f32; model.output_outlets()?.len()];
+    let mut sum_error = vec![0f64; max_error.len()];
+    let mut amplitude = vec![0f32; max_error.len()];
+    let mut count = vec![0usize; max_error.len()];
+    for sample in &samples {
+        let expected = float_plan.run(sample.clone())?;
+        let found = quantized_plan.run(sample.clone())?;
+        for (ix, (e, f)) in expected.iter().zip(found.iter()).enumerate() {
+            let e = e.cast_to::<f32>()?;
+            let f = f.cast_to::<f32>()?;
+            for (e, f) in e.as_slice::<f32>()?.iter().zip(f.as_slice::<f32>()?.iter()) {
+                max_error[ix] = max_error[ix].max((e - f).abs());
+                sum_error[ix] += (e - f).abs() as f64;
+                amplitude[ix] = amplitude[ix].max(e.abs());
+            }
+            count[ix] += e.len();
+        }
+    }
+    for (ix, output) in model.output_outlets()?.iter().enumerate() {
+        println!(
+            "{}: max abs error: {:.6} mean abs error: {:.6} (float max abs: {:.6})",
+            model.node(output.node).name,
+            max_error[ix],
+            sum_error[ix] / count[ix].max(1) as f64,
+            amplitude[ix]
+        );
+    }
+
+    if let Some(path) = matches.value_of("nnef-tar") {
+        let file = fs::File::create(path)?;
+        tract_nnef::nnef().with_tract_core().write_to_tar(&quantized, file)?;
+    }
+    Ok(())
+}
+
+/// Calibration samples, one tensor per model input. `path` is either a npz
+/// file with an entry per input (stacking samples on an extra first axis), or
+/// a directory of samples: npz files, or (with onnx) directories of protobuf
+/// tensors.
+fn load_samples(model: &TypedModel, path: &Path) -> CliResult<Vec<TVec<Tensor>>> {
+    let names =
+        model.input_outlets()?.iter().map(|i| model.node(i.node).name.clone()).collect::<Vec<_>>();
+    if path.is_dir() {
+        let mut entries =
+            fs::read_dir(path)?.map(|e| Ok(e?.path())).collect::<CliResult<Vec<_>>>()?;
+        entries.sort();
+        let mut samples = vec![];
+        for entry in entries {
+            let mut values = HashMap::new();
+            if entry.is_dir() {
+                #[cfg(feature = "onnx")]
+                for file in fs::read_dir(&entry)? {
+                    let tensor =
+                        tract_onnx::tensor::proto_from_reader(fs::File::open(file?.path())?)?;
+                    values.insert(tensor.name.to_string(), tensor.try_into()?);
+                }
+            } else if entry.extension().map(|e| e == "npz").unwrap_or(false) {
+                values = read_npz(&entry)?;
+            } else {
+                continue;
+            }
+            let sample = names
+                .iter()
+                .map(|n| {
+                    values.remove(n).with_context(|| format!("No value for {} in {:?}", n, entry))
+                })
+                .collect::<CliResult<TVec<Tensor>>>()?;
+            samples.push(sample);
+        }
+        Ok(samples)
+    } else {
+        let mut values = read_npz(path)?;
+        let inputs = names
+            .iter()
+            .zip(model.input_outlets()?.iter())
+            .map(|(n, &i)| {
+                let value = values.remove(n).with_context(|| format!("No value for {}", n))?;
+                let stacked = value.rank() == model.outlet_fact(i)?.rank() + 1;
+                let samples = if stacked {
+                    (0..value.shape()[0])
+                        .map(|s| {
+                            let mut t = value.slice(0, s, s + 1)?;
+                            t.remove_axis(0)?;
+                            Ok(t)
+                        })
+                        .collect::<CliResult<Vec<_>>>()?
+                } else {
+                    vec![value]
+                };
+                Ok(samples)
+            })
+            .collect::<CliResult<Vec<_>>>()?;
+        let count = inputs.iter().map(|s| s.len()).min().unwrap_or(0);
+        Ok((0..count).map(|s| inputs.iter().map(|i| i[s].clone()).collect()).collect())
+    }
+}
+
+fn read_npz(path: &Path) -> CliResult<HashMap<String, Tensor>> {
+    let mut npz = ndarray_npy::NpzReader::new(fs::File::open(path)?)?;
+    let mut values = HashMap::new();
+    for name in npz.names()? {
+        let value = tensor::for_npz(&mut npz, &name)?;
+        values.insert(name.trim_end_matches(".npy").to_string(), value);
+    }
+    Ok(values)
+}
@@ -84,58 +84,42 @@ fn run_regular(
 
 #[cfg(feature = "pulse")]
 fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<Tensor>>> {
-    let input_fact = model.input_fact(0)?;
-    let output_fact = model.output_fact(0)?;
-
-    let output_pulse = output_fact.pulse();
-    //    println!("output_fact: {:?}", output_fact);
-    let axis = input_fact.axis;
-    let input: &Tensor = &params.input_values[0].as_ref().unwrap();
-    //    println!("input_shape: {:?}", input.shape());
-    let input_dim = input.shape()[axis];
-    //    println!("output_fact: {:?}", output_fact);
-    let output_dim = output_fact
-        .dim
-        .eval(&SymbolValues::default().with(stream_symbol(), input_dim as i64))
-        .to_usize()?;
-    let mut output_shape = output_fact.shape.to_vec();
-    output_shape[output_fact.axis] =
-        (output_dim as usize + output_fact.delay + 4 * output_fact.pulse()).to_dim();
-    let output_shape: TVec<usize> = output_shape.iter().map(|d| d.to_usize().unwrap()).collect();
-    let plan = SimplePlan::new(model)?;
-    let mut state = ::tract_core::plan::SimpleState::new(&plan)?;
-    //    println!("output_shape: {:?}", output_shape);
-    let pulse = input_fact.pulse();
-    let mut result = tract_ndarray::ArrayD::<f32>::default(&*output_shape);
-    let input = input.to_array_view::<f32>()?;
-    for ix in 0..input_dim.div_ceil(pulse) {
-        let chunk =
-            input.slice_axis(tract_ndarray::Axis(axis), (ix * pulse..(ix + 1) * pulse).into());
-        let input = if chunk.shape()[input_fact.axis] < pulse {
-            let mut chunk_shape = chunk.shape().to_vec();
-            chunk_shape[input_fact.axis] = pulse;
-            let mut padded_chunk = tract_ndarray::ArrayD::<f32>::default(chunk_shape);
-            padded_chunk
-                .slice_axis_mut(
-                    tract_ndarray::Axis(input_fact.axis),
-                    (..chunk.shape()[input_fact.axis]).into(),
-                )
-                .assign(&chunk);
-            padded_chunk
-        } else {
-            chunk.to_owned()
-        };
-        let outputs = state.run(tvec!(input.into()))?;
-        let result_chunk = outputs[0].to_array_view::<f32>()?;
-        result
-            .slice_axis_mut(
-                tract_ndarray::Axis(output_fact.axis),
-                ((output_pulse * ix)..(output_pulse * (ix + 1))).into(),
-            )
-            .assign(&result_chunk);
+    let inputs = (0..model.input_outlets()?.len())
+        .map(|ix| {
+            let fact = model.input_fact(ix)?;
+            let input = params.input_values.get(ix).and_then(|v| v.as_ref());
+            let input = input.context("Pulsed run needs a value for each input")?;
+            Ok((input, fact.axis, fact.base_pulse()))
+        })
+        .collect::<CliResult<TVec<_>>>()?;
+    let plan = SimplePlan::new(model.clone().into_typed()?)?;
+    let mut stream = PulsedStream::new(model, &plan)?;
+    let steps = inputs
+        .iter()
+        .map(|(input, axis, pulse)| (input.shape()[*axis] + pulse - 1) / pulse)
+        .max()
+        .unwrap_or(0);
+    let mut chunks = vec![vec![]; model.output_outlets()?.len()];
+    let mut collect = |results: TVec<Tensor>| {
+        results.into_iter().zip(chunks.iter_mut()).for_each(|(r, chunks)| chunks.push(r))
+    };
+    for ix in 0..steps {
+        let pushed = inputs
+            .iter()
+            .map(|(input, axis, pulse)| {
+                let len = input.shape()[*axis];
+                input.slice(*axis, (ix * pulse).min(len), ((ix + 1) * pulse).min(len))
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        collect(stream.push(pushed)?);
     }
-    result.slice_axis_inplace(tract_ndarray::Axis(output_fact.axis), (output_fact.delay..).into());
-    result
-        .slice_axis_inplace(tract_ndarray::Axis(output_fact.axis), (..output_dim as usize).into());
-    Ok(tvec!(result.into_arc_tensor()))
+    collect(stream.flush()?);
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(ix, chunks)| {
+            let axis = model.output_fact(ix)?.axis;
+            Ok(Tensor::stack_tensors(axis, &chunks)?.into_arc_tensor())
+        })
+        .collect()
 }
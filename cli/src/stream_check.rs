@@ -1,8 +1,7 @@
 use tract_core::itertools::Itertools;
-use tract_core::ndarray::{ArrayD, Axis};
+use tract_core::ndarray::Axis;
 
 use tract_core::model::OutletId;
-use tract_core::plan::SimpleState;
 
 use tract_pulse::internal::*;
 
@@ -17,129 +16,105 @@ pub fn handle(params: &Parameters, options: &DisplayParams) -> CliResult<()> {
         .context("Decluttered model not generated. (using --pass ?)")?;
     let pulsed =
         params.pulsed_model.as_ref().context("Pulsed model not generated. (using --pass ?)")?;
-    let model = params
-        .tract_model
-        .downcast_ref::<TypedModel>()
-        .context("Final model is not Typed. (using --pass ?)")?;
-
-    let decl_input_fact = decl.input_fact(0)?;
     let pulsed_input_fact = pulsed.input_fact(0)?;
-    let input_pulse = pulsed_input_fact.pulse();
+    // pulse of the stream symbol itself, inputs may stream at other rates
+    let (num, den) = pulsed_input_fact.dim.slope(stream_symbol());
+    let input_pulse = pulsed_input_fact.base_pulse() * den as usize / num as usize;
 
     let annotations = crate::annotations::Annotations::from_model(&*params.tract_model)?
         .with_graph_def(&*params.tract_model, &params.graph)?;
 
-    let eval_order = ::tract_core::model::eval_order(&decl)?;
-
-    for &decl_node in eval_order.iter() {
-        let pulsed_node = match pulsed.node_by_name(&*decl.node(decl_node).name) {
-            Ok(node) => node.id,
-            _ => continue,
-        };
-        let node = match model.node_by_name(&*decl.node(decl_node).name) {
-            Ok(node) => node.id,
+    let model = params
+        .tract_model
+        .downcast_ref::<TypedModel>()
+        .context("Stream check expects a typed model")?;
+
+    let mut decl_outlets = vec![];
+    let mut pulsed_outlets = vec![];
+    let mut outlets = vec![];
+    for &decl_node in ::tract_core::model::eval_order(&decl)?.iter() {
+        let name = &*decl.node(decl_node).name;
+        let (pulsed_node, node) = match (pulsed.node_by_name(name), model.node_by_name(name)) {
+            (Ok(pulsed_node), Ok(node)) => (pulsed_node.id, node.id),
             _ => continue,
         };
         for output_slot in 0..decl.node(decl_node).outputs.len() {
-            debug!("checking node: {} output: {}", decl.node(decl_node).name, output_slot);
-            let decl_outlet = OutletId::new(decl_node, output_slot);
-            let pulsed_outlet = OutletId::new(pulsed_node, output_slot);
-            let outlet = OutletId::new(node, output_slot);
-
-            let pulsed_output_fact = pulsed.outlet_fact(pulsed_outlet)?;
-            let output_pulse = pulsed_output_fact.pulse();
-            let output_axis = pulsed_output_fact.axis;
-            let delay = pulsed_output_fact.delay;
-
-            let stream_dim = delay + 3 * input_pulse + input_pulse / 2;
-
-            let fixed_input = crate::tensor::tensor_for_fact(decl_input_fact, Some(stream_dim))?;
-
-            let decl = (*decl).clone();
-            let fixed_result = decl
-                .with_output_outlets(&[decl_outlet])?
-                .concretize_dims(&SymbolValues::default().with(stream_symbol(), stream_dim as _))?
-                .into_runnable()?
-                .run(tvec!(fixed_input.clone()))?
-                .remove(output_slot);
-            let fixed_output_len = fixed_result.shape()[output_axis];
-
-            let plan = model.clone().with_output_outlets(&[outlet])?.into_runnable()?;
-            let mut state = SimpleState::new(&plan)?;
-
-            for i in 0.. {
-                let input_shape = pulsed_input_fact
-                    .shape
-                    .iter()
-                    .map(|d| d.to_usize())
-                    .collect::<TractResult<TVec<_>>>()?;
-                let mut pulsed_input = ArrayD::from_elem(&*input_shape, std::f32::NAN);
-                let offset = i * input_pulse;
-                if offset < stream_dim {
-                    let count = input_pulse.min(stream_dim - offset);
-                    pulsed_input
-                        .slice_axis_mut(Axis(pulsed_input_fact.axis), (0..count).into())
-                        .assign(&fixed_input.to_array_view::<f32>()?.slice_axis(
-                            Axis(pulsed_input_fact.axis),
-                            (offset..offset + count).into(),
-                        ));
-                };
-                if offset + input_pulse > stream_dim {
-                    debug!("Set known_stream_len: {}", stream_dim);
-                    state.session_state.resolved_symbols[stream_symbol()] = Some(stream_dim as _);
-                };
-
-                let output = state.run(tvec!(pulsed_input.into()))?.remove(output_slot);
+            decl_outlets.push(OutletId::new(decl_node, output_slot));
+            pulsed_outlets.push(OutletId::new(pulsed_node, output_slot));
+            outlets.push(OutletId::new(node, output_slot));
+        }
+    }
 
-                let output_offset = i * output_pulse;
-                let (f_o, p_o, count) = if output_offset + output_pulse <= delay {
-                    // entire pulse before signal, wait
-                    continue;
-                } else if output_offset >= delay + fixed_output_len {
-                    // entire pulse after signal, we stop
-                    break;
-                } else if output_offset < delay {
-                    // beginning of signal
-                    let count = output_pulse + output_offset - delay;
-                    (0, output_pulse - count, count)
-                } else if output_offset + output_pulse > delay + fixed_output_len {
-                    // end of signal
-                    let count = fixed_output_len + delay - output_offset;
-                    (output_offset - delay, 0, count)
-                } else {
-                    (output_offset - delay, 0, output_pulse)
-                };
-                let valid_pulse_result = output.slice(output_axis, p_o, p_o + count)?;
-                let valid_fixed_result = fixed_result.slice(output_axis, f_o, f_o + count)?;
-                if valid_pulse_result != valid_fixed_result {
-                    terminal::render_node(
-                        &*params.tract_model,
-                        pulsed_node,
-                        &annotations,
-                        options,
-                    )?;
-                    println!("pulse: {} ({}..{})", i, i * output_pulse, (i + 1) * output_pulse);
-                    println!("expected: {:?}", &valid_fixed_result.as_slice::<f32>()?[0..10]);
-                    println!(
-                        "expected: {}",
-                        valid_fixed_result
-                            .to_array_view::<f32>()?
-                            .axis_iter(Axis(output_axis))
-                            .map(|s| *s.iter().next().unwrap())
-                            .join(" ")
-                    );
-                    println!("got: {:?}", &valid_pulse_result.as_slice::<f32>()?[0..10]);
-                    println!(
-                        "got: {}",
-                        valid_pulse_result
-                            .to_array_view::<f32>()?
-                            .axis_iter(Axis(output_axis))
-                            .map(|s| *s.iter().next().unwrap())
-                            .join(" ")
-                    );
-                    bail!("Pulse check failed")
-                }
+    let max_delay = pulsed_outlets
+        .iter()
+        .map(|o| Ok(pulsed.outlet_fact(*o)?.delay))
+        .collect::<TractResult<Vec<_>>>()?
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+    let stream_dim = max_delay + 3 * input_pulse + input_pulse / 2;
+    let fixed_inputs = (0..decl.input_outlets()?.len())
+        .map(|ix| crate::tensor::tensor_for_fact(decl.input_fact(ix)?, Some(stream_dim)))
+        .collect::<CliResult<TVec<_>>>()?;
+
+    let fixed_results = (*decl)
+        .clone()
+        .with_output_outlets(&decl_outlets)?
+        .concretize_dims(&SymbolValues::default().with(stream_symbol(), stream_dim as _))?
+        .into_runnable()?
+        .run(fixed_inputs.clone())?;
+
+    let pulsed = (**pulsed).clone().with_output_outlets(&pulsed_outlets)?;
+    let plan = model.clone().with_output_outlets(&outlets)?.into_runnable()?;
+    let mut stream = PulsedStream::new(&pulsed, &plan)?;
+    if fixed_inputs.iter().all(|input| input.datum_type().is_float()) {
+        stream = stream.with_filler(tensor0(std::f32::NAN))?;
+    }
+    let mut got = vec![vec![]; outlets.len()];
+    let mut collect = |results: TVec<Tensor>| {
+        results.into_iter().zip(got.iter_mut()).for_each(|(r, got)| got.push(r))
+    };
+    let input_facts = (0..fixed_inputs.len())
+        .map(|ix| pulsed.input_fact(ix).map(|f| (f.axis, f.base_pulse())))
+        .collect::<TractResult<TVec<_>>>()?;
+    let steps = fixed_inputs
+        .iter()
+        .zip(&input_facts)
+        .map(|(input, (axis, pulse))| (input.shape()[*axis] + pulse - 1) / pulse)
+        .max()
+        .unwrap_or(0);
+    for step in 0..steps {
+        let chunks = fixed_inputs
+            .iter()
+            .zip(&input_facts)
+            .map(|(input, &(axis, pulse))| {
+                let len = input.shape()[axis];
+                input.slice(axis, (step * pulse).min(len), ((step + 1) * pulse).min(len))
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        collect(stream.push(chunks)?);
+    }
+    collect(stream.flush()?);
+
+    for (ix, (fixed_result, got)) in fixed_results.iter().zip(got).enumerate() {
+        debug!("checking node: {}", pulsed.node(pulsed_outlets[ix].node).name);
+        let output_axis = pulsed.outlet_fact(pulsed_outlets[ix])?.axis;
+        let pulsed_result = Tensor::stack_tensors(output_axis, &got)?;
+        if pulsed_result != **fixed_result {
+            terminal::render_node(&*params.tract_model, outlets[ix].node, &annotations, options)?;
+            for (name, result) in &[("expected", &**fixed_result), ("got", &pulsed_result)] {
+                println!(
+                    "{} ({} frames): {}",
+                    name,
+                    result.shape()[output_axis],
+                    result
+                        .to_array_view::<f32>()?
+                        .axis_iter(Axis(output_axis))
+                        .map(|s| *s.iter().next().unwrap())
+                        .join(" ")
+                );
             }
+            bail!("Pulse check failed")
         }
     }
 
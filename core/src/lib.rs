@@ -87,7 +87,7 @@ pub type TractResult<T> = Result<T, anyhow::Error>;
 pub mod prelude {
     pub use crate::framework::Framework;
     pub use crate::model::*;
-    pub use crate::plan::{SimplePlan, SimpleState};
+    pub use crate::plan::{SimplePlan, SimpleState, StateSnapshot};
     pub use crate::{TractError, TractResult};
     pub use std::sync::Arc;
     pub use tract_data::prelude::*;
@@ -12,6 +12,11 @@ pub trait Fact: std::fmt::Debug + Downcast + dyn_clone::DynClone + Send + Sync +
         self.to_typed_fact()?.matches(t)
     }
 
+    /// Like `matches`, but symbolic dimensions match their value in `symbols`.
+    fn matches_with_symbols(&self, t: &Tensor, symbols: &SymbolValues) -> TractResult<bool> {
+        self.to_typed_fact()?.matches_with_symbols(t, symbols)
+    }
+
     fn same_as(&self, _other: &dyn Fact) -> bool;
 }
 
@@ -209,6 +214,12 @@ impl Fact for TypedFact {
         Ok(self.datum_type == t.datum_type() && self.shape == t.shape())
     }
 
+    fn matches_with_symbols(&self, t: &Tensor, symbols: &SymbolValues) -> TractResult<bool> {
+        let shape = self.shape.eval(symbols);
+        Ok(self.datum_type == t.datum_type()
+            && shape.map(|shape| shape.as_slice() == t.shape()).unwrap_or(false))
+    }
+
     fn same_as(&self, other: &dyn Fact) -> bool {
         if cfg!(debug_assertions) {
             self.consistent().unwrap()
@@ -78,6 +78,15 @@ impl TypedOp for TypedConcat {
                 }
             })
             .unwrap();
+        let dts = inputs
+            .iter()
+            .map(|i| i.datum_type)
+            .chain(self.slices.iter().filter_map(|s| s.as_const()).map(|t| t.datum_type()));
+        for dt in dts {
+            if dt != fact.datum_type && (dt.is_quantized() || fact.datum_type.is_quantized()) {
+                bail!("Concat of inconsistently quantized inputs: {:?}", self);
+            }
+        }
         for input in inputs {
             if input.rank() != fact.rank()
                 || input
@@ -85,4 +85,12 @@ impl OpState for ConstantOfShapeState {
             .collect::<TractResult<TVec<_>>>()?;
         Ok(tvec!(op.scalar.broadcast_scalar_to_shape(&*shape)?.into_arc_tensor()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
+
+    fn load(&mut self, _tensors: TVec<Tensor>) -> TractResult<()> {
+        Ok(())
+    }
 }
@@ -34,6 +34,13 @@ pub trait BinMiniOp:
         let c_shape = crate::broadcast::multi_broadcast(&[a.shape(), b.shape()])
             .ok_or_else(|| format_err!("Can not compute resulting shape"))?;
         let c_dt = self.result_datum_type(a.datum_type(), b.datum_type())?;
+        if a.datum_type().is_quantized() || b.datum_type().is_quantized() {
+            // compute on real values, requantize with the result parameters
+            let a = a.cast_to::<f32>()?.into_owned().into_arc_tensor();
+            let b = b.cast_to::<f32>()?.into_owned().into_arc_tensor();
+            let c = self.eval_broadcast(tvec!(a, b))?.remove(0);
+            return Ok(tvec!(c.cast_to_dt(c_dt)?.into_owned().into_arc_tensor()));
+        }
         let mut c = unsafe { Tensor::uninitialized_dt(c_dt, &*c_shape)? };
         self.eval_out_of_place(&mut c, a.as_ref(), b.as_ref())?;
         Ok(tvec!(c.into_arc_tensor()))
@@ -209,6 +216,22 @@ impl TypedOp for TypedBinOp {
         if let Some(patch) = self.0.declutter_bin(model, node)? {
             return Ok(Some(patch));
         }
+        if let (Some(kind), Some(a), Some(b)) = (
+            crate::ops::quant::QuantizedBinaryKind::from_mini_op(&*self.0),
+            inputs[0].datum_type.zp_scale(),
+            inputs[1].datum_type.zp_scale(),
+        ) {
+            let c_dt = self.0.result_datum_type(inputs[0].datum_type, inputs[1].datum_type)?;
+            if let Some(c) = c_dt.zp_scale() {
+                let op = crate::ops::quant::QuantizedBinary::new(kind, a, b, c, c_dt);
+                return Ok(Some(TypedModelPatch::replace_single_op(
+                    &model,
+                    &node,
+                    &node.inputs,
+                    op,
+                )?));
+            }
+        }
         for i in 0..2 {
             use super::array::MultiBroadcastTo;
             let prec = model.node(node.inputs[i].node);
@@ -1,5 +1,8 @@
 use crate::internal::*;
 use crate::ops::element_wise::ElementWiseOp;
+use crate::ops::quant::{
+    quantize_linear_i8, quantize_linear_u8, storage_range, DequantizeLinearF32,
+};
 
 pub fn cast(to: DatumType) -> ElementWiseOp {
     ElementWiseOp(Box::new(Cast { to }))
@@ -31,9 +34,71 @@ impl ElementWiseMiniOp for Cast {
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
         if model.outlet_fact(node.inputs[0])?.datum_type == self.to {
-            Ok(Some(TypedModelPatch::shunt_one_op(model, node)?))
+            return Ok(Some(TypedModelPatch::shunt_one_op(model, node)?));
+        }
+        let prec = model.node(node.inputs[0].node);
+        if let Some(prec_cast) =
+            prec.op_as::<ElementWiseOp>().and_then(|op| op.0.downcast_ref::<Cast>())
+        {
+            let source = prec.inputs[0];
+            if model.outlet_fact(source)?.datum_type == self.to
+                && round_trips(self.to, prec_cast.to)
+            {
+                let mut patch = TypedModelPatch::default();
+                let wire = patch.tap_model(model, source)?;
+                patch.shunt_outside(model, node.id.into(), wire)?;
+                return Ok(Some(patch));
+            }
+        }
+        self.declutter_quantization(model, node)
+    }
+}
+
+impl Cast {
+    /// Rewrites casts between f32 and a quantized type as explicit
+    /// (de)quantization of the storage type, which the quantization
+    /// declutters know how to fold.
+    fn declutter_quantization(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let from = model.outlet_fact(node.inputs[0])?.datum_type;
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        if let (Some(zps), DatumType::F32) = (from.zp_scale(), self.to) {
+            wire = patch.wire_node(
+                format!("{}.storage", node.name),
+                cast(from.unquantized()),
+                &[wire],
+            )?[0];
+            let op = DequantizeLinearF32::new(zps.scale, zps.zero_point);
+            wire = patch.wire_node(&*node.name, op, &[wire])?[0];
+        } else if let (DatumType::F32, Some(zps)) = (from, self.to.zp_scale()) {
+            let (min, max) = storage_range(self.to)?;
+            if zps.zero_point < min || zps.zero_point > max {
+                bail!("Zero point of {:?} out of its storage range", self.to);
+            }
+            let op = match self.to.unquantized() {
+                DatumType::I8 => quantize_linear_i8(zps.scale.recip(), zps.zero_point as i8),
+                _ => quantize_linear_u8(zps.scale.recip(), zps.zero_point as u8),
+            };
+            wire = patch.wire_node(format!("{}.quantize", node.name), op, &[wire])?[0];
+            wire = patch.wire_node(&*node.name, cast(self.to), &[wire])?[0];
         } else {
-            Ok(None)
+            return Ok(None);
         }
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+}
+
+/// Whether casting from `from` to `via` and back gives back the original
+/// values: quantized types through f32 or f64, or through their storage type.
+fn round_trips(from: DatumType, via: DatumType) -> bool {
+    if from.is_quantized() {
+        via == DatumType::F32 || via == DatumType::F64 || via == from.unquantized()
+    } else {
+        via.is_quantized() && via.unquantized() == from
     }
 }
@@ -1,4 +1,5 @@
 use crate::internal::*;
+use crate::ops::cnn::patches::Scanner;
 use crate::ops::cnn::Patch;
 use crate::ops::nn::DataShape;
 use ndarray::*;
@@ -29,11 +30,144 @@ impl EvalOp for DepthWise {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        dispatch_floatlike!(Self::eval_t(inputs[0].datum_type())(self, inputs))
+        let mult = *self.output_shape.c() / *self.input_shape.c();
+        match inputs[0].datum_type() {
+            DatumType::F32 if mult == 1 => {
+                let dw = (tract_linalg::ops().depthwise_f32)();
+                let output = self.eval_linalg::<f32, f32>(&inputs[0], &*dw)?;
+                Ok(tvec!(output.into_arc_tensor()))
+            }
+            DatumType::I8 => {
+                if mult != 1 {
+                    bail!("i8 depthwise convolution requires as many output as input channels")
+                }
+                let dw = (tract_linalg::ops().depthwise_i8_i32)();
+                let mut output = self.eval_linalg::<i8, i32>(&inputs[0], &*dw)?;
+                let output = output
+                    .as_slice_mut::<i32>()?
+                    .iter()
+                    .map(|&x| x.max(i8::min_value() as i32).min(i8::max_value() as i32) as i8)
+                    .collect::<Vec<i8>>();
+                Ok(tvec!(tensor1(&output).into_shape(&self.output_shape.shape)?.into_arc_tensor()))
+            }
+            dt => dispatch_floatlike!(Self::eval_t(dt)(self, inputs)),
+        }
     }
 }
 
 impl DepthWise {
+    /// Evaluation using tract_linalg depthwise kernels, with one kernel per
+    /// channel (no depth multiplier). Results are returned in the
+    /// accumulator type.
+    ///
+    /// In channels-last layouts, all channels of an output point are
+    /// accumulated in one kernel call. In channels-first layouts, kernel calls
+    /// cover runs of contiguous output points of a single channel.
+    fn eval_linalg<TA, TI>(
+        &self,
+        img: &Tensor,
+        dw: &dyn tract_linalg::depthwise::DepthWise<TA, TI>,
+    ) -> TractResult<Tensor>
+    where
+        TA: Datum + Copy,
+        TI: Datum + Copy + num_traits::Zero,
+    {
+        let channels = *self.input_shape.c();
+        let kernel = self.kernel_chw.as_slice::<TA>()?;
+        let taps = kernel.len() / channels;
+        let bias = if let Some(bias) = &self.bias {
+            bias.cast_to::<TI>()?.into_owned()
+        } else {
+            Tensor::zero::<TI>(&[channels])?
+        };
+        let bias = bias.as_slice::<TI>()?;
+        let mut output = unsafe { Tensor::uninitialized::<TI>(&*self.output_shape.shape)? };
+        let optr = output.as_ptr_mut::<TI>()?;
+        let iptr = img.as_ptr::<TA>()?;
+        let n = *self.input_shape.n().unwrap_or(&1);
+        let n_stride_i = *self.input_shape.n_stride().unwrap_or(&0) as isize;
+        let n_stride_o = *self.output_shape.n_stride().unwrap_or(&0) as isize;
+        let c_stride_i = *self.input_shape.c_stride() as isize;
+        let c_stride_o = *self.output_shape.c_stride() as isize;
+        // valid (kernel tap index, input offset from patch center), per zone
+        let mut zones: Vec<Option<(TVec<usize>, TVec<isize>)>> = vec![None; self.patch.zones.len()];
+        let zone_taps = |zones: &mut Vec<Option<(TVec<usize>, TVec<isize>)>>, visitor: &Scanner| {
+            if zones[visitor.zone_id].is_none() {
+                zones[visitor.zone_id] = Some(
+                    visitor
+                        .valid_offsets_with_indexes()
+                        .map(|(ix, o)| (ix, o - visitor.input_center_offset))
+                        .unzip(),
+                );
+            }
+        };
+        unsafe {
+            if c_stride_i == 1 && c_stride_o == 1 {
+                let mut kernels: Vec<Option<Vec<TA>>> = vec![None; self.patch.zones.len()];
+                self.patch.visit_output(|visitor| {
+                    zone_taps(&mut zones, visitor);
+                    let (ixs, offsets) = zones[visitor.zone_id].as_ref().unwrap();
+                    let zone_kernel = kernels[visitor.zone_id].get_or_insert_with(|| {
+                        ixs.iter()
+                            .flat_map(|&ix| (0..channels).map(move |c| kernel[c * taps + ix]))
+                            .collect()
+                    });
+                    for n in 0..n as isize {
+                        let acc = std::slice::from_raw_parts_mut(
+                            optr.offset(n_stride_o * n + visitor.output_offset),
+                            channels,
+                        );
+                        acc.copy_from_slice(bias);
+                        let input = iptr.offset(n_stride_i * n + visitor.input_center_offset);
+                        dw.run_hwc(acc, zone_kernel, input, offsets);
+                    }
+                });
+            } else {
+                // (zone, output offset, input center offset, length)
+                let mut runs: Vec<(usize, isize, isize, usize)> = vec![];
+                self.patch.visit_output(|visitor| {
+                    zone_taps(&mut zones, visitor);
+                    if let Some(run) = runs.last_mut() {
+                        if run.0 == visitor.zone_id
+                            && run.1 + run.3 as isize == visitor.output_offset
+                            && run.2 + run.3 as isize == visitor.input_center_offset
+                        {
+                            run.3 += 1;
+                            return;
+                        }
+                    }
+                    runs.push((
+                        visitor.zone_id,
+                        visitor.output_offset,
+                        visitor.input_center_offset,
+                        1,
+                    ));
+                });
+                let mut kernel_taps = vec![];
+                for (zone, output_offset, input_offset, len) in runs {
+                    let (ixs, offsets) = zones[zone].as_ref().unwrap();
+                    for c in 0..channels {
+                        kernel_taps.clear();
+                        kernel_taps.extend(ixs.iter().map(|&ix| kernel[c * taps + ix]));
+                        for n in 0..n as isize {
+                            let acc = std::slice::from_raw_parts_mut(
+                                optr.offset(
+                                    n_stride_o * n + c_stride_o * c as isize + output_offset,
+                                ),
+                                len,
+                            );
+                            acc.iter_mut().for_each(|a| *a = bias[c]);
+                            let input = iptr
+                                .offset(n_stride_i * n + c_stride_i * c as isize + input_offset);
+                            dw.run_chw(acc, &kernel_taps, input, offsets);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(output)
+    }
+
     fn eval_t<T: Datum + Copy + num_traits::Zero + ndarray::LinalgScalar>(
         &self,
         mut inputs: TVec<Arc<Tensor>>,
@@ -1,6 +1,7 @@
 mod depth_wise;
 mod im2col;
 mod unary;
+mod winograd;
 #[cfg(test)]
 mod proptest;
 
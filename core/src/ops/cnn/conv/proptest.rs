@@ -77,14 +77,18 @@ impl ConvProblem {
 
     fn tract(&self) -> anyhow::Result<ArrayD<f32>> {
         assert_eq!(self.data.shape(), &*self.shape_in.shape);
+        self.tract_with(&self.data, PaddingSpec::Valid)
+    }
+
+    fn tract_with(&self, data: &ArrayD<f32>, padding: PaddingSpec) -> anyhow::Result<ArrayD<f32>> {
         let mut model = TypedModel::default();
-        let wire = model
-            .add_source("input", TypedFact::dt_shape(f32::datum_type(), &self.shape_in.shape))?;
+        let wire =
+            model.add_source("input", TypedFact::dt_shape(f32::datum_type(), data.shape()))?;
         let op = ConvUnary::new(
             PoolSpec::new(
                 self.shape_in.fmt,
                 self.geo_ker().into(),
-                PaddingSpec::Valid,
+                padding,
                 None,
                 None,
                 Some(*self.shape_out.c()),
@@ -98,7 +102,7 @@ impl ConvProblem {
         let wire = model.wire_node("conv", op, &[wire])?[0];
         model.set_output_outlets(&[wire])?;
         let mut output =
-            model.into_optimized()?.into_runnable()?.run(tvec![self.data.clone().into_tensor()])?;
+            model.into_optimized()?.into_runnable()?.run(tvec![data.clone().into_tensor()])?;
         Ok(output.remove(0).into_tensor().into_array::<f32>()?)
     }
 }
@@ -187,6 +191,314 @@ proptest::proptest! {
     fn prop(pb in any::<ConvProblem>()) {
         prop_assert_eq!(pb.tract().unwrap(), pb.reference());
     }
+
+    #[test]
+    fn sparse(pb in sparse_problem()) {
+        prop_assert_eq!(pb.tract().unwrap(), pb.reference());
+    }
+
+    #[test]
+    fn winograd(pb in any::<WinogradProblem>()) {
+        pb.check().unwrap()
+    }
+
+    #[test]
+    fn depthwise_padded(pb in any::<DepthwiseProblem>()) {
+        pb.check().unwrap()
+    }
+}
+
+/// A 3x3 convolution eligible for winograd. The reference is a valid
+/// convolution over the explicitly zero-padded input.
+#[derive(Debug)]
+struct WinogradProblem {
+    conv: ConvProblem,
+    data: ArrayD<f32>,
+    padding: PaddingSpec,
+}
+
+impl WinogradProblem {
+    fn new(
+        conv: ConvProblem,
+        data: ArrayD<f32>,
+        padding: PaddingSpec,
+    ) -> anyhow::Result<WinogradProblem> {
+        let shape: DataShape = conv.shape_in.fmt.shape(data.shape().into())?;
+        let padded = padding.compute(shape.hw_dims(), &[3, 3], &[1, 1], &[1, 1]);
+        let mut padded_shape: TVec<usize> = shape.shape.clone();
+        for (ax, p) in shape.hw_axes().zip(padded.iter()) {
+            padded_shape[ax] += p.pad_before + p.pad_after;
+        }
+        let mut padded_data = ArrayD::zeros(&*padded_shape);
+        for (coords, v) in data.indexed_iter() {
+            let mut coords: TVec<usize> = coords.slice().into();
+            for (ax, p) in shape.hw_axes().zip(padded.iter()) {
+                coords[ax] += p.pad_before;
+            }
+            padded_data[&*coords] = *v;
+        }
+        let hw: TVec<usize> = padded.iter().map(|p| p.output).collect();
+        let shape_in = conv.shape_in.fmt.shape(padded_shape)?;
+        let shape_out =
+            conv.shape_in.fmt.from_n_c_hw(*shape.n().unwrap_or(&1), *conv.shape_out.c(), &*hw)?;
+        let conv = ConvProblem { shape_in, shape_out, data: padded_data, ..conv };
+        Ok(WinogradProblem { conv, data, padding })
+    }
+
+    fn check(&self) -> anyhow::Result<()> {
+        let found = self.conv.tract_with(&self.data, self.padding.clone())?;
+        let expected = self.conv.reference();
+        anyhow::ensure!(found.shape() == expected.shape());
+        anyhow::ensure!(found.iter().all(|f| f.is_finite()));
+        let error =
+            found.iter().zip(expected.iter()).map(|(f, e)| (f - e).abs()).fold(0f32, f32::max);
+        anyhow::ensure!(
+            error < 1e-3,
+            "error: {} found: {:?} expected: {:?}",
+            error,
+            found,
+            expected
+        );
+        Ok(())
+    }
+}
+
+impl Arbitrary for WinogradProblem {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<WinogradProblem>;
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (
+            any::<DataFormat>(),
+            any::<KernelFormat>(),
+            1usize..=2,
+            8usize..=10,
+            8usize..=10,
+            3usize..=11,
+            3usize..=11,
+            prop_oneof!(Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)),
+        )
+            .prop_flat_map(|(df, kf, n, ci, co, h, w, padding)| {
+                let shape_in = df.from_n_c_hw(n, ci, &[h, w]).unwrap();
+                let ker_shape = match kf {
+                    KernelFormat::HWIO => vec![3, 3, ci, co],
+                    KernelFormat::OIHW => vec![co, ci, 3, 3],
+                };
+                let data = small_tensor(shape_in.shape.iter().cloned().collect());
+                let kernel = small_tensor(ker_shape);
+                let bias = proptest::option::of(small_tensor(vec![co]));
+                (Just((df, kf, n, co, padding)), data, kernel, bias)
+            })
+            .prop_map(|((df, kernel_format, n, co, padding), data, kernel, bias)| {
+                let shape_in = df.shape(data.shape().into()).unwrap();
+                let shape_out = df.from_n_c_hw(n, co, &[1, 1]).unwrap();
+                let conv = ConvProblem {
+                    shape_in,
+                    shape_out,
+                    kernel_format,
+                    group: 1,
+                    data: data.clone(),
+                    kernel,
+                    bias,
+                };
+                WinogradProblem::new(conv, data, padding).unwrap()
+            })
+            .boxed()
+    }
+}
+
+/// A depthwise convolution, with enough channels to exercise both the
+/// linalg kernel blocks and their scalar tails. As for winograd, the
+/// reference runs over the explicitly zero-padded input.
+#[derive(Debug)]
+struct DepthwiseProblem {
+    conv: ConvProblem,
+    data: ArrayD<f32>,
+    padding: PaddingSpec,
+}
+
+impl DepthwiseProblem {
+    fn new(
+        conv: ConvProblem,
+        data: ArrayD<f32>,
+        padding: PaddingSpec,
+    ) -> anyhow::Result<DepthwiseProblem> {
+        let shape: DataShape = conv.shape_in.fmt.shape(data.shape().into())?;
+        let ones = tvec!(1; shape.hw_rank());
+        let padded = padding.compute(shape.hw_dims(), conv.geo_ker(), &ones, &ones);
+        let mut padded_shape: TVec<usize> = shape.shape.clone();
+        for (ax, p) in shape.hw_axes().zip(padded.iter()) {
+            padded_shape[ax] += p.pad_before + p.pad_after;
+        }
+        let mut padded_data = ArrayD::zeros(&*padded_shape);
+        for (coords, v) in data.indexed_iter() {
+            let mut coords: TVec<usize> = coords.slice().into();
+            for (ax, p) in shape.hw_axes().zip(padded.iter()) {
+                coords[ax] += p.pad_before;
+            }
+            padded_data[&*coords] = *v;
+        }
+        let hw: TVec<usize> = padded.iter().map(|p| p.output).collect();
+        let shape_in = conv.shape_in.fmt.shape(padded_shape)?;
+        let shape_out =
+            conv.shape_in.fmt.from_n_c_hw(*shape.n().unwrap_or(&1), *conv.shape_out.c(), &*hw)?;
+        let conv = ConvProblem { shape_in, shape_out, data: padded_data, ..conv };
+        Ok(DepthwiseProblem { conv, data, padding })
+    }
+
+    fn check(&self) -> anyhow::Result<()> {
+        let found = self.conv.tract_with(&self.data, self.padding.clone())?;
+        let expected = self.conv.reference();
+        anyhow::ensure!(found.shape() == expected.shape());
+        let error =
+            found.iter().zip(expected.iter()).map(|(f, e)| (f - e).abs()).fold(0f32, f32::max);
+        anyhow::ensure!(
+            error < 1e-3,
+            "error: {} found: {:?} expected: {:?}",
+            error,
+            found,
+            expected
+        );
+        Ok(())
+    }
+}
+
+impl Arbitrary for DepthwiseProblem {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<DepthwiseProblem>;
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (
+            any::<DataFormat>(),
+            any::<KernelFormat>(),
+            1usize..=2,
+            2usize..=20,
+            1usize..=3,
+            1usize..=3,
+            prop_oneof!(Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)),
+        )
+            .prop_flat_map(|(df, kf, n, c, kh, kw, padding)| {
+                (Just((df, kf, n, c, kh, kw, padding)), kh..=8usize, kw..=8usize)
+            })
+            .prop_flat_map(|((df, kf, n, c, kh, kw, padding), h, w)| {
+                let shape_in = df.from_n_c_hw(n, c, &[h, w]).unwrap();
+                let ker_shape = match kf {
+                    KernelFormat::HWIO => vec![kh, kw, c, 1],
+                    KernelFormat::OIHW => vec![c, 1, kh, kw],
+                };
+                let data = small_tensor(shape_in.shape.iter().cloned().collect());
+                let kernel = small_tensor(ker_shape);
+                let bias = proptest::option::of(small_tensor(vec![c]));
+                (Just((df, kf, n, c, padding)), data, kernel, bias)
+            })
+            .prop_map(|((df, kernel_format, n, c, padding), data, kernel, bias)| {
+                let shape_in = df.shape(data.shape().into()).unwrap();
+                let shape_out = df.from_n_c_hw(n, c, &[1, 1]).unwrap();
+                let conv = ConvProblem {
+                    shape_in,
+                    shape_out,
+                    kernel_format,
+                    group: c,
+                    data: data.clone(),
+                    kernel,
+                    bias,
+                };
+                DepthwiseProblem::new(conv, data, padding).unwrap()
+            })
+            .boxed()
+    }
+}
+
+/// Pruned kernels, sparse enough for the block sparse multiplier.
+fn sparse_problem() -> BoxedStrategy<ConvProblem> {
+    any::<ConvProblem>()
+        .prop_map(|mut pb| {
+            pb.kernel.iter_mut().enumerate().filter(|(ix, _)| ix % 16 != 0).for_each(|(_, x)| {
+                *x = 0.0;
+            });
+            pb
+        })
+        .boxed()
+}
+
+fn small_tensor(shape: Vec<usize>) -> BoxedStrategy<ArrayD<f32>> {
+    let len = shape.iter().product::<usize>();
+    vec((-100i32..100).prop_map(|i| i as f32 / 100.0), len..=len)
+        .prop_map(move |vec| ArrayD::from_shape_vec(shape.clone(), vec).unwrap())
+        .boxed()
+}
+
+#[test]
+fn winograd_is_used() -> anyhow::Result<()> {
+    for (h, tile) in
+        &[(6, super::winograd::WinogradTile::F2x2), (10, super::winograd::WinogradTile::F4x4)]
+    {
+        let mut model = TypedModel::default();
+        let input_shape = DataFormat::NCHW.from_n_c_hw(1, 8, &[*h, *h])?;
+        let wire = model
+            .add_source("input", TypedFact::dt_shape(f32::datum_type(), &input_shape.shape))?;
+        let op = ConvUnary::new(
+            PoolSpec::new(
+                DataFormat::NCHW,
+                tvec!(3, 3),
+                PaddingSpec::SameUpper,
+                None,
+                None,
+                Some(8),
+            ),
+            KernelFormat::OIHW,
+            Tensor::zero::<f32>(&[8, 8, 3, 3])?.into_arc_tensor(),
+            1,
+            None,
+            None,
+        );
+        let wire = model.wire_node("conv", op, &[wire])?[0];
+        model.set_output_outlets(&[wire])?;
+        let model = model.into_optimized()?;
+        let op = model.nodes().iter().find_map(|n| n.op_as::<super::winograd::Winograd>());
+        assert_eq!(op.map(|op| op.tile), Some(*tile));
+    }
+    Ok(())
+}
+
+#[test]
+fn sparse_is_used() -> anyhow::Result<()> {
+    let pb = ConvProblem {
+        shape_in: DataFormat::NCHW.from_n_c_hw(2, 6, &[5, 4])?,
+        shape_out: DataFormat::NCHW.from_n_c_hw(2, 10, &[3, 3])?,
+        kernel_format: KernelFormat::OIHW,
+        group: 2,
+        data: ArrayD::from_shape_fn(vec![2, 6, 5, 4], |ix| {
+            ((ix[0] + ix[1] * 3 + ix[2] * 5 + ix[3]) % 7) as f32 - 3.0
+        }),
+        kernel: ArrayD::from_shape_fn(vec![10, 3, 3, 2], |ix| {
+            if ix[1] == 1 && ix[2] == ix[0] % 3 && ix[3] == 0 {
+                ix[0] as f32 - 4.0
+            } else {
+                0.0
+            }
+        }),
+        bias: Some(ArrayD::from_shape_fn(vec![10], |ix| ix[0] as f32)),
+    };
+    let mut model = TypedModel::default();
+    let wire =
+        model.add_source("input", TypedFact::dt_shape(f32::datum_type(), pb.data.shape()))?;
+    let op = ConvUnary::new(
+        PoolSpec::new(DataFormat::NCHW, tvec!(3, 2), PaddingSpec::Valid, None, None, Some(10)),
+        KernelFormat::OIHW,
+        pb.kernel.clone().into_arc_tensor(),
+        2,
+        pb.bias.clone().map(|b| b.into_arc_tensor()),
+        None,
+    );
+    let wire = model.wire_node("conv", op, &[wire])?[0];
+    model.set_output_outlets(&[wire])?;
+    let model = model.into_optimized()?;
+    assert!(model
+        .nodes()
+        .iter()
+        .any(|n| n.op_is::<crate::ops::matmul::lir_sparse::LirSparseMatMulUnary>()));
+    let output = model.into_runnable()?.run(tvec!(pb.data.clone().into_tensor()))?;
+    assert_eq!(output[0].to_array_view::<f32>()?, pb.reference().view());
+    Ok(())
 }
 
 #[test]
@@ -445,3 +757,40 @@ fn batch_0() -> anyhow::Result<()> {
     assert_eq!(pb.tract().unwrap(), pb.reference());
     Ok(())
 }
+
+#[test]
+fn depthwise_i8() -> anyhow::Result<()> {
+    let shape_in = DataFormat::NHWC.from_n_c_hw(1, 9, &[4, 5])?;
+    let data = ArrayD::from_shape_fn(shape_in.shape.to_vec(), |ix| {
+        ((ix[1] * 7 + ix[2] * 3 + ix[3]) % 9) as f32 - 4.0
+    });
+    let kernel = ArrayD::from_shape_fn(vec![9, 1, 3, 3], |ix| {
+        ((ix[0] + 2 * ix[2] + ix[3]) % 5) as f32 - 2.0
+    });
+    let pb = ConvProblem {
+        shape_in,
+        shape_out: DataFormat::NHWC.from_n_c_hw(1, 9, &[2, 3])?,
+        kernel_format: KernelFormat::OIHW,
+        group: 9,
+        data,
+        kernel,
+        bias: None,
+    };
+    let mut model = TypedModel::default();
+    let wire = model.add_source("input", TypedFact::dt_shape(i8::datum_type(), pb.data.shape()))?;
+    let op = ConvUnary::new(
+        PoolSpec::new(DataFormat::NHWC, tvec!(3, 3), PaddingSpec::Valid, None, None, Some(9)),
+        KernelFormat::OIHW,
+        pb.kernel.mapv(|x| x as i8).into_arc_tensor(),
+        9,
+        None,
+        None,
+    );
+    let wire = model.wire_node("conv", op, &[wire])?[0];
+    model.set_output_outlets(&[wire])?;
+    let model = model.into_optimized()?;
+    assert!(model.nodes().iter().any(|n| n.op_is::<super::depth_wise::DepthWise>()));
+    let output = model.into_runnable()?.run(tvec!(pb.data.mapv(|x| x as i8).into_tensor()))?;
+    assert_eq!(output[0].to_array_view::<i8>()?, pb.reference().mapv(|x| x as i8).view());
+    Ok(())
+}
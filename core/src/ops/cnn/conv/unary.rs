@@ -5,6 +5,7 @@ use crate::model::*;
 
 use super::depth_wise::DepthWise;
 use super::im2col::Im2Col;
+use super::winograd::{Winograd, WinogradTile};
 use crate::ops::cnn::conv::KernelFormat;
 use crate::ops::cnn::PoolSpec;
 use crate::ops::matmul;
@@ -12,9 +13,7 @@ use crate::ops::nn::{DataFormat, DataShape};
 use crate::ops::quant::QParams;
 
 use tract_linalg::frame::Packer;
-use tract_linalg::mmm::FusedSpec;
-
-use std::iter::Sum;
+use tract_linalg::mmm::{FusedSpec, WeightQuant};
 
 #[derive(Debug, Clone, new, Hash)]
 pub struct ConvUnary {
@@ -75,34 +74,58 @@ impl ConvUnary {
 
     fn kernel_as_packed_as(&self, packer: &Packer, m: usize) -> TractResult<ArrayD<Arc<Tensor>>> {
         let kernel = self.kernel_as_group_o_ihw()?;
-        unsafe {
-            let mut packed_as = Array1::from(
-                (0..self.group)
-                    .map(|g| {
-                        let mut packed = Tensor::uninitialized_aligned_dt(
-                            kernel.datum_type(),
-                            &[packer.len(m)],
-                            packer.alignment(),
-                        )?;
-                        packer.pack(
-                            &mut TensorView::at_prefix(&mut packed, &[])?,
-                            &kernel.view_at_prefix(&[g])?,
-                            1,
-                            0,
-                        );
-                        Ok(packed.into_arc_tensor())
-                    })
-                    .collect::<TractResult<Vec<_>>>()?,
-            )
-            .into_dyn();
-            if self.group == 1 {
-                packed_as.index_axis_inplace(Axis(0), 0);
-            }
-            if self.pool_spec.data_format.has_n() {
-                packed_as.insert_axis_inplace(Axis(0));
-            }
-            Ok(packed_as)
+        let kernel_hash = matmul::pack_cache::content_hash(&kernel);
+        let packed_as = (0..self.group)
+            .map(|g| {
+                matmul::pack_cache::packed(&kernel, kernel_hash, (packer.clone(), g), || unsafe {
+                    let mut packed = Tensor::uninitialized_aligned_dt(
+                        kernel.datum_type(),
+                        &[packer.len(m)],
+                        packer.alignment(),
+                    )?;
+                    packer.pack(
+                        &mut TensorView::at_prefix(&mut packed, &[])?,
+                        &kernel.view_at_prefix(&[g])?,
+                        1,
+                        0,
+                    );
+                    Ok(packed)
+                })
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        Ok(self.per_group(packed_as))
+    }
+
+    /// Lay out one item per group the way the matrix multipliers iterate
+    /// over the C prefix: a group axis if group > 1, preceded by a
+    /// (broadcast) N axis if the data format has one.
+    fn per_group<T>(&self, items: Vec<T>) -> ArrayD<T> {
+        let mut array = Array1::from(items).into_dyn();
+        if self.group == 1 {
+            array.index_axis_inplace(Axis(0), 0);
+        }
+        if self.pool_spec.data_format.has_n() {
+            array.insert_axis_inplace(Axis(0));
         }
+        array
+    }
+
+    /// Dimensions and strides of the C prefix (N and group axes).
+    fn c_prefix_dim_and_stride(
+        &self,
+        output_shape: &DataShape,
+    ) -> Option<(TVec<usize>, TVec<isize>)> {
+        let mut dims = tvec!(self.group as usize);
+        let mut strides = tvec!((output_shape.c() / self.group * output_shape.c_stride()) as isize);
+        if self.group == 1 {
+            dims.clear();
+            strides.clear();
+        }
+        if output_shape.n().is_some() {
+            dims.insert(0, *output_shape.n().unwrap());
+            strides.insert(0, *output_shape.n_stride().unwrap() as isize);
+        }
+        Some((dims, strides)).filter(|it| it.0.len() > 0)
     }
 
     fn bias_as_non_linear<T>(&self) -> TractResult<Option<ArrayD<Vec<FusedSpec>>>>
@@ -110,29 +133,36 @@ impl ConvUnary {
         T: Datum + Copy,
     {
         use crate::itertools::Itertools;
+        let m = self.output_channels() / self.group;
+        let mut ops = vec![vec![]; self.group];
         if let Some(bias) = &self.bias {
             let bias = bias.cast_to::<T>()?;
             let bias = bias.as_slice::<T>()?;
-            let mut bias = Array1::from(
-                bias.iter()
-                    .chunks(self.output_channels() / self.group)
-                    .into_iter()
-                    .map(|c| vec![FusedSpec::PerRowAdd(tensor1(&*c.cloned().collect::<Vec<_>>()))])
-                    .collect::<Vec<_>>(),
-            )
-            .into_dyn();
-            if self.group == 1 {
-                bias.index_axis_inplace(Axis(0), 0);
+            for (g, c) in bias.iter().chunks(m).into_iter().enumerate() {
+                ops[g].push(FusedSpec::PerRowAdd(tensor1(&*c.cloned().collect::<Vec<_>>())));
             }
-            if self.pool_spec.data_format.has_n() {
-                bias.insert_axis_inplace(Axis(0));
+        }
+        // the per-row requantization of each group comes after its bias
+        if let Some(factors) = self.grouped_scale_factors() {
+            let factors = factors.as_slice::<f32>()?;
+            for (g, c) in factors.chunks(m).enumerate() {
+                let (multis, shift) = tract_linalg::mmm::q_per_row_multipliers(c);
+                ops[g].push(FusedSpec::PerRowQTowardsPlusInf(tensor1(&multis), shift));
             }
-            Ok(Some(bias))
-        } else {
+        }
+        if ops.iter().all(|ops| ops.is_empty()) {
             Ok(None)
+        } else {
+            Ok(Some(self.per_group(ops)))
         }
     }
 
+    /// Per-channel scale factors of a grouped quantized convolution: the
+    /// multiplier is shared by all groups, so they go to the fused ops.
+    fn grouped_scale_factors(&self) -> Option<&Arc<Tensor>> {
+        self.q_params.as_ref().and_then(|qp| qp.scale_factors.as_ref()).filter(|_| self.group > 1)
+    }
+
     pub unsafe fn wire_as_im2col_pair(
         &self,
         model: &mut TypedModel,
@@ -153,6 +183,12 @@ impl ConvUnary {
         let k = self.kernel.len() / self.output_channels();
         let n = geo.output_shape.iter().cloned().product::<usize>();
 
+        if !direct {
+            if let Some(wire) = self.wire_as_sparse_im2col_pair(model, name, wire, b_dt)? {
+                return Ok(wire);
+            }
+        }
+
         let c_dt = self.q_params.as_ref().map(|qp| qp.c_datum_type).unwrap_or(a_dt);
         let mut mmm = tract_linalg::ops()
             .mmm(a_dt, b_dt, c_dt, m, k, n)
@@ -164,7 +200,11 @@ impl ConvUnary {
         mmm.c_from_data_and_strides(rsc, csc);
 
         if let Some(q) = self.q_params.as_ref() {
-            q.inject_into_mmm(&mut *mmm)?;
+            if self.grouped_scale_factors().is_some() {
+                QParams { scale_factors: None, ..q.clone() }.inject_into_mmm(&mut *mmm)?;
+            } else {
+                q.inject_into_mmm(&mut *mmm)?;
+            }
         }
 
         trace!(
@@ -210,17 +250,9 @@ impl ConvUnary {
             )?[0];
         }
 
-        let mut dims = tvec!(self.group as usize);
-        let mut strides = tvec!((output_shape.c() / self.group * output_shape.c_stride()) as isize);
-        if self.group == 1 {
-            dims.clear();
-            strides.clear();
-        }
-        if output_shape.n().is_some() {
-            dims.insert(0, *output_shape.n().unwrap());
-            strides.insert(0, *output_shape.n_stride().unwrap() as isize);
-        }
-        let c_prefix_dim_and_stride = Some((ShapeFact::from(dims), ShapeFact::from(strides))).filter(|it| it.0.len() > 0);
+        let c_prefix_dim_and_stride = self
+            .c_prefix_dim_and_stride(&output_shape)
+            .map(|(dims, strides)| (ShapeFact::from(dims), ShapeFact::from(strides)));
         let fused_ops = dispatch_copy!(Self::bias_as_non_linear(mmm.internal_type())(self))?;
 
         let kernels = self.kernel_as_packed_as(&mmm.a_pack(), m)?;
@@ -244,10 +276,173 @@ impl ConvUnary {
         Ok(wire)
     }
 
-    pub fn to_depth_wise<T>(&self, input_full_shape: &[usize]) -> TractResult<Box<dyn TypedOp>>
-    where
-        T: Datum + Clone + ::ndarray::LinalgScalar + PartialEq + Sum,
-    {
+    /// Pruned f32 kernels are multiplied in block sparse format, provided
+    /// they are sparse enough.
+    unsafe fn wire_as_sparse_im2col_pair(
+        &self,
+        model: &mut TypedModel,
+        name: &str,
+        wire: OutletId,
+        b_dt: DatumType,
+    ) -> TractResult<Option<OutletId>> {
+        if self.kernel.datum_type() != f32::datum_type()
+            || b_dt != f32::datum_type()
+            || self.q_params.is_some()
+        {
+            return Ok(None);
+        }
+        let (input_shape, geo, output_shape) =
+            self.pool_spec.compute_geo(&*model.outlet_fact(wire)?.shape.as_concrete().unwrap())?;
+        let m = self.output_channels() / self.group;
+        let k = self.kernel.len() / self.output_channels();
+        let n = geo.output_shape.iter().cloned().product::<usize>();
+        let mut mm = (tract_linalg::ops().sparse_mmm_f32)(m, k, n);
+        let kernel = self.kernel_as_group_o_ihw()?;
+        let sparse_as = if let Some(sparse_as) =
+            matmul::lir_sparse::block_sparse_as(&kernel, false, mm.mr())?
+        {
+            sparse_as
+        } else {
+            return Ok(None);
+        };
+        trace!("Sparse gemm m={} k={} n={} {}", m, k, n, mm);
+        let (rsc, csc) = match output_shape.fmt {
+            DataFormat::NHWC | DataFormat::HWC => (1, self.output_channels() as isize),
+            DataFormat::NCHW | DataFormat::CHW => (n as isize, 1),
+        };
+        mm.c_from_data_and_strides(rsc, csc);
+        let c_dim = *input_shape.c_dim();
+        let wire = model.wire_node(
+            format!("{}.im2col", name),
+            Im2Col::new(
+                geo.clone(),
+                self.pool_spec.data_format.clone(),
+                m,
+                k,
+                n,
+                self.group,
+                c_dim / self.group,
+                mm.b_pack(),
+                Tensor::zero::<f32>(&[])?,
+            )?,
+            &[wire],
+        )?[0];
+        let bias = if let Some(bias) = &self.bias {
+            let bias = bias.cast_to::<f32>()?;
+            let bias = bias
+                .as_slice::<f32>()?
+                .chunks(m)
+                .map(|c| tensor1(c).into_arc_tensor())
+                .collect::<Vec<_>>();
+            Some(self.per_group(bias))
+        } else {
+            None
+        };
+        let wire = model.wire_node(
+            format!("{}.sparse", name),
+            matmul::lir_sparse::LirSparseMatMulUnary {
+                c_fact: TypedFact::dt_shape(f32::datum_type(), output_shape.shape.clone()),
+                c_prefix_dim_and_stride: self.c_prefix_dim_and_stride(&output_shape),
+                sparse_as: self.per_group(sparse_as.into_raw_vec()),
+                bias,
+                mm,
+                n,
+            },
+            &[wire],
+        )?[0];
+        Ok(Some(wire))
+    }
+
+    /// Weight-only quantized kernels (i8 values in OIHW, f32 scales per
+    /// output channel and group of k) are expanded panel by panel by the f32
+    /// multiplier.
+    pub(crate) unsafe fn wire_as_weight_only_im2col_pair(
+        &self,
+        model: &mut TypedModel,
+        name: &str,
+        wire: OutletId,
+        scales: &Tensor,
+        quant: WeightQuant,
+    ) -> TractResult<OutletId> {
+        if self.kernel_fmt != KernelFormat::OIHW || self.q_params.is_some() {
+            bail!("Weight-only quantized convolution expects an OIHW kernel and no q_params");
+        }
+        let (input_shape, geo, output_shape) =
+            self.pool_spec.compute_geo(&*model.outlet_fact(wire)?.shape.as_concrete().unwrap())?;
+        let m = self.output_channels() / self.group;
+        let k = self.kernel.len() / self.output_channels();
+        let n = geo.output_shape.iter().cloned().product::<usize>();
+        let mut mmm = tract_linalg::ops().mmm_f32(m, k, n);
+        let (rsc, csc) = match output_shape.fmt {
+            DataFormat::NHWC | DataFormat::HWC => (1, self.output_channels() as isize),
+            DataFormat::NCHW | DataFormat::CHW => (n as isize, 1),
+        };
+        mmm.c_from_data_and_strides(rsc, csc);
+        mmm.a_from_quantized_weights(quant);
+        trace!("Weight-only gemm m={} k={} n={} {}", m, k, n, quant);
+        let c_dim = *input_shape.c_dim();
+        let wire = model.wire_node(
+            format!("{}.im2col", name),
+            Im2Col::new(
+                geo.clone(),
+                self.pool_spec.data_format.clone(),
+                m,
+                k,
+                n,
+                self.group,
+                c_dim / self.group,
+                mmm.b_pack(),
+                Tensor::zero::<f32>(&[])?,
+            )?,
+            &[wire],
+        )?[0];
+        let packer = mmm.a_weights_pack(quant);
+        let kernel = self.kernel_as_group_o_ihw()?;
+        let kernel_hash = matmul::pack_cache::content_hash(&kernel);
+        let packed_as = (0..self.group)
+            .map(|g| {
+                let scales = scales.slice(0, g * m, (g + 1) * m)?;
+                matmul::pack_cache::packed(
+                    &kernel,
+                    kernel_hash,
+                    (packer.clone(), g, scales.clone()),
+                    || {
+                        let mut packed = Tensor::uninitialized_aligned::<u8>(
+                            &[packer.len(m)],
+                            packer.alignment(),
+                        )?;
+                        packer.pack(
+                            &mut packed.view_mut(),
+                            &kernel.view_at_prefix(&[g])?,
+                            &scales.view(),
+                            1,
+                            0,
+                        );
+                        Ok(packed)
+                    },
+                )
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        let c_prefix_dim_and_stride = self
+            .c_prefix_dim_and_stride(&output_shape)
+            .map(|(dims, strides)| (ShapeFact::from(dims), ShapeFact::from(strides)));
+        let wire = model.wire_node(
+            format!("{}.matmatmul", name),
+            matmul::lir_unary::LirMatMulUnary {
+                c_trans: true,
+                c_fact: TypedFact::dt_shape(f32::datum_type(), output_shape.shape.clone()),
+                c_prefix_dim_and_stride,
+                packed_as: self.per_group(packed_as),
+                fused_ops: self.bias_as_non_linear::<f32>()?,
+                mmm,
+                k,
+            },
+            &[wire],
+        )?[0];
+        Ok(wire)
+    }
+
+    pub fn to_depth_wise(&self, input_full_shape: &[usize]) -> TractResult<Box<dyn TypedOp>> {
         let (input_shape, patch, output_shape) = self.pool_spec.compute_geo(input_full_shape)?;
         let op = DepthWise::new(
             patch,
@@ -259,6 +454,86 @@ impl ConvUnary {
         Ok(Box::new(op))
     }
 
+    /// Winograd is used for stride 1, dilation 1, 3x3 f32 convolutions with
+    /// enough channels to amortize the tile transforms.
+    pub fn to_winograd(
+        &self,
+        input_fact: &TypedFact,
+        input_full_shape: &[usize],
+    ) -> TractResult<Option<Box<dyn TypedOp>>> {
+        if input_fact.datum_type != f32::datum_type()
+            || self.q_params.is_some()
+            || self.group != 1
+            || self.pool_spec.rank() != 2
+            || &*self.pool_spec.kernel_shape != &[3, 3]
+            || (0..2).any(|i| self.pool_spec.stride(i) != 1 || self.pool_spec.dilation(i) != 1)
+            || self.input_channels() < 8
+            || self.output_channels() < 8
+        {
+            return Ok(None);
+        }
+        let (input_shape, patch, output_shape) = self.pool_spec.compute_geo(input_full_shape)?;
+        let tile = if output_shape.hw_dims().iter().all(|&d| d >= 8) {
+            WinogradTile::F4x4
+        } else {
+            WinogradTile::F2x2
+        };
+        let kernel = self.kernel_as_group_o_ihw()?;
+        let op = Winograd::new(
+            tile,
+            input_shape,
+            output_shape,
+            [patch.pad_before[0], patch.pad_before[1]],
+            &kernel,
+            self.bias.clone(),
+        )?;
+        Ok(Some(Box::new(op)))
+    }
+
+    fn quantized_output_type(&self) -> Option<DatumType> {
+        self.q_params.as_ref().map(|qp| qp.c_datum_type).filter(|dt| dt.is_quantized())
+    }
+
+    /// The same convolution on the storage types, with the zero points and
+    /// scales of the quantized types moved to the QParams.
+    fn with_storage_types(&self, input_dt: DatumType) -> TractResult<ConvUnary> {
+        let kernel_dt = self.kernel.datum_type();
+        let q_params = self.q_params.as_ref().unwrap().with_storage_types(kernel_dt, input_dt)?;
+        let mut kernel = self.kernel.clone().into_tensor();
+        unsafe { kernel.set_datum_type(kernel_dt.unquantized()) };
+        Ok(ConvUnary { kernel: kernel.into_arc_tensor(), q_params: Some(q_params), ..self.clone() })
+    }
+
+    fn declutter_quantized_types(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let c_dt = if let Some(c_dt) = self.quantized_output_type() {
+            c_dt
+        } else {
+            return Ok(None);
+        };
+        let input_dt = model.outlet_fact(node.inputs[0])?.datum_type;
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        if input_dt.is_quantized() {
+            wire = patch.wire_node(
+                format!("{}.storage", node.name),
+                crate::ops::cast::cast(input_dt.unquantized()),
+                &[wire],
+            )?[0];
+        }
+        wire = patch.wire_node(&*node.name, self.with_storage_types(input_dt)?, &[wire])?[0];
+        wire = patch.wire_node(
+            format!("{}.quantized", node.name),
+            crate::ops::cast::cast(c_dt),
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+
     fn declutter_stride_slice_to_downsample(
         &self,
         model: &TypedModel,
@@ -335,6 +610,7 @@ impl ConvUnary {
                     c_datum_type: self.bias.as_ref().unwrap().datum_type(),
                     zero_point_c: None,
                     scale_factor: None,
+                    scale_factors: None,
                     ..self.q_params.clone().unwrap()
                 })
             } else {
@@ -356,6 +632,21 @@ impl ConvUnary {
                 use crate::ops::quant::*;
                 let qp = self.q_params.as_ref().unwrap();
                 let scale = qp.scale_factor.unwrap_or(1.0);
+                if let Some(factors) = &qp.scale_factors {
+                    let mut factors_shape = tvec!(1; input_shape.rank());
+                    factors_shape[input_shape.c_axis()] = co;
+                    let factors = factors.clone().into_tensor().into_shape(&factors_shape)?;
+                    wire = patch.wire_node(
+                        format!("{}.cast", node.name),
+                        crate::ops::cast::cast(f32::datum_type()),
+                        &[wire],
+                    )?[0];
+                    wire = patch.wire_node(
+                        format!("{}.scale", node.name),
+                        crate::ops::math::mul::unary(factors.into_arc_tensor()),
+                        &[wire],
+                    )?[0];
+                }
                 let op = match output_type {
                     DatumType::I8 => quantize_linear_i8(
                         scale,
@@ -416,6 +707,12 @@ impl EvalOp for ConvUnary {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        if let Some(c_dt) = self.quantized_output_type() {
+            let op = self.with_storage_types(inputs[0].datum_type())?;
+            let input = inputs[0].cast_to_dt(inputs[0].datum_type().unquantized())?;
+            let output = op.eval(tvec!(input.into_owned().into_arc_tensor()))?.remove(0);
+            return Ok(tvec!(output.cast_to_dt(c_dt)?.into_owned().into_arc_tensor()));
+        }
         let mut model = TypedModel::default();
         let dt = inputs[0].datum_type();
         let wire = model.add_source("source", TypedFact::dt_shape(dt, inputs[0].shape()))?;
@@ -454,6 +751,20 @@ impl TypedOp for ConvUnary {
                 self
                 );
         }
+        if let Some(factors) = self.q_params.as_ref().and_then(|qp| qp.scale_factors.as_ref()) {
+            if factors.len() != self.output_channels() {
+                bail!(
+                    "Inconsistent convolution: {} scale factors for {} output channels",
+                    factors.len(),
+                    self.output_channels()
+                );
+            }
+        }
+        if (inputs[0].datum_type.is_quantized() || self.kernel.datum_type().is_quantized())
+            && !self.q_params.as_ref().map(|qp| qp.c_datum_type.is_quantized()).unwrap_or(false)
+        {
+            bail!("Convolution of quantized types must have a quantized output type: {:?}", self);
+        }
         let mut fact = self.pool_spec.output_facts(inputs)?.remove(0);
 
         if let Some(bias) = &self.bias {
@@ -490,7 +801,11 @@ impl TypedOp for ConvUnary {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
-        for d in &[Self::declutter_stride_slice_to_downsample, Self::declutter_as_matmul] {
+        for d in &[
+            Self::declutter_quantized_types,
+            Self::declutter_stride_slice_to_downsample,
+            Self::declutter_as_matmul,
+        ] {
             if let Some(p) = d(&self, model, node)? {
                 return Ok(Some(p));
             }
@@ -632,6 +947,9 @@ impl TypedOp for ConvUnary {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
+        if self.quantized_output_type().is_some() {
+            return Ok(None);
+        }
         let full_input_shape = model.outlet_fact(node.inputs[0])?.shape.to_tvec();
         let input_fact = model.outlet_fact(node.inputs[0])?;
         let input_shape = self.pool_spec.data_format.shape(&full_input_shape)?;
@@ -707,6 +1025,10 @@ impl TypedOp for ConvUnary {
                     )?[0];
                     patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
                     return Ok(Some(patch));
+                } else if let Some(op) =
+                    self.to_winograd(input_fact, &shape).context("in to_winograd")?
+                {
+                    return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
                 } else if should_use_direct(
                     &self.pool_spec.data_format.shape(shape.into())?,
                     &self.pool_spec,
@@ -728,9 +1050,9 @@ impl TypedOp for ConvUnary {
                 } else if self.group != 1
                     && self.group == self.output_channels()
                     && self.group == self.input_channels()
+                    && (dt.is_float() || (dt == i8::datum_type() && self.q_params.is_none()))
                 {
-                    let op = dispatch_floatlike!(Self::to_depth_wise(dt)(self, &shape))
-                        .context("in to_depth_wise")?;
+                    let op = self.to_depth_wise(&shape).context("in to_depth_wise")?;
                     return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
                 } else {
                     let mut patch = TypedModelPatch::default();
@@ -892,4 +1214,63 @@ mod test {
         assert!(!use_direct(24, 3)); // tdnn3
         assert!(!use_direct(10, 1)); // tdnn4,5
     }
+
+    fn check_per_channel_scales(group: usize, kernel: Tensor, expected: Tensor) -> TractResult<()> {
+        let q_params =
+            QParams::new(i8::datum_type()).with_scale_factors(&rctensor1(&[0.5f32, 0.25]))?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::NCHW, tvec!(1), PaddingSpec::Valid, None, None, Some(2)),
+            KernelFormat::OIHW,
+            kernel.into_arc_tensor(),
+            group,
+            None,
+            Some(q_params),
+        );
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(i8::datum_type(), &[1, 2, 3]))?;
+        let y = model.wire_node("conv", conv, &[x])?;
+        model.set_output_outlets(&y)?;
+        let input = tensor3(&[[[1i8, 2, -3], [4, -5, 6]]]);
+        let found = SimplePlan::new(&model)?.run(tvec!(input.clone()))?;
+        assert_eq!(*found[0], expected);
+        let found = SimplePlan::new(model.into_optimized()?)?.run(tvec!(input))?;
+        assert_eq!(*found[0], expected);
+        Ok(())
+    }
+
+    #[test]
+    fn per_channel_scales() -> TractResult<()> {
+        check_per_channel_scales(
+            1,
+            tensor3(&[[[2i8], [4]], [[-4], [8]]]),
+            tensor3(&[[[9i8, -8, 9], [7, -12, 15]]]),
+        )
+    }
+
+    #[test]
+    fn per_channel_scales_depthwise() -> TractResult<()> {
+        check_per_channel_scales(
+            2,
+            tensor3(&[[[4i8]], [[-8]]]),
+            tensor3(&[[[2i8, 4, -6], [-8, 10, -12]]]),
+        )
+    }
+
+    #[test]
+    fn per_channel_scales_count_is_checked() -> TractResult<()> {
+        let q_params =
+            QParams::new(i8::datum_type()).with_scale_factors(&rctensor1(&[0.5f32, 0.25, 1.0]))?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::NCHW, tvec!(1), PaddingSpec::Valid, None, None, Some(2)),
+            KernelFormat::OIHW,
+            tensor3(&[[[2i8], [4]], [[-4], [8]]]).into_arc_tensor(),
+            1,
+            None,
+            Some(q_params),
+        );
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(i8::datum_type(), &[1, 2, 3]))?;
+        assert!(model.wire_node("conv", conv, &[x]).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,328 @@
+use crate::internal::*;
+use crate::ops::matmul::lir_unary::hash_mmm;
+use crate::ops::matmul::pack_cache;
+use crate::ops::nn::DataShape;
+use tract_linalg::mmm::MatMatMul;
+
+/// Output tile of a Winograd F(m x m, 3 x 3) convolution.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum WinogradTile {
+    F2x2,
+    F4x4,
+}
+
+#[rustfmt::skip]
+const F2X2_BT: [f32; 16] = [
+    1.0,  0.0, -1.0,  0.0,
+    0.0,  1.0,  1.0,  0.0,
+    0.0, -1.0,  1.0,  0.0,
+    0.0,  1.0,  0.0, -1.0,
+];
+
+#[rustfmt::skip]
+const F2X2_G: [f32; 12] = [
+    1.0,  0.0, 0.0,
+    0.5,  0.5, 0.5,
+    0.5, -0.5, 0.5,
+    0.0,  0.0, 1.0,
+];
+
+#[rustfmt::skip]
+const F2X2_AT: [f32; 8] = [
+    1.0, 1.0,  1.0,  0.0,
+    0.0, 1.0, -1.0, -1.0,
+];
+
+#[rustfmt::skip]
+const F4X4_BT: [f32; 36] = [
+    4.0,  0.0, -5.0,  0.0, 1.0, 0.0,
+    0.0, -4.0, -4.0,  1.0, 1.0, 0.0,
+    0.0,  4.0, -4.0, -1.0, 1.0, 0.0,
+    0.0, -2.0, -1.0,  2.0, 1.0, 0.0,
+    0.0,  2.0, -1.0, -2.0, 1.0, 0.0,
+    0.0,  4.0,  0.0, -5.0, 0.0, 1.0,
+];
+
+#[rustfmt::skip]
+const F4X4_G: [f32; 18] = [
+    1.0 / 4.0,   0.0,         0.0,
+    -1.0 / 6.0,  -1.0 / 6.0,  -1.0 / 6.0,
+    -1.0 / 6.0,  1.0 / 6.0,   -1.0 / 6.0,
+    1.0 / 24.0,  1.0 / 12.0,  1.0 / 6.0,
+    1.0 / 24.0,  -1.0 / 12.0, 1.0 / 6.0,
+    0.0,         0.0,         1.0,
+];
+
+#[rustfmt::skip]
+const F4X4_AT: [f32; 24] = [
+    1.0, 1.0,  1.0, 1.0,  1.0, 0.0,
+    0.0, 1.0, -1.0, 2.0, -2.0, 0.0,
+    0.0, 1.0,  1.0, 4.0,  4.0, 0.0,
+    0.0, 1.0, -1.0, 8.0, -8.0, 1.0,
+];
+
+impl WinogradTile {
+    /// Output tile side
+    pub fn m(&self) -> usize {
+        match self {
+            WinogradTile::F2x2 => 2,
+            WinogradTile::F4x4 => 4,
+        }
+    }
+
+    /// Input tile side
+    pub fn alpha(&self) -> usize {
+        self.m() + 2
+    }
+
+    fn bt(&self) -> &'static [f32] {
+        match self {
+            WinogradTile::F2x2 => &F2X2_BT,
+            WinogradTile::F4x4 => &F4X4_BT,
+        }
+    }
+
+    fn g(&self) -> &'static [f32] {
+        match self {
+            WinogradTile::F2x2 => &F2X2_G,
+            WinogradTile::F4x4 => &F4X4_G,
+        }
+    }
+
+    fn at(&self) -> &'static [f32] {
+        match self {
+            WinogradTile::F2x2 => &F2X2_AT,
+            WinogradTile::F4x4 => &F4X4_AT,
+        }
+    }
+}
+
+/// out = l.x.l^T with l a rows x cols matrix and x a cols x cols matrix.
+fn sandwich(l: &[f32], rows: usize, cols: usize, x: &[f32], tmp: &mut [f32], out: &mut [f32]) {
+    for r in 0..rows {
+        for c in 0..cols {
+            tmp[r * cols + c] = (0..cols).map(|k| l[r * cols + k] * x[k * cols + c]).sum();
+        }
+    }
+    for r in 0..rows {
+        for s in 0..rows {
+            out[r * rows + s] = (0..cols).map(|c| tmp[r * cols + c] * l[s * cols + c]).sum();
+        }
+    }
+}
+
+/// Stride 1, dilation 1, 3x3 f32 convolution using Winograd minimal filtering.
+///
+/// The kernel is transformed and packed when the op is built. At runtime,
+/// input tiles are transformed, each of the alpha x alpha transformed
+/// positions is a (co x ci) by (ci x tiles) product, then output tiles are
+/// transformed back.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct Winograd {
+    pub tile: WinogradTile,
+    pub input_shape: DataShape,
+    pub output_shape: DataShape,
+    pub pad_before: [usize; 2],
+    pub packed_kernels: TVec<Arc<Tensor>>,
+    pub bias: Option<Arc<Tensor>>,
+    #[educe(Hash(method = "hash_mmm"))]
+    pub mmm: Box<dyn MatMatMul>,
+}
+
+impl_dyn_hash!(Winograd);
+
+impl Winograd {
+    /// `kernel` is expected as (output channels, input channels * 3 * 3).
+    pub fn new(
+        tile: WinogradTile,
+        input_shape: DataShape,
+        output_shape: DataShape,
+        pad_before: [usize; 2],
+        kernel: &Tensor,
+        bias: Option<Arc<Tensor>>,
+    ) -> TractResult<Winograd> {
+        let ci = *input_shape.c();
+        let co = *output_shape.c();
+        let alpha = tile.alpha();
+        let tiles = Self::tiles(tile, &output_shape).iter().product::<usize>();
+        let mmm = tract_linalg::ops()
+            .mmm(DatumType::F32, DatumType::F32, DatumType::F32, co, ci, tiles)
+            .context("No f32 multiplier for winograd")?;
+        let kernel = kernel.as_slice::<f32>()?;
+        let mut transformed = Tensor::zero::<f32>(&[alpha * alpha, co, ci])?;
+        {
+            let transformed = transformed.as_slice_mut::<f32>()?;
+            let mut tmp = vec![0f32; alpha * 3];
+            let mut u = vec![0f32; alpha * alpha];
+            for o in 0..co {
+                for i in 0..ci {
+                    let g = &kernel[(o * ci + i) * 9..][..9];
+                    sandwich(tile.g(), alpha, 3, g, &mut tmp, &mut u);
+                    for (xi, u) in u.iter().enumerate() {
+                        transformed[(xi * co + o) * ci + i] = *u;
+                    }
+                }
+            }
+        }
+        let packer = mmm.a_pack();
+        let transformed = transformed.into_arc_tensor();
+        let transformed_hash = pack_cache::content_hash(&transformed);
+        let packed_kernels = (0..alpha * alpha)
+            .map(|xi| {
+                pack_cache::packed(
+                    &transformed,
+                    transformed_hash,
+                    (packer.clone(), xi),
+                    || unsafe {
+                        let mut packed = Tensor::uninitialized_aligned::<f32>(
+                            &[packer.len(co)],
+                            packer.alignment(),
+                        )?;
+                        packer.pack(packed.view_mut(), transformed.view_at_prefix(&[xi])?, 1, 0);
+                        Ok(packed)
+                    },
+                )
+            })
+            .collect::<TractResult<_>>()?;
+        Ok(Winograd { tile, input_shape, output_shape, pad_before, packed_kernels, bias, mmm })
+    }
+
+    fn tiles(tile: WinogradTile, output_shape: &DataShape) -> [usize; 2] {
+        let m = tile.m();
+        let hw = output_shape.hw_dims();
+        [(hw[0] + m - 1) / m, (hw[1] + m - 1) / m]
+    }
+
+    unsafe fn eval_t(&self, input: &Tensor) -> TractResult<Tensor> {
+        let (m, alpha) = (self.tile.m(), self.tile.alpha());
+        let a2 = alpha * alpha;
+        let [tiles_h, tiles_w] = Self::tiles(self.tile, &self.output_shape);
+        let tiles = tiles_h * tiles_w;
+        let (ci, co) = (*self.input_shape.c(), *self.output_shape.c());
+        let (ih, iw) = (self.input_shape.hw_dims()[0], self.input_shape.hw_dims()[1]);
+        let (oh, ow) = (self.output_shape.hw_dims()[0], self.output_shape.hw_dims()[1]);
+        let (i_hs, i_ws) = (self.input_shape.hw_strides()[0], self.input_shape.hw_strides()[1]);
+        let (o_hs, o_ws) = (self.output_shape.hw_strides()[0], self.output_shape.hw_strides()[1]);
+        let (i_cs, o_cs) = (*self.input_shape.c_stride(), *self.output_shape.c_stride());
+        let bias = self.bias.as_ref().map(|b| b.as_slice::<f32>()).transpose()?;
+
+        let input = input.as_slice::<f32>()?;
+        let mut output = Tensor::uninitialized::<f32>(&*self.output_shape.shape)?;
+        let output_slice = output.as_slice_mut::<f32>()?;
+        let b_pack = self.mmm.b_pack();
+        let mut packed_b =
+            Tensor::uninitialized_aligned::<f32>(&[b_pack.len(tiles)], b_pack.alignment())?;
+        let mut v = Tensor::uninitialized::<f32>(&[a2, ci, tiles])?;
+        let mut products = Tensor::uninitialized::<f32>(&[a2, co, tiles])?;
+        let mut d = vec![0f32; a2];
+        let mut tmp = vec![0f32; a2];
+        let mut tile = vec![0f32; a2];
+
+        for n in 0..*self.input_shape.n().unwrap_or(&1) {
+            let input = &input[n * self.input_shape.n_stride().unwrap_or(&0)..];
+            let output = &mut output_slice[n * self.output_shape.n_stride().unwrap_or(&0)..];
+            let v_slice = v.as_slice_mut::<f32>()?;
+            for c in 0..ci {
+                for ty in 0..tiles_h {
+                    for tx in 0..tiles_w {
+                        for y in 0..alpha {
+                            let iy = (ty * m + y) as isize - self.pad_before[0] as isize;
+                            for x in 0..alpha {
+                                let ix = (tx * m + x) as isize - self.pad_before[1] as isize;
+                                d[y * alpha + x] = if iy >= 0
+                                    && (iy as usize) < ih
+                                    && ix >= 0
+                                    && (ix as usize) < iw
+                                {
+                                    input[c * i_cs + iy as usize * i_hs + ix as usize * i_ws]
+                                } else {
+                                    0.0
+                                };
+                            }
+                        }
+                        sandwich(self.tile.bt(), alpha, alpha, &d, &mut tmp, &mut tile);
+                        let t = ty * tiles_w + tx;
+                        for xi in 0..a2 {
+                            v_slice[(xi * ci + c) * tiles + t] = tile[xi];
+                        }
+                    }
+                }
+            }
+            for xi in 0..a2 {
+                b_pack.pack(packed_b.view_mut(), v.view_at_prefix(&[xi])?, 0, 1);
+                self.mmm.run(
+                    &self.packed_kernels[xi].view(),
+                    &packed_b.view(),
+                    &mut products.view_at_prefix_mut(&[xi])?,
+                    &[],
+                )?;
+            }
+            let products_slice = products.as_slice::<f32>()?;
+            for o in 0..co {
+                let bias = bias.map(|b| b[o]).unwrap_or(0.0);
+                for ty in 0..tiles_h {
+                    for tx in 0..tiles_w {
+                        let t = ty * tiles_w + tx;
+                        for xi in 0..a2 {
+                            d[xi] = products_slice[(xi * co + o) * tiles + t];
+                        }
+                        sandwich(self.tile.at(), m, alpha, &d, &mut tmp, &mut tile);
+                        for y in 0..m.min(oh - ty * m) {
+                            for x in 0..m.min(ow - tx * m) {
+                                output[o * o_cs + (ty * m + y) * o_hs + (tx * m + x) * o_ws] =
+                                    tile[y * m + x] + bias;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Op for Winograd {
+    fn name(&self) -> Cow<str> {
+        "WinogradConv".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("{:?} {}", self.tile, self.mmm)])
+    }
+
+    op_core_lir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Winograd {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = unsafe { self.eval_t(&input)? };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Winograd {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &self.output_shape.shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let alpha = self.tile.alpha();
+        let tiles = Self::tiles(self.tile, &self.output_shape).iter().product::<usize>();
+        let products = alpha * alpha * self.input_shape.c() * self.output_shape.c() * tiles;
+        let transforms =
+            alpha * alpha * alpha * 2 * tiles * (self.input_shape.c() + self.output_shape.c());
+        Ok(tvec!((
+            Cost::FMA(inputs[0].datum_type),
+            (self.input_shape.n().unwrap_or(&1) * (products + transforms)).to_dim()
+        )))
+    }
+
+    as_op!();
+}
@@ -0,0 +1,244 @@
+use crate::internal::*;
+use crate::ops::cnn::{PaddingSpec, PoolSpec};
+use crate::ops::nn::DataFormat;
+use ndarray::*;
+
+/// Transposed convolution, also known as deconvolution.
+///
+/// The kernel has the layout of the convolution it transposes: `[input
+/// channels, output channels / group, spatial...]`. `adjustments` are extra
+/// cells appended at the end of each output spatial axis (ONNX
+/// `output_padding`). With `Same*` padding, the output spatial dims are the
+/// input ones multiplied by the strides.
+#[derive(Debug, Clone, new, Hash)]
+pub struct DeconvUnary {
+    pub pool_spec: PoolSpec,
+    pub kernel: Arc<Tensor>,
+    pub bias: Option<Arc<Tensor>>,
+    pub adjustments: TVec<usize>,
+    pub group: usize,
+}
+
+impl_dyn_hash!(DeconvUnary);
+
+impl DeconvUnary {
+    pub fn output_channels(&self) -> usize {
+        self.kernel.shape()[1] * self.group
+    }
+
+    /// Output length and padding before the first output cell, for each
+    /// spatial axis.
+    pub fn output_geometry<D: DimLike>(&self, input_hw: &[D]) -> TVec<(D, usize)> {
+        input_hw
+            .iter()
+            .enumerate()
+            .map(|(ix, input)| {
+                let stride = self.pool_spec.stride(ix);
+                let field = (self.pool_spec.kernel_shape[ix] - 1) * self.pool_spec.dilation(ix) + 1;
+                let adjustment = self.adjustments.get(ix).cloned().unwrap_or(0);
+                let full = (input.clone() - 1) * stride + field + adjustment;
+                match &self.pool_spec.padding {
+                    PaddingSpec::Valid => (full, 0),
+                    PaddingSpec::Explicit(before, after, _) => {
+                        (full - before[ix] - after[ix], before[ix])
+                    }
+                    PaddingSpec::SameUpper | PaddingSpec::SameLower => {
+                        let total = field.saturating_sub(stride);
+                        let before = if self.pool_spec.padding == PaddingSpec::SameUpper {
+                            total / 2
+                        } else {
+                            total - total / 2
+                        };
+                        (input.clone() * stride + adjustment, before)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn eval_t<T: Datum + LinalgScalar>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let fmt = self.pool_spec.data_format;
+        let input_shape = fmt.shape(input.shape())?;
+        let geometry = self.output_geometry(input_shape.hw_dims());
+        let output_hw: TVec<usize> = geometry.iter().map(|g| g.0).collect();
+        let output_shape =
+            fmt.from_n_c_hw(*input_shape.n().unwrap_or(&1), self.output_channels(), &*output_hw)?;
+
+        // work on [N, C, spatial...] views, whatever the data format
+        let mut input = input.to_array_view::<T>()?;
+        if !fmt.has_n() {
+            input = input.insert_axis(Axis(0));
+        }
+        let rank = input.ndim();
+        if fmt == DataFormat::NHWC || fmt == DataFormat::HWC {
+            let mut axes: Vec<usize> = vec![0, rank - 1];
+            axes.extend(1..rank - 1);
+            input = input.permuted_axes(axes);
+        }
+        let n = input.shape()[0];
+        let ci_per_group = input.shape()[1] / self.group;
+        let co_per_group = self.kernel.shape()[1];
+        let input_hw: TVec<usize> = input.shape()[2..].into();
+        let kernel_len: usize = self.pool_spec.kernel_shape.iter().product();
+        let kernel = self
+            .kernel
+            .to_array_view::<T>()?
+            .into_shape((self.kernel.shape()[0], co_per_group * kernel_len))?;
+        let input_positions: Vec<IxDyn> = indices(&*input_hw).into_iter().collect();
+        let kernel_positions: Vec<IxDyn> =
+            indices(&*self.pool_spec.kernel_shape).into_iter().collect();
+
+        let mut canonical_shape: TVec<usize> = tvec!(n, self.output_channels());
+        canonical_shape.extend(output_hw.iter().cloned());
+        let mut output = ArrayD::<T>::zeros(&*canonical_shape);
+        let mut coords: TVec<usize> = tvec!(0; rank);
+        for batch in 0..n {
+            coords[0] = batch;
+            for g in 0..self.group {
+                let x = Array2::from_shape_fn((ci_per_group, input_positions.len()), |(c, l)| {
+                    let mut ix: TVec<usize> = tvec!(batch, g * ci_per_group + c);
+                    ix.extend(input_positions[l].slice().iter().cloned());
+                    input[&*ix]
+                });
+                let w = kernel.slice(s![g * ci_per_group..(g + 1) * ci_per_group, ..]);
+                let cols = w.t().dot(&x);
+                for co in 0..co_per_group {
+                    coords[1] = g * co_per_group + co;
+                    for (k, kpos) in kernel_positions.iter().enumerate() {
+                        let row = cols.row(co * kernel_len + k);
+                        'position: for (l, ipos) in input_positions.iter().enumerate() {
+                            for axis in 0..input_hw.len() {
+                                let o = ipos[axis] * self.pool_spec.stride(axis)
+                                    + kpos[axis] * self.pool_spec.dilation(axis);
+                                if o < geometry[axis].1 || o - geometry[axis].1 >= output_hw[axis] {
+                                    continue 'position;
+                                }
+                                coords[axis + 2] = o - geometry[axis].1;
+                            }
+                            output[&*coords] = output[&*coords] + row[l];
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(bias) = &self.bias {
+            let bias = bias.as_slice::<T>()?;
+            for (mut channel, b) in output.axis_iter_mut(Axis(1)).zip(bias.iter()) {
+                channel.mapv_inplace(|x| x + *b);
+            }
+        }
+
+        // back to the model data format
+        if fmt == DataFormat::NHWC || fmt == DataFormat::HWC {
+            let mut axes: Vec<usize> = vec![0];
+            axes.extend(2..rank);
+            axes.push(1);
+            output = output.permuted_axes(axes);
+        }
+        if !fmt.has_n() {
+            output = output.index_axis_move(Axis(0), 0);
+        }
+        let output =
+            ArrayD::from_shape_vec(&*output_shape.shape, output.iter().cloned().collect())?;
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for DeconvUnary {
+    fn name(&self) -> Cow<str> {
+        "DeconvUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut info = self.pool_spec.info();
+        info.push(format!(
+            "Kernel shape: {:?}, group: {}, adjustments: {:?}",
+            self.kernel.shape(),
+            self.group,
+            self.adjustments
+        ));
+        Ok(info)
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for DeconvUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::F32 => self.eval_t::<f32>(&input)?,
+            DatumType::F64 => self.eval_t::<f64>(&input)?,
+            dt => bail!("DeconvUnary does not support {:?}", dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for DeconvUnary {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let input_shape = self.pool_spec.data_format.shape(inputs[0].shape.to_tvec())?;
+        if self.kernel.rank() != input_shape.hw_rank() + 2
+            || self.kernel.shape()[2..] != *self.pool_spec.kernel_shape
+        {
+            bail!(
+                "Kernel shape {:?} inconsistent with pool spec {:?}",
+                self.kernel.shape(),
+                self.pool_spec
+            )
+        }
+        if let Ok(c) = input_shape.c().to_usize() {
+            if c != self.kernel.shape()[0] || c % self.group != 0 {
+                bail!(
+                    "Input has {} channels, kernel expects {} (group: {})",
+                    c,
+                    self.kernel.shape()[0],
+                    self.group
+                )
+            }
+        }
+        if self.bias.as_ref().map(|b| b.len() != self.output_channels()).unwrap_or(false) {
+            bail!("Bias must have one value per output channel ({})", self.output_channels())
+        }
+        let output_hw: TVec<TDim> =
+            self.output_geometry(input_shape.hw_dims()).into_iter().map(|g| g.0).collect();
+        let output_shape = self.pool_spec.data_format.from_n_c_hw(
+            input_shape.n().cloned().unwrap_or(1.to_dim()),
+            self.output_channels().to_dim(),
+            output_hw,
+        )?;
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, output_shape.shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn deconv_1d(strides: usize, padding: PaddingSpec) -> DeconvUnary {
+        let pool_spec =
+            PoolSpec::new(DataFormat::NCHW, tvec!(2), padding, None, Some(tvec!(strides)), None);
+        DeconvUnary::new(pool_spec, rctensor3(&[[[1f32, 2.]]]), None, tvec!(), 1)
+    }
+
+    #[test]
+    fn deconv_stride_1() {
+        let op = deconv_1d(1, PaddingSpec::Valid);
+        let output = op.eval(tvec!(rctensor3(&[[[1f32, 2., 3.]]]))).unwrap();
+        assert_eq!(*output[0], tensor3(&[[[1f32, 4., 7., 6.]]]));
+    }
+
+    #[test]
+    fn deconv_stride_2_padded() {
+        let op = deconv_1d(2, PaddingSpec::Explicit(tvec!(1), tvec!(0), false));
+        let output = op.eval(tvec!(rctensor3(&[[[1f32, 2., 3.]]]))).unwrap();
+        assert_eq!(*output[0], tensor3(&[[[2f32, 2., 4., 3., 6.]]]));
+    }
+}
@@ -1,6 +1,5 @@
 use crate::internal::*;
 use ndarray::prelude::*;
-use num_traits::Float;
 
 use crate::ops::cnn::pools::PoolSpec;
 use crate::ops::cnn::Patch;
@@ -15,7 +14,11 @@ pub struct MaxPool {
 impl_dyn_hash!(MaxPool);
 
 impl MaxPool {
-    fn to_fixed<T: Datum + Float>(&self, input_shape: &[usize]) -> TractResult<Box<dyn TypedOp>> {
+    fn to_fixed(&self, dt: DatumType, input_shape: &[usize]) -> TractResult<Box<dyn TypedOp>> {
+        // max on storage values is max on real values for quantized types
+        if !dt.is_float() && !dt.is_quantized() {
+            bail!("MaxPool does not support {:?}", dt);
+        }
         let (input_shape, patch, output_shape) = self.pool_spec.compute_geo(input_shape)?;
         let op = MaxPoolFixed::new(patch, input_shape, output_shape, self.with_index_outputs);
         Ok(Box::new(op))
@@ -41,10 +44,7 @@ impl EvalOp for MaxPool {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let op = dispatch_floatlike!(MaxPool::to_fixed(inputs[0].datum_type())(
-            self,
-            inputs[0].shape()
-        ))?;
+        let op = self.to_fixed(inputs[0].datum_type(), inputs[0].shape())?;
         op.eval(inputs)
     }
 }
@@ -86,7 +86,7 @@ impl TypedOp for MaxPool {
         let inputs = model.node_input_facts(node.id)?;
         if let Some(shape) = inputs[0].shape.as_concrete() {
             let dt = inputs[0].datum_type;
-            let op = dispatch_floatlike!(MaxPool::to_fixed(dt)(self, &*shape))?;
+            let op = self.to_fixed(dt, &*shape)?;
             return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
         }
         Ok(None)
@@ -119,6 +119,7 @@ impl MaxPoolFixed {
         &self,
         input: &Tensor,
     ) -> TractResult<TVec<Arc<Tensor>>> {
+        let dt = input.datum_type();
         let input: ArrayViewD<T> = input.to_array_view()?;
         let input_ptr = input.as_ptr();
 
@@ -156,6 +157,8 @@ impl MaxPoolFixed {
                 }
             });
         }
+        let mut values = values.into_tensor();
+        unsafe { values.set_datum_type(dt) };
         if let Some(dt) = self.with_index_outputs {
             Ok(tvec!(
                 values.into_arc_tensor(),
@@ -174,7 +177,7 @@ impl EvalOp for MaxPoolFixed {
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let input = args_1!(inputs);
-        dispatch_numbers!(Self::eval_t(input.datum_type())(self, &*input))
+        dispatch_numbers!(Self::eval_t(input.datum_type().unquantized())(self, &*input))
     }
 }
 
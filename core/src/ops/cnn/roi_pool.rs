@@ -0,0 +1,161 @@
+use crate::internal::*;
+use ndarray::*;
+use num_traits::{Float, FromPrimitive};
+
+/// Region of interest pooling, as in Fast R-CNN.
+///
+/// Inputs are the feature map `[N, C, spatial...]`, the regions `[R, 2 *
+/// spatial rank]` (begin coordinates then inclusive end coordinates, in
+/// feature map cells) and the batch index of each region `[R]`. Each region
+/// is split in `output_size` bins which are max- or average-pooled, giving
+/// a `[R, C, output_size...]` output. Empty bins produce zeros.
+#[derive(Debug, Clone, new, Hash)]
+pub struct RoiPool {
+    pub output_size: TVec<usize>,
+    pub average: bool,
+}
+
+impl_dyn_hash!(RoiPool);
+
+impl RoiPool {
+    fn eval_t<T: Datum + Float + FromPrimitive>(
+        &self,
+        input: &Tensor,
+        rois: &Tensor,
+        batch_index: &Tensor,
+    ) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?;
+        let rois = rois.cast_to::<f32>()?;
+        let rois = rois.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let batch_index = batch_index.cast_to::<i64>()?;
+        let batch_index = batch_index.as_slice::<i64>()?;
+        let rank = self.output_size.len();
+        let input_hw = &input.shape()[2..];
+        let channels = input.shape()[1];
+        let mut shape = tvec!(rois.shape()[0], channels);
+        shape.extend(self.output_size.iter().cloned());
+        let mut output = ArrayD::<T>::zeros(&*shape);
+        for (r, roi) in rois.outer_iter().enumerate() {
+            let batch = batch_index[r] as usize;
+            if batch >= input.shape()[0] {
+                bail!("Region {} refers to batch {}, input has {}", r, batch, input.shape()[0])
+            }
+            // bins boundaries, per spatial axis
+            let bins: TVec<Vec<(usize, usize)>> = (0..rank)
+                .map(|axis| {
+                    let begin = roi[axis].round();
+                    let len = (roi[axis + rank].round() - begin + 1.0).max(1.0);
+                    let bin_len = len / self.output_size[axis] as f32;
+                    let clamp = |x: f32| x.max(0.0).min(input_hw[axis] as f32) as usize;
+                    (0..self.output_size[axis])
+                        .map(|bin| {
+                            let start = (bin as f32 * bin_len).floor() + begin;
+                            let end = ((bin + 1) as f32 * bin_len).ceil() + begin;
+                            (clamp(start), clamp(end))
+                        })
+                        .collect()
+                })
+                .collect();
+            for (c, mut region) in output.index_axis_mut(Axis(0), r).outer_iter_mut().enumerate() {
+                let channel = input.index_axis(Axis(0), batch);
+                let channel = channel.index_axis(Axis(0), c);
+                for (bin, value) in region.indexed_iter_mut() {
+                    let ranges: TVec<(usize, usize)> =
+                        (0..rank).map(|axis| bins[axis][bin[axis]]).collect();
+                    if ranges.iter().any(|range| range.0 >= range.1) {
+                        continue;
+                    }
+                    let slice: Vec<SliceOrIndex> = ranges
+                        .iter()
+                        .map(|range| (range.0 as isize..range.1 as isize).into())
+                        .collect();
+                    let cells = channel.slice(SliceInfo::<_, IxDyn>::new(slice)?.as_ref());
+                    *value = if self.average {
+                        cells.fold(T::zero(), |acc, &x| acc + x)
+                            / T::from_usize(cells.len()).unwrap()
+                    } else {
+                        cells.fold(T::neg_infinity(), |acc, &x| acc.max(x))
+                    };
+                }
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for RoiPool {
+    fn name(&self) -> Cow<str> {
+        "RoiPool".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("output_size: {:?}, average: {}", self.output_size, self.average)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for RoiPool {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, rois, batch_index) = args_3!(inputs);
+        let output = dispatch_floatlike!(Self::eval_t(input.datum_type())(
+            self,
+            &input,
+            &rois,
+            &batch_index
+        ))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for RoiPool {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != self.output_size.len() + 2 {
+            bail!("Output size {:?} does not match input rank", self.output_size)
+        }
+        if inputs[1].rank() != 2 || inputs[1].shape[1] != (2 * self.output_size.len()).to_dim() {
+            bail!("Regions must be a [R, {}] tensor", 2 * self.output_size.len())
+        }
+        let mut shape: TVec<TDim> = tvec!(inputs[1].shape[0].clone(), inputs[0].shape[1].clone());
+        shape.extend(self.output_size.iter().map(|d| d.to_dim()));
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn max_and_avg() {
+        let input = rctensor4(&[[[[1f32, 2., 3.], [4., 5., 6.], [7., 8., 9.]]]]);
+        let rois = rctensor2(&[[0f32, 0., 1., 2.]]);
+        let batch_index = rctensor1(&[0i64]);
+        let max = RoiPool::new(tvec!(1, 2), false)
+            .eval(tvec!(input.clone(), rois.clone(), batch_index.clone()))
+            .unwrap();
+        assert_eq!(*max[0], tensor4(&[[[[5f32, 6.]]]]));
+        let avg = RoiPool::new(tvec!(1, 1), true).eval(tvec!(input, rois, batch_index)).unwrap();
+        assert_eq!(*avg[0], tensor4(&[[[[3.5f32]]]]));
+    }
+
+    #[test]
+    fn batch_index() {
+        let input = rctensor4(&[[[[1f32, 2.], [3., 4.]]], [[[5f32, 6.], [7., 8.]]]]);
+        let rois = rctensor2(&[[0f32, 0., 1., 1.], [1., 0., 1., 1.]]);
+        let max = RoiPool::new(tvec!(1, 1), false)
+            .eval(tvec!(input.clone(), rois.clone(), rctensor1(&[1i64, 0])))
+            .unwrap();
+        assert_eq!(*max[0], tensor4(&[[[[8f32]]], [[[4.]]]]));
+        assert!(RoiPool::new(tvec!(1, 1), false)
+            .eval(tvec!(input, rois, rctensor1(&[2i64, 0])))
+            .is_err());
+    }
+}
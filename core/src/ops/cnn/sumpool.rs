@@ -160,9 +160,16 @@ impl EvalOp for SumPoolFixed {
     }
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        if self.datum_type.is_quantized() {
+            // pool real values, requantize with the input parameters
+            let input = input.cast_to::<f32>()?;
+            let mut values = unsafe { Tensor::uninitialized::<f32>(&*self.output_shape.shape)? };
+            self.eval_t::<f32>(&input, values.as_ptr_mut()?)?;
+            return Ok(tvec!(values.cast_to_dt(self.datum_type)?.into_owned().into_arc_tensor()));
+        }
         let mut values =
             unsafe { Tensor::uninitialized_dt(self.datum_type, &*self.output_shape.shape)? };
-        let input = args_1!(inputs);
         dispatch_floatlike!(Self::eval_t(input.datum_type())(self, &*input, values.as_ptr_mut()?))?;
         Ok(tvec!(values.into_arc_tensor()))
     }
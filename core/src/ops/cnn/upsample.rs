@@ -0,0 +1,163 @@
+use crate::internal::*;
+use ndarray::*;
+use num_traits::{Float, FromPrimitive};
+
+/// Interpolation used by `Upsample`, and how output cells map back to input
+/// coordinates for the linear ones.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum UpsampleMethod {
+    Nearest,
+    /// `x_in = (x_out + 0.5) / factor - 0.5`
+    LinearSymmetric,
+    /// `x_in = x_out / factor`
+    LinearAsymmetric,
+    /// `x_in = x_out * (in - 1) / (out - 1)`, first and last cells aligned
+    LinearAligned,
+}
+
+/// Upsampling by integer factors, one per axis (1 for unchanged axes).
+///
+/// Linear interpolation is separable. Samples falling outside the input
+/// replicate the border cell when `replicate_border` is set, and are zero
+/// otherwise.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Upsample {
+    pub factors: TVec<usize>,
+    pub method: UpsampleMethod,
+    pub replicate_border: bool,
+}
+
+impl_dyn_hash!(Upsample);
+
+impl Upsample {
+    fn upsample_axis<T: Datum + Float + FromPrimitive>(
+        &self,
+        input: ArrayViewD<T>,
+        axis: usize,
+    ) -> ArrayD<T> {
+        let factor = self.factors[axis];
+        let len = input.shape()[axis];
+        let mut shape = input.shape().to_vec();
+        shape[axis] *= factor;
+        let out_len = shape[axis];
+        let sample = |coords: &mut IxDyn, x: isize| -> T {
+            let x = if self.replicate_border {
+                x.max(0).min(len as isize - 1)
+            } else if x < 0 || x >= len as isize {
+                return T::zero();
+            } else {
+                x
+            };
+            coords[axis] = x as usize;
+            input[&*coords]
+        };
+        ArrayD::from_shape_fn(shape, |mut coords| {
+            let o = coords[axis];
+            let position = match self.method {
+                UpsampleMethod::Nearest => {
+                    coords[axis] = o / factor;
+                    return input[&coords];
+                }
+                UpsampleMethod::LinearSymmetric => (o as f64 + 0.5) / factor as f64 - 0.5,
+                UpsampleMethod::LinearAsymmetric => o as f64 / factor as f64,
+                UpsampleMethod::LinearAligned if out_len > 1 => {
+                    o as f64 * (len - 1) as f64 / (out_len - 1) as f64
+                }
+                UpsampleMethod::LinearAligned => 0.0,
+            };
+            let left = position.floor();
+            let weight = T::from_f64(position - left).unwrap();
+            let left = left as isize;
+            let a = sample(&mut coords, left);
+            let b = sample(&mut coords, left + 1);
+            a + (b - a) * weight
+        })
+    }
+
+    fn eval_t<T: Datum + Float + FromPrimitive>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        for axis in 0..self.factors.len() {
+            if self.factors[axis] != 1 {
+                output = self.upsample_axis(output.view(), axis);
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+impl Op for Upsample {
+    fn name(&self) -> Cow<str> {
+        "Upsample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "factors: {:?}, method: {:?}, replicate_border: {}",
+            self.factors, self.method, self.replicate_border
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Upsample {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = dispatch_floatlike!(Self::eval_t(input.datum_type())(self, &input))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Upsample {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.factors.len() != inputs[0].rank() || self.factors.iter().any(|&f| f == 0) {
+            bail!("Expected one strictly positive factor per axis, got {:?}", self.factors)
+        }
+        let shape: TVec<TDim> = inputs[0]
+            .shape
+            .to_tvec()
+            .into_iter()
+            .zip(self.factors.iter())
+            .map(|(d, &f)| d * f)
+            .collect();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+
+    fn invariants(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Invariants> {
+        let rank = model.outlet_fact(node.inputs[0])?.rank();
+        let axes = (0..rank)
+            .filter(|&axis| self.factors[axis] == 1)
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nearest() {
+        let op = Upsample::new(tvec!(1, 2), UpsampleMethod::Nearest, true);
+        let output = op.eval(tvec!(rctensor2(&[[1f32, 2.]]))).unwrap();
+        assert_eq!(*output[0], tensor2(&[[1f32, 1., 2., 2.]]));
+    }
+
+    #[test]
+    fn linear_symmetric() {
+        let op = Upsample::new(tvec!(2), UpsampleMethod::LinearSymmetric, true);
+        let output = op.eval(tvec!(rctensor1(&[0f32, 4.]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[0f32, 1., 3., 4.]));
+        let op = Upsample::new(tvec!(2), UpsampleMethod::LinearSymmetric, false);
+        let output = op.eval(tvec!(rctensor1(&[0f32, 4.]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[0f32, 1., 3., 3.]));
+    }
+}
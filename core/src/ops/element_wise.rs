@@ -84,14 +84,56 @@ impl Op for ElementWiseOp {
 
 impl_dyn_hash!(ElementWiseOp);
 
+impl ElementWiseOp {
+    /// On a quantized input, replaces the op by a lookup table over the 256
+    /// possible values, so it runs without going through floats.
+    fn declutter_quantized(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let dt = model.outlet_fact(node.inputs[0])?.datum_type;
+        if !dt.is_quantized() || self.0.output_type(dt).is_some() {
+            return Ok(None);
+        }
+        let table = match crate::ops::quant::build_lut(dt, |input| {
+            Ok(self.eval(tvec!(input.into_arc_tensor()))?.remove(0).into_tensor())
+        }) {
+            Ok(table) => table,
+            Err(_) => return Ok(None),
+        };
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        wire = patch.wire_node(
+            format!("{}.storage", node.name),
+            crate::ops::cast::cast(dt.unquantized()),
+            &[wire],
+        )?[0];
+        wire = patch.wire_node(&*node.name, crate::ops::quant::lookup_table(table), &[wire])?[0];
+        wire = patch.wire_node(
+            format!("{}.quantized", node.name),
+            crate::ops::cast::cast(dt),
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+}
+
 impl EvalOp for ElementWiseOp {
     fn is_stateless(&self) -> bool {
         true
     }
 
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        if let Some(_dt) = self.0.output_type(inputs[0].datum_type()) {
+        let dt = inputs[0].datum_type();
+        if let Some(_dt) = self.0.output_type(dt) {
             Ok(tvec!(self.0.eval_out_of_place(&inputs[0])?.into_arc_tensor()))
+        } else if dt.is_quantized() {
+            // compute on real values, requantize with the input parameters
+            let mut t = inputs[0].cast_to::<f32>()?.into_owned();
+            self.0.eval_in_place(&mut t)?;
+            Ok(tvec!(t.cast_to_dt(dt)?.into_owned().into_arc_tensor()))
         } else {
             let mut t = args_1!(inputs).into_tensor();
             self.0.eval_in_place(&mut t)?;
@@ -105,6 +147,9 @@ impl TypedOp for ElementWiseOp {
         let mut fact = inputs[0].clone();
         if let Some(dt) = self.0.output_type(fact.datum_type) {
             fact.datum_type = dt;
+        } else if fact.datum_type.is_quantized() && self.0.output_type(f32::datum_type()).is_some()
+        {
+            bail!("{} does not support {:?}", self.0.name(), fact.datum_type);
         }
         Ok(tvec!(fact))
     }
@@ -124,6 +169,9 @@ impl TypedOp for ElementWiseOp {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(patch) = self.declutter_quantized(model, node)? {
+            return Ok(Some(patch));
+        }
         self.0.declutter(model, node)
     }
 
@@ -234,6 +282,7 @@ macro_rules! element_wise_oop {
     ($func:ident, $Op:ident $({$( $(#[$meta: meta])? $var: ident : $var_typ: path),*})?,
         $( [$($typ:ident),*] => $typ_dst:ident $f:expr ),*
         $(; cost: $cost:expr )?
+        $(; declutter: $declutter:expr )?
         $(; info: $info:expr )?
         $(; prefix: $prefix:expr )?
         $(; quantize: $quantize:expr )?
@@ -273,6 +322,15 @@ macro_rules! element_wise_oop {
                 $cost(dt)
             }
             )?
+            $(
+                fn declutter(
+                    &self,
+                    model: &TypedModel,
+                    node: &TypedNode,
+                ) -> TractResult<Option<TypedModelPatch>> {
+                    $declutter(model, node)
+                }
+            )?
             $(
             fn info(&self) -> TractResult<Vec<String>> {
                 $info(self)
@@ -1,7 +1,9 @@
+pub mod lir_sparse;
 pub mod lir_unary;
 pub mod mir;
 pub mod mir_unary;
 pub mod pack;
+pub mod pack_cache;
 
 use crate::internal::*;
 use tract_itertools::Itertools;
@@ -52,6 +54,16 @@ pub fn compute_shape<D: DimLike>(
     Ok((m, ka, n, c_shape))
 }
 
+/// Datum type of an unquantized product. Mixed float operands (f16 weights
+/// against f32 activations for instance) give the wider type.
+pub fn output_type(a: DatumType, b: DatumType) -> DatumType {
+    if a.is_float() && b.is_float() {
+        a.common_super_type(b).unwrap_or(a)
+    } else {
+        a
+    }
+}
+
 pub(super) fn eval(
     a: &Tensor,
     b: &Tensor,
@@ -63,7 +75,9 @@ pub(super) fn eval(
     unsafe {
         let rank = a.rank();
         let (m, k, n, c_shape) = compute_shape(a.shape(), b.shape(), a_trans, b_trans, c_trans)?;
-        let c_dt = q_params.map(|q| q.c_datum_type).unwrap_or(a.datum_type());
+        let c_dt = q_params
+            .map(|q| q.c_datum_type)
+            .unwrap_or_else(|| output_type(a.datum_type(), b.datum_type()));
         let mut mm = tract_linalg::ops()
             .mmm(a.datum_type(), b.datum_type(), c_dt, m, k, n)
             .with_context(|| {
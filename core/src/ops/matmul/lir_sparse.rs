@@ -0,0 +1,161 @@
+use crate::internal::*;
+use ndarray::*;
+
+use tract_linalg::sparse::{BlockSparseMatrix, SparseMatMul};
+
+/// Above this ratio of stored blocks, dense multipliers are faster.
+pub const SPARSE_DENSITY_THRESHOLD: f32 = 0.3;
+
+/// Multiplication of a constant, pruned, f32 A by the (packed) input B.
+///
+/// A is kept in block sparse format: only the columns of each row block
+/// holding a non-zero value are stored and multiplied.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct LirSparseMatMulUnary {
+    pub c_fact: TypedFact,
+    pub c_prefix_dim_and_stride: Option<(TVec<usize>, TVec<isize>)>,
+    pub sparse_as: ArrayD<Arc<BlockSparseMatrix<f32>>>,
+    pub bias: Option<ArrayD<Arc<Tensor>>>,
+    #[educe(Hash(method = "hash_sparse_mm"))]
+    pub mm: Box<dyn SparseMatMul<f32>>,
+    pub n: usize,
+}
+
+fn hash_sparse_mm<H: std::hash::Hasher>(mm: &Box<dyn SparseMatMul<f32>>, state: &mut H) {
+    mm.to_string().hash(state)
+}
+
+impl DynHash for LirSparseMatMulUnary {
+    fn dyn_hash(&self, hasher: &mut dyn std::hash::Hasher) {
+        dyn_hash(&self, hasher)
+    }
+}
+
+/// Compress the matrices of `a`, shaped `[..prefix, m, k]` (or
+/// `[..prefix, k, m]` if `a_trans`), by row blocks of `mr`.
+///
+/// Returns None if any of them is too dense for the sparse multiplier to pay
+/// off.
+pub(crate) fn block_sparse_as(
+    a: &Tensor,
+    a_trans: bool,
+    mr: usize,
+) -> TractResult<Option<ArrayD<Arc<BlockSparseMatrix<f32>>>>> {
+    let prefix = &a.shape()[..a.rank() - 2];
+    let mut sparse_as = vec![];
+    for coords in indices(prefix) {
+        let view = a.view_at_prefix(coords.slice())?;
+        let sparse =
+            BlockSparseMatrix::<f32>::from_view(&view, a_trans as usize, !a_trans as usize, mr)?;
+        if sparse.density() > SPARSE_DENSITY_THRESHOLD {
+            return Ok(None);
+        }
+        sparse_as.push(Arc::new(sparse));
+    }
+    Ok(Some(ArrayD::from_shape_vec(IxDyn(prefix), sparse_as)?))
+}
+
+impl Op for LirSparseMatMulUnary {
+    fn name(&self) -> Cow<str> {
+        "LirSparseMatMulUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let density = self.sparse_as.iter().map(|a| a.density()).fold(0.0f32, f32::max);
+        Ok(vec![
+            format!("c_prefix: {:?} density: {:.3}", self.c_prefix_dim_and_stride, density),
+            format!("Mult: {}", self.mm),
+        ])
+    }
+
+    op_core_lir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LirSparseMatMulUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = &inputs[0];
+        unsafe {
+            let mut c = Tensor::uninitialized::<f32>(self.c_fact.shape.as_concrete().unwrap())?;
+            if let Some((prefix_dim, prefix_strides)) = &self.c_prefix_dim_and_stride {
+                for prefix in indices(&**prefix_dim).into_iter() {
+                    let mut c = TensorView::from_bytes(&c, 0, prefix_dim, prefix_strides);
+                    let mut a = self.sparse_as.view();
+                    let mut bias = self.bias.as_ref().map(|b| b.view());
+                    let mut b_prefix = tvec!();
+                    for (ix, &dim) in prefix.slice().iter().enumerate() {
+                        a.index_axis_inplace(Axis(0), dim.min(a.shape()[0] - 1));
+                        if let Some(bias) = bias.as_mut() {
+                            bias.index_axis_inplace(Axis(0), dim.min(bias.shape()[0] - 1));
+                        }
+                        b_prefix.push(dim.min(input.shape()[ix] - 1));
+                        c.offset_axis_unchecked(ix, dim as isize);
+                    }
+                    let bias = bias
+                        .map(|b| b.into_iter().next().unwrap().as_slice::<f32>())
+                        .transpose()?;
+                    self.mm.run(
+                        a.iter().next().unwrap(),
+                        &TensorView::at_prefix_unchecked(&input, &*b_prefix),
+                        &mut c,
+                        bias,
+                    )?;
+                }
+            } else {
+                let bias = self
+                    .bias
+                    .as_ref()
+                    .map(|b| b.iter().next().unwrap().as_slice::<f32>())
+                    .transpose()?;
+                self.mm.run(
+                    self.sparse_as.iter().next().unwrap(),
+                    &input.view(),
+                    &mut c.view_mut(),
+                    bias,
+                )?;
+            }
+            Ok(tvec!(c.into_arc_tensor()))
+        }
+    }
+}
+
+impl TypedOp for LirSparseMatMulUnary {
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(self.c_fact.clone()))
+    }
+
+    fn cost(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mul: usize =
+            self.c_prefix_dim_and_stride.as_ref().map(|c| c.0.iter().product()).unwrap_or(1);
+        let stored = self.sparse_as.iter().map(|a| a.stored_columns()).max().unwrap_or(0);
+        Ok(tvec!(
+            (Cost::FMA(f32::datum_type()), (mul * stored * self.mm.mr() * self.n).to_dim()),
+            (
+                Cost::Params(f32::datum_type()),
+                self.sparse_as.iter().map(|a| a.stored_columns() * a.mr()).sum::<usize>().to_dim()
+            )
+        ))
+    }
+
+    fn fuse(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(succ) = model.single_succ(node.id)? {
+            if let Some(op) = succ.op_as::<crate::ops::AxisOp>() {
+                if op.only_shape() {
+                    return Ok(Some(TypedModelPatch::fuse_with_next(
+                        model,
+                        &node,
+                        Self { c_fact: succ.outputs[0].fact.clone(), ..self.clone() },
+                    )?));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
@@ -1,7 +1,7 @@
 use crate::internal::*;
 use ndarray::*;
 
-use tract_linalg::mmm::{FusedSpec, MatMatMul};
+use tract_linalg::mmm::{Activation, FusedSpec, MatMatMul};
 
 #[derive(Debug, Clone, Educe)]
 #[educe(Hash)]
@@ -30,7 +30,7 @@ impl LirMatMulUnary {
     }
 }
 
-fn hash_mmm<H: std::hash::Hasher>(mmm: &Box<dyn MatMatMul>, state: &mut H) {
+pub(crate) fn hash_mmm<H: std::hash::Hasher>(mmm: &Box<dyn MatMatMul>, state: &mut H) {
     // FIXME: this is buggy, but it should not matter too much
     mmm.type_id().hash(state)
 }
@@ -89,6 +89,14 @@ impl OpState for State {
             eval(op, &inputs[0], &op.c_fact.shape.eval(&session.resolved_symbols)?, None)
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
+
+    fn load(&mut self, _tensors: TVec<Tensor>) -> TractResult<()> {
+        Ok(())
+    }
 }
 
 impl EvalOp for LirMatMulUnary {
@@ -235,25 +243,50 @@ impl TypedOp for LirMatMulUnary {
                     )?));
                 }
             }
-            let fused_micro_op = if let Some(op) = succ.op_as::<ops::binary::UnaryOp>() {
+            let activated = self
+                .fused_ops
+                .as_ref()
+                .map(|f| {
+                    f.iter().flatten().any(|s| match s {
+                        FusedSpec::Activation(_) => true,
+                        _ => false,
+                    })
+                })
+                .unwrap_or(false);
+            let fused_micro_op = if let Some(op) = succ.op_as::<ops::element_wise::ElementWiseOp>()
+            {
+                if self.c_fact.datum_type == f32::datum_type() {
+                    activation(op).map(|a| tvec!(FusedSpec::Activation(a)))
+                } else {
+                    None
+                }
+            } else if activated {
+                None
+            } else if let Some(op) = succ.op_as::<ops::binary::UnaryOp>() {
                 let m = self.m();
+                // float products may accumulate in a wider type than their output
+                let a = if self.c_fact.datum_type.is_float() {
+                    op.a.cast_to_dt(self.mmm.internal_type())?.into_owned()
+                } else {
+                    op.a.clone().into_tensor()
+                };
                 if op.a.len() == m
                     && op.a.shape()[op.a.rank() - 1 - ((!self.c_trans) as usize)] == m
                 {
                     if op.mini_op.is::<ops::math::Mul>() {
-                        Some(tvec!(FusedSpec::PerRowMul(op.a.clone().into_tensor())))
+                        Some(tvec!(FusedSpec::PerRowMul(a)))
                     } else if op.mini_op.is::<ops::math::Add>() {
-                        Some(tvec!(FusedSpec::PerRowAdd(op.a.clone().into_tensor())))
+                        Some(tvec!(FusedSpec::PerRowAdd(a)))
                     } else {
                         None
                     }
                 } else if op.a.len() == 1 {
                     if op.mini_op.is::<ops::math::Max>() {
-                        Some(tvec!(FusedSpec::Max(op.a.clone().into_tensor())))
+                        Some(tvec!(FusedSpec::Max(a)))
                     } else if op.mini_op.is::<ops::math::Min>() {
-                        Some(tvec!(FusedSpec::Min(op.a.clone().into_tensor())))
+                        Some(tvec!(FusedSpec::Min(a)))
                     } else if op.mini_op.is::<ops::math::Mul>() {
-                        Some(tvec!(FusedSpec::ScalarMul(op.a.clone().into_tensor())))
+                        Some(tvec!(FusedSpec::ScalarMul(a)))
                     } else {
                         None
                     }
@@ -286,3 +319,22 @@ impl TypedOp for LirMatMulUnary {
 
     as_op!();
 }
+
+/// Activations that the matrix multipliers can apply on their output.
+fn activation(op: &crate::ops::element_wise::ElementWiseOp) -> Option<Activation> {
+    use crate::ops::{math, nn};
+    let mini_op = &op.0;
+    if mini_op.is::<nn::Sigmoid>() {
+        Some(Activation::Sigmoid)
+    } else if mini_op.is::<math::Tanh>() {
+        Some(Activation::Tanh)
+    } else if let Some(op) = mini_op.downcast_ref::<nn::LeakyRelu>() {
+        Some(Activation::LeakyRelu(op.alpha))
+    } else if mini_op.is::<nn::HardSwish>() {
+        Some(Activation::HardSwish)
+    } else if mini_op.is::<nn::Gelu>() {
+        Some(Activation::Gelu)
+    } else {
+        None
+    }
+}
@@ -104,7 +104,11 @@ impl TypedOp for MatMul {
                 inputs[1]
             );
         }
-        let dt = self.q_params.as_ref().map(|qp| qp.c_datum_type).unwrap_or(inputs[0].datum_type);
+        let dt = self
+            .q_params
+            .as_ref()
+            .map(|qp| qp.c_datum_type)
+            .unwrap_or_else(|| output_type(inputs[0].datum_type, inputs[1].datum_type));
         let (_m, _k, _n, c_shape) = compute_shape(
             &inputs[0].shape,
             &inputs[1].shape,
@@ -158,7 +162,7 @@ impl TypedOp for MatMul {
     as_op!();
 }
 
-pub(super) fn cost<A: DimLike + Clone, B: DimLike + Clone>(
+pub(crate) fn cost<A: DimLike + Clone, B: DimLike + Clone>(
     a: &[A],
     b: &[B],
     dt: DatumType,
@@ -230,4 +234,81 @@ mod test {
         model.declutter()?.optimize()?.into_runnable()?.run(tvec!(input))?;
         Ok(())
     }
+
+    #[test]
+    fn fused_activation() -> TractResult<()> {
+        fused_activation_t(7, 3, 9)
+    }
+
+    #[test]
+    fn fused_activation_mat_vec() -> TractResult<()> {
+        fused_activation_t(17, 3, 1)
+    }
+
+    fn fused_activation_t(m: usize, k: usize, n: usize) -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let mut wire =
+            tvec!(model.add_source("s", TypedFact::dt_shape(f32::datum_type(), &[k, n]))?);
+        let a = tensor1(&*(0..m * k).map(|i| i as f32 / 10.0 - 1.0).collect::<Vec<_>>())
+            .into_shape(&[m, k])?;
+        wire = model.wire_node(
+            "m",
+            MatMulUnary {
+                a: a.into_arc_tensor(),
+                a_trans: false,
+                b_trans: false,
+                c_trans: false,
+                q_params: None,
+            },
+            &wire,
+        )?;
+        wire = model.wire_node("gelu", crate::ops::nn::gelu(), &wire)?;
+        model.set_output_outlets(&wire)?;
+        let input = tensor1(&*(0..k * n).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>())
+            .into_shape(&[k, n])?;
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let optimized = model.declutter()?.optimize()?;
+        assert!(optimized
+            .nodes()
+            .iter()
+            .all(|node| !node.op_is::<crate::ops::element_wise::ElementWiseOp>()));
+        let found = optimized.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], true)
+    }
+
+    #[test]
+    fn sparse_a() -> TractResult<()> {
+        let (m, k, n) = (9, 12, 5);
+        let mut model = TypedModel::default();
+        let mut wire =
+            tvec!(model.add_source("s", TypedFact::dt_shape(f32::datum_type(), &[2, k, n]))?);
+        let a = tensor1(
+            &*(0..2 * m * k)
+                .map(|i| if i % 23 == 0 { (i % 7) as f32 + 1.0 } else { 0.0 })
+                .collect::<Vec<_>>(),
+        )
+        .into_shape(&[2, m, k])?;
+        wire = model.wire_node(
+            "m",
+            MatMulUnary {
+                a: a.into_arc_tensor(),
+                a_trans: false,
+                b_trans: false,
+                c_trans: true,
+                q_params: None,
+            },
+            &wire,
+        )?;
+        model.set_output_outlets(&wire)?;
+        let input = tensor1(&*(0..2 * k * n).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>())
+            .into_shape(&[2, k, n])?;
+        let expected = model.clone().into_runnable()?.run(tvec!(input.clone()))?;
+        let optimized = model.declutter()?.optimize()?;
+        assert!(optimized
+            .nodes()
+            .iter()
+            .any(|node| node.op_is::<super::lir_sparse::LirSparseMatMulUnary>()));
+        let found = optimized.into_runnable()?.run(tvec!(input))?;
+        found[0].close_enough(&expected[0], true)
+    }
 }
@@ -1,3 +1,4 @@
+use super::lir_sparse::{block_sparse_as, LirSparseMatMulUnary};
 use super::lir_unary::LirMatMulUnary;
 use super::mir::q_params_from_inputs;
 use super::*;
@@ -46,6 +47,12 @@ impl EvalOp for MatMulUnary {
     }
 
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        if let Some(c_dt) = self.quantized_output_type() {
+            let op = self.with_storage_types(inputs[0].datum_type())?;
+            let b = inputs[0].cast_to_dt(inputs[0].datum_type().unquantized())?;
+            let c = op.eval(tvec!(b.into_owned().into_arc_tensor()))?.remove(0);
+            return Ok(tvec!(c.cast_to_dt(c_dt)?.into_owned().into_arc_tensor()));
+        }
         let q_params = q_params_from_inputs(&self.q_params, &inputs)?;
         let q_params = q_params.as_ref().or(self.q_params.as_ref());
         let t = eval(&self.a, &inputs[0], self.a_trans, self.b_trans, self.c_trans, q_params)?;
@@ -62,14 +69,31 @@ impl TypedOp for MatMulUnary {
                 self.a
             );
         }
-        let (_m, _k, _n, c_shape) = compute_shape(
+        let (m, _k, _n, c_shape) = compute_shape(
             &self.a.shape().iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
             &inputs[0].shape,
             self.a_trans,
             self.b_trans,
             self.c_trans,
         )?;
-        let c_dt = self.q_params.as_ref().map(|qp| qp.c_datum_type).unwrap_or(inputs[0].datum_type);
+        if let Some(factors) = self.q_params.as_ref().and_then(|qp| qp.scale_factors.as_ref()) {
+            if factors.len().to_dim() != m {
+                bail!("Inconsistent matmul: {} scale factors for {} rows", factors.len(), m);
+            }
+        }
+        let c_dt = self
+            .q_params
+            .as_ref()
+            .map(|qp| qp.c_datum_type)
+            .unwrap_or_else(|| output_type(self.a.datum_type(), inputs[0].datum_type));
+        if (self.a.datum_type().is_quantized() || inputs[0].datum_type.is_quantized())
+            && !c_dt.is_quantized()
+        {
+            bail!(
+                "Product of quantized matrices must have a quantized output type, got {:?}",
+                c_dt
+            );
+        }
         Ok(tvec!(TypedFact::dt_shape(c_dt, c_shape)))
     }
 
@@ -155,6 +179,9 @@ impl TypedOp for MatMulUnary {
         use crate::ops::array::concat::ConcatSlice;
         use crate::ops::array::TypedConcat;
         let input_fact = model.outlet_fact(node.inputs[0])?;
+        if node.inputs.len() == 1 && self.quantized_output_type().is_some() {
+            return Ok(Some(self.declutter_quantized_types(model, node)?));
+        }
         if let Some(concat) = model.nodes()[node.inputs[0].node].op().downcast_ref::<TypedConcat>()
         {
             let mut patch = TypedModelPatch::new("split over k-concatenated input");
@@ -249,8 +276,16 @@ impl TypedOp for MatMulUnary {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
+        if self.quantized_output_type().is_some() {
+            return Ok(None);
+        }
         let b = args_1!(model.node_input_facts(node.id)?);
         if let Some(b_shape) = b.shape.as_concrete() {
+            if let Some(patch) =
+                self.new_sparse_mat_mul_unary(model, node, &b_shape, b.datum_type)?
+            {
+                return Ok(Some(patch));
+            }
             return Ok(Some(self.new_mat_mul_unary_finite(model, node, &b_shape, b.datum_type)?));
         }
         Ok(None)
@@ -270,7 +305,11 @@ impl MatMulUnary {
         let mut patch = TypedModelPatch::default();
         let mut wire = patch.tap_model(model, node.inputs[0])?;
 
-        let c_dt = self.q_params.as_ref().map(|q| q.c_datum_type).unwrap_or(self.a.datum_type());
+        let c_dt = self
+            .q_params
+            .as_ref()
+            .map(|q| q.c_datum_type)
+            .unwrap_or_else(|| output_type(self.a.datum_type(), b_dt));
         let (m, k, n, c_shape) =
             compute_shape(&self.a.shape(), b_shape, self.a_trans, self.b_trans, self.c_trans)?;
 
@@ -285,22 +324,28 @@ impl MatMulUnary {
                 )
             })?;
 
-        let packed_as =
-            Array::from_shape_fn(&self.a.shape()[0..self.a.rank() - 2], |a_prefix| unsafe {
+        let packer = mm.a_pack();
+        let a_hash = pack_cache::content_hash(&self.a);
+        let a_prefix_shape = &self.a.shape()[0..self.a.rank() - 2];
+        let mut packed_as = vec![];
+        for a_prefix in tract_ndarray::indices(a_prefix_shape) {
+            let geometry = (packer.clone(), a_prefix.slice().to_vec(), self.a_trans);
+            packed_as.push(pack_cache::packed(&self.a, a_hash, geometry, || unsafe {
                 let mut pa = Tensor::uninitialized_aligned_dt(
                     self.a.datum_type(),
-                    &[mm.a_pack().len(m)],
-                    mm.a_pack().alignment(),
-                )
-                .unwrap();
-                mm.a_pack().pack(
+                    &[packer.len(m)],
+                    packer.alignment(),
+                )?;
+                packer.pack(
                     &mut pa.view_mut(),
-                    &self.a.view_at_prefix(a_prefix.slice()).unwrap(),
+                    &self.a.view_at_prefix(a_prefix.slice())?,
                     !self.a_trans as usize,
                     self.a_trans as usize,
                 );
-                pa.into_arc_tensor()
-            });
+                Ok(pa)
+            })?);
+        }
+        let packed_as = Array::from_shape_vec(a_prefix_shape, packed_as)?;
         unsafe {
             if n == 1 {
                 mm.b_vec_from_data_and_stride(if self.b_trans {
@@ -323,7 +368,6 @@ impl MatMulUnary {
                 q.inject_into_mmm(&mut *mm)?;
             }
         }
-        let rank = c_shape.len();
         if n > 1 {
             let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
             packed_b_shape.push(mm.b_pack().len(n));
@@ -337,25 +381,8 @@ impl MatMulUnary {
                 &[wire],
             )?[0];
         }
-        let c_prefix_dim_and_stride = if c_shape[..rank - 2].iter().any(|d| *d > 1) {
-            let c_prefix_strides: TVec<TDim> = c_shape
-                .iter()
-                .rev()
-                .scan(1isize, |s, &d| {
-                    let now: isize = *s;
-                    *s *= d as isize;
-                    Some(now)
-                })
-                .collect::<TVec<_>>()
-                .into_iter()
-                .skip(2)
-                .rev()
-                .map(|d| d.to_dim())
-                .collect::<TVec<_>>();
-            Some((c_shape[..rank - 2].into(), c_prefix_strides.into()))
-        } else {
-            None
-        };
+        let c_prefix_dim_and_stride =
+            c_prefix_dim_and_stride(&c_shape).map(|(dim, strides)| (dim.into(), strides.into()));
         wire = patch.wire_node(
             format!("{}.matmatmul", &*node.name),
             LirMatMulUnary {
@@ -372,4 +399,128 @@ impl MatMulUnary {
         patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
         Ok(patch)
     }
+
+    fn quantized_output_type(&self) -> Option<DatumType> {
+        self.q_params.as_ref().map(|qp| qp.c_datum_type).filter(|dt| dt.is_quantized())
+    }
+
+    /// The same product on the storage types, with the zero points and scales
+    /// of the quantized types moved to the QParams.
+    fn with_storage_types(&self, b_dt: DatumType) -> TractResult<MatMulUnary> {
+        let a_dt = self.a.datum_type();
+        let q_params = self.q_params.as_ref().unwrap().with_storage_types(a_dt, b_dt)?;
+        let mut a = self.a.clone().into_tensor();
+        unsafe { a.set_datum_type(a_dt.unquantized()) };
+        Ok(MatMulUnary { a: a.into_arc_tensor(), q_params: Some(q_params), ..self.clone() })
+    }
+
+    /// Replace quantized datum types by their storage types, moving their
+    /// zero points and scales to the QParams.
+    fn declutter_quantized_types(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<TypedModelPatch> {
+        let b_dt = model.outlet_fact(node.inputs[0])?.datum_type;
+        let c_dt = self.q_params.as_ref().unwrap().c_datum_type;
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        if b_dt.is_quantized() {
+            wire = patch.wire_node(
+                format!("{}.storage", node.name),
+                crate::ops::cast::cast(b_dt.unquantized()),
+                &[wire],
+            )?[0];
+        }
+        wire = patch.wire_node(&*node.name, self.with_storage_types(b_dt)?, &[wire])?[0];
+        wire = patch.wire_node(
+            format!("{}.quantized", node.name),
+            crate::ops::cast::cast(c_dt),
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, node.id.into(), wire)?;
+        Ok(patch)
+    }
+
+    /// Pruned f32 weights are multiplied in block sparse format, provided
+    /// they are sparse enough.
+    fn new_sparse_mat_mul_unary(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        b_shape: &[usize],
+        b_dt: DatumType,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.a.datum_type() != f32::datum_type()
+            || b_dt != f32::datum_type()
+            || self.q_params.is_some()
+        {
+            return Ok(None);
+        }
+        let (m, k, n, c_shape) =
+            compute_shape(&self.a.shape(), b_shape, self.a_trans, self.b_trans, self.c_trans)?;
+        let mut mm = (tract_linalg::ops().sparse_mmm_f32)(m, k, n);
+        let sparse_as = if let Some(sparse_as) = block_sparse_as(&self.a, self.a_trans, mm.mr())? {
+            sparse_as
+        } else {
+            return Ok(None);
+        };
+        unsafe {
+            mm.c_from_data_and_strides(
+                if self.c_trans { 1 } else { *c_shape.last().unwrap() as isize },
+                if !self.c_trans { 1 } else { *c_shape.last().unwrap() as isize },
+            );
+        }
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
+        packed_b_shape.push(mm.b_pack().len(n));
+        wire = patch.wire_node(
+            format!("{}.pack", &*node.name),
+            super::MatMatMulPack {
+                packer: mm.b_pack(),
+                trans: self.b_trans,
+                output_shape: packed_b_shape,
+            },
+            &[wire],
+        )?[0];
+        wire = patch.wire_node(
+            format!("{}.sparse", &*node.name),
+            LirSparseMatMulUnary {
+                c_fact: TypedFact::dt_shape(f32::datum_type(), &c_shape),
+                c_prefix_dim_and_stride: c_prefix_dim_and_stride(&c_shape),
+                sparse_as,
+                bias: None,
+                mm,
+                n,
+            },
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
+        Ok(Some(patch))
+    }
+}
+
+/// Dimensions and strides of the matrix prefix of a contiguous C, if any of
+/// the prefix axes is not trivial.
+pub(crate) fn c_prefix_dim_and_stride(c_shape: &[usize]) -> Option<(TVec<usize>, TVec<isize>)> {
+    let rank = c_shape.len();
+    if c_shape[..rank - 2].iter().any(|d| *d > 1) {
+        let c_prefix_strides: TVec<isize> = c_shape
+            .iter()
+            .rev()
+            .scan(1isize, |s, &d| {
+                let now: isize = *s;
+                *s *= d as isize;
+                Some(now)
+            })
+            .collect::<TVec<_>>()
+            .into_iter()
+            .skip(2)
+            .rev()
+            .collect();
+        Some((c_shape[..rank - 2].into(), c_prefix_strides))
+    } else {
+        None
+    }
 }
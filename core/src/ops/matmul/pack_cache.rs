@@ -0,0 +1,186 @@
+//! Process-wide cache for packed constant operands.
+//!
+//! Packed weights are addressed by the content of the tensor they are packed
+//! from and by the packing geometry, so that plans built from the same
+//! network (several concretized, pulsed or non-pulsed versions of it, or
+//! models sharing a backbone) share their buffers. The cache only holds weak
+//! references, to the buffers and to the tensors they are packed from: a
+//! buffer goes away with the last plan using it.
+
+use crate::internal::*;
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, Weak};
+
+struct Entry {
+    source: Weak<Tensor>,
+    geometry: Box<dyn Any + Send>,
+    packed: Weak<Tensor>,
+}
+
+impl Entry {
+    /// The packed buffer, if it is still alive and was packed from the same
+    /// source under the same geometry (the key is only a hash of them). Once
+    /// the source is gone, its content hash vouches for it.
+    fn get<G: PartialEq + 'static>(
+        &self,
+        source: &Arc<Tensor>,
+        geometry: &G,
+    ) -> Option<Arc<Tensor>> {
+        let packed = self.packed.upgrade()?;
+        if self.geometry.downcast_ref::<G>() != Some(geometry) {
+            return None;
+        }
+        if let Some(known) = self.source.upgrade() {
+            if !Arc::ptr_eq(&known, source) && *known != **source {
+                return None;
+            }
+        }
+        Some(packed)
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref PACKED: Mutex<HashMap<(u64, u64), Entry>> = Mutex::new(HashMap::new());
+}
+
+fn hash<H: Hash + ?Sized>(it: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    it.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Content hash of a tensor, to be passed to `packed`.
+pub fn content_hash(tensor: &Tensor) -> u64 {
+    hash(tensor)
+}
+
+/// Get the packed buffer for `source` (hashing to `content`) under
+/// `geometry`, calling `pack` if no live plan holds it yet.
+///
+/// `geometry` must capture everything but the source tensor that the packed
+/// buffer depends on: packer, prefix, strides, scales... The cache entry
+/// keeps `geometry` and a weak reference to `source` to check hits against
+/// them.
+pub fn packed<G: Hash + PartialEq + Send + 'static>(
+    source: &Arc<Tensor>,
+    content: u64,
+    geometry: G,
+    pack: impl FnOnce() -> TractResult<Tensor>,
+) -> TractResult<Arc<Tensor>> {
+    let key = (content, hash(&geometry));
+    if let Some(packed) = PACKED.lock().unwrap().get(&key).and_then(|e| e.get(source, &geometry)) {
+        return Ok(packed);
+    }
+    let packed = pack()?.into_arc_tensor();
+    let mut cache = PACKED.lock().unwrap();
+    if let Some(entry) = cache.get(&key) {
+        // an other thread may have packed the same operand in the meantime
+        if let Some(other) = entry.get(source, &geometry) {
+            return Ok(other);
+        }
+        // or the key collides with a live, different operand: do not share
+        if entry.packed.strong_count() > 0 {
+            return Ok(packed);
+        }
+    }
+    cache.retain(|_, e| e.packed.strong_count() > 0);
+    let entry = Entry {
+        source: Arc::downgrade(source),
+        geometry: Box::new(geometry),
+        packed: Arc::downgrade(&packed),
+    };
+    cache.insert(key, entry);
+    Ok(packed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_content_and_geometry_share_buffer() -> TractResult<()> {
+        let weights = rctensor2(&[[1f32, 2.0], [3.0, 4.0]]);
+        let content = content_hash(&weights);
+        let a = packed(&weights, content, ("test-share", 1usize), || Ok((*weights).clone()))?;
+        let same = rctensor2(&[[1f32, 2.0], [3.0, 4.0]]);
+        let b = packed(&same, content, ("test-share", 1usize), || bail!("should be cached"))?;
+        assert!(Arc::ptr_eq(&a, &b));
+        let c = packed(&weights, content, ("test-share", 2usize), || Ok((*weights).clone()))?;
+        assert!(!Arc::ptr_eq(&a, &c));
+        let other = rctensor2(&[[1f32, 2.0], [3.0, 5.0]]);
+        let d =
+            packed(&other, content_hash(&other), ("test-share", 1usize), || Ok((*other).clone()))?;
+        assert!(!Arc::ptr_eq(&a, &d));
+        Ok(())
+    }
+
+    #[test]
+    fn hash_collisions_are_not_shared() -> TractResult<()> {
+        let weights = rctensor1(&[1f32, 2.0]);
+        let other = rctensor1(&[3f32, 4.0]);
+        let a = packed(&weights, 42, "test-collision", || Ok((*weights).clone()))?;
+        let b = packed(&other, 42, "test-collision", || Ok((*other).clone()))?;
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(*b, *other);
+        Ok(())
+    }
+
+    #[test]
+    fn plans_share_packed_weights() -> TractResult<()> {
+        use crate::ops::matmul::lir_unary::LirMatMulUnary;
+        use crate::ops::matmul::MatMulUnary;
+        let a = tract_ndarray::Array2::from_shape_fn((16, 8), |(i, j)| (i * 8 + j) as f32 + 1.0)
+            .into_tensor();
+        let packed_a = || -> TractResult<Arc<Tensor>> {
+            let mut model = TypedModel::default();
+            let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[8, 4]))?;
+            let op = MatMulUnary::new(a.clone().into_arc_tensor(), false, false, false, None);
+            let c = model.wire_node("mm", op, &[b])?[0];
+            model.set_output_outlets(&[c])?;
+            let model = model.into_optimized()?;
+            let op = model.nodes().iter().find_map(|n| n.op_as::<LirMatMulUnary>()).unwrap();
+            Ok(op.packed_as.iter().next().unwrap().clone())
+        };
+        assert!(Arc::ptr_eq(&packed_a()?, &packed_a()?));
+        Ok(())
+    }
+
+    #[test]
+    fn dropped_plan_frees_entry_and_source() -> TractResult<()> {
+        use crate::ops::matmul::MatMulUnary;
+        let a = tract_ndarray::Array2::from_shape_fn((16, 8), |(i, j)| (i * 8 + j) as f32 - 7.0)
+            .into_arc_tensor();
+        let source = Arc::downgrade(&a);
+        let mut model = TypedModel::default();
+        let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[8, 4]))?;
+        let c = model.wire_node("mm", MatMulUnary::new(a, false, false, false, None), &[b])?;
+        model.set_output_outlets(&c)?;
+        let plan = SimplePlan::new(model.into_optimized()?)?;
+        assert!(PACKED.lock().unwrap().values().any(|e| e.source.ptr_eq(&source)));
+        drop(plan);
+        assert!(source.upgrade().is_none());
+        // dead entries are purged on the next insertion
+        let other = rctensor1(&[1f32]);
+        packed(&other, content_hash(&other), "test-purge", || Ok((*other).clone()))?;
+        assert!(!PACKED.lock().unwrap().values().any(|e| e.source.ptr_eq(&source)));
+        Ok(())
+    }
+
+    #[test]
+    fn released_with_last_user() -> TractResult<()> {
+        let weights = rctensor1(&[1f32, 2.0, 3.0]);
+        let content = content_hash(&weights);
+        let a = packed(&weights, content, "test-release", || Ok((*weights).clone()))?;
+        drop(a);
+        let mut repacked = false;
+        packed(&weights, content, "test-release", || {
+            repacked = true;
+            Ok((*weights).clone())
+        })?;
+        assert!(repacked);
+        Ok(())
+    }
+}
@@ -28,6 +28,7 @@ pub mod matmul;
 pub mod nn;
 pub mod quant;
 pub mod scan;
+pub mod signal;
 pub mod source;
 pub mod unimpl;
 
@@ -72,6 +73,17 @@ pub trait OpState: fmt::Debug + Send + dyn_clone::DynClone {
         op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Dump the state as tensors, for `SimpleState::snapshot`.
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        bail!("Snapshot not supported for {:?}", self)
+    }
+
+    /// Restore a state dumped by `save`.
+    #[allow(unused_variables)]
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        bail!("Snapshot not supported for {:?}", self)
+    }
 }
 dyn_clone::clone_trait_object!(OpState);
 
@@ -0,0 +1,246 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// Key frames a query frame can attend to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttentionWindow {
+    /// Frame `t` sees frames `t - left` to `t + right`.
+    Band { left: usize, right: usize },
+    /// Frames are grouped in chunks of `size`: a frame sees its own chunk and
+    /// the `left` previous ones.
+    Chunked { size: usize, left: usize },
+}
+
+impl AttentionWindow {
+    /// Frames visible from frame `t`, ignoring the stream bounds.
+    pub fn range(&self, t: isize) -> std::ops::Range<isize> {
+        match *self {
+            AttentionWindow::Band { left, right } => t - left as isize..t + right as isize + 1,
+            AttentionWindow::Chunked { size, left } => {
+                let chunk = t.div_euclid(size as isize);
+                (chunk - left as isize) * size as isize..(chunk + 1) * size as isize
+            }
+        }
+    }
+
+    /// How far in the past a frame can look.
+    pub fn lookback(&self) -> usize {
+        match *self {
+            AttentionWindow::Band { left, .. } => left,
+            AttentionWindow::Chunked { size, left } => (left + 1) * size - 1,
+        }
+    }
+
+    /// How far in the future a frame can look.
+    pub fn lookahead(&self) -> usize {
+        match *self {
+            AttentionWindow::Band { right, .. } => right,
+            AttentionWindow::Chunked { size, .. } => size - 1,
+        }
+    }
+}
+
+/// Scaled dot-product attention restricted to a local window over time.
+///
+/// Inputs are queries, keys and values with shapes `[..., T, dk]`,
+/// `[..., T, dk]` and `[..., T, dv]`: time is the second-to-last axis.
+/// Frame `t` output is the softmax of `scale * q[t].k[s]` over the frames `s`
+/// of its window, applied to `v[s]`. This is what a masked attention block
+/// computes, but its bounded context allows it to be pulsified.
+#[derive(Debug, Clone, Educe)]
+#[educe(Hash)]
+pub struct LocalAttention {
+    pub window: AttentionWindow,
+    #[educe(Hash(method = "hash_f32"))]
+    pub scale: f32,
+}
+
+impl_dyn_hash!(LocalAttention);
+
+impl LocalAttention {
+    /// Attend the query rows of `q`, starting at frame `q_pos`, over the rows
+    /// of `k` and `v`, starting at frame `kv_pos`. Frames before zero or
+    /// beyond `len` are ignored, rows with nothing to attend to are zeroed.
+    ///
+    /// Offline and pulsed evaluations share this, so they give the same
+    /// results bit for bit.
+    pub fn attend(
+        &self,
+        q: ArrayView2<f32>,
+        q_pos: isize,
+        k: ArrayView2<f32>,
+        v: ArrayView2<f32>,
+        kv_pos: isize,
+        len: isize,
+        mut output: ArrayViewMut2<f32>,
+    ) {
+        let mut weights = vec![0f32; k.nrows()];
+        for (row, (q, mut output)) in q.outer_iter().zip(output.outer_iter_mut()).enumerate() {
+            output.fill(0.0);
+            let range = self.window.range(q_pos + row as isize);
+            let lo = range.start.max(0).max(kv_pos);
+            let hi = range.end.min(len).min(kv_pos + k.nrows() as isize);
+            if lo >= hi {
+                continue;
+            }
+            let rows = (lo - kv_pos) as usize..(hi - kv_pos) as usize;
+            let weights = &mut weights[..rows.len()];
+            for (w, k) in weights.iter_mut().zip(k.slice(s![rows.clone(), ..]).outer_iter()) {
+                *w = q.dot(&k) * self.scale;
+            }
+            let max = weights.iter().fold(std::f32::NEG_INFINITY, |acc, &w| acc.max(w));
+            weights.iter_mut().for_each(|w| *w = (*w - max).exp());
+            let sum = weights.iter().sum::<f32>();
+            for (w, v) in weights.iter().zip(v.slice(s![rows, ..]).outer_iter()) {
+                output.scaled_add(*w / sum, &v);
+            }
+        }
+    }
+
+    /// `attend` over tensors of any rank, time being the second-to-last axis.
+    pub fn eval_at(
+        &self,
+        q: &Tensor,
+        q_pos: isize,
+        k: &Tensor,
+        v: &Tensor,
+        kv_pos: isize,
+        len: isize,
+    ) -> TractResult<Tensor> {
+        let rank = q.rank();
+        let (tq, dk) = (q.shape()[rank - 2], q.shape()[rank - 1]);
+        let (tkv, dv) = (v.shape()[rank - 2], v.shape()[rank - 1]);
+        let prefix = q.shape()[..rank - 2].iter().product::<usize>();
+        let mut output_shape: TVec<usize> = q.shape().into();
+        output_shape[rank - 1] = dv;
+        let mut output = Tensor::zero::<f32>(&output_shape)?;
+        {
+            let q = q.to_array_view::<f32>()?.into_shape((prefix, tq, dk))?;
+            let k = k.to_array_view::<f32>()?.into_shape((prefix, tkv, dk))?;
+            let v = v.to_array_view::<f32>()?.into_shape((prefix, tkv, dv))?;
+            let mut output = output.to_array_view_mut::<f32>()?.into_shape((prefix, tq, dv))?;
+            for p in 0..prefix {
+                self.attend(
+                    q.index_axis(Axis(0), p),
+                    q_pos,
+                    k.index_axis(Axis(0), p),
+                    v.index_axis(Axis(0), p),
+                    kv_pos,
+                    len,
+                    output.index_axis_mut(Axis(0), p),
+                );
+            }
+        }
+        Ok(output)
+    }
+}
+
+impl Op for LocalAttention {
+    fn name(&self) -> Cow<str> {
+        "LocalAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("window: {:?}, scale: {}", self.window, self.scale)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for LocalAttention {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (q, k, v) = args_3!(inputs);
+        let len = q.shape()[q.rank() - 2] as isize;
+        Ok(tvec!(self.eval_at(&q, 0, &k, &v, 0, len)?.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for LocalAttention {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs.iter().any(|i| i.datum_type != f32::datum_type()) {
+            bail!("LocalAttention only supports f32")
+        }
+        let rank = inputs[0].rank();
+        if rank < 2 || inputs[1].rank() != rank || inputs[2].rank() != rank {
+            bail!("LocalAttention expects q, k and v of the same rank, at least 2")
+        }
+        if let AttentionWindow::Chunked { size: 0, .. } = self.window {
+            bail!("LocalAttention chunks must not be empty")
+        }
+        if inputs[0].shape[rank - 1] != inputs[1].shape[rank - 1]
+            || inputs[1].shape[..rank - 1] != inputs[2].shape[..rank - 1]
+        {
+            bail!("Inconsistent shapes for LocalAttention: {:?}", inputs)
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[rank - 1] = inputs[2].shape[rank - 1].clone();
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &*shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // masked attention the long way: full score matrix, -inf out of the window
+    fn masked_reference(
+        op: &LocalAttention,
+        q: &Array2<f32>,
+        k: &Array2<f32>,
+        v: &Array2<f32>,
+    ) -> Array2<f32> {
+        let mut scores = q.dot(&k.t()) * op.scale;
+        for (i, mut row) in scores.outer_iter_mut().enumerate() {
+            let range = op.window.range(i as isize);
+            for (j, s) in row.iter_mut().enumerate() {
+                if !range.contains(&(j as isize)) {
+                    *s = std::f32::NEG_INFINITY;
+                }
+            }
+            let max = row.iter().fold(std::f32::NEG_INFINITY, |acc, &s| acc.max(s));
+            row.mapv_inplace(|s| (s - max).exp());
+            let sum = row.sum();
+            row.mapv_inplace(|s| s / sum);
+        }
+        scores.dot(v)
+    }
+
+    fn check(window: AttentionWindow) -> TractResult<()> {
+        let op = LocalAttention { window, scale: 0.5 };
+        let q = Array2::from_shape_fn((7, 3), |(i, j)| ((i * 3 + j) % 5) as f32 * 0.3 - 0.5);
+        let k = Array2::from_shape_fn((7, 3), |(i, j)| ((i * 2 + j) % 7) as f32 * 0.2 - 0.6);
+        let v = Array2::from_shape_fn((7, 2), |(i, j)| (i + j) as f32);
+        let expected = masked_reference(&op, &q, &k, &v);
+        let found =
+            op.eval(tvec!(q.into_arc_tensor(), k.into_arc_tensor(), v.into_arc_tensor()))?;
+        found[0].close_enough(&expected.into_tensor(), true)
+    }
+
+    #[test]
+    fn band() -> TractResult<()> {
+        check(AttentionWindow::Band { left: 2, right: 1 })
+    }
+
+    #[test]
+    fn chunked() -> TractResult<()> {
+        check(AttentionWindow::Chunked { size: 3, left: 1 })
+    }
+
+    #[test]
+    fn windows() {
+        let band = AttentionWindow::Band { left: 2, right: 1 };
+        assert_eq!(band.range(5), 3..7);
+        assert_eq!((band.lookback(), band.lookahead()), (2, 1));
+        let chunked = AttentionWindow::Chunked { size: 3, left: 1 };
+        assert_eq!(chunked.range(5), 0..6);
+        assert_eq!(chunked.range(-1), -6..0);
+        assert_eq!((chunked.lookback(), chunked.lookahead()), (5, 2));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// Local response normalization across channels (axis 1).
+///
+/// `alpha` is applied to the mean of the squares over the `size` neighbouring
+/// channels, as in ONNX and NNEF.
+#[derive(Debug, Clone, Default, Educe)]
+#[educe(Hash)]
+pub struct Lrn {
+    #[educe(Hash(method = "hash_f32"))]
+    pub alpha: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub beta: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    pub bias: f32,
+    pub size: usize,
+}
+
+impl_dyn_hash!(Lrn);
+
+impl Lrn {
+    fn eval_t<T: Datum + num_traits::Float + num_traits::FromPrimitive + ::std::iter::Sum>(
+        &self,
+        input: Arc<Tensor>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = input.to_array_view::<T>()?;
+        let channels = input.shape()[1];
+        let output = Array::from_shape_fn(input.shape(), |mut coords| {
+            let c = coords[1];
+            let x = input[&coords];
+            let c_min = c.saturating_sub((self.size - 1) / 2);
+            let c_max = (c + ((self.size - 1).div_ceil(2))).min(channels - 1);
+            let square_sum: T = (c_min..=c_max)
+                .map(|c| {
+                    coords[1] = c;
+                    input[&coords].powi(2)
+                })
+                .sum();
+            x / (T::from(self.bias).unwrap()
+                + T::from(self.alpha).unwrap() / T::from(self.size).unwrap() * square_sum)
+                .powf(T::from(self.beta).unwrap())
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl Op for Lrn {
+    fn name(&self) -> Cow<str> {
+        "Lrn".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "alpha:{} beta:{} bias:{} size:{}",
+            self.alpha, self.beta, self.bias, self.size
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Lrn {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        dispatch_floatlike!(Self::eval_t(input.datum_type())(self, input))
+    }
+}
+
+impl TypedOp for Lrn {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.size == 0 {
+            bail!("Lrn size must be strictly positive")
+        }
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn across_channels() {
+        let lrn = Lrn { alpha: 3.0, beta: 1.0, bias: 1.0, size: 3 };
+        let input = rctensor4(&[[[[1f32]], [[2.]], [[3.]]]]);
+        let output = lrn.eval(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor4(&[[[[1f32 / 6.]], [[2. / 15.]], [[3. / 14.]]]]));
+    }
+}
@@ -1,8 +1,14 @@
+mod attention;
 mod data_formats;
+mod lrn;
 mod reduce;
+mod softmax;
 
+pub use self::attention::{AttentionWindow, LocalAttention};
 pub use self::data_formats::{BaseDataShape, DataFormat, DataShape};
+pub use self::lrn::Lrn;
 pub use self::reduce::{Reduce, Reducer};
+pub use self::softmax::{QuantizedSoftmax, Softmax};
 
 pub use crate::internal::*;
 
@@ -12,3 +18,41 @@ element_wise!(sigmoid, Sigmoid, [f32] => |_, xs| {
 };
     cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
 );
+
+element_wise!(leaky_relu, LeakyRelu { #[educe(Hash(method = "hash_f32"))] alpha: f32 },
+    [f32] => |op, xs| {
+        tract_linalg::mmm::Activation::LeakyRelu(op.alpha).apply(xs);
+        Ok(())
+    },
+    [f64] => |op, xs| {
+        xs.iter_mut().for_each(|x| if *x < 0.0 { *x *= op.alpha as f64 });
+        Ok(())
+    };
+    cost: |dt| {tvec!((Cost::FMA(dt), 1))}
+);
+
+element_wise!(hard_swish, HardSwish,
+    [f32] => |_, xs| {
+        tract_linalg::mmm::Activation::HardSwish.apply(xs);
+        Ok(())
+    },
+    [f64] => |_, xs| {
+        xs.iter_mut().for_each(|x| *x = *x * (*x + 3.0).max(0.0).min(6.0) / 6.0);
+        Ok(())
+    };
+    cost: |dt| {tvec!((Cost::FMA(dt), 3))}
+);
+
+element_wise!(gelu, Gelu,
+    [f32] => |_, xs| {
+        tract_linalg::mmm::Activation::Gelu.apply(xs);
+        Ok(())
+    },
+    [f64] => |_, xs| {
+        xs.iter_mut().for_each(|x| {
+            *x = 0.5 * *x * (1.0 + (0.7978845608028654 * (*x + 0.044715 * *x * *x * *x)).tanh())
+        });
+        Ok(())
+    };
+    cost: |dt| {tvec!((Cost::FMA(dt), 15), (Cost::Div(dt), 1))}
+);
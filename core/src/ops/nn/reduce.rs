@@ -19,6 +19,8 @@ macro_rules! r {
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq)]
 pub enum Reducer {
+    All,
+    Any,
     ArgMax(bool), // take last
     ArgMin(bool),
     Max,
@@ -37,8 +39,15 @@ impl Reducer {
             .enumerate()
             .map(|(ax, &d)| if axes.contains(&ax) { 1 } else { d })
             .collect();
+        if let All | Any = self {
+            if dt != DatumType::Bool {
+                bail!("{:?} reducer expects booleans, got {:?}", self, dt)
+            }
+        }
         Ok(unsafe {
             match self {
+                All => Self::reduce_t(self, axes, &output_shape, input, all_t, false),
+                Any => Self::reduce_t(self, axes, &output_shape, input, any_t, false),
                 ArgMax(last) => {
                     r!(Self::reduce_t(dt)(self, axes, &output_shape, input, argmax_t, *last))
                 }
@@ -131,6 +140,14 @@ impl Reducer {
     }
 }
 
+fn all_t<'a>(v: ArrayViewD<'a, bool>, _last: bool) -> bool {
+    v.iter().all(|&b| b)
+}
+
+fn any_t<'a>(v: ArrayViewD<'a, bool>, _last: bool) -> bool {
+    v.iter().any(|&b| b)
+}
+
 fn argmax_t<'a, T>(v: ArrayViewD<'a, T>, last: bool) -> i64
 where
     T: Copy + Datum + num_traits::Bounded + ::std::cmp::PartialOrd,
@@ -253,3 +270,22 @@ impl TypedOp for Reduce {
         Ok(Some(AxisChangeConsequence::new(model, node, op, change)))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn any_and_all() {
+        let input = rctensor2(&[[true, false], [true, true], [false, false]]);
+        let any = Reduce::new(tvec!(1), Reducer::Any).eval(tvec!(input.clone())).unwrap();
+        assert_eq!(*any[0], tensor2(&[[true], [true], [false]]));
+        let all = Reduce::new(tvec!(1), Reducer::All).eval(tvec!(input)).unwrap();
+        assert_eq!(*all[0], tensor2(&[[false], [true], [false]]));
+    }
+
+    #[test]
+    fn any_on_floats() {
+        assert!(Reducer::Any.reduce(&[0], &tensor1(&[0f32, 1.])).is_err());
+    }
+}
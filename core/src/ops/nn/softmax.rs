@@ -0,0 +1,319 @@
+use crate::internal::*;
+use crate::ops::quant::{quantizer_params, storage_range, DequantizeLinearF32};
+use num_traits::Float;
+
+/// Softmax (or log-softmax) over `axes`.
+///
+/// f32 goes through the tract_linalg kernels, one contiguous lane at a time:
+/// when `axes` are not the innermost ones, the input is transposed first.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Softmax {
+    pub axes: TVec<usize>,
+    pub log: bool,
+}
+
+impl_dyn_hash!(Softmax);
+
+impl Softmax {
+    fn permutation(&self, rank: usize) -> TVec<usize> {
+        let mut permutation: TVec<usize> = (0..rank).filter(|a| !self.axes.contains(a)).collect();
+        let mut axes = self.axes.clone();
+        axes.sort();
+        permutation.extend(axes);
+        permutation
+    }
+
+    fn eval_lanes<T: Datum>(
+        &self,
+        input: Arc<Tensor>,
+        lane: impl FnMut(&mut [T]),
+    ) -> TractResult<Tensor> {
+        let rank = input.rank();
+        let permutation = self.permutation(rank);
+        let identity = permutation.iter().enumerate().all(|(ix, &a)| ix == a);
+        let mut tensor = input.into_tensor();
+        if !identity {
+            tensor = tensor.permute_axes(&permutation)?;
+        }
+        let lane_len: usize = tensor.shape()[rank - self.axes.len()..].iter().product();
+        if lane_len > 0 {
+            tensor.as_slice_mut::<T>()?.chunks_mut(lane_len).for_each(lane);
+        }
+        if !identity {
+            let mut inverse: TVec<usize> = tvec!(0; rank);
+            for (ix, &a) in permutation.iter().enumerate() {
+                inverse[a] = ix;
+            }
+            tensor = tensor.permute_axes(&inverse)?;
+        }
+        Ok(tensor)
+    }
+
+    fn eval_float<T: Datum + Float>(&self, input: Arc<Tensor>) -> TractResult<Tensor> {
+        let log = self.log;
+        self.eval_lanes(input, |lane: &mut [T]| {
+            let max = lane.iter().fold(T::neg_infinity(), |acc, &x| acc.max(x));
+            let sum = lane.iter().fold(T::zero(), |acc, &x| acc + (x - max).exp());
+            if log {
+                let shift = max + sum.ln();
+                lane.iter_mut().for_each(|x| *x = *x - shift);
+            } else {
+                lane.iter_mut().for_each(|x| *x = (*x - max).exp() / sum);
+            }
+        })
+    }
+
+    fn eval_f32(&self, input: Arc<Tensor>) -> TractResult<Tensor> {
+        let kernel = (tract_linalg::ops().softmax_f32)();
+        if self.log {
+            let mut scratch = vec![];
+            self.eval_lanes(input, |lane: &mut [f32]| kernel.run_log(lane, &mut scratch))
+        } else {
+            self.eval_lanes(input, |lane: &mut [f32]| kernel.run(lane))
+        }
+    }
+}
+
+impl Op for Softmax {
+    fn name(&self) -> Cow<str> {
+        if self.log {
+            "LogSoftmax".into()
+        } else {
+            "Softmax".into()
+        }
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axes: {:?}", self.axes)])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Softmax {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match input.datum_type() {
+            DatumType::F32 => self.eval_f32(input)?,
+            DatumType::F64 => self.eval_float::<f64>(input)?,
+            DatumType::F16 => {
+                let input = input.cast_to::<f32>()?.into_owned().into_arc_tensor();
+                self.eval_f32(input)?.cast_to::<f16>()?.into_owned()
+            }
+            dt => bail!("{} does not support {:?}", self.name(), dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Softmax {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.axes.iter().any(|&axis| axis >= inputs[0].rank()) {
+            bail!("Softmax axes {:?} out of input rank {}", self.axes, inputs[0].rank())
+        }
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn invariants(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Invariants> {
+        let rank = model.outlet_fact(node.inputs[0])?.rank();
+        let axes = (0..rank)
+            .filter(|axis| !self.axes.contains(axis))
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        let count: TDim = inputs[0].shape.iter().maybe_product()?;
+        Ok(tvec!((Cost::FMA(dt), count.clone() * 8), (Cost::Div(dt), count)))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.log {
+            return Ok(None);
+        }
+        let prec = model.node(node.inputs[0].node);
+        let dequant = if let Some(op) = prec.op_as::<DequantizeLinearF32>() {
+            op
+        } else {
+            return Ok(None);
+        };
+        if storage_range(model.outlet_fact(prec.inputs[0])?.datum_type).is_err() {
+            return Ok(None);
+        }
+        let quant = if let Some(quant) = model.single_succ(node.id)? {
+            quant
+        } else {
+            return Ok(None);
+        };
+        if let Some((scale, zero_point, dt)) = quantizer_params(quant) {
+            let op = QuantizedSoftmax::new(
+                self.axes.clone(),
+                dequant.scale,
+                ZpScale::new(zero_point, scale.recip()),
+                dt,
+            );
+            let mut patch = TypedModelPatch::default();
+            let wire = patch.tap_model(model, prec.inputs[0])?;
+            let wire = patch.wire_node(&*node.name, op, &[wire])?[0];
+            patch.shunt_outside(model, quant.id.into(), wire)?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+}
+
+/// Softmax over `axes` of an 8-bit quantized input, computed on integers,
+/// quantized to `output` and stored as `dt`.
+///
+/// exp is looked up in a 20-bit fixed point table indexed by the distance to
+/// the lane maximum.
+#[derive(Debug, Clone, Hash)]
+pub struct QuantizedSoftmax {
+    pub axes: TVec<usize>,
+    pub output: ZpScale,
+    pub dt: DatumType,
+    exp_table: Vec<u32>,
+    output_multiplier: u64,
+}
+
+impl_dyn_hash!(QuantizedSoftmax);
+
+impl QuantizedSoftmax {
+    pub fn new(
+        axes: TVec<usize>,
+        input_scale: f32,
+        output: ZpScale,
+        dt: DatumType,
+    ) -> QuantizedSoftmax {
+        let exp_table = (0..256)
+            .map(|d| ((-input_scale * d as f32).exp() * (1 << 20) as f32).round() as u32)
+            .collect();
+        let output_multiplier = ((1 << 16) as f32 / output.scale).round() as u64;
+        QuantizedSoftmax { axes, output, dt, exp_table, output_multiplier }
+    }
+}
+
+impl Op for QuantizedSoftmax {
+    fn name(&self) -> Cow<str> {
+        "QuantizedSoftmax".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axes: {:?} output: {:?} ({:?})", self.axes, self.output, self.dt)])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for QuantizedSoftmax {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let (min, max) = storage_range(self.dt)?;
+        let input = input.cast_to::<i32>()?.into_owned().into_arc_tensor();
+        let zero_point = self.output.zero_point;
+        let output =
+            Softmax::new(self.axes.clone(), false).eval_lanes(input, |lane: &mut [i32]| {
+                let lane_max = lane.iter().copied().max().unwrap();
+                let sum: u64 =
+                    lane.iter().map(|x| self.exp_table[(lane_max - x) as usize] as u64).sum();
+                // round(e / sum / scale), with e and 1/scale in fixed point
+                let den = sum << 16;
+                lane.iter_mut().for_each(|x| {
+                    let num =
+                        self.exp_table[(lane_max - *x) as usize] as u64 * self.output_multiplier;
+                    *x = (((2 * num + den) / (2 * den)) as i32 + zero_point).max(min).min(max);
+                })
+            })?;
+        Ok(tvec!(output.cast_to_dt(self.dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for QuantizedSoftmax {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.axes.iter().any(|&axis| axis >= inputs[0].rank()) {
+            bail!("Softmax axes {:?} out of input rank {}", self.axes, inputs[0].rank())
+        }
+        storage_range(inputs[0].datum_type)?;
+        storage_range(self.dt)?;
+        let mut fact = inputs[0].clone();
+        fact.datum_type = self.dt;
+        Ok(tvec!(fact))
+    }
+
+    fn invariants(&self, model: &TypedModel, node: &TypedNode) -> TractResult<Invariants> {
+        let rank = model.outlet_fact(node.inputs[0])?.rank();
+        let axes = (0..rank)
+            .filter(|axis| !self.axes.contains(axis))
+            .map(AxisInfo::simple)
+            .collect::<TVec<_>>();
+        Ok(axes.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn softmax_inner_axis() {
+        let op = Softmax::new(tvec!(1), false);
+        let output = op.eval(tvec!(rctensor2(&[[0f32, 0.], [1., 1.]]))).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0.5f32, 0.5], [0.5, 0.5]]));
+    }
+
+    #[test]
+    fn log_softmax_outer_axis() {
+        let input = tensor2(&[[1f32, 2.], [3., -4.]]);
+        let op = Softmax::new(tvec!(0), true);
+        let output = op.eval(tvec!(input.clone().into_arc_tensor())).unwrap();
+        let reference =
+            op.eval(tvec!(input.cast_to::<f64>().unwrap().into_owned().into())).unwrap();
+        output[0].close_enough(&reference[0].cast_to::<f32>().unwrap(), true).unwrap();
+        let x = output[0].to_array_view::<f32>().unwrap();
+        assert!((x[[0, 0]] - (1f32 - (1f32.exp() + 3f32.exp()).ln())).abs() < 1e-5);
+    }
+
+    #[test]
+    fn quantized_softmax() -> TractResult<()> {
+        use crate::ops::quant::quantize_linear_u8;
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(u8::datum_type(), &[2, 4]))?;
+        let y = model.wire_node("dequant", DequantizeLinearF32::new(0.1, 128), &[x])?;
+        let y = model.wire_node("softmax", Softmax::new(tvec!(1), false), &y)?;
+        let y = model.wire_node("quant", quantize_linear_u8(256.0, 0), &y)?;
+        model.set_output_outlets(&y)?;
+        let decluttered = model.clone().declutter()?;
+        assert!(decluttered.node_by_name("softmax")?.op_is::<QuantizedSoftmax>());
+        let x = tensor2(&[[0u8, 128, 200, 255], [100, 101, 102, 103]]);
+        let expected = SimplePlan::new(&model)?.run(tvec!(x.clone()))?.remove(0);
+        let found = SimplePlan::new(&decluttered)?.run(tvec!(x))?.remove(0);
+        for (e, f) in expected.as_slice::<u8>()?.iter().zip(found.as_slice::<u8>()?.iter()) {
+            assert!((*e as i32 - *f as i32).abs() <= 1);
+        }
+        Ok(())
+    }
+}
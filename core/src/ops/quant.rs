@@ -1,10 +1,14 @@
 use crate::internal::*;
+use crate::ops::binary::{BinMiniOp, TypedBinOp};
 use crate::ops::element_wise::ElementWiseOp;
 use num_traits::AsPrimitive;
 use num_traits::Zero;
 use tract_linalg::frame::MatMatMul;
 use tract_linalg::lut::Lut;
 
+pub mod calibration;
+pub mod weights;
+
 #[derive(Clone, Debug, Educe)]
 #[educe(Hash)]
 pub struct QParams {
@@ -14,6 +18,9 @@ pub struct QParams {
     pub zero_point_c: Option<Arc<Tensor>>,
     #[educe(Hash(method = "hash_scale"))]
     pub scale_factor: Option<f32>,
+    /// One f32 scale factor per output channel (row of C), replacing
+    /// scale_factor.
+    pub scale_factors: Option<Arc<Tensor>>,
     pub inputs_kind: Option<TVec<QParamsInputKind>>,
 }
 
@@ -60,6 +67,7 @@ impl QParams {
             zero_point_b: None,
             zero_point_c: None,
             scale_factor: None,
+            scale_factors: None,
             inputs_kind: None,
         }
     }
@@ -77,7 +85,12 @@ impl QParams {
     }
 
     pub fn with_scale_factor(self, scale_factor: f32) -> QParams {
-        QParams { scale_factor: Some(scale_factor), ..self }
+        QParams { scale_factor: Some(scale_factor), scale_factors: None, ..self }
+    }
+
+    pub fn with_scale_factors(mut self, scale_factors: &Arc<Tensor>) -> TractResult<QParams> {
+        self.set_scale_factors(scale_factors)?;
+        Ok(self)
     }
 
     pub fn with_inputs_kind(self, inputs_kind: TVec<QParamsInputKind>) -> QParams {
@@ -97,7 +110,70 @@ impl QParams {
     }
 
     pub fn set_scale_factor(&mut self, scale_factor: f32) {
-        self.scale_factor = Some(scale_factor)
+        self.scale_factor = Some(scale_factor);
+        self.scale_factors = None;
+    }
+
+    /// Per output channel scale factors. They collapse to a single
+    /// scale_factor when they are all equal.
+    pub fn set_scale_factors(&mut self, scale_factors: &Arc<Tensor>) -> TractResult<()> {
+        let scale_factors = scale_factors.cast_to::<f32>()?;
+        let slice = scale_factors.as_slice::<f32>()?;
+        if slice.is_empty() {
+            bail!("Empty scale factors");
+        }
+        if scale_factors.rank() == 0 || slice[1..].iter().all(|&x| x == slice[0]) {
+            self.set_scale_factor(slice[0]);
+        } else if scale_factors.rank() == 1 {
+            self.scale_factor = None;
+            self.scale_factors = Some(scale_factors.into_owned().into_arc_tensor());
+        } else {
+            bail!("Scale factors must be a scalar or a vector, got {:?}", scale_factors);
+        }
+        Ok(())
+    }
+
+    /// Parameters for the same product on the storage types, when the zero
+    /// points and scales are carried by the quantized types of a, b and c.
+    pub fn with_storage_types(&self, a_dt: DatumType, b_dt: DatumType) -> TractResult<QParams> {
+        fn zero_point(dt: DatumType) -> Option<Arc<Tensor>> {
+            match dt {
+                DatumType::QI8(zps) => Some(rctensor0(zps.zero_point as i8)),
+                DatumType::QU8(zps) => Some(rctensor0(zps.zero_point as u8)),
+                _ => None,
+            }
+        }
+        let scale = |dt: DatumType| dt.zp_scale().map(|zps| zps.scale).unwrap_or(1.0);
+        let c_dt = self.c_datum_type;
+        if (a_dt.is_quantized() && self.zero_point_a.is_some())
+            || (b_dt.is_quantized() && self.zero_point_b.is_some())
+            || (c_dt.is_quantized() && self.zero_point_c.is_some())
+        {
+            bail!("Zero points given both by quantized types and in {:?}", self);
+        }
+        let mut q_params = self.clone();
+        if let Some(zp) = zero_point(a_dt) {
+            q_params.set_zero_point_a(&zp);
+        }
+        if let Some(zp) = zero_point(b_dt) {
+            q_params.set_zero_point_b(&zp);
+        }
+        if let Some(zp) = zero_point(c_dt) {
+            q_params.set_zero_point_c(&zp);
+        }
+        let ratio = scale(a_dt) * scale(b_dt) / scale(c_dt);
+        if let Some(factors) = q_params.scale_factors.clone() {
+            let mut factors = factors.into_tensor();
+            factors.as_slice_mut::<f32>()?.iter_mut().for_each(|f| *f *= ratio);
+            q_params.set_scale_factors(&factors.into_arc_tensor())?;
+        } else {
+            let factor = q_params.scale_factor.unwrap_or(1.0) * ratio;
+            if factor != 1.0 {
+                q_params.set_scale_factor(factor);
+            }
+        }
+        q_params.c_datum_type = c_dt.unquantized();
+        Ok(q_params)
     }
 
     pub fn set_inputs_kind(&mut self, inputs_kind: TVec<QParamsInputKind>) {
@@ -118,6 +194,9 @@ impl QParams {
             if let Some(factor) = self.scale_factor {
                 mmm.set_scale_factor(factor);
             }
+            if let Some(factors) = self.scale_factors.as_ref() {
+                mmm.set_scale_factors(factors.as_slice::<f32>()?);
+            }
         }
         Ok(())
     }
@@ -183,15 +262,127 @@ fn info_quantize_linear_i8(q: &QuantizeLinearI8) -> TractResult<Vec<String>> {
     )])
 }
 
+/// Scale, zero point and output type of a QuantizeLinearI8 or
+/// QuantizeLinearU8 node.
+pub(crate) fn quantizer_params(node: &TypedNode) -> Option<(f32, i32, DatumType)> {
+    let op = node.op_as::<ElementWiseOp>()?;
+    if let Some(mop) = op.0.downcast_ref::<QuantizeLinearU8>() {
+        Some((mop.scale, mop.zero_point as i32, u8::datum_type()))
+    } else if let Some(mop) = op.0.downcast_ref::<QuantizeLinearI8>() {
+        Some((mop.scale, mop.zero_point as i32, i8::datum_type()))
+    } else {
+        None
+    }
+}
+
+/// Range of the values stored by an 8-bit (possibly quantized) type.
+pub(crate) fn storage_range(dt: DatumType) -> TractResult<(i32, i32)> {
+    match dt.unquantized() {
+        DatumType::I8 => Ok((i8::min_value() as i32, i8::max_value() as i32)),
+        DatumType::U8 => Ok((u8::min_value() as i32, u8::max_value() as i32)),
+        _ => bail!("{:?} is not an 8-bit type", dt),
+    }
+}
+
+/// Lookup table giving, for each of the 256 values of `dt`, the result of
+/// `f`. `f` must map `dt` to a type with the same storage.
+pub(crate) fn build_lut(
+    dt: DatumType,
+    f: impl FnOnce(Tensor) -> TractResult<Tensor>,
+) -> TractResult<Box<dyn Lut>> {
+    storage_range(dt)?;
+    let input = (0u8..=255).collect::<Vec<u8>>();
+    let input = match dt.unquantized() {
+        DatumType::I8 => unsafe { tensor1(std::mem::transmute::<&[u8], &[i8]>(&*input)) },
+        _ => tensor1(&input),
+    };
+    let output = f(input.cast_to_dt(dt)?.into_owned())?;
+    if output.datum_type().unquantized() != dt.unquantized() {
+        bail!("Lookup table from {:?} to {:?}", dt, output.datum_type());
+    }
+    let output = output.cast_to_dt(dt.unquantized())?;
+    let table: &[u8] = match dt.unquantized() {
+        DatumType::I8 => unsafe { std::mem::transmute(output.as_slice::<i8>()?) },
+        _ => output.as_slice::<u8>()?,
+    };
+    Ok((tract_linalg::ops().lut_u8)(table))
+}
+
 #[derive(Clone, Debug, new, Educe)]
 #[educe(Hash)]
 pub struct DequantizeLinearF32 {
     #[educe(Hash(method = "hash_f32"))]
-    scale: f32,
-    zero_point: i32,
+    pub scale: f32,
+    pub zero_point: i32,
 }
 
 impl DequantizeLinearF32 {
+    /// Whether `quant` gives back exactly the dequantized values of type `dt`.
+    fn is_inverted_by(&self, quant: &TypedNode, dt: DatumType) -> bool {
+        // quantized values are at most 255 away from the zero point, so the
+        // product of the scales must be close enough to 1 for them to round back
+        quantizer_params(quant)
+            .map(|(scale, zero_point, quant_dt)| {
+                quant_dt == dt
+                    && zero_point == self.zero_point
+                    && (scale * self.scale - 1.0).abs() < 1e-3
+            })
+            .unwrap_or(false)
+    }
+
+    /// Folds dequantize → add or mul → quantize into a QuantizedBinary, when
+    /// both operands are dequantized.
+    fn declutter_binary(
+        model: &TypedModel,
+        dequant: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let bin =
+            if let Some(bin) = model.single_succ(dequant.id)? { bin } else { return Ok(None) };
+        let kind = if let Some(kind) =
+            bin.op_as::<TypedBinOp>().and_then(|op| QuantizedBinaryKind::from_mini_op(&*op.0))
+        {
+            kind
+        } else {
+            return Ok(None);
+        };
+        let (scale, zero_point, c_dt) =
+            if let Some(params) = model.single_succ(bin.id)?.and_then(quantizer_params) {
+                params
+            } else {
+                return Ok(None);
+            };
+        let mut operands = tvec!();
+        for input in &bin.inputs {
+            let prec = model.node(input.node);
+            if let Some(op) = prec.op_as::<DequantizeLinearF32>() {
+                if storage_range(model.outlet_fact(prec.inputs[0])?.datum_type).is_err() {
+                    return Ok(None);
+                }
+                operands.push((prec.inputs[0], ZpScale::new(op.zero_point, op.scale)));
+            } else {
+                return Ok(None);
+            }
+        }
+        let quant = model.single_succ(bin.id)?.unwrap();
+        let op = QuantizedBinary::new(
+            kind,
+            operands[0].1,
+            operands[1].1,
+            ZpScale::new(zero_point, scale.recip()),
+            c_dt,
+        );
+        let mut patch = TypedModelPatch::default();
+        let a = patch.tap_model(model, operands[0].0)?;
+        let b = if operands[1].0 == operands[0].0 {
+            a
+        } else {
+            patch.tap_model(model, operands[1].0)?
+        };
+        let wire = patch.wire_node(&*bin.name, op, &[a, b])?[0];
+        patch.shunt_outside(model, quant.id.into(), wire)?;
+        Ok(Some(patch))
+    }
+
     fn eval_t<T: Datum + AsPrimitive<i32>>(&self, input: &Tensor) -> TractResult<Tensor> {
         let mut output = unsafe { Tensor::uninitialized::<f32>(input.shape())? };
         input
@@ -227,7 +418,7 @@ impl EvalOp for DequantizeLinearF32 {
         true
     }
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
-        let output = match inputs[0].datum_type() {
+        let output = match inputs[0].datum_type().unquantized() {
             DatumType::I8 => self.eval_t::<i8>(&inputs[0])?,
             DatumType::I32 => self.eval_t::<i32>(&inputs[0])?,
             DatumType::U8 => self.eval_t::<u8>(&inputs[0])?,
@@ -239,6 +430,11 @@ impl EvalOp for DequantizeLinearF32 {
 
 impl TypedOp for DequantizeLinearF32 {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if let Some(zps) = inputs[0].datum_type.zp_scale() {
+            if zps != ZpScale::new(self.zero_point, self.scale) {
+                bail!("{:?} can not dequantize {:?}", self, inputs[0].datum_type);
+            }
+        }
         let mut fact = inputs[0].clone();
         fact.datum_type = f32::datum_type();
         Ok(tvec!(fact))
@@ -265,19 +461,19 @@ impl TypedOp for DequantizeLinearF32 {
     ) -> TractResult<Option<TypedModelPatch>> {
         let mut current = dequant;
         let incoming_dt = model.node_input_facts(dequant.id)?[0].datum_type;
+        if let Some(quant) = model.single_succ(dequant.id)? {
+            if self.is_inverted_by(quant, incoming_dt) {
+                let mut patch = TypedModelPatch::default();
+                let wire = patch.tap_model(model, dequant.inputs[0])?;
+                patch.shunt_outside(model, quant.id.into(), wire)?;
+                return Ok(Some(patch));
+            }
+        }
+        if let Some(patch) = Self::declutter_binary(model, dequant)? {
+            return Ok(Some(patch));
+        }
         while let Some(quant) = model.single_succ(current.id)? {
-            let q_params = if let Some(op) = quant.op_as::<ElementWiseOp>() {
-                if let Some(mop) = op.0.downcast_ref::<QuantizeLinearU8>() {
-                    Some((mop.scale, mop.zero_point as i32, u8::datum_type()))
-                } else if let Some(mop) = op.0.downcast_ref::<QuantizeLinearI8>() {
-                    Some((mop.scale, mop.zero_point as i32, i8::datum_type()))
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            if let Some((scale, zero_point, dt)) = q_params {
+            if let Some((scale, zero_point, dt)) = quantizer_params(quant) {
                 // first, try Op::quantize() on all ops in the chain
                 let mut patch = TypedModelPatch::default();
                 let mut wire: OutletId = patch.tap_model(model, dequant.inputs[0])?.into();
@@ -300,10 +496,10 @@ impl TypedOp for DequantizeLinearF32 {
                     }
                 }
                 // or else make a lookup table
-                if incoming_dt == DatumType::I8 || incoming_dt == DatumType::U8 {
+                if incoming_dt == dt {
                     let mut adhoc_model = TypedModel::default();
-                    let mut wire = adhoc_model
-                        .add_source("ad-hoc", TypedFact::dt_shape(dt, &[256]))?;
+                    let mut wire =
+                        adhoc_model.add_source("ad-hoc", TypedFact::dt_shape(dt, &[256]))?;
                     let mut next = model.single_succ(dequant.id)?.unwrap();
                     let mut name = None;
                     // plug in dequant
@@ -323,21 +519,10 @@ impl TypedOp for DequantizeLinearF32 {
                     wire =
                         adhoc_model.wire_node(&*quant.name, quant.op.clone(), [wire].as_ref())?[0];
                     adhoc_model.set_output_outlets(&[wire])?;
-                    let input = (0u8..=255).collect::<Vec<u8>>();
-                    let input = match dt {
-                        DatumType::I8 => unsafe {
-                            tensor1(std::mem::transmute::<&[u8], &[i8]>(&*input))
-                        },
-                        DatumType::U8 => tensor1(&input),
-                        _ => unreachable!(),
-                    };
-                    let output = SimplePlan::new(adhoc_model)?.run(tvec!(input))?.remove(0);
-                    let table: &[u8] = match dt {
-                        DatumType::I8 => unsafe { std::mem::transmute(output.as_slice::<i8>()?) },
-                        DatumType::U8 => output.as_slice::<u8>()?,
-                        _ => unreachable!(),
-                    };
-                    let op = lookup_table((tract_linalg::ops().lut_u8)(table));
+                    let plan = SimplePlan::new(adhoc_model)?;
+                    let table =
+                        build_lut(dt, |input| Ok(plan.run(tvec!(input))?.remove(0).into_tensor()))?;
+                    let op = lookup_table(table);
                     let mut patch = TypedModelPatch::default();
                     let mut wire: OutletId = patch.tap_model(model, dequant.inputs[0])?.into();
                     wire = patch.wire_node(name.unwrap_or(&*dequant.name), op, [wire].as_ref())?[0];
@@ -349,7 +534,7 @@ impl TypedOp for DequantizeLinearF32 {
                 .op
                 .invariants(model, quant)
                 .with_context(|| format!("Querying invariants for {}", quant))?;
-            if invariants.element_wise() {
+            if invariants.element_wise() && quant.inputs.len() == 1 {
                 current = quant;
             } else {
                 break;
@@ -361,6 +546,124 @@ impl TypedOp for DequantizeLinearF32 {
     as_op!();
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QuantizedBinaryKind {
+    Add,
+    Mul,
+}
+
+impl QuantizedBinaryKind {
+    pub fn from_mini_op(op: &dyn BinMiniOp) -> Option<QuantizedBinaryKind> {
+        if op.is::<crate::ops::math::Add>() {
+            Some(QuantizedBinaryKind::Add)
+        } else if op.is::<crate::ops::math::Mul>() {
+            Some(QuantizedBinaryKind::Mul)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sum or product of two 8-bit quantized tensors, computed on integers and
+/// rescaled to the output quantization `c`, stored as `c_dt`.
+#[derive(Clone, Debug, new, Hash)]
+pub struct QuantizedBinary {
+    pub kind: QuantizedBinaryKind,
+    pub a: ZpScale,
+    pub b: ZpScale,
+    pub c: ZpScale,
+    pub c_dt: DatumType,
+}
+
+impl_dyn_hash!(QuantizedBinary);
+
+/// Fractional bits of the QuantizedBinary rescaling multipliers.
+const RESCALE_SHIFT: u32 = 24;
+
+fn rescale_multiplier(factor: f32) -> i64 {
+    (factor as f64 * (1i64 << RESCALE_SHIFT) as f64).round() as i64
+}
+
+impl Op for QuantizedBinary {
+    fn name(&self) -> Cow<str> {
+        format!("Quantized{:?}", self.kind).into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("a: {:?} b: {:?} c: {:?} ({:?})", self.a, self.b, self.c, self.c_dt)])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for QuantizedBinary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (a, b) = args_2!(inputs);
+        let (min, max) = storage_range(self.c_dt)?;
+        let a = a.cast_to::<i32>()?;
+        let b = b.cast_to::<i32>()?;
+        let a = a.to_array_view::<i32>()?;
+        let b = b.to_array_view::<i32>()?;
+        let shape = crate::broadcast::multi_broadcast(&[a.shape(), b.shape()])
+            .ok_or_else(|| format_err!("Can not broadcast {:?} and {:?}", a.shape(), b.shape()))?;
+        let mut c = tract_ndarray::ArrayD::<i32>::zeros(&*shape);
+        let (za, zb, zc) = (self.a.zero_point, self.b.zero_point, self.c.zero_point);
+        let half = 1i64 << (RESCALE_SHIFT - 1);
+        let requant = |acc: i64| {
+            (((acc + half) >> RESCALE_SHIFT) + zc as i64).max(min as i64).min(max as i64) as i32
+        };
+        let zip = tract_ndarray::Zip::from(&mut c).and_broadcast(&a).and_broadcast(&b);
+        match self.kind {
+            QuantizedBinaryKind::Add => {
+                let ma = rescale_multiplier(self.a.scale / self.c.scale);
+                let mb = rescale_multiplier(self.b.scale / self.c.scale);
+                zip.apply(|c, a, b| {
+                    *c = requant((a - za) as i64 * ma + (b - zb) as i64 * mb);
+                })
+            }
+            QuantizedBinaryKind::Mul => {
+                let m = rescale_multiplier(self.a.scale * self.b.scale / self.c.scale);
+                zip.apply(|c, a, b| *c = requant((a - za) as i64 * (b - zb) as i64 * m))
+            }
+        }
+        Ok(tvec!(c.into_tensor().cast_to_dt(self.c_dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl TypedOp for QuantizedBinary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].rank() != inputs[1].rank() {
+            bail!("Typed ops require rank match. Invalid inputs for {}: {:?}", self.name(), inputs);
+        }
+        for dt in &[inputs[0].datum_type, inputs[1].datum_type, self.c_dt] {
+            storage_range(*dt)?;
+        }
+        Ok(tvec!(TypedFact::dt_shape(
+            self.c_dt,
+            &*crate::broadcast::multi_broadcast(&[
+                &inputs[0].shape.to_tvec(),
+                &inputs[1].shape.to_tvec()
+            ])
+            .ok_or_else(|| format_err!(
+                "Can not broadcast shapes a:{:?} b:{:?}",
+                &inputs[0],
+                &inputs[1]
+            ))?
+        )))
+    }
+
+    as_op!();
+}
+
 element_wise_oop!(lookup_table,
  LookupTable {
      #[educe(Hash(method="hash_lookup_table"))]
@@ -378,9 +681,218 @@ element_wise_oop!(lookup_table,
      ys.copy_from_slice(xs);
      op.table.run(ys);
      Ok(())
- }
+ };
+ declutter: declutter_lookup_table
 );
 
+/// Merges a lookup table fed by another one into a single table.
+fn declutter_lookup_table(
+    model: &TypedModel,
+    node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let prec = model.node(node.inputs[0].node);
+    if model.outlet_successors(node.inputs[0]).len() != 1 {
+        return Ok(None);
+    }
+    let (first, second) = match (
+        prec.op_as::<ElementWiseOp>().and_then(|op| op.0.downcast_ref::<LookupTable>()),
+        node.op_as::<ElementWiseOp>().and_then(|op| op.0.downcast_ref::<LookupTable>()),
+    ) {
+        (Some(first), Some(second)) => (first, second),
+        _ => return Ok(None),
+    };
+    let table: Vec<u8> =
+        first.table.table().iter().map(|&x| second.table.table()[x as usize]).collect();
+    let mut patch = TypedModelPatch::default();
+    let wire = patch.tap_model(model, prec.inputs[0])?;
+    let op = lookup_table((tract_linalg::ops().lut_u8)(&table));
+    let wire = patch.wire_node(&*node.name, op, &[wire])?[0];
+    patch.shunt_outside(model, node.id.into(), wire)?;
+    Ok(Some(patch))
+}
+
 fn hash_lookup_table<H: std::hash::Hasher>(lut: &Box<dyn Lut>, h: &mut H) {
     Hash::hash_slice(lut.table(), h)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cast::cast;
+    use crate::ops::matmul::MatMulUnary;
+
+    fn qi8(zero_point: i32, scale: f32) -> DatumType {
+        DatumType::QI8(ZpScale::new(zero_point, scale))
+    }
+
+    #[test]
+    fn mat_mul_on_quantized_types() -> TractResult<()> {
+        let a = tensor2(&[[1f32, -2.0], [0.5, 1.5]]).cast_to_dt(qi8(0, 0.5))?.into_owned();
+        let x_dt = qi8(3, 0.25);
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(x_dt, &[2, 3]))?;
+        let q_params = QParams::new(qi8(-2, 0.25));
+        let op = MatMulUnary::new(a.into_arc_tensor(), false, false, false, Some(q_params));
+        let y = model.wire_node("mm", op, &[x])?;
+        let y = model.wire_node("dequant", cast(f32::datum_type()), &y)?;
+        model.set_output_outlets(&y)?;
+        let model = model.declutter()?;
+        let mm = model.node_by_name("mm")?.op_as::<MatMulUnary>().unwrap();
+        assert_eq!(mm.a.datum_type(), i8::datum_type());
+        assert_eq!(mm.q_params.as_ref().unwrap().scale_factor, Some(0.5));
+        let x = tensor2(&[[1f32, 0.5, -1.0], [0.5, 2.0, 0.0]]).cast_to_dt(x_dt)?.into_owned();
+        let found = SimplePlan::new(&model)?.run(tvec!(x))?.remove(0);
+        assert_eq!(*found, tensor2(&[[0f32, -3.5, -1.0], [1.25, 3.25, -0.5]]));
+        Ok(())
+    }
+
+    #[test]
+    fn element_wise_on_quantized_types() -> TractResult<()> {
+        let dt = qi8(1, 0.5);
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(dt, &[3]))?;
+        let y = model.wire_node("abs", crate::ops::math::abs(), &[x])?;
+        model.set_output_outlets(&y)?;
+        let x = tensor1(&[-1f32, 0.5, 2.0]).cast_to_dt(dt)?.into_owned();
+        let found = SimplePlan::new(&model)?.run(tvec!(x))?.remove(0);
+        assert_eq!(found.datum_type(), dt);
+        assert_eq!(found.as_slice::<i8>()?, &[3, 2, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn fold_lossless_round_trips() -> TractResult<()> {
+        let dt = qi8(1, 0.5);
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(dt, &[3]))?;
+        let y = model.wire_node("dequant", cast(f32::datum_type()), &[x])?;
+        let y = model.wire_node("quant", cast(dt), &y)?;
+        let y = model.wire_node("storage", cast(i8::datum_type()), &y)?;
+        let y = model.wire_node("dequant-linear", DequantizeLinearF32::new(0.5, 1), &y)?;
+        let y = model.wire_node("quant-linear", quantize_linear_i8(2.0, 1), &y)?;
+        model.set_output_outlets(&y)?;
+        let model = model.declutter()?;
+        assert_eq!(model.nodes().len(), 2);
+        Ok(())
+    }
+
+    fn is_lut(node: &TypedNode) -> bool {
+        node.op_as::<ElementWiseOp>().map(|op| op.0.is::<LookupTable>()).unwrap_or(false)
+    }
+
+    fn assert_close_q<T: Datum + AsPrimitive<i32>>(found: &Tensor, expected: &Tensor) {
+        for (f, e) in found.as_slice::<T>().unwrap().iter().zip(expected.as_slice::<T>().unwrap()) {
+            assert!(
+                ((*f).as_() - (*e).as_()).abs() <= 1,
+                "found {:?} expected {:?}",
+                found,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn lookup_tables_for_quantized_activations() -> TractResult<()> {
+        let dt = DatumType::QU8(ZpScale::new(128, 0.05));
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(dt, &[256]))?;
+        let y = model.wire_node("sigmoid", crate::ops::nn::sigmoid(), &[x])?;
+        let y = model.wire_node("dequant", cast(f32::datum_type()), &y)?;
+        let y = model.wire_node("tanh", crate::ops::math::tanh(), &y)?;
+        let y_dt = DatumType::QU8(ZpScale::new(128, 1.0 / 128.0));
+        let y = model.wire_node("quant", cast(y_dt), &y)?;
+        model.set_output_outlets(&y)?;
+        let decluttered = model.clone().declutter()?;
+        assert_eq!(decluttered.nodes().iter().filter(|n| is_lut(n)).count(), 2);
+        let x = tensor1(&(0u8..=255).collect::<Vec<_>>()).cast_to_dt(dt)?.into_owned();
+        let expected = SimplePlan::new(&model)?.run(tvec!(x.clone()))?.remove(0);
+        let found = SimplePlan::new(&decluttered)?.run(tvec!(x))?.remove(0);
+        assert_eq!(found.datum_type(), y_dt);
+        assert_close_q::<u8>(&found, &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_chained_lookup_tables() -> TractResult<()> {
+        let dt = DatumType::QU8(ZpScale::new(128, 0.05));
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(dt, &[256]))?;
+        let y = model.wire_node("abs", crate::ops::math::abs(), &[x])?;
+        let y = model.wire_node("neg", crate::ops::math::neg(), &y)?;
+        let y = model.wire_node("sigmoid", crate::ops::nn::sigmoid(), &y)?;
+        model.set_output_outlets(&y)?;
+        let decluttered = model.clone().declutter()?;
+        assert_eq!(decluttered.nodes().iter().filter(|n| is_lut(n)).count(), 1);
+        assert_eq!(decluttered.nodes().len(), 4);
+        let x = tensor1(&(0u8..=255).collect::<Vec<_>>()).cast_to_dt(dt)?.into_owned();
+        let expected = SimplePlan::new(&model)?.run(tvec!(x.clone()))?.remove(0);
+        let found = SimplePlan::new(&decluttered)?.run(tvec!(x))?.remove(0);
+        assert_eq!(found, expected);
+        Ok(())
+    }
+
+    fn quantized_binary(mini_op: Box<dyn BinMiniOp>, kind: QuantizedBinaryKind) -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", TypedFact::dt_shape(i8::datum_type(), &[2, 3]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(u8::datum_type(), &[1, 3]))?;
+        let a = model.wire_node("a.dequant", DequantizeLinearF32::new(0.02, -3), &[a])?[0];
+        let b = model.wire_node("b.dequant", DequantizeLinearF32::new(0.05, 120), &[b])?[0];
+        let c = model.wire_node("bin", TypedBinOp(mini_op), &[a, b])?;
+        let c = model.wire_node("quant", quantize_linear_i8(10.0, 5), &c)?;
+        model.set_output_outlets(&c)?;
+        let decluttered = model.clone().declutter()?;
+        let bin = decluttered.node_by_name("bin")?.op_as::<QuantizedBinary>().unwrap();
+        assert_eq!(bin.kind, kind);
+        let a = tensor2(&[[-128i8, -3, 7], [50, 100, 127]]);
+        let b = tensor2(&[[0u8, 120, 255]]);
+        let expected = SimplePlan::new(&model)?.run(tvec!(a.clone(), b.clone()))?.remove(0);
+        let found = SimplePlan::new(&decluttered)?.run(tvec!(a, b))?.remove(0);
+        assert_eq!(found.shape(), &[2, 3]);
+        assert_close_q::<i8>(&found, &expected);
+        Ok(())
+    }
+
+    #[test]
+    fn quantized_add() -> TractResult<()> {
+        quantized_binary(Box::new(crate::ops::math::Add), QuantizedBinaryKind::Add)
+    }
+
+    #[test]
+    fn quantized_mul() -> TractResult<()> {
+        quantized_binary(Box::new(crate::ops::math::Mul), QuantizedBinaryKind::Mul)
+    }
+
+    #[test]
+    fn quantized_mul_saturates() -> TractResult<()> {
+        let op = QuantizedBinary::new(
+            QuantizedBinaryKind::Mul,
+            ZpScale { zero_point: 0, scale: 100.0 },
+            ZpScale { zero_point: 0, scale: 100.0 },
+            ZpScale { zero_point: 0, scale: 0.01 },
+            i8::datum_type(),
+        );
+        let a = tensor1(&[127i8, -128, 1]).into_arc_tensor();
+        let b = tensor1(&[127i8, 127, 0]).into_arc_tensor();
+        let found = op.eval(tvec!(a, b))?.remove(0);
+        assert_eq!(*found, tensor1(&[127i8, -128, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn quantized_add_on_quantized_types() -> TractResult<()> {
+        let dt = qi8(2, 0.5);
+        let mut model = TypedModel::default();
+        let a = model.add_source("a", TypedFact::dt_shape(dt, &[3]))?;
+        let b = model.add_source("b", TypedFact::dt_shape(dt, &[3]))?;
+        let c = model.wire_node("add", crate::ops::math::add::bin_typed(), &[a, b])?;
+        model.set_output_outlets(&c)?;
+        let decluttered = model.declutter()?;
+        assert!(decluttered.node_by_name("add")?.op_is::<QuantizedBinary>());
+        let a = tensor1(&[1f32, -2.0, 10.0]).cast_to_dt(dt)?.into_owned();
+        let b = tensor1(&[0.5f32, 0.5, 60.0]).cast_to_dt(dt)?.into_owned();
+        let found = SimplePlan::new(&decluttered)?.run(tvec!(a, b))?.remove(0);
+        assert_eq!(found.datum_type(), dt);
+        assert_eq!(*found.cast_to::<f32>()?, tensor1(&[1.5f32, -1.5, 62.5]));
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use crate::internal::*;
+use crate::model::translator::Translate;
+use crate::ops::cnn::ConvUnary;
+use crate::ops::matmul::MatMulUnary;
+use crate::ops::quant::{quantize_linear_i8, DequantizeLinearF32, QParams};
+
+const BINS: usize = 2048;
+
+/// How the quantized range of an activation is picked from its statistics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationMethod {
+    /// Observed minimum and maximum.
+    MinMax,
+    /// Clip the absolute values at a percentile (like 99.99) of the histogram.
+    Percentile(f32),
+}
+
+/// Value statistics of a f32 outlet over a calibration dataset.
+#[derive(Debug, Clone)]
+pub struct OutletStats {
+    pub min: f32,
+    pub max: f32,
+    /// Absolute values counts, over BINS bins spanning [0, range].
+    histogram: Vec<u64>,
+    range: f32,
+}
+
+impl Default for OutletStats {
+    fn default() -> OutletStats {
+        OutletStats {
+            min: std::f32::INFINITY,
+            max: std::f32::NEG_INFINITY,
+            histogram: vec![0; BINS],
+            range: 0.0,
+        }
+    }
+}
+
+impl OutletStats {
+    pub fn observe(&mut self, values: &[f32]) {
+        let mut absmax = self.range;
+        for &v in values.iter().filter(|v| v.is_finite()) {
+            self.min = self.min.min(v);
+            self.max = self.max.max(v);
+            absmax = absmax.max(v.abs());
+        }
+        if self.range == 0.0 {
+            self.range = absmax;
+        }
+        // widen the histogram by merging pairs of bins
+        while self.range < absmax {
+            for i in 0..BINS / 2 {
+                self.histogram[i] = self.histogram[2 * i] + self.histogram[2 * i + 1];
+            }
+            self.histogram[BINS / 2..].iter_mut().for_each(|c| *c = 0);
+            self.range *= 2.0;
+        }
+        for &v in values.iter().filter(|v| v.is_finite()) {
+            let bin =
+                if self.range > 0.0 { (v.abs() / self.range * BINS as f32) as usize } else { 0 };
+            self.histogram[bin.min(BINS - 1)] += 1;
+        }
+    }
+
+    fn threshold(&self, percentile: f32) -> f32 {
+        let total = self.histogram.iter().sum::<u64>();
+        let target = (total as f64 * percentile as f64 / 100.0).ceil() as u64;
+        let mut seen = 0;
+        for (ix, count) in self.histogram.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return (ix + 1) as f32 * self.range / BINS as f32;
+            }
+        }
+        self.range
+    }
+
+    /// The range to quantize. It always contains zero.
+    pub fn range(&self, method: CalibrationMethod) -> (f32, f32) {
+        let (min, max) = if self.min <= self.max { (self.min, self.max) } else { (0.0, 0.0) };
+        let (min, max) = match method {
+            CalibrationMethod::MinMax => (min, max),
+            CalibrationMethod::Percentile(p) => {
+                let threshold = self.threshold(p);
+                (min.max(-threshold), max.min(threshold))
+            }
+        };
+        (min.min(0.0), max.max(0.0))
+    }
+
+    /// Asymmetric i8 scale and zero point covering the range.
+    pub fn i8_params(&self, method: CalibrationMethod) -> (f32, i8) {
+        let (min, max) = self.range(method);
+        let scale = if max > min { (max - min) / 255.0 } else { 1.0 };
+        let zero_point = (-128.0 - min / scale).round().max(-128.0).min(127.0) as i8;
+        (scale, zero_point)
+    }
+}
+
+/// Statistics of every f32 outlet of a model, for post-training static
+/// quantization.
+#[derive(Debug, Clone, Default)]
+pub struct Calibration {
+    pub stats: HashMap<OutletId, OutletStats>,
+}
+
+impl Calibration {
+    /// Run the samples (one tensor per model input) through the model,
+    /// observing every f32 outlet.
+    pub fn collect<I>(model: &TypedModel, samples: I) -> TractResult<Calibration>
+    where
+        I: IntoIterator<Item = TVec<Tensor>>,
+    {
+        let plan = SimplePlan::new(model)?;
+        let mut calibration = Calibration::default();
+        for (ix, sample) in samples.into_iter().enumerate() {
+            let mut state = SimpleState::new(&plan)?;
+            state
+                .run_plan_with_eval(sample, |session, op_state, node, inputs| -> TractResult<_> {
+                    let outputs = crate::plan::eval(session, op_state, node, inputs)?;
+                    for (slot, output) in outputs.iter().enumerate() {
+                        if output.datum_type() == f32::datum_type() {
+                            calibration
+                                .stats
+                                .entry(OutletId::new(node.id, slot))
+                                .or_default()
+                                .observe(output.as_slice::<f32>()?);
+                        }
+                    }
+                    Ok(outputs)
+                })
+                .with_context(|| format!("Calibrating with sample #{}", ix))?;
+        }
+        Ok(calibration)
+    }
+
+    /// Rewrite the f32 MatMulUnary and ConvUnary of the calibrated model to
+    /// i8 weights and activations. Element-wise chains between them are then
+    /// folded to integer ops or lookup tables by declutter.
+    pub fn quantize(
+        &self,
+        model: &TypedModel,
+        method: CalibrationMethod,
+    ) -> TractResult<TypedModel> {
+        StaticQuantizer { calibration: self, method }.translate_model(model)?.declutter()
+    }
+}
+
+#[derive(Debug)]
+struct StaticQuantizer<'a> {
+    calibration: &'a Calibration,
+    method: CalibrationMethod,
+}
+
+/// Symmetric i8 quantization of constant weights, with a single scale.
+fn symmetric_i8(weights: &Tensor) -> TractResult<(Tensor, f32)> {
+    let values = weights.as_slice::<f32>()?;
+    let absmax = values.iter().fold(0f32, |m, x| m.max(x.abs()));
+    let scale = if absmax > 0.0 { absmax / 127.0 } else { 1.0 };
+    let values = values.iter().map(|x| (x / scale).round() as i8).collect::<Vec<_>>();
+    Ok((tensor1(&values).into_shape(weights.shape())?, scale))
+}
+
+impl<'a> StaticQuantizer<'a> {
+    fn params(&self, outlet: OutletId) -> Option<(f32, i8)> {
+        self.calibration.stats.get(&outlet).map(|s| s.i8_params(self.method))
+    }
+
+    fn quantized_op(
+        &self,
+        node: &TypedNode,
+        (in_scale, in_zp): (f32, i8),
+        (out_scale, out_zp): (f32, i8),
+    ) -> TractResult<Option<Box<dyn TypedOp>>> {
+        let q_params = |w_scale: f32| {
+            QParams::new(i8::datum_type())
+                .with_zero_point_b(&rctensor0(in_zp))
+                .with_zero_point_c(&rctensor0(out_zp))
+                .with_scale_factor(w_scale * in_scale / out_scale)
+        };
+        if let Some(op) = node.op_as::<MatMulUnary>() {
+            if op.a.datum_type() == f32::datum_type() && op.q_params.is_none() {
+                let (a, a_scale) = symmetric_i8(&op.a)?;
+                return Ok(Some(Box::new(MatMulUnary {
+                    a: a.into_arc_tensor(),
+                    q_params: Some(q_params(a_scale)),
+                    ..op.clone()
+                })));
+            }
+        } else if let Some(op) = node.op_as::<ConvUnary>() {
+            if op.kernel.datum_type() == f32::datum_type() && op.q_params.is_none() {
+                let (kernel, k_scale) = symmetric_i8(&op.kernel)?;
+                let bias = if let Some(bias) = &op.bias {
+                    let bias = bias.cast_to::<f32>()?;
+                    let bias = bias
+                        .as_slice::<f32>()?
+                        .iter()
+                        .map(|b| (b / (in_scale * k_scale)).round() as i32)
+                        .collect::<Vec<_>>();
+                    Some(rctensor1(&bias))
+                } else {
+                    None
+                };
+                return Ok(Some(Box::new(ConvUnary {
+                    kernel: kernel.into_arc_tensor(),
+                    bias,
+                    q_params: Some(q_params(k_scale)),
+                    ..op.clone()
+                })));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Quantize a f32 wire, unless it has just been dequantized with the
+    /// same parameters.
+    fn quantize_input(
+        &self,
+        target: &mut TypedModel,
+        name: &str,
+        wire: OutletId,
+        (scale, zero_point): (f32, i8),
+    ) -> TractResult<OutletId> {
+        let producer = target.node(wire.node);
+        if let Some(dequant) = producer.op_as::<DequantizeLinearF32>() {
+            if dequant.scale == scale
+                && dequant.zero_point == zero_point as i32
+                && target.outlet_fact(producer.inputs[0])?.datum_type == i8::datum_type()
+            {
+                return Ok(producer.inputs[0]);
+            }
+        }
+        Ok(target.wire_node(
+            format!("{}.quantize", name),
+            quantize_linear_i8(scale.recip(), zero_point),
+            &[wire],
+        )?[0])
+    }
+}
+
+impl<'a> Translate<TypedFact, Box<dyn TypedOp>, TypedFact, Box<dyn TypedOp>>
+    for StaticQuantizer<'a>
+{
+    fn translate_node(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        if inputs.len() == 1 {
+            if let (Some(input), Some(output)) =
+                (self.params(node.inputs[0]), self.params(OutletId::new(node.id, 0)))
+            {
+                if let Some(op) = self.quantized_op(node, input, output)? {
+                    let wire = self.quantize_input(target, &node.name, inputs[0], input)?;
+                    let wire = target.wire_node(&*node.name, op, &[wire])?[0];
+                    return target.wire_node(
+                        format!("{}.dequantize", node.name),
+                        DequantizeLinearF32::new(output.0, output.1 as i32),
+                        &[wire],
+                    );
+                }
+            }
+        }
+        target.wire_node(&*node.name, node.op.clone(), &inputs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::{KernelFormat, PaddingSpec, PoolSpec};
+    use crate::ops::element_wise::ElementWiseOp;
+    use crate::ops::nn::DataFormat;
+
+    fn data(shape: &[usize], factor: f32) -> Tensor {
+        let len = shape.iter().product::<usize>();
+        tensor1(&(0..len).map(|i| ((i * 7 % 13) as f32 - 6.0) / 3.0 * factor).collect::<Vec<_>>())
+            .into_shape(shape)
+            .unwrap()
+    }
+
+    fn check(model: TypedModel, shape: &[usize]) -> TractResult<TypedModel> {
+        let samples = [1.0, -0.5, 0.8].iter().map(|&f| tvec!(data(shape, f)));
+        let calibration = Calibration::collect(&model, samples)?;
+        let quantized = calibration.quantize(&model, CalibrationMethod::MinMax)?;
+        let input = data(shape, 0.9);
+        let float = SimplePlan::new(&model)?.run(tvec!(input.clone()))?.remove(0);
+        let found = SimplePlan::new(&quantized)?.run(tvec!(input))?.remove(0);
+        let float = float.as_slice::<f32>()?;
+        let absmax = float.iter().fold(0f32, |m, x| m.max(x.abs()));
+        for (f, q) in float.iter().zip(found.as_slice::<f32>()?) {
+            assert!((f - q).abs() <= 0.05 * absmax, "float:{} quantized:{}", f, q);
+        }
+        Ok(quantized)
+    }
+
+    #[test]
+    fn percentile_clips_outliers() {
+        let mut stats = OutletStats::default();
+        stats.observe(&(0..1000).map(|i| i as f32 / 1000.0).collect::<Vec<_>>());
+        stats.observe(&[-0.5, 1000.0]);
+        assert_eq!(stats.range(CalibrationMethod::MinMax), (-0.5, 1000.0));
+        let (min, max) = stats.range(CalibrationMethod::Percentile(99.0));
+        assert_eq!(min, -0.5);
+        assert!(max >= 0.98 && max < 1.5, "{}", max);
+        let (scale, zero_point) = stats.i8_params(CalibrationMethod::MinMax);
+        assert_eq!(((-0.5f32 / scale).round() as i32 + zero_point as i32), -128);
+    }
+
+    #[test]
+    fn chained_mat_muls() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let mut wire = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[7, 3]))?;
+        for (ix, a) in [data(&[5, 7], 1.0), data(&[4, 5], -0.7)].iter().enumerate() {
+            let op = MatMulUnary::new(a.clone().into_arc_tensor(), false, false, false, None);
+            wire = model.wire_node(format!("mm{}", ix), op, &[wire])?[0];
+        }
+        model.set_output_outlets(&[wire])?;
+        let quantized = check(model, &[7, 3])?;
+        let count =
+            |f: &dyn Fn(&TypedNode) -> bool| quantized.nodes().iter().filter(|n| f(n)).count();
+        assert_eq!(
+            count(&|n| n.op_as::<MatMulUnary>().map(|op| op.q_params.is_some()) == Some(true)),
+            2
+        );
+        assert_eq!(count(&|n| n.op_is::<ElementWiseOp>()), 1);
+        assert_eq!(count(&|n| n.op_is::<DequantizeLinearF32>()), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn conv_with_bias() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[1, 4, 6, 6]))?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::NCHW, tvec!(3, 3), PaddingSpec::Valid, None, None, Some(6)),
+            KernelFormat::OIHW,
+            data(&[6, 2, 3, 3], 1.0).into_arc_tensor(),
+            2,
+            Some(rctensor1(&[0.5f32, -1.0, 0.0, 1.0, 2.0, -0.5])),
+            None,
+        );
+        let y = model.wire_node("conv", conv, &[x])?;
+        model.set_output_outlets(&y)?;
+        let quantized = check(model, &[1, 4, 6, 6])?;
+        let conv = quantized.node_by_name("conv")?.op_as::<ConvUnary>().unwrap();
+        assert_eq!(conv.kernel.datum_type(), i8::datum_type());
+        assert_eq!(conv.bias.as_ref().unwrap().datum_type(), i32::datum_type());
+        Ok(())
+    }
+}
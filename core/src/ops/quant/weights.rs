@@ -0,0 +1,475 @@
+use crate::internal::*;
+use crate::model::translator::Translate;
+use crate::ops::cnn::ConvUnary;
+use crate::ops::matmul::lir_unary::LirMatMulUnary;
+use crate::ops::matmul::pack::MatMatMulPack;
+use crate::ops::matmul::pack_cache;
+use crate::ops::matmul::{compute_shape, MatMulUnary};
+use tract_linalg::mmm::WeightQuant;
+use tract_ndarray::prelude::*;
+
+/// Quantize the constant f32 weights of the matrix products and
+/// convolutions of `model`, activations staying in f32.
+pub fn quantize_weights(model: &TypedModel, quant: WeightQuant) -> TractResult<TypedModel> {
+    quant.translate_model(model)
+}
+
+impl Translate<TypedFact, Box<dyn TypedOp>, TypedFact, Box<dyn TypedOp>> for WeightQuant {
+    fn translate_node(
+        &self,
+        _source: &TypedModel,
+        node: &TypedNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        let op: Option<Box<dyn TypedOp>> = if let Some(op) = node.op_as::<MatMulUnary>() {
+            WeightOnlyMatMulUnary::quantize(op, *self)?.map(|op| Box::new(op) as _)
+        } else if let Some(op) = node.op_as::<ConvUnary>() {
+            WeightOnlyConvUnary::quantize(op, *self)?.map(|op| Box::new(op) as _)
+        } else {
+            None
+        };
+        target.wire_node(&*node.name, op.unwrap_or_else(|| node.op.clone()), &inputs)
+    }
+}
+
+/// Stack one tensor per prefix coordinates into a single tensor.
+fn stack_at_prefix(prefix: &[usize], items: Vec<Tensor>) -> TractResult<Tensor> {
+    let mut shape: TVec<usize> = prefix.into();
+    shape.extend(items[0].shape().iter().copied());
+    let items = items
+        .into_iter()
+        .map(|mut t| {
+            t.insert_axis(0)?;
+            Ok(t)
+        })
+        .collect::<TractResult<Vec<_>>>()?;
+    Ok(Tensor::stack_tensors(0, &items)?.into_shape(&shape)?)
+}
+
+/// Matrix product where the constant A is stored as i8 or i4 values with f32
+/// scales. B is the f32 input.
+#[derive(Debug, Clone, Hash)]
+pub struct WeightOnlyMatMulUnary {
+    /// i8 values, with the shape of the original f32 A.
+    pub values: Arc<Tensor>,
+    /// f32 scales, shaped `[..prefix, m, groups]`.
+    pub scales: Arc<Tensor>,
+    pub quant: WeightQuant,
+    pub a_trans: bool,
+    pub b_trans: bool,
+    pub c_trans: bool,
+}
+
+impl_dyn_hash!(WeightOnlyMatMulUnary);
+
+impl WeightOnlyMatMulUnary {
+    pub fn quantize(op: &MatMulUnary, quant: WeightQuant) -> TractResult<Option<Self>> {
+        if op.a.datum_type() != f32::datum_type() || op.q_params.is_some() {
+            return Ok(None);
+        }
+        let prefix = &op.a.shape()[..op.a.rank() - 2];
+        let mut values = vec![];
+        let mut scales = vec![];
+        for coords in indices(prefix) {
+            let (v, s) = quant.quantize(
+                &op.a.view_at_prefix(coords.slice())?,
+                op.a_trans as usize,
+                !op.a_trans as usize,
+            )?;
+            values.push(v);
+            scales.push(s);
+        }
+        Ok(Some(WeightOnlyMatMulUnary {
+            values: stack_at_prefix(prefix, values)?.into_arc_tensor(),
+            scales: stack_at_prefix(prefix, scales)?.into_arc_tensor(),
+            quant,
+            a_trans: op.a_trans,
+            b_trans: op.b_trans,
+            c_trans: op.c_trans,
+        }))
+    }
+
+    /// Expand the weights back to a f32 A.
+    pub fn dequantize(&self) -> TractResult<Tensor> {
+        let prefix = &self.values.shape()[..self.values.rank() - 2];
+        let a = indices(prefix)
+            .into_iter()
+            .map(|coords| {
+                self.quant.dequantize(
+                    &self.values.view_at_prefix(coords.slice())?,
+                    &self.scales.view_at_prefix(coords.slice())?,
+                    self.a_trans as usize,
+                    !self.a_trans as usize,
+                )
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        stack_at_prefix(prefix, a)
+    }
+
+    fn new_mat_mul_unary_finite(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+        b_shape: &[usize],
+    ) -> TractResult<TypedModelPatch> {
+        let mut patch = TypedModelPatch::default();
+        let mut wire = patch.tap_model(model, node.inputs[0])?;
+        let (m, k, n, c_shape) =
+            compute_shape(&self.values.shape(), b_shape, self.a_trans, self.b_trans, self.c_trans)?;
+        let mut mm = tract_linalg::ops().mmm_f32(m, k, n);
+        let packer = mm.a_weights_pack(self.quant);
+        let values_hash = pack_cache::content_hash(&self.values);
+        let prefix_shape = &self.values.shape()[0..self.values.rank() - 2];
+        let mut packed_as = vec![];
+        for a_prefix in tract_ndarray::indices(prefix_shape) {
+            let geometry =
+                (packer.clone(), a_prefix.slice().to_vec(), self.a_trans, self.scales.clone());
+            packed_as.push(pack_cache::packed(&self.values, values_hash, geometry, || unsafe {
+                let mut pa =
+                    Tensor::uninitialized_aligned::<u8>(&[packer.len(m)], packer.alignment())?;
+                packer.pack(
+                    &mut pa.view_mut(),
+                    &self.values.view_at_prefix(a_prefix.slice())?,
+                    &self.scales.view_at_prefix(a_prefix.slice())?,
+                    !self.a_trans as usize,
+                    self.a_trans as usize,
+                );
+                Ok(pa)
+            })?);
+        }
+        let packed_as = Array::from_shape_vec(prefix_shape, packed_as)?;
+        unsafe {
+            mm.a_from_quantized_weights(self.quant);
+            mm.c_from_data_and_strides(
+                if self.c_trans { 1 } else { *c_shape.last().unwrap() as isize },
+                if !self.c_trans { 1 } else { *c_shape.last().unwrap() as isize },
+            );
+        }
+        let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
+        packed_b_shape.push(mm.b_pack().len(n));
+        wire = patch.wire_node(
+            format!("{}.pack", &*node.name),
+            MatMatMulPack {
+                packer: mm.b_pack(),
+                trans: self.b_trans,
+                output_shape: packed_b_shape,
+            },
+            &[wire],
+        )?[0];
+        let c_prefix_dim_and_stride =
+            crate::ops::matmul::mir_unary::c_prefix_dim_and_stride(&c_shape)
+                .map(|(dim, strides)| (dim.into(), strides.into()));
+        wire = patch.wire_node(
+            format!("{}.matmatmul", &*node.name),
+            LirMatMulUnary {
+                c_trans: self.c_trans,
+                c_fact: TypedFact::dt_shape(f32::datum_type(), &c_shape),
+                c_prefix_dim_and_stride,
+                packed_as,
+                fused_ops: None,
+                mmm: mm,
+                k,
+            },
+            &[wire],
+        )?[0];
+        patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
+        Ok(patch)
+    }
+}
+
+impl Op for WeightOnlyMatMulUnary {
+    fn name(&self) -> Cow<str> {
+        "WeightOnlyMatMulUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!(
+                "a_trans:{:?} b_trans:{:?} c_trans:{:?}",
+                self.a_trans, self.b_trans, self.c_trans
+            ),
+            format!("A: {:?} {}", self.values.shape(), self.quant),
+        ])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for WeightOnlyMatMulUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        MatMulUnary::new(
+            self.dequantize()?.into_arc_tensor(),
+            self.a_trans,
+            self.b_trans,
+            self.c_trans,
+            None,
+        )
+        .eval(inputs)
+    }
+}
+
+impl TypedOp for WeightOnlyMatMulUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != f32::datum_type() {
+            bail!("Weight-only quantized products expect a f32 input, got {:?}", inputs[0]);
+        }
+        if inputs[0].rank() != self.values.rank() {
+            bail!(
+                "Inconsistent matmul between input {:?} and attribute {:?} (rank mismatch)",
+                inputs[0],
+                self.values
+            );
+        }
+        let (_m, _k, _n, c_shape) = compute_shape(
+            &self.values.shape().iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
+            &inputs[0].shape,
+            self.a_trans,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), c_shape)))
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = crate::ops::matmul::mir::cost(
+            self.values.shape(),
+            &inputs[0].shape.to_tvec(),
+            f32::datum_type(),
+            self.a_trans,
+            self.b_trans,
+        )?;
+        cost.push((Cost::Params(i8::datum_type()), self.values.len().to_dim()));
+        cost.push((Cost::Params(f32::datum_type()), self.scales.len().to_dim()));
+        Ok(cost)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let b = args_1!(model.node_input_facts(node.id)?);
+        if let Some(b_shape) = b.shape.as_concrete() {
+            return Ok(Some(self.new_mat_mul_unary_finite(model, node, &b_shape)?));
+        }
+        Ok(None)
+    }
+
+    as_op!();
+}
+
+/// Convolution with a constant kernel stored as i8 or i4 values with f32
+/// scales. The input and output stay f32.
+#[derive(Debug, Clone, Hash)]
+pub struct WeightOnlyConvUnary {
+    /// The convolution, with a i8 OIHW kernel.
+    pub conv: ConvUnary,
+    /// f32 scales, shaped `[output_channels, groups]`.
+    pub scales: Arc<Tensor>,
+    pub quant: WeightQuant,
+}
+
+impl_dyn_hash!(WeightOnlyConvUnary);
+
+impl WeightOnlyConvUnary {
+    pub fn quantize(op: &ConvUnary, quant: WeightQuant) -> TractResult<Option<Self>> {
+        if op.kernel.datum_type() != f32::datum_type() || op.q_params.is_some() {
+            return Ok(None);
+        }
+        let kernel = op.kernel_as_group_o_ihw()?;
+        let (o, k) = (kernel.shape()[0] * kernel.shape()[1], kernel.shape()[2]);
+        let kernel = kernel.into_tensor().into_shape(&[o, k])?;
+        let (values, scales) = quant.quantize(&kernel.view(), 0, 1)?;
+        let mut oihw: TVec<usize> =
+            tvec!(o, k / op.pool_spec.kernel_shape.iter().product::<usize>());
+        oihw.extend(op.pool_spec.kernel_shape.iter().copied());
+        Ok(Some(WeightOnlyConvUnary {
+            conv: ConvUnary {
+                kernel_fmt: crate::ops::cnn::KernelFormat::OIHW,
+                kernel: values.into_shape(&oihw)?.into_arc_tensor(),
+                ..op.clone()
+            },
+            scales: scales.into_arc_tensor(),
+            quant,
+        }))
+    }
+
+    /// Expand the weights back to a f32 convolution.
+    pub fn dequantize(&self) -> TractResult<ConvUnary> {
+        let shape = self.conv.kernel.shape();
+        let values = self
+            .conv
+            .kernel
+            .clone()
+            .into_tensor()
+            .into_shape(&[shape[0], self.conv.kernel.len() / shape[0]])?;
+        let kernel =
+            self.quant.dequantize(&values.view(), &self.scales.view(), 0, 1)?.into_shape(shape)?;
+        Ok(ConvUnary { kernel: kernel.into_arc_tensor(), ..self.conv.clone() })
+    }
+}
+
+impl Op for WeightOnlyConvUnary {
+    fn name(&self) -> Cow<str> {
+        "WeightOnlyConvUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut info = self.conv.info()?;
+        info.push(format!("Weights: {}", self.quant));
+        Ok(info)
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for WeightOnlyConvUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.dequantize()?.eval(inputs)
+    }
+}
+
+impl TypedOp for WeightOnlyConvUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if inputs[0].datum_type != f32::datum_type() {
+            bail!("Weight-only quantized convolutions expect a f32 input, got {:?}", inputs[0]);
+        }
+        self.conv.output_facts(inputs)
+    }
+
+    fn cost(&self, inputs: &[&TypedFact]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = self
+            .conv
+            .cost(inputs)?
+            .into_iter()
+            .filter(|(c, _)| if let Cost::Params(_) = c { false } else { true })
+            .collect::<TVec<_>>();
+        cost.push((Cost::Params(i8::datum_type()), self.conv.kernel.len().to_dim()));
+        cost.push((Cost::Params(f32::datum_type()), self.scales.len().to_dim()));
+        Ok(cost)
+    }
+
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if model.outlet_fact(node.inputs[0])?.shape.as_concrete().is_none() {
+            return Ok(None);
+        }
+        let mut patch = TypedModelPatch::default();
+        let wire = patch.tap_model(model, node.inputs[0])?;
+        let wire = unsafe {
+            self.conv
+                .wire_as_weight_only_im2col_pair(
+                    &mut patch,
+                    &*node.name,
+                    wire,
+                    &self.scales,
+                    self.quant,
+                )
+                .context("in wire_as_weight_only_im2col_pair")?
+        };
+        patch.shunt_outside(model, OutletId::new(node.id, 0), wire)?;
+        Ok(Some(patch))
+    }
+
+    as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::{KernelFormat, PaddingSpec, PoolSpec};
+    use crate::ops::nn::DataFormat;
+    use tract_linalg::mmm::WeightFormat;
+
+    fn close(found: &Tensor, expected: &Tensor) -> TractResult<()> {
+        found.close_enough(expected, true)
+    }
+
+    fn run(model: &TypedModel, input: Tensor) -> TractResult<Tensor> {
+        let mut outputs = SimplePlan::new(model)?.run(tvec!(input))?;
+        Ok(outputs.remove(0).into_tensor())
+    }
+
+    /// Inputs and weights are in [-2, 2]: each product error is bounded by
+    /// the input magnitude times half a quantization step.
+    fn check(model: TypedModel, input: Tensor, quant: WeightQuant, k: usize) -> TractResult<()> {
+        let quantized = quantize_weights(&model, quant)?;
+        assert!(quantized
+            .nodes()
+            .iter()
+            .any(|n| n.op_is::<WeightOnlyMatMulUnary>() || n.op_is::<WeightOnlyConvUnary>()));
+        let reference = run(&quantized, input.clone())?;
+        let optimized = quantized.into_optimized()?;
+        assert!(optimized.nodes().iter().any(|n| n.op_is::<LirMatMulUnary>()));
+        close(&run(&optimized, input.clone())?, &reference)?;
+        let float = run(&model, input)?;
+        let bound = k as f32 * 2.0 * 2.0 / (2.0 * quant.format.max() as f32) + 1e-4;
+        for (f, q) in float.as_slice::<f32>()?.iter().zip(reference.as_slice::<f32>()?) {
+            assert!((f - q).abs() <= bound, "float:{} quantized:{}", f, q);
+        }
+        Ok(())
+    }
+
+    fn mat_mul_model(a: Tensor, b_shape: &[usize], a_trans: bool) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let b = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), b_shape))?;
+        let c = model.wire_node(
+            "mm",
+            MatMulUnary::new(a.into_arc_tensor(), a_trans, false, false, None),
+            &[b],
+        )?;
+        model.set_output_outlets(&c)?;
+        Ok(model)
+    }
+
+    fn data(shape: &[usize]) -> Tensor {
+        let len = shape.iter().product::<usize>();
+        tensor1(&(0..len).map(|i| ((i * 7 % 13) as f32 - 6.0) / 3.0).collect::<Vec<_>>())
+            .into_shape(shape)
+            .unwrap()
+    }
+
+    #[test]
+    fn mat_mul_i8_per_channel() -> TractResult<()> {
+        let model = mat_mul_model(data(&[5, 7]), &[7, 3], false)?;
+        check(model, data(&[7, 3]), WeightQuant::per_channel(WeightFormat::I8), 7)
+    }
+
+    #[test]
+    fn mat_mul_i4_groups_trans_prefix() -> TractResult<()> {
+        let model = mat_mul_model(data(&[2, 9, 4]), &[2, 9, 6], true)?;
+        check(model, data(&[2, 9, 6]), WeightQuant::per_group(WeightFormat::I4, 4), 9)
+    }
+
+    #[test]
+    fn conv_i4_groups() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let x = model.add_source("x", TypedFact::dt_shape(f32::datum_type(), &[1, 4, 6, 6]))?;
+        let conv = ConvUnary::new(
+            PoolSpec::new(DataFormat::NCHW, tvec!(3, 3), PaddingSpec::Valid, None, None, Some(6)),
+            KernelFormat::OIHW,
+            data(&[6, 2, 3, 3]).into_arc_tensor(),
+            2,
+            Some(rctensor1(&[0.5f32, -1.0, 0.0, 1.0, 2.0, -0.5])),
+            None,
+        );
+        let y = model.wire_node("conv", conv, &[x])?;
+        model.set_output_outlets(&y)?;
+        check(model, data(&[1, 4, 6, 6]), WeightQuant::per_group(WeightFormat::I4, 8), 18)
+    }
+}
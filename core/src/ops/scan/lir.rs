@@ -262,6 +262,26 @@ impl OpState for State {
 
         Ok(outputs.into_iter().map(Arc::new).collect())
     }
+
+    // position, hidden states count, hidden states, then the body state
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mutable = &self.mutable;
+        let mut tensors =
+            tvec!(tensor0(mutable.position as i64), tensor0(mutable.hidden_state.len() as i64));
+        tensors.extend(mutable.hidden_state.iter().cloned());
+        tensors.extend(mutable.model_state.snapshot()?.to_tensors()?);
+        Ok(tensors)
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let mut tensors = tensors.into_iter();
+        let mut next = || tensors.next().ok_or_else(|| format_err!("Truncated scan state"));
+        self.mutable.position = *next()?.to_scalar::<i64>()? as usize;
+        let hidden = *next()?.to_scalar::<i64>()? as usize;
+        self.mutable.hidden_state = (0..hidden).map(|_| next()).collect::<TractResult<_>>()?;
+        let body = StateSnapshot::from_tensors(tensors)?;
+        self.mutable.model_state.restore(&body)
+    }
 }
 
 impl TypedOp for LirScan {
@@ -0,0 +1,227 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// Discrete Fourier transform along `axis`.
+///
+/// Complex values are represented by an extra trailing axis: input can be real
+/// (trailing axis of size 1) or complex (size 2), output is always complex.
+/// In `onesided` mode, only the `n / 2 + 1` non-redundant bins are computed.
+/// The inverse transform is normalized by `1 / n`.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Dft {
+    pub axis: usize,
+    pub inverse: bool,
+    pub onesided: bool,
+}
+
+impl_dyn_hash!(Dft);
+
+impl Dft {
+    fn output_len<D: DimLike>(&self, n: &D) -> D {
+        if self.onesided {
+            n.clone() / 2 + 1
+        } else {
+            n.clone()
+        }
+    }
+
+    fn eval_t<T: Datum + num_traits::Float>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?;
+        let last = input.ndim() - 1;
+        let complex = input.shape()[last] == 2;
+        let n = input.shape()[self.axis];
+        let mut output_shape = input.shape().to_vec();
+        output_shape[self.axis] = self.output_len(&n);
+        output_shape[last] = 2;
+        let mut output = ArrayD::<T>::zeros(&*output_shape);
+        let mut iter_shape = input.shape().to_vec();
+        iter_shape[self.axis] = 1;
+        iter_shape[last] = 1;
+        let mut buffer = vec![(0f64, 0f64); n];
+        for coords in indices(&*iter_shape) {
+            let mut coords: TVec<usize> = coords.slice().into();
+            for i in 0..n {
+                coords[self.axis] = i;
+                coords[last] = 0;
+                let re = input[&*coords].to_f64().unwrap();
+                let im = if complex {
+                    coords[last] = 1;
+                    input[&*coords].to_f64().unwrap()
+                } else {
+                    0.0
+                };
+                buffer[i] = (re, im);
+            }
+            transform(&mut buffer, self.inverse);
+            for i in 0..output_shape[self.axis] {
+                coords[self.axis] = i;
+                coords[last] = 0;
+                output[&*coords] = num_traits::cast(buffer[i].0).unwrap();
+                coords[last] = 1;
+                output[&*coords] = num_traits::cast(buffer[i].1).unwrap();
+            }
+        }
+        Ok(output.into_tensor())
+    }
+}
+
+/// In place transform: radix-2 FFT for powers of two, plain DFT otherwise.
+fn transform(data: &mut [(f64, f64)], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    if n.is_power_of_two() {
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        let mut len = 2;
+        while len <= n {
+            let angle = sign * 2.0 * std::f64::consts::PI / len as f64;
+            for start in (0..n).step_by(len) {
+                for k in 0..len / 2 {
+                    let (wr, wi) = ((angle * k as f64).cos(), (angle * k as f64).sin());
+                    let (ar, ai) = data[start + k];
+                    let (br, bi) = data[start + k + len / 2];
+                    let (tr, ti) = (br * wr - bi * wi, br * wi + bi * wr);
+                    data[start + k] = (ar + tr, ai + ti);
+                    data[start + k + len / 2] = (ar - tr, ai - ti);
+                }
+            }
+            len <<= 1;
+        }
+    } else {
+        let input = data.to_vec();
+        for k in 0..n {
+            let mut acc = (0.0, 0.0);
+            for (t, &(re, im)) in input.iter().enumerate() {
+                let angle = sign * 2.0 * std::f64::consts::PI * ((k * t) % n) as f64 / n as f64;
+                let (wr, wi) = (angle.cos(), angle.sin());
+                acc.0 += re * wr - im * wi;
+                acc.1 += re * wi + im * wr;
+            }
+            data[k] = acc;
+        }
+    }
+    if inverse {
+        data.iter_mut().for_each(|c| {
+            c.0 /= n as f64;
+            c.1 /= n as f64;
+        });
+    }
+}
+
+impl Op for Dft {
+    fn name(&self) -> Cow<str> {
+        "Dft".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis:{} inverse:{:?} onesided:{:?}",
+            self.axis, self.inverse, self.onesided
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Dft {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let output = match inputs[0].datum_type() {
+            DatumType::F32 => self.eval_t::<f32>(&inputs[0])?,
+            DatumType::F64 => self.eval_t::<f64>(&inputs[0])?,
+            dt => bail!("Dft does not support {:?}", dt),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Dft {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        if rank < 2 || self.axis >= rank - 1 {
+            bail!("Dft expects a trailing complex axis, and axis {} to be before it", self.axis);
+        }
+        if inputs[0].shape[rank - 1] != 1.to_dim() && inputs[0].shape[rank - 1] != 2.to_dim() {
+            bail!("Dft trailing axis must be 1 (real) or 2 (complex), got {:?}", inputs[0]);
+        }
+        if self.inverse && self.onesided {
+            bail!("Onesided inverse Dft is not supported");
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[self.axis] = self.output_len(&shape[self.axis]);
+        shape[rank - 1] = 2.to_dim();
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn naive(signal: &[f32]) -> Vec<(f64, f64)> {
+        let n = signal.len();
+        (0..n)
+            .map(|k| {
+                signal.iter().enumerate().fold((0.0, 0.0), |acc, (t, &x)| {
+                    let angle = -2.0 * std::f64::consts::PI * (k * t) as f64 / n as f64;
+                    (acc.0 + x as f64 * angle.cos(), acc.1 + x as f64 * angle.sin())
+                })
+            })
+            .collect()
+    }
+
+    fn check(len: usize) {
+        let signal: Vec<f32> = (0..len).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let input = tensor1(&signal).into_shape(&[len, 1]).unwrap();
+        let output = Dft::new(0, false, false).eval(tvec!(input.into_arc_tensor())).unwrap();
+        let output = output[0].as_slice::<f32>().unwrap();
+        for (k, (re, im)) in naive(&signal).into_iter().enumerate() {
+            assert!((output[2 * k] as f64 - re).abs() < 1e-4);
+            assert!((output[2 * k + 1] as f64 - im).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn fft_power_of_two() {
+        check(16)
+    }
+
+    #[test]
+    fn dft_odd_length() {
+        check(9)
+    }
+
+    #[test]
+    fn onesided_then_inverse() {
+        let input = tensor1(&[1f32, 2., 3., 4.]).into_shape(&[4, 1]).unwrap();
+        let onesided =
+            Dft::new(0, false, true).eval(tvec!(input.clone().into_arc_tensor())).unwrap();
+        assert_eq!(onesided[0].shape(), &[3, 2]);
+        let full = Dft::new(0, false, false).eval(tvec!(input.into_arc_tensor())).unwrap();
+        let back = Dft::new(0, true, false).eval(full).unwrap();
+        let back = back[0].as_slice::<f32>().unwrap();
+        for (i, v) in [1f32, 2., 3., 4.].iter().enumerate() {
+            assert!((back[2 * i] - v).abs() < 1e-5);
+            assert!(back[2 * i + 1].abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::internal::*;
+use crate::ops::array::{Pad, PadMode, Slice, TypedConcat};
+use crate::ops::math;
+use crate::ops::matmul::MatMulUnary;
+use crate::ops::nn::{Reduce, Reducer};
+
+use super::{mel, Dft, Frame, Window};
+
+/// Kaldi `compute-fbank-feats` options.
+///
+/// `Default` matches Kaldi defaults, except for dithering which is not
+/// supported (compare with Kaldi output computed with `--dither=0`).
+#[derive(Clone, Debug)]
+pub struct FbankConfig {
+    pub sample_rate: f32,
+    pub frame_length_ms: f32,
+    pub frame_shift_ms: f32,
+    pub preemphasis_coefficient: f32,
+    pub remove_dc_offset: bool,
+    pub window: Window,
+    pub round_to_power_of_two: bool,
+    pub num_mel_bins: usize,
+    pub low_freq: f32,
+    pub high_freq: f32,
+    pub use_power: bool,
+    pub use_log_fbank: bool,
+    pub use_energy: bool,
+}
+
+impl Default for FbankConfig {
+    fn default() -> FbankConfig {
+        FbankConfig {
+            sample_rate: 16000.0,
+            frame_length_ms: 25.0,
+            frame_shift_ms: 10.0,
+            preemphasis_coefficient: 0.97,
+            remove_dc_offset: true,
+            window: Window::Povey,
+            round_to_power_of_two: true,
+            num_mel_bins: 23,
+            low_freq: 20.0,
+            high_freq: 0.0,
+            use_power: true,
+            use_log_fbank: true,
+            use_energy: false,
+        }
+    }
+}
+
+/// Kaldi `compute-mfcc-feats` options. `fbank.use_power` and
+/// `fbank.use_log_fbank` are ignored, Kaldi always uses log power here.
+#[derive(Clone, Debug)]
+pub struct MfccConfig {
+    pub fbank: FbankConfig,
+    pub num_ceps: usize,
+    pub cepstral_lifter: f32,
+    pub use_energy: bool,
+}
+
+impl Default for MfccConfig {
+    fn default() -> MfccConfig {
+        MfccConfig {
+            fbank: FbankConfig::default(),
+            num_ceps: 13,
+            cepstral_lifter: 22.0,
+            use_energy: true,
+        }
+    }
+}
+
+impl FbankConfig {
+    pub fn window_size(&self) -> usize {
+        (self.sample_rate * 0.001 * self.frame_length_ms) as usize
+    }
+
+    pub fn window_shift(&self) -> usize {
+        (self.sample_rate * 0.001 * self.frame_shift_ms) as usize
+    }
+
+    pub fn padded_window_size(&self) -> usize {
+        if self.round_to_power_of_two {
+            self.window_size().next_power_of_two()
+        } else {
+            self.window_size()
+        }
+    }
+
+    /// Wires the filterbank computation. Input is a f32 waveform with time
+    /// on its last axis (samples are expected in 16-bit range, as Kaldi
+    /// does). Output replaces time by frames and appends the feature axis.
+    pub fn wire(
+        &self,
+        model: &mut TypedModel,
+        prefix: &str,
+        input: OutletId,
+    ) -> TractResult<OutletId> {
+        let (wire, energy) = self.wire_power_spectrum(model, prefix, input, self.use_energy)?;
+        let wire = self.wire_mel(model, prefix, wire, self.use_log_fbank)?;
+        if let Some(energy) = energy {
+            let rank = model.outlet_fact(wire)?.rank();
+            Ok(model.wire_node(prefix, TypedConcat::concat_vars(rank - 1, 2), &[energy, wire])?[0])
+        } else {
+            Ok(wire)
+        }
+    }
+
+    /// Framing, DC removal, preemphasis, windowing and power spectrum, plus
+    /// the raw log-energy of each frame if requested.
+    fn wire_power_spectrum(
+        &self,
+        model: &mut TypedModel,
+        prefix: &str,
+        input: OutletId,
+        with_energy: bool,
+    ) -> TractResult<(OutletId, Option<OutletId>)> {
+        let window_size = self.window_size();
+        let padded = self.padded_window_size();
+        let rank = model.outlet_fact(input)?.rank() + 1;
+        let axis = rank - 1;
+        let mut wire = model.wire_node(
+            format!("{}.frame", prefix),
+            Frame::new(axis - 1, window_size, self.window_shift()),
+            &[input],
+        )?[0];
+        if self.remove_dc_offset {
+            let sum = model.wire_node(
+                format!("{}.dc.sum", prefix),
+                Reduce::new(tvec!(axis), Reducer::Sum),
+                &[wire],
+            )?;
+            let mean = model.wire_node(
+                format!("{}.dc.mean", prefix),
+                math::mul::unary(broadcast(tensor0(1.0 / window_size as f32), rank)?),
+                &sum,
+            )?[0];
+            wire =
+                model.wire_node(format!("{}.dc", prefix), math::sub::bin_typed(), &[wire, mean])?
+                    [0];
+        }
+        let energy = if with_energy {
+            let sqr = model.wire_node(format!("{}.energy.sqr", prefix), math::square(), &[wire])?;
+            let sum = model.wire_node(
+                format!("{}.energy.sum", prefix),
+                Reduce::new(tvec!(axis), Reducer::Sum),
+                &sqr,
+            )?;
+            Some(wire_log(model, &format!("{}.energy", prefix), sum[0], rank)?)
+        } else {
+            None
+        };
+        if self.preemphasis_coefficient != 0.0 {
+            let first = model.wire_node(
+                format!("{}.preemph.first", prefix),
+                Slice::new(axis, 0, 1),
+                &[wire],
+            )?;
+            let previous = model.wire_node(
+                format!("{}.preemph.previous", prefix),
+                Slice::new(axis, 0, window_size - 1),
+                &[wire],
+            )?;
+            let shifted = model.wire_node(
+                format!("{}.preemph.shifted", prefix),
+                TypedConcat::concat_vars(axis, 2),
+                &[first[0], previous[0]],
+            )?;
+            let scaled = model.wire_node(
+                format!("{}.preemph.scaled", prefix),
+                math::mul::unary(broadcast(tensor0(self.preemphasis_coefficient), rank)?),
+                &shifted,
+            )?[0];
+            wire = model.wire_node(
+                format!("{}.preemph", prefix),
+                math::sub::bin_typed(),
+                &[wire, scaled],
+            )?[0];
+        }
+        if self.window != Window::Rectangular {
+            wire = model.wire_node(
+                format!("{}.window", prefix),
+                math::mul::unary(broadcast(self.window.tensor(window_size), rank)?),
+                &[wire],
+            )?[0];
+        }
+        if padded > window_size {
+            let mut pads = vec![(0, 0); rank];
+            pads[axis] = (0, padded - window_size);
+            wire = model.wire_node(
+                format!("{}.pad", prefix),
+                Pad::new(pads, PadMode::Constant(rctensor0(0f32))),
+                &[wire],
+            )?[0];
+        }
+        wire = model.wire_node(format!("{}.complex", prefix), AxisOp::Add(rank), &[wire])?[0];
+        wire = model.wire_node(format!("{}.fft", prefix), Dft::new(axis, false, true), &[wire])?[0];
+        wire = model.wire_node(format!("{}.fft.sqr", prefix), math::square(), &[wire])?[0];
+        wire = model.wire_node(
+            format!("{}.power.sum", prefix),
+            Reduce::new(tvec!(rank), Reducer::Sum),
+            &[wire],
+        )?[0];
+        wire = model.wire_node(format!("{}.power", prefix), AxisOp::Rm(rank), &[wire])?[0];
+        Ok((wire, energy))
+    }
+
+    fn wire_mel(
+        &self,
+        model: &mut TypedModel,
+        prefix: &str,
+        spectrum: OutletId,
+        log: bool,
+    ) -> TractResult<OutletId> {
+        let rank = model.outlet_fact(spectrum)?.rank();
+        let mut wire = spectrum;
+        if !self.use_power {
+            wire = model.wire_node(format!("{}.magnitude", prefix), math::sqrt(), &[wire])?[0];
+        }
+        let banks = mel::kaldi_mel_banks(
+            self.num_mel_bins,
+            self.padded_window_size(),
+            self.sample_rate as f64,
+            self.low_freq as f64,
+            self.high_freq as f64,
+        )?;
+        wire = wire_right_product(model, &format!("{}.mel", prefix), wire, banks)?;
+        if log {
+            wire = wire_log(model, &format!("{}.log", prefix), wire, rank)?;
+        }
+        Ok(wire)
+    }
+}
+
+impl MfccConfig {
+    /// Wires the MFCC computation, see `FbankConfig::wire`.
+    pub fn wire(
+        &self,
+        model: &mut TypedModel,
+        prefix: &str,
+        input: OutletId,
+    ) -> TractResult<OutletId> {
+        if self.num_ceps > self.fbank.num_mel_bins {
+            bail!("num_ceps ({}) can not exceed num_mel_bins", self.num_ceps)
+        }
+        let fbank = FbankConfig { use_power: true, ..self.fbank.clone() };
+        let (wire, energy) = fbank.wire_power_spectrum(model, prefix, input, self.use_energy)?;
+        let wire = fbank.wire_mel(model, prefix, wire, true)?;
+        let rank = model.outlet_fact(wire)?.rank();
+        let dct = mel::dct_matrix(fbank.num_mel_bins, self.num_ceps, true);
+        let mut wire = wire_right_product(model, &format!("{}.dct", prefix), wire, dct)?;
+        if self.cepstral_lifter != 0.0 {
+            let lifter = mel::kaldi_lifter(self.num_ceps, self.cepstral_lifter as f64);
+            wire = model.wire_node(
+                format!("{}.lifter", prefix),
+                math::mul::unary(broadcast(lifter, rank)?),
+                &[wire],
+            )?[0];
+        }
+        if let Some(energy) = energy {
+            let ceps = model.wire_node(
+                format!("{}.ceps", prefix),
+                Slice::new(rank - 1, 1, self.num_ceps),
+                &[wire],
+            )?[0];
+            wire =
+                model.wire_node(prefix, TypedConcat::concat_vars(rank - 1, 2), &[energy, ceps])?[0];
+        }
+        Ok(wire)
+    }
+}
+
+fn broadcast(tensor: Tensor, rank: usize) -> TractResult<Arc<Tensor>> {
+    Ok(tensor.broadcast_into_rank(rank)?.into_arc_tensor())
+}
+
+/// Kaldi flooring and log: `ln(max(x, f32::EPSILON))`.
+fn wire_log(
+    model: &mut TypedModel,
+    prefix: &str,
+    input: OutletId,
+    rank: usize,
+) -> TractResult<OutletId> {
+    let floored = model.wire_node(
+        format!("{}.floor", prefix),
+        math::max::unary(broadcast(tensor0(std::f32::EPSILON), rank)?),
+        &[input],
+    )?;
+    Ok(model.wire_node(prefix, math::ln(), &floored)?[0])
+}
+
+/// Computes `input . matrix`, with input features on the last axis.
+pub(super) fn wire_right_product(
+    model: &mut TypedModel,
+    prefix: &str,
+    input: OutletId,
+    matrix: Tensor,
+) -> TractResult<OutletId> {
+    let rank = model.outlet_fact(input)?.rank();
+    let matrix = broadcast(matrix, rank)?;
+    Ok(model.wire_node(prefix, MatMulUnary::new(matrix, true, true, true, None), &[input])?[0])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fbank_shapes() {
+        let mut model = TypedModel::default();
+        let source =
+            model.add_source("wav", TypedFact::dt_shape(f32::datum_type(), &[16000])).unwrap();
+        let fbank = FbankConfig::default().wire(&mut model, "fbank", source).unwrap();
+        assert_eq!(
+            model.outlet_fact(fbank).unwrap().shape.to_tvec(),
+            tvec!(98.to_dim(), 23.to_dim())
+        );
+        let mfcc = MfccConfig::default().wire(&mut model, "mfcc", source).unwrap();
+        assert_eq!(
+            model.outlet_fact(mfcc).unwrap().shape.to_tvec(),
+            tvec!(98.to_dim(), 13.to_dim())
+        );
+    }
+
+    #[test]
+    fn fbank_of_silence_is_floored() {
+        let mut model = TypedModel::default();
+        let source =
+            model.add_source("wav", TypedFact::dt_shape(f32::datum_type(), &[800])).unwrap();
+        let fbank = FbankConfig::default().wire(&mut model, "fbank", source).unwrap();
+        model.set_output_outlets(&[fbank]).unwrap();
+        let output = model
+            .into_runnable()
+            .unwrap()
+            .run(tvec!(Tensor::zero::<f32>(&[800]).unwrap()))
+            .unwrap();
+        assert_eq!(output[0].shape(), &[3, 23]);
+        assert!(output[0].as_slice::<f32>().unwrap().iter().all(|&x| x == std::f32::EPSILON.ln()));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// Cuts a signal in overlapping frames.
+///
+/// The axis `axis` (of length T) is replaced by two axes: the frame index and
+/// the position in the frame (of length `frame_length`). Trailing samples not
+/// filling a full frame are dropped, matching Kaldi `--snip-edges=true`.
+#[derive(Debug, Clone, new, Hash)]
+pub struct Frame {
+    pub axis: usize,
+    pub frame_length: usize,
+    pub frame_step: usize,
+}
+
+impl_dyn_hash!(Frame);
+
+impl Frame {
+    pub fn frame_count(&self, len: &TDim) -> TDim {
+        if let Ok(len) = len.to_usize() {
+            if len < self.frame_length {
+                return 0.to_dim();
+            }
+        }
+        (len.clone() + self.frame_step - self.frame_length) / self.frame_step
+    }
+
+    fn eval_t<T: Datum>(&self, input: &Tensor) -> TractResult<Tensor> {
+        let view = unsafe { input.to_array_view_unchecked::<T>() };
+        let len = view.shape()[self.axis];
+        let frames = if len < self.frame_length {
+            0
+        } else {
+            (len - self.frame_length) / self.frame_step + 1
+        };
+        let mut shape: TVec<usize> = view.shape().into();
+        shape[self.axis] = frames;
+        shape.insert(self.axis + 1, self.frame_length);
+        let output = ArrayD::from_shape_fn(&*shape, |coords| {
+            let mut coords: TVec<usize> = coords.slice().into();
+            let offset = coords.remove(self.axis + 1);
+            coords[self.axis] = coords[self.axis] * self.frame_step + offset;
+            view[&*coords].clone()
+        });
+        let mut output = output.into_tensor();
+        unsafe {
+            output.set_datum_type(input.datum_type());
+        }
+        Ok(output)
+    }
+}
+
+impl Op for Frame {
+    fn name(&self) -> Cow<str> {
+        "Frame".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis:{} frame_length:{} frame_step:{}",
+            self.axis, self.frame_length, self.frame_step
+        )])
+    }
+
+    op_core_mir!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Frame {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let output =
+            dispatch_datum_by_size!(Self::eval_t(inputs[0].datum_type())(self, &inputs[0]))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Frame {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        if self.frame_step == 0 || self.frame_length == 0 {
+            bail!("Frame length and step must be strictly positive");
+        }
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[self.axis] = self.frame_count(&shape[self.axis]);
+        shape.insert(self.axis + 1, self.frame_length.to_dim());
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, shape)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_snip_edges() {
+        let op = Frame::new(0, 4, 2);
+        let input = tensor1(&[0f32, 1., 2., 3., 4., 5., 6.]);
+        let output = op.eval(tvec!(input.into_arc_tensor())).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0f32, 1., 2., 3.], [2., 3., 4., 5.]]));
+    }
+
+    #[test]
+    fn frame_count() {
+        let op = Frame::new(0, 400, 160);
+        assert_eq!(op.frame_count(&399.to_dim()), 0.to_dim());
+        assert_eq!(op.frame_count(&400.to_dim()), 1.to_dim());
+        assert_eq!(op.frame_count(&16000.to_dim()), 98.to_dim());
+    }
+}
@@ -0,0 +1,195 @@
+//! Mel filterbank and DCT matrices.
+//!
+//! All matrices are laid out as `[input_features, output_features]` so they
+//! can be applied to a `[frames, features]` tensor by a right-side matrix
+//! product. The variants reproduce the exact conventions of the frameworks
+//! they are named after.
+use crate::internal::*;
+
+/// Mel scale, as used by Kaldi and TensorFlow.
+pub fn hz_to_mel(freq: f64) -> f64 {
+    1127.0 * (1.0 + freq / 700.0).ln()
+}
+
+/// Kaldi triangular mel banks (`MelBanks` in `feat/mel-computations.cc`).
+///
+/// Output is `[padded_window_size / 2 + 1, num_bins]`. Kaldi ignores the
+/// Nyquist bin, so the last row is zero. `high_freq` is an offset from the
+/// Nyquist frequency when not strictly positive.
+pub fn kaldi_mel_banks(
+    num_bins: usize,
+    padded_window_size: usize,
+    sample_rate: f64,
+    low_freq: f64,
+    high_freq: f64,
+) -> TractResult<Tensor> {
+    let nyquist = 0.5 * sample_rate;
+    let high_freq = if high_freq <= 0.0 { nyquist + high_freq } else { high_freq };
+    if low_freq < 0.0 || low_freq >= nyquist || high_freq <= low_freq || high_freq > nyquist {
+        bail!(
+            "Invalid mel bank frequency range: {} to {} (nyquist: {})",
+            low_freq,
+            high_freq,
+            nyquist
+        )
+    }
+    let num_fft_bins = padded_window_size / 2;
+    let fft_bin_width = sample_rate / padded_window_size as f64;
+    let mel_low = hz_to_mel(low_freq);
+    let mel_high = hz_to_mel(high_freq);
+    let mel_delta = (mel_high - mel_low) / (num_bins + 1) as f64;
+    let mut banks = tract_ndarray::Array2::<f32>::zeros((num_fft_bins + 1, num_bins));
+    for bin in 0..num_bins {
+        let left = mel_low + bin as f64 * mel_delta;
+        let center = left + mel_delta;
+        let right = center + mel_delta;
+        for i in 0..num_fft_bins {
+            let mel = hz_to_mel(fft_bin_width * i as f64);
+            if mel > left && mel < right {
+                let weight = if mel <= center {
+                    (mel - left) / (center - left)
+                } else {
+                    (right - mel) / (right - center)
+                };
+                banks[(i, bin)] = weight as f32;
+            }
+        }
+    }
+    Ok(banks.into_tensor())
+}
+
+/// ONNX `MelWeightMatrix`: `[dft_length / 2 + 1, num_mel_bins]`.
+pub fn onnx_mel_weight_matrix(
+    num_mel_bins: usize,
+    dft_length: usize,
+    sample_rate: usize,
+    lower_edge_hertz: f64,
+    upper_edge_hertz: f64,
+) -> TractResult<Tensor> {
+    if sample_rate == 0 || upper_edge_hertz <= lower_edge_hertz {
+        bail!("Invalid MelWeightMatrix parameters")
+    }
+    let hz_to_mel = |hz: f64| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_low = hz_to_mel(lower_edge_hertz);
+    let mel_step = (hz_to_mel(upper_edge_hertz) - mel_low) / (num_mel_bins + 1) as f64;
+    let num_spectrogram_bins = dft_length / 2 + 1;
+    let bins: Vec<usize> = (0..num_mel_bins + 2)
+        .map(|i| {
+            let hz = 700.0 * (10f64.powf((i as f64 * mel_step + mel_low) / 2595.0) - 1.0);
+            (((dft_length + 1) as f64 * hz) as usize / sample_rate).min(num_spectrogram_bins - 1)
+        })
+        .collect();
+    let mut matrix = tract_ndarray::Array2::<f32>::zeros((num_spectrogram_bins, num_mel_bins));
+    for i in 0..num_mel_bins {
+        let (lower, center, higher) = (bins[i], bins[i + 1], bins[i + 2]);
+        if center == lower {
+            matrix[(center, i)] = 1.0;
+        } else {
+            for j in lower..=center {
+                matrix[(j, i)] = (j - lower) as f32 / (center - lower) as f32;
+            }
+        }
+        for j in center..higher {
+            matrix[(j, i)] = (higher - j) as f32 / (higher - center) as f32;
+        }
+    }
+    Ok(matrix.into_tensor())
+}
+
+/// TensorFlow `MfccMelFilterbank` (`core/kernels/mfcc_mel_filterbank.cc`):
+/// `[input_length, channel_count]`. TensorFlow applies it to the magnitude
+/// (not the power) spectrum.
+pub fn tf_mfcc_mel_filterbank(
+    input_length: usize,
+    sample_rate: f64,
+    channel_count: usize,
+    lower_frequency_limit: f64,
+    upper_frequency_limit: f64,
+) -> TractResult<Tensor> {
+    if input_length < 2 || channel_count == 0 || upper_frequency_limit <= lower_frequency_limit {
+        bail!("Invalid Mfcc filterbank parameters")
+    }
+    let mel_low = hz_to_mel(lower_frequency_limit);
+    let mel_spacing = (hz_to_mel(upper_frequency_limit) - mel_low) / (channel_count + 1) as f64;
+    let centers: Vec<f64> =
+        (0..channel_count + 1).map(|i| mel_low + mel_spacing * (i + 1) as f64).collect();
+    let hz_per_sbin = 0.5 * sample_rate / (input_length - 1) as f64;
+    let start = (1.5 + lower_frequency_limit / hz_per_sbin) as usize;
+    let end = ((upper_frequency_limit / hz_per_sbin) as usize).min(input_length - 1);
+    let mut matrix = tract_ndarray::Array2::<f32>::zeros((input_length, channel_count));
+    let mut channel = 0;
+    for i in start..=end {
+        let mel = hz_to_mel(i as f64 * hz_per_sbin);
+        while channel < channel_count && centers[channel] < mel {
+            channel += 1;
+        }
+        let weight = if channel > 0 {
+            (centers[channel] - mel) / (centers[channel] - centers[channel - 1])
+        } else {
+            (centers[0] - mel) / (centers[0] - mel_low)
+        };
+        if channel > 0 {
+            matrix[(i, channel - 1)] += weight as f32;
+        }
+        if channel < channel_count {
+            matrix[(i, channel)] += 1.0 - weight as f32;
+        }
+    }
+    Ok(matrix.into_tensor())
+}
+
+/// DCT-II matrix: `[input_length, output_length]`.
+///
+/// Kaldi scales the first coefficient by `sqrt(1 / n)` (orthonormal DCT),
+/// TensorFlow uses `sqrt(2 / n)` for all of them.
+pub fn dct_matrix(input_length: usize, output_length: usize, orthonormal: bool) -> Tensor {
+    let n = input_length as f64;
+    tract_ndarray::Array2::from_shape_fn((input_length, output_length), |(j, k)| {
+        let normalizer = if orthonormal && k == 0 { (1.0 / n).sqrt() } else { (2.0 / n).sqrt() };
+        (normalizer * (std::f64::consts::PI / n * (j as f64 + 0.5) * k as f64).cos()) as f32
+    })
+    .into_tensor()
+}
+
+/// Kaldi cepstral liftering coefficients.
+pub fn kaldi_lifter(num_ceps: usize, lifter: f64) -> Tensor {
+    let coefs: Vec<f32> = (0..num_ceps)
+        .map(|i| (1.0 + 0.5 * lifter * (std::f64::consts::PI * i as f64 / lifter).sin()) as f32)
+        .collect();
+    tensor1(&coefs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_ndarray::Ix2;
+
+    #[test]
+    fn kaldi_banks_are_triangles() {
+        let banks = kaldi_mel_banks(23, 512, 16000.0, 20.0, 0.0).unwrap();
+        let banks = banks.into_array::<f32>().unwrap().into_dimensionality::<Ix2>().unwrap();
+        assert_eq!(banks.shape(), &[257, 23]);
+        assert!(banks.row(256).iter().all(|&w| w == 0.0));
+        for bin in 0..23 {
+            let col = banks.column(bin);
+            assert!(col.iter().all(|&w| w >= 0.0 && w <= 1.0));
+            assert!(col.iter().any(|&w| w > 0.5));
+        }
+    }
+
+    #[test]
+    fn dct_is_orthonormal() {
+        let dct = dct_matrix(8, 8, true);
+        let dct = dct.into_array::<f32>().unwrap().into_dimensionality::<Ix2>().unwrap();
+        let product = dct.t().dot(&dct);
+        for ((i, j), v) in product.indexed_iter() {
+            assert!((v - if i == j { 1.0 } else { 0.0 }).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn onnx_mel_weight_matrix_shape() {
+        let m = onnx_mel_weight_matrix(8, 16, 8192, 0.0, 4096.0).unwrap();
+        assert_eq!(m.shape(), &[9, 8]);
+    }
+}
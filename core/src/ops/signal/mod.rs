@@ -0,0 +1,12 @@
+//! Signal processing ops, used to compute audio features (spectrograms, mel
+//! filterbanks, MFCC) inside the model.
+mod dft;
+mod fbank;
+mod frame;
+pub mod mel;
+mod window;
+
+pub use dft::Dft;
+pub use fbank::{FbankConfig, MfccConfig};
+pub use frame::Frame;
+pub use window::Window;
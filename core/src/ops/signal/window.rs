@@ -0,0 +1,66 @@
+use crate::internal::*;
+
+/// Window functions applied to frames before the Fourier transform.
+///
+/// Symmetric windows use `len - 1` as denominator (Kaldi, numpy), periodic
+/// ones use `len` (TensorFlow, ONNX `HannWindow` default).
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum Window {
+    Rectangular,
+    Hann {
+        periodic: bool,
+    },
+    Hamming {
+        periodic: bool,
+    },
+    Blackman {
+        periodic: bool,
+    },
+    /// Kaldi default window: a Hann window raised to the power 0.85.
+    Povey,
+}
+
+impl Window {
+    pub fn coefficients(&self, len: usize) -> Vec<f32> {
+        let denominator = |periodic: bool| if periodic { len } else { len.saturating_sub(1) };
+        let a = |periodic: bool| 2.0 * std::f64::consts::PI / denominator(periodic).max(1) as f64;
+        (0..len)
+            .map(|i| {
+                let i = i as f64;
+                let w = match *self {
+                    Window::Rectangular => 1.0,
+                    Window::Hann { periodic } => 0.5 - 0.5 * (a(periodic) * i).cos(),
+                    Window::Hamming { periodic } => 0.54 - 0.46 * (a(periodic) * i).cos(),
+                    Window::Blackman { periodic } => {
+                        0.42 - 0.5 * (a(periodic) * i).cos() + 0.08 * (2.0 * a(periodic) * i).cos()
+                    }
+                    Window::Povey => (0.5 - 0.5 * (a(false) * i).cos()).powf(0.85),
+                };
+                w as f32
+            })
+            .collect()
+    }
+
+    pub fn tensor(&self, len: usize) -> Tensor {
+        tensor1(&self.coefficients(len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hann() {
+        let symmetric = Window::Hann { periodic: false }.coefficients(5);
+        assert!(symmetric
+            .iter()
+            .zip([0f32, 0.5, 1.0, 0.5, 0.0].iter())
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+        let periodic = Window::Hann { periodic: true }.coefficients(4);
+        assert!(periodic
+            .iter()
+            .zip([0f32, 0.5, 1.0, 0.5].iter())
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+}
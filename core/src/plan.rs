@@ -13,6 +13,64 @@ pub struct SessionState {
     pub tensors: HashMap<String, Tensor>,
 }
 
+/// Op states of a `SimpleState`, as saved by `SimpleState::snapshot`.
+///
+/// It only holds plain tensors, so it can be kept around to rewind a stream
+/// or sent to another process running the same plan.
+#[derive(Clone, Debug, Default)]
+pub struct StateSnapshot {
+    pub resolved_symbols: Vec<(Symbol, i64)>,
+    pub op_states: Vec<Option<TVec<Tensor>>>,
+}
+
+impl StateSnapshot {
+    /// Flatten the snapshot to a list of tensors: the resolved symbols first,
+    /// then for each node the number of tensors in its state (-1 for nodes
+    /// without a state), followed by these tensors.
+    pub fn to_tensors(&self) -> TractResult<TVec<Tensor>> {
+        let symbols: Vec<i64> =
+            self.resolved_symbols.iter().flat_map(|(s, v)| vec![s.as_char() as i64, *v]).collect();
+        let mut tensors = tvec!(tensor1(&symbols).into_shape(&[self.resolved_symbols.len(), 2])?);
+        for state in &self.op_states {
+            if let Some(state) = state {
+                tensors.push(tensor0(state.len() as i64));
+                tensors.extend(state.iter().cloned());
+            } else {
+                tensors.push(tensor0(-1i64));
+            }
+        }
+        Ok(tensors)
+    }
+
+    pub fn from_tensors(tensors: impl IntoIterator<Item = Tensor>) -> TractResult<StateSnapshot> {
+        let mut tensors = tensors.into_iter();
+        let symbols = tensors.next().ok_or_else(|| format_err!("Empty snapshot"))?;
+        let resolved_symbols = symbols
+            .to_array_view::<i64>()?
+            .outer_iter()
+            .map(|pair| {
+                let c = std::char::from_u32(pair[0] as u32)
+                    .ok_or_else(|| format_err!("Invalid symbol in snapshot"))?;
+                Ok((Symbol::from(c), pair[1]))
+            })
+            .collect::<TractResult<_>>()?;
+        let mut op_states = vec![];
+        while let Some(count) = tensors.next() {
+            let count = *count.to_scalar::<i64>()?;
+            if count < 0 {
+                op_states.push(None);
+            } else {
+                let state: TVec<Tensor> = (&mut tensors).take(count as usize).collect();
+                if state.len() != count as usize {
+                    bail!("Truncated snapshot");
+                }
+                op_states.push(Some(state));
+            }
+        }
+        Ok(StateSnapshot { resolved_symbols, op_states })
+    }
+}
+
 #[derive(Debug, Clone, Educe)]
 #[educe(Hash)]
 pub struct SimplePlan<F, O, M>
@@ -104,6 +162,8 @@ where
     pub states: Vec<Option<Box<dyn OpState>>>,
     pub session_state: SessionState,
     pub values: Vec<Option<TVec<Arc<Tensor>>>>,
+    /// symbols resolved from the input shapes, for the current run only
+    inferred_symbols: TVec<Symbol>,
     _phantom: PhantomData<(M, F, O)>,
 }
 
@@ -123,7 +183,14 @@ where
             .iter()
             .map(|n: &Node<F, O>| n.op().state(&mut session, n.id))
             .collect::<TractResult<_>>()?;
-        Ok(SimpleState { plan, states, session_state: session, values, _phantom: PhantomData })
+        Ok(SimpleState {
+            plan,
+            states,
+            session_state: session,
+            values,
+            inferred_symbols: tvec!(),
+            _phantom: PhantomData,
+        })
     }
 
     /// Reset wires state.
@@ -145,6 +212,53 @@ where
         Ok(())
     }
 
+    /// Save the op states and resolved symbols, to be restored later with
+    /// `restore`.
+    pub fn snapshot(&self) -> TractResult<StateSnapshot> {
+        let nodes = self.plan.borrow().model().nodes();
+        let op_states = self
+            .states
+            .iter()
+            .zip(nodes)
+            .map(|(state, node)| {
+                state
+                    .as_ref()
+                    .map(|s| s.save().with_context(|| format!("Saving state of {}", node)))
+                    .transpose()
+            })
+            .collect::<TractResult<_>>()?;
+        let resolved_symbols = self.session_state.resolved_symbols.resolved();
+        Ok(StateSnapshot { resolved_symbols, op_states })
+    }
+
+    /// Restore a snapshot taken from a state running the same plan.
+    pub fn restore(&mut self, snapshot: &StateSnapshot) -> TractResult<()> {
+        self.reset_op_states()?;
+        let &mut SimpleState { ref plan, ref mut session_state, ref mut states, .. } = self;
+        let nodes = plan.borrow().model().nodes();
+        if snapshot.op_states.len() != nodes.len() {
+            bail!(
+                "Snapshot has {} op states, plan has {} nodes",
+                snapshot.op_states.len(),
+                nodes.len()
+            );
+        }
+        for ((state, saved), node) in states.iter_mut().zip(&snapshot.op_states).zip(nodes) {
+            match (state, saved) {
+                (Some(state), Some(saved)) => state
+                    .load(saved.clone())
+                    .with_context(|| format!("Restoring state of {}", node))?,
+                (None, None) => (),
+                _ => bail!("Snapshot does not match the plan for {}", node),
+            }
+        }
+        session_state.resolved_symbols = SymbolValues::default();
+        for (symbol, value) in &snapshot.resolved_symbols {
+            session_state.resolved_symbols[*symbol] = Some(*value);
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         self.run_plan_with_eval(inputs, self::eval)
     }
@@ -165,6 +279,7 @@ where
     {
         let mut result = tvec!();
         {
+            self.forget_inferred_symbols();
             self.set_inputs(inputs)?;
             let &mut SimpleState {
                 ref plan,
@@ -204,7 +319,7 @@ where
                         );
                     }
                     for (ix, (v, f)) in inputs.iter().zip(facts.iter()).enumerate() {
-                        if !f.matches(v)? {
+                        if !f.matches_with_symbols(v, &session_state.resolved_symbols)? {
                             bail!(
                                 "Evaluating {}: input {:?}, expected {:?}, got {:?}",
                                 node,
@@ -234,7 +349,7 @@ where
                         if node.outputs[ix].successors.len() == 0 {
                             continue;
                         }
-                        if !f.matches(v)? {
+                        if !f.matches_with_symbols(v, &session_state.resolved_symbols)? {
                             bail!(
                                 "Evaluating {}: output {:?}, expected {:?}, got {:?}",
                                 node,
@@ -254,9 +369,16 @@ where
             }
         }
         self.reset_wires()?;
+        self.forget_inferred_symbols();
         Ok(result)
     }
 
+    fn forget_inferred_symbols(&mut self) {
+        for sym in self.inferred_symbols.drain(..) {
+            self.session_state.resolved_symbols[sym] = None;
+        }
+    }
+
     pub fn set_inputs(&mut self, inputs: TVec<Tensor>) -> TractResult<()> {
         for (ix, t) in inputs.into_iter().enumerate() {
             self.set_input(ix, t)?
@@ -270,12 +392,43 @@ where
             .input_outlets()?
             .get(input)
             .ok_or_else(|| format_err!("Invalid input id for model ({}).", input))?;
-        self.plan
-            .borrow()
-            .model()
-            .outlet_fact(outlet)?
-            .matches(&t)
-            .with_context(|| format!("Setting input {}", input))?;
+        let typed = self.plan.borrow().model().outlet_fact(outlet)?.to_typed_fact();
+        let fact = match typed {
+            Ok(fact) => fact,
+            Err(_) => {
+                // partially known inference facts: unify, as there are no symbols to infer
+                self.plan
+                    .borrow()
+                    .model()
+                    .outlet_fact(outlet)?
+                    .matches(&t)
+                    .with_context(|| format!("Setting input {}", input))?;
+                self.session_state.inputs.insert(outlet.node, t.into());
+                return Ok(());
+            }
+        };
+        // a dimension linear in a single unresolved symbol (like a symbolic
+        // pulse) gives its value for this run
+        let symbols = &mut self.session_state.resolved_symbols;
+        for (dim, &size) in fact.shape.iter().zip(t.shape()) {
+            let dim_symbols = dim.symbols();
+            if dim_symbols.len() == 1 {
+                let sym = *dim_symbols.iter().next().unwrap();
+                if symbols[sym].is_some() {
+                    continue;
+                }
+                let at = |v| dim.eval(&SymbolValues::default().with(sym, v)).to_i64();
+                let (offset, slope) = (at(0)?, at(1)? - at(0)?);
+                if slope != 0 && (size as i64 - offset) % slope == 0 {
+                    symbols[sym] = Some((size as i64 - offset) / slope);
+                    self.inferred_symbols.push(sym);
+                }
+            }
+        }
+        let shape = fact.shape.eval(symbols).with_context(|| format!("Setting input {}", input))?;
+        if fact.datum_type != t.datum_type() || shape.as_slice() != t.shape() {
+            bail!("Setting input {}: expected {:?}, got {:?}", input, fact, t);
+        }
         self.session_state.inputs.insert(outlet.node, t.into());
         Ok(())
     }
@@ -413,3 +566,39 @@ where
     .with_context(|| format!("Evaluating {}", node));
     r
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn model(s: Symbol) -> TractResult<TypedModel> {
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source("a", TypedFact::dt_shape(f32::datum_type(), [TDim::from(s)].as_ref()))?;
+        model.set_output_outlets(&[a])?;
+        Ok(model)
+    }
+
+    #[test]
+    fn inferred_symbols_are_per_run() -> TractResult<()> {
+        let s = Symbol::new('S');
+        let plan = SimplePlan::new(model(s)?)?;
+        let mut state = SimpleState::new(&plan)?;
+        state.run(tvec!(tensor1(&[0f32; 2])))?;
+        state.run(tvec!(tensor1(&[0f32; 5])))?;
+        assert_eq!(state.session_state.resolved_symbols[s], None);
+        Ok(())
+    }
+
+    #[test]
+    fn resolved_symbols_are_checked() -> TractResult<()> {
+        let s = Symbol::new('S');
+        let plan = SimplePlan::new(model(s)?)?;
+        let mut state = SimpleState::new(&plan)?;
+        state.session_state.resolved_symbols[s] = Some(3);
+        assert!(state.run(tvec!(tensor1(&[0f32; 4]))).is_err());
+        state.run(tvec!(tensor1(&[0f32; 3])))?;
+        assert_eq!(state.session_state.resolved_symbols[s], Some(3));
+        Ok(())
+    }
+}
@@ -34,6 +34,60 @@ impl std::str::FromStr for Blob {
     }
 }
 
+/// Affine quantization parameters: a quantized value `q` stands for the real
+/// value `scale * (q - zero_point)`. `scale` must be positive.
+#[derive(Debug, Copy, Clone)]
+pub struct ZpScale {
+    pub zero_point: i32,
+    pub scale: f32,
+}
+
+impl ZpScale {
+    pub fn new(zero_point: i32, scale: f32) -> ZpScale {
+        ZpScale { zero_point, scale }
+    }
+
+    /// Real value for a quantized value.
+    pub fn dq(&self, q: i32) -> f32 {
+        (q - self.zero_point) as f32 * self.scale
+    }
+
+    /// Quantized value for a real value, before clamping to the storage type.
+    pub fn q(&self, x: f32) -> i32 {
+        (x / self.scale).round() as i32 + self.zero_point
+    }
+
+    fn key(&self) -> (i32, u32) {
+        (self.zero_point, self.scale.to_bits())
+    }
+}
+
+impl PartialEq for ZpScale {
+    fn eq(&self, other: &ZpScale) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for ZpScale {}
+
+impl Hash for ZpScale {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl PartialOrd for ZpScale {
+    fn partial_cmp(&self, other: &ZpScale) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ZpScale {
+    fn cmp(&self, other: &ZpScale) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub enum DatumType {
     Bool,
@@ -51,12 +105,15 @@ pub enum DatumType {
     TDim,
     Blob,
     String,
+    QI8(ZpScale),
+    QU8(ZpScale),
 }
 
 impl DatumType {
     pub fn super_types(&self) -> TVec<DatumType> {
         use DatumType::*;
-        if *self == String || *self == TDim || *self == Blob || *self == Bool {
+        if *self == String || *self == TDim || *self == Blob || *self == Bool || self.is_quantized()
+        {
             tvec!(*self)
         } else if self.is_float() {
             [F16, F32, F64].iter().filter(|s| s.size_of() >= self.size_of()).copied().collect()
@@ -121,7 +178,50 @@ impl DatumType {
     }
 
     pub fn is_copy(&self) -> bool {
-        *self == DatumType::Bool || self.is_unsigned() || self.is_signed() || self.is_float()
+        *self == DatumType::Bool
+            || self.is_unsigned()
+            || self.is_signed()
+            || self.is_float()
+            || self.is_quantized()
+    }
+
+    pub fn is_quantized(&self) -> bool {
+        self.zp_scale().is_some()
+    }
+
+    pub fn zp_scale(&self) -> Option<ZpScale> {
+        match self {
+            DatumType::QI8(zps) | DatumType::QU8(zps) => Some(*zps),
+            _ => None,
+        }
+    }
+
+    /// The storage type of a quantized type, the type itself otherwise.
+    pub fn unquantized(&self) -> DatumType {
+        match self {
+            DatumType::QI8(_) => DatumType::I8,
+            DatumType::QU8(_) => DatumType::U8,
+            _ => *self,
+        }
+    }
+
+    /// Quantized type storing values as this type (which must be i8 or u8,
+    /// or already quantized).
+    pub fn quantize(&self, zps: ZpScale) -> DatumType {
+        match self.unquantized() {
+            DatumType::I8 => DatumType::QI8(zps),
+            DatumType::U8 => DatumType::QU8(zps),
+            dt => panic!("{:?} can not be quantized", dt),
+        }
+    }
+
+    /// Range of the values of a quantized type, in the storage type.
+    pub fn quantized_range(&self) -> Option<(i32, i32)> {
+        match self {
+            DatumType::QI8(_) => Some((i8::min_value() as i32, i8::max_value() as i32)),
+            DatumType::QU8(_) => Some((u8::min_value() as i32, u8::max_value() as i32)),
+            _ => None,
+        }
     }
 
     pub fn integer(signed: bool, size: usize) -> Self {
@@ -251,9 +351,48 @@ mod tests {
         t_i32.cast_to::<TDim>().unwrap();
     }
 
+    #[test]
+    fn zp_scale_eq_is_consistent_with_hash() {
+        let nan = ZpScale::new(0, std::f32::NAN);
+        assert_eq!(nan, nan);
+        assert_ne!(ZpScale::new(0, 0.0), ZpScale::new(0, -0.0));
+    }
+
     #[test]
     fn test_cast_i64_to_bool() {
         let t_i64: Tensor = tensor1(&[0i64]);
         t_i64.cast_to::<bool>().unwrap();
     }
+
+    #[test]
+    fn test_cast_f32_to_quantized_and_back() {
+        let qi8 = DatumType::QI8(ZpScale::new(10, 0.5));
+        let reals = tensor1(&[0f32, 1.0, -2.2, 100.0]);
+        let q = reals.cast_to_dt(qi8).unwrap().into_owned();
+        assert_eq!(q.datum_type(), qi8);
+        assert_eq!(q.as_slice::<i8>().unwrap(), &[10, 12, 6, 127]);
+        let back = q.cast_to::<f32>().unwrap().into_owned();
+        assert_eq!(back, tensor1(&[0f32, 1.0, -2.0, 58.5]));
+    }
+
+    #[test]
+    fn test_requantize() {
+        let from = DatumType::QU8(ZpScale::new(128, 0.5));
+        let to = DatumType::QI8(ZpScale::new(0, 0.25));
+        let mut q = tensor1(&[128u8, 130, 120]);
+        unsafe { q.set_datum_type(from) };
+        let requantized = q.cast_to_dt(to).unwrap().into_owned();
+        let mut expected = tensor1(&[0i8, 4, -16]);
+        unsafe { expected.set_datum_type(to) };
+        assert_eq!(requantized, expected);
+    }
+
+    #[test]
+    fn test_quantized_super_types() {
+        let a = DatumType::QI8(ZpScale::new(0, 0.5));
+        let b = DatumType::QI8(ZpScale::new(1, 0.5));
+        assert_eq!(a.common_super_type(a), Some(a));
+        assert_eq!(a.common_super_type(b), None);
+        assert_eq!(a.common_super_type(DatumType::I8), None);
+    }
 }
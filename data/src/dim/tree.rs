@@ -18,6 +18,10 @@ impl Symbol {
         table.push(c);
         Symbol(c, table.len() - 1)
     }
+
+    pub fn as_char(&self) -> char {
+        self.0
+    }
 }
 
 impl From<char> for Symbol {
@@ -40,6 +44,16 @@ impl SymbolValues {
         self[s] = Some(v);
         self
     }
+
+    /// Symbols with a value.
+    pub fn resolved(&self) -> Vec<(Symbol, i64)> {
+        let table = SYMBOL_TABLE.lock().unwrap();
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, v)| v.map(|v| (Symbol(table[ix], ix), v)))
+            .collect()
+    }
 }
 
 impl std::ops::Index<Symbol> for SymbolValues {
@@ -323,7 +337,8 @@ impl TDim {
         }
     }
 
-    fn gcd(&self) -> u64 {
+    /// Greatest integer all the values this dimension can take are multiples of.
+    pub fn gcd(&self) -> u64 {
         use self::TDim::*;
         use num_integer::Integer;
         match self {
@@ -378,7 +393,7 @@ impl TDim {
                 Add(terms) => terms
                     .iter()
                     .map(|d| slope_rec(d, sym))
-                    .fold((1, 1), |a, b| ((a.0 * b.1 + a.1 * b.0), (b.1 * a.1))),
+                    .fold((0, 1), |a, b| ((a.0 * b.1 + a.1 * b.0), (b.1 * a.1))),
                 Mul(p, a) => {
                     let (n, d) = slope_rec(a, sym);
                     (p * n, d)
@@ -685,6 +700,13 @@ mod tests {
         assert_eq!(mul(2, &div(&mul(-1, &s()), 3)).reduce(), mul(-2, &div(&s(), 3)))
     }
 
+    #[test]
+    fn slope_of_sum() {
+        assert_eq!((s() + 3).slope(*S), (1, 1));
+        assert_eq!((s() + 3).div_ceil(4).slope(*S), (1, 4));
+        assert_eq!((s() * 3 + s() / 2).slope(*S), (7, 2));
+    }
+
     #[test]
     fn const_and_add() {
         let e: TDim = 2i64.into();
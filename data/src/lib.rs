@@ -13,7 +13,7 @@ mod macros;
 pub type TVec<T> = smallvec::SmallVec<[T; 4]>;
 
 pub mod prelude {
-    pub use crate::datum::{Blob, Datum, DatumType};
+    pub use crate::datum::{Blob, Datum, DatumType, ZpScale};
     pub use crate::dim::{Symbol, SymbolValues, TDim};
     pub use crate::f16::*;
     pub use crate::tensor::litteral::*;
@@ -49,6 +49,8 @@ impl Hash for Tensor {
                 F16 => self.as_slice_unchecked::<i16>().hash(state),
                 F32 => self.as_slice_unchecked::<i32>().hash(state),
                 F64 => self.as_slice_unchecked::<i64>().hash(state),
+                QI8(_) => self.as_slice_unchecked::<i8>().hash(state),
+                QU8(_) => self.as_slice_unchecked::<u8>().hash(state),
                 TDim => self.as_slice_unchecked::<crate::dim::TDim>().hash(state),
                 String => self.as_slice_unchecked::<std::string::String>().hash(state),
                 Blob => self.as_slice_unchecked::<crate::datum::Blob>().hash(state),
@@ -172,6 +174,7 @@ impl Tensor {
                 DatumType::TDim => TDim::stack_tensors(axis, &tensors),
                 DatumType::Blob => Blob::stack_tensors(axis, &tensors),
                 DatumType::String => String::stack_tensors(axis, &tensors),
+                DatumType::QI8(_) | DatumType::QU8(_) => i8::stack_tensors(axis, &tensors),
             }
         }?;
         tensor.dt = dt;
@@ -579,7 +582,7 @@ impl Tensor {
     }
 
     fn check_for_access<D: Datum>(&self) -> anyhow::Result<()> {
-        if self.datum_type() != D::datum_type() {
+        if self.datum_type().unquantized() != D::datum_type() {
             anyhow::bail!(
                 "Tensor datum type error: tensor is {:?}, accessed as {:?}",
                 self.datum_type(),
@@ -747,6 +750,9 @@ impl Tensor {
             if self.dt == dt {
                 return Ok(Cow::Borrowed(self));
             }
+            if self.dt.is_quantized() || dt.is_quantized() {
+                return Ok(Cow::Owned(self.cast_quantized(dt)?));
+            }
             if self.dt == TDim::datum_type() && (dt.is_integer() || dt.is_float()) {
                 let slice = self.as_slice_unchecked::<TDim>();
                 let mut ints = Self::uninitialized::<i64>(&self.shape)?;
@@ -819,6 +825,35 @@ impl Tensor {
         }
     }
 
+    /// Casts from and to quantized types: quantized values are dequantized to
+    /// floats and floats quantized, while integer types are taken as the
+    /// storage values of the quantized type.
+    fn cast_quantized(&self, dt: DatumType) -> anyhow::Result<Tensor> {
+        if let Some(zps) = self.dt.zp_scale() {
+            let mut storage = self.clone();
+            storage.dt = self.dt.unquantized();
+            if dt.is_float() || dt.is_quantized() {
+                let mut reals = storage.cast_to::<f32>()?.into_owned();
+                reals.as_slice_mut::<f32>()?.iter_mut().for_each(|x| *x = zps.dq(*x as i32));
+                return Ok(reals.cast_to_dt(dt)?.into_owned());
+            }
+            return Ok(storage.cast_to_dt(dt)?.into_owned());
+        }
+        let zps = dt.zp_scale().unwrap();
+        let mut result = if self.dt.is_float() {
+            let (min, max) = dt.quantized_range().unwrap();
+            let mut ints = self.cast_to::<f32>()?.into_owned();
+            ints.as_slice_mut::<f32>()?
+                .iter_mut()
+                .for_each(|x| *x = zps.q(*x).max(min).min(max) as f32);
+            ints.cast_to_dt(dt.unquantized())?.into_owned()
+        } else {
+            self.cast_to_dt(dt.unquantized())?.into_owned()
+        };
+        result.dt = dt;
+        Ok(result)
+    }
+
     /// Access the data as a scalar, after a cast.
     pub fn cast_to_scalar<D: Datum + Copy>(&self) -> anyhow::Result<D> {
         let casted = self.cast_to::<D>()?;
@@ -920,7 +955,9 @@ impl Tensor {
                 .into_owned()
                 .into_tensor())
         }
-        dispatch_datum!(slice_t(self.datum_type())(&self, axis, start, end))
+        let mut t = dispatch_datum!(slice_t(self.datum_type())(&self, axis, start, end))?;
+        t.dt = self.dt;
+        Ok(t)
     }
 
     pub fn view(&self) -> view::TensorView {
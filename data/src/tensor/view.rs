@@ -80,7 +80,7 @@ impl<'a> TensorView<'a> {
     }
 
     fn check_dt<D: Datum>(&self) -> anyhow::Result<()> {
-        if self.datum_type() != D::datum_type() {
+        if self.datum_type().unquantized() != D::datum_type() {
             anyhow::bail!(
                 "TensorView datum type error: tensor is {:?}, accessed as {:?}",
                 self.datum_type(),
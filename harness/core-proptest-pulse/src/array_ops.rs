@@ -0,0 +1,110 @@
+use proptest::proptest;
+use proptest::test_runner::TestCaseResult;
+use tract_hir::internal::*;
+use tract_hir::ops::{array, cast};
+
+use super::*;
+
+fn indices(len: impl Strategy<Value = usize>, max: usize) -> impl Strategy<Value = Vec<f32>> {
+    len.prop_flat_map(move |l| proptest::collection::vec(0..max, l..=l))
+        .prop_map(|v| v.into_iter().map(|i| i as f32).collect())
+}
+
+fn embedding_lookup(input: &[f32], pulse: usize) -> TestCaseResult {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S)))
+        .unwrap();
+    let table = Array2::from_shape_fn((5, 3), |(i, j)| (i * 3 + j) as f32);
+    let table = model.add_const("table", table.into_tensor()).unwrap();
+    let indices = model.wire_node("indices", cast(i64::datum_type()), &[a]).unwrap();
+    let gather = model.wire_node("gather", array::Gather::new(0), &[table, indices[0]]).unwrap();
+    model.set_output_outlets(&gather).unwrap();
+    proptest_regular_against_pulse(model, pulse, arr1(input).into_dyn(), 0)
+}
+
+fn gather_across_stream(input: &[f32], pulse: usize) -> TestCaseResult {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S, 4)))
+        .unwrap();
+    let indices = model.add_const("indices", tensor1(&[3i64, 1])).unwrap();
+    let gather = model.wire_node("gather", array::Gather::new(1), &[a, indices]).unwrap();
+    model.set_output_outlets(&gather).unwrap();
+    let input = arr1(input).into_shape((input.len() / 4, 4)).unwrap();
+    proptest_regular_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+fn tile(input: &[f32], pulse: usize) -> TestCaseResult {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S, 2)))
+        .unwrap();
+    let mult = model.add_const("mult", tensor1(&[1i64, 3])).unwrap();
+    let tile = model.wire_node("tile", expand(array::Tile::new()), &[a, mult]).unwrap();
+    model.set_output_outlets(&tile).unwrap();
+    let input = arr1(input).into_shape((input.len() / 2, 2)).unwrap();
+    proptest_regular_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+fn broadcast(input: &[f32], pulse: usize) -> TestCaseResult {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S, 1)))
+        .unwrap();
+    let shape = model.add_const("shape", tensor1(&[2i64, 1, 3])).unwrap();
+    let bc = model.wire_node("bc", expand(array::MultiBroadcastTo::new()), &[a, shape]).unwrap();
+    model.set_output_outlets(&bc).unwrap();
+    let input = arr1(input).into_shape((input.len(), 1)).unwrap();
+    proptest_regular_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+fn one_hot(input: &[f32], axis: usize, pulse: usize) -> TestCaseResult {
+    let mut model = TypedModel::default();
+    let a = model
+        .add_source("a", TypedFact::dt_shape(f32::datum_type(), [stream_dim()].as_ref()))
+        .unwrap();
+    let op =
+        tract_core::ops::array::OneHot { axis, dim: 4, off: rctensor0(0f32), on: rctensor0(1f32) };
+    let one_hot = model.wire_node("one_hot", op, &[a]).unwrap();
+    model.set_output_outlets(&one_hot).unwrap();
+    proptest_typed_against_pulse(model, pulse, arr1(input).into_dyn(), 0)
+}
+
+proptest! {
+    #[test]
+    fn proptest_embedding_lookup(pulse in 1usize..4, input in indices(0usize..10, 5)) {
+        embedding_lookup(&input, pulse)?;
+    }
+
+    #[test]
+    fn proptest_gather_across_stream(pulse in 1usize..4, len in 0usize..6) {
+        let input: Vec<f32> = (0..len * 4).map(|i| i as f32).collect();
+        gather_across_stream(&input, pulse)?;
+    }
+
+    #[test]
+    fn proptest_tile(pulse in 1usize..4, input in vec((0usize..6).prop_map(|l| l * 2))) {
+        tile(&input, pulse)?;
+    }
+
+    #[test]
+    fn proptest_broadcast(pulse in 1usize..4, input in vec(0usize..8)) {
+        broadcast(&input, pulse)?;
+    }
+
+    #[test]
+    fn proptest_one_hot(pulse in 1usize..4, axis in 0usize..2, input in indices(0usize..10, 4)) {
+        one_hot(&input, axis, pulse)?;
+    }
+}
+
+#[test]
+fn test_embedding_lookup() {
+    embedding_lookup(&[4.0, 0.0, 2.0], 2).unwrap()
+}
+
+#[test]
+fn test_one_hot_before_stream() {
+    one_hot(&[1.0, 3.0, 0.0], 0, 2).unwrap()
+}
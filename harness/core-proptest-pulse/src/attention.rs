@@ -0,0 +1,111 @@
+use proptest::proptest;
+use proptest::test_runner::TestCaseResult;
+use tract_core::ops::nn::{AttentionWindow, LocalAttention};
+use tract_hir::internal::*;
+
+use super::*;
+
+// self-attention over frames of 3 features: q = k = v = input
+fn self_attention_model(op: LocalAttention) -> TypedModel {
+    let mut model = TypedModel::default();
+    let a = model
+        .add_source(
+            "a",
+            TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 3.to_dim()].as_ref()),
+        )
+        .unwrap();
+    let attention = model.wire_node("attention", op, &[a, a, a]).unwrap();
+    model.set_output_outlets(&attention).unwrap();
+    model
+}
+
+fn self_attention(input: &[f32], window: AttentionWindow, pulse: usize) -> TestCaseResult {
+    let model = self_attention_model(LocalAttention { window, scale: 0.5 });
+    let input = arr1(input).into_shape((input.len() / 3, 3)).unwrap();
+    proptest_typed_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+fn window() -> impl Strategy<Value = AttentionWindow> {
+    prop_oneof![
+        (0usize..4, 0usize..3).prop_map(|(left, right)| AttentionWindow::Band { left, right }),
+        (1usize..4, 0usize..3).prop_map(|(size, left)| AttentionWindow::Chunked { size, left }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn proptest_self_attention(
+        pulse in 1usize..4,
+        window in window(),
+        input in vec((0usize..8).prop_map(|l| l * 3))
+    ) {
+        self_attention(&input, window, pulse)?;
+    }
+}
+
+#[test]
+fn test_band_attention() {
+    let input: Vec<f32> = (0..21).map(|i| (i % 5) as f32).collect();
+    self_attention(&input, AttentionWindow::Band { left: 2, right: 1 }, 2).unwrap()
+}
+
+#[test]
+fn test_chunked_attention() {
+    let input: Vec<f32> = (0..21).map(|i| (i % 5) as f32).collect();
+    self_attention(&input, AttentionWindow::Chunked { size: 3, left: 1 }, 2).unwrap()
+}
+
+#[test]
+fn test_nnef_attention() {
+    use tract_pulse::internal::tract_nnef;
+    use tract_pulse::tract_pulse_opl::ops::PulsedLocalAttention;
+    use tract_pulse::WithPulse;
+    let nnef = tract_nnef::nnef().with_pulse();
+    let doc = tract_nnef::ast::parse::parse_document(
+        "version 1.0;
+        extension tract_registry pulse;
+        graph network(input) -> (output) {
+            input = external(shape = [7, 3]);
+            output = tract_pulse_local_attention(input, input, input,
+                window = 'chunked', size = 3, left = 1, scale = 0.5);
+        }",
+    )
+    .unwrap();
+    let proto = tract_nnef::ProtoModel { doc, tensors: vec![], quantization: None };
+    let loaded = nnef.model_for_proto_model(&proto).unwrap();
+    let op = loaded
+        .node(loaded.output_outlets().unwrap()[0].node)
+        .op_as::<LocalAttention>()
+        .unwrap()
+        .clone();
+    assert_eq!(op.window, AttentionWindow::Chunked { size: 3, left: 1 });
+
+    // pulsify and run the loaded op
+    let model = self_attention_model(op);
+    let input: Vec<f32> = (0..21).map(|i| (i % 5) as f32).collect();
+    let input = arr1(&input).into_shape((7, 3)).unwrap();
+    proptest_typed_against_pulse(model.clone(), 2, input.into_dyn(), 0).unwrap();
+
+    // the pulsed model goes through NNEF too
+    let pulsed = PulsedModel::new(&model, 2).unwrap().into_typed().unwrap();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&pulsed, &mut buffer).unwrap();
+    let reloaded = nnef.model_for_read(&mut &*buffer).unwrap();
+    let find = |model: &TypedModel| {
+        model.nodes().iter().find_map(|n| n.op_as::<PulsedLocalAttention>()).unwrap().clone()
+    };
+    let (op, reloaded_op) = (find(&pulsed), find(&reloaded));
+    assert_eq!(reloaded_op.op.window, op.op.window);
+    assert_eq!((reloaded_op.q_delay, reloaded_op.kv_delay), (op.q_delay, op.kv_delay));
+    assert_eq!(reloaded_op.stream_len, op.stream_len);
+    let pulsed = SimplePlan::new(pulsed).unwrap();
+    let reloaded = SimplePlan::new(reloaded).unwrap();
+    let mut pulsed = SimpleState::new(&pulsed).unwrap();
+    let mut reloaded = SimpleState::new(&reloaded).unwrap();
+    for chunk in 0..4 {
+        let input = tensor2(&[[chunk as f32, 1.0, -1.0], [0.5, chunk as f32, 2.0]]);
+        let expected = pulsed.run(tvec!(input.clone())).unwrap();
+        let found = reloaded.run(tvec!(input)).unwrap();
+        assert_eq!(expected, found);
+    }
+}
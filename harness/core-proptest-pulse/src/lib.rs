@@ -11,8 +11,11 @@ use tract_hir::internal::*;
 use tract_ndarray::*;
 use tract_pulse::internal::*;
 
+mod array_ops;
+mod attention;
 mod conv_plus_conv;
 mod delay_plus_pool;
+mod matmul;
 mod pad_plus_conv;
 
 #[allow(dead_code)]
@@ -28,7 +31,6 @@ fn proptest_regular_against_pulse(
 ) -> TestCaseResult {
     setup_test_logger();
     let mut ref_model = model.clone();
-    let s = stream_symbol();
     debug!("Run reference");
     ref_model
         .set_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), input_array.shape()))
@@ -37,9 +39,36 @@ fn proptest_regular_against_pulse(
     let plan = SimplePlan::new(&ref_model).unwrap();
     let outputs = plan.run(tvec!(input.clone())).unwrap();
 
-    debug!("Build pulsing model");
     let model = model.into_typed().unwrap();
-    let pulsed = PulsedModel::new(&model, pulse).unwrap();
+    check_pulsed_against(&model, pulse, input_array, axis, &outputs[0])
+}
+
+fn proptest_typed_against_pulse(
+    model: TypedModel,
+    pulse: usize,
+    input_array: ArrayD<f32>,
+    axis: usize,
+) -> TestCaseResult {
+    setup_test_logger();
+    debug!("Run reference");
+    let len = input_array.shape()[axis] as i64;
+    let ref_model =
+        model.concretize_dims(&SymbolValues::default().with(stream_symbol(), len)).unwrap();
+    let plan = SimplePlan::new(&ref_model).unwrap();
+    let outputs = plan.run(tvec!(Tensor::from(input_array.clone()))).unwrap();
+    check_pulsed_against(&model, pulse, input_array, axis, &outputs[0])
+}
+
+fn check_pulsed_against(
+    model: &TypedModel,
+    pulse: usize,
+    input_array: ArrayD<f32>,
+    axis: usize,
+    expected: &Tensor,
+) -> TestCaseResult {
+    let s = stream_symbol();
+    debug!("Build pulsing model");
+    let pulsed = PulsedModel::new(model, pulse).unwrap();
     let output_fact = pulsed.output_fact(0).unwrap().clone();
 
     let output_stream_axis = output_fact.axis;
@@ -101,10 +130,10 @@ fn proptest_regular_against_pulse(
         .into_tensor();
 
     prop_assert!(
-        &pulsed_output.close_enough(&*outputs[0], true).is_ok(),
+        &pulsed_output.close_enough(expected, true).is_ok(),
         "{:?} == {:?}",
         pulsed_output,
-        outputs[0]
+        expected
     );
     Ok(())
 }
@@ -0,0 +1,44 @@
+use proptest::proptest;
+use proptest::test_runner::TestCaseResult;
+use tract_hir::internal::*;
+use tract_hir::ops::array::{Crop, Pad, PadMode};
+use tract_hir::ops::matmul::MatMulInference;
+
+use super::*;
+
+// b_t = a_t . a_t' (or a_(t-1) . a_t' if delayed)
+fn frame_gram(input: &[f32], delayed: bool, pulse: usize) -> TestCaseResult {
+    let mut model = InferenceModel::default();
+    let a = model
+        .add_source("a", InferenceFact::dt_shape(f32::datum_type(), shapefactoid!(S, 2, 3)))
+        .unwrap();
+    let mut b = a;
+    if delayed {
+        let pad =
+            Pad::new(vec![(1, 0), (0, 0), (0, 0)], PadMode::Constant(Arc::new(Tensor::from(0f32))));
+        b = model.wire_node("pad", pad, &[b]).unwrap()[0];
+        b = model.wire_node("crop", expand(Crop::new(0, 0, 1)), &[b]).unwrap()[0];
+    }
+    let mm = MatMulInference::default().with_b_trans(true);
+    let mm = model.wire_node("mm", expand(mm), &[b, a]).unwrap();
+    model.set_output_outlets(&mm).unwrap();
+    let input = arr1(input).into_shape((input.len() / 6, 2, 3)).unwrap();
+    proptest_regular_against_pulse(model, pulse, input.into_dyn(), 0)
+}
+
+proptest! {
+    #[test]
+    fn proptest_frame_gram(
+        pulse in 1usize..4,
+        delayed in proptest::bool::ANY,
+        input in vec((0usize..6).prop_map(|l| l * 6))
+    ) {
+        frame_gram(&input, delayed, pulse)?;
+    }
+}
+
+#[test]
+fn test_delayed_frame_gram() {
+    let input: Vec<f32> = (0..18).map(|i| i as f32).collect();
+    frame_gram(&input, true, 2).unwrap()
+}
@@ -128,6 +128,10 @@ impl Fact for InferenceFact {
         Ok(self.unify(&InferenceFact::from(t)).is_ok())
     }
 
+    fn matches_with_symbols(&self, t: &Tensor, _symbols: &SymbolValues) -> TractResult<bool> {
+        self.matches(t)
+    }
+
     fn same_as(&self, other: &dyn Fact) -> bool {
         if let Some(other) = other.downcast_ref::<Self>() {
             self.unify(other).is_ok()
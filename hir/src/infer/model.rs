@@ -163,4 +163,15 @@ mod test {
         fn is_sync<T: Sync>() {}
         is_sync::<InferenceModel>();
     }
+
+    #[test]
+    fn run_with_partial_input_fact() -> TractResult<()> {
+        let mut model = InferenceModel::default();
+        let a = model.add_source("a", InferenceFact::default())?;
+        model.set_output_outlets(&[a])?;
+        let plan = SimplePlan::new(model)?;
+        let result = plan.run(tvec!(tensor1(&[1f32, 2.])))?;
+        assert_eq!(*result[0], tensor1(&[1f32, 2.]));
+        Ok(())
+    }
 }
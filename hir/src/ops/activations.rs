@@ -131,6 +131,10 @@ activation!(HardSigmoid, |op, name: &str, model: &mut TypedModel, inputs| {
 pub struct LeakyRelu(#[educe(Hash(method = "hash_f32"))] pub f32);
 
 activation!(LeakyRelu, |op, name: &str, model: &mut TypedModel, inputs| {
+    let dt = model.outlet_fact(inputs[0])?.datum_type;
+    if dt == f32::datum_type() || dt == f64::datum_type() {
+        return model.wire_node(name, tract_core::ops::nn::leaky_relu(op.0), inputs);
+    }
     let zero = broadcast_scalar(0.0, model, inputs)?;
     let alpha = broadcast_scalar(op.0, model, inputs)?;
     let neg = model.wire_node(name.to_string() + ".mul_alpha", mul::unary(alpha), &inputs)?;
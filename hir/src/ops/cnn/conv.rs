@@ -132,9 +132,14 @@ impl Conv {
                     bail!("Input scale must be const")
                 }
             }
+            let mut k_scales = None;
             if let Some(slot) = self.k_scale_input {
                 if let Some(ref value) = inputs[slot].borrow().konst {
-                    scale *= value.to_scalar::<f32>()?;
+                    if value.len() == 1 {
+                        scale *= value.cast_to::<f32>()?.as_slice::<f32>()?[0];
+                    } else {
+                        k_scales = Some(value.cast_to::<f32>()?.into_owned());
+                    }
                 } else {
                     bail!("Filter scale must be const")
                 }
@@ -146,7 +151,11 @@ impl Conv {
                     bail!("Output scale must be const")
                 }
             }
-            if scale != 1.0 {
+            if let Some(mut k_scales) = k_scales {
+                k_scales.as_slice_mut::<f32>()?.iter_mut().for_each(|k| *k *= scale);
+                qp.get_or_insert(QParams::new(dt))
+                    .set_scale_factors(&k_scales.into_arc_tensor())?;
+            } else if scale != 1.0 {
                 qp.get_or_insert(QParams::new(dt)).set_scale_factor(scale);
             }
             if let Some(slot) = self.x_zero_point_input {
@@ -98,8 +98,13 @@ impl Expansion for LayerLogSoftmax {
         target: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        let softmax = LayerSoftmax { axis: self.axis }.wire(name, target, inputs)?;
-        target.wire_node(format!("{}.logsoftmax", name), tract_core::ops::math::ln(), &softmax)
+        let rank = target.outlet_fact(inputs[0])?.rank();
+        let axis = if self.axis < 0 { rank as isize + self.axis } else { self.axis } as usize;
+        target.wire_node(
+            format!("{}.logsoftmax", name),
+            tract_core::ops::nn::Softmax::new((axis..rank).collect(), true),
+            inputs,
+        )
     }
 }
 
@@ -131,32 +136,12 @@ impl Expansion for LayerSoftmax {
         target: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        use tract_core::ops::{math, nn};
-        let input = inputs[0];
-        let rank = target.outlet_fact(input)?.rank();
+        let rank = target.outlet_fact(inputs[0])?.rank();
         let axis = if self.axis < 0 { rank as isize + self.axis } else { self.axis } as usize;
-        let reducing_axes = (axis..rank).collect::<TVec<usize>>();
-        let maxes = target.wire_node(
-            format!("{}.max", name),
-            nn::Reduce::new(reducing_axes.clone(), nn::Reducer::Max),
-            &[input],
-        )?[0];
-        let normed = target.wire_node(
-            format!("{}.normed", name),
-            math::sub::bin_typed(),
-            &[input, maxes],
-        )?[0];
-        let exp =
-            target.wire_node(format!("{}.exp", name), tract_core::ops::math::exp(), &[normed])?[0];
-        let sum = target.wire_node(
-            format!("{}.sum", name),
-            nn::Reduce::new(reducing_axes, nn::Reducer::Sum),
-            &[exp],
-        )?[0];
         target.wire_node(
             format!("{}.softmax", name),
-            tract_core::ops::math::div::bin_typed(),
-            &[exp, sum],
+            tract_core::ops::nn::Softmax::new((axis..rank).collect(), false),
+            inputs,
         )
     }
 }
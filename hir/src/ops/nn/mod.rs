@@ -6,4 +6,4 @@ pub use global_pools::*;
 pub use layer_max::*;
 pub use reduce::{Reduce, Reducer};
 
-pub use tract_core::ops::nn::{sigmoid, DataFormat};
+pub use tract_core::ops::nn::{gelu, hard_swish, leaky_relu, sigmoid, DataFormat};
@@ -32,25 +32,66 @@ pub struct OutputLine {
 #[derive(Clone, Debug, PartialEq)]
 pub enum GeneralDescriptor {
     Append(Vec<GeneralDescriptor>),
+    Const(f32, usize),
+    Failover(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
     IfDefined(Box<GeneralDescriptor>),
     Name(String),
     Offset(Box<GeneralDescriptor>, isize),
+    ReplaceIndex(Box<GeneralDescriptor>, char, isize),
+    Round(Box<GeneralDescriptor>, usize),
+    Scale(f32, Box<GeneralDescriptor>),
+    Sum(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
+    Switch(Vec<GeneralDescriptor>),
 }
 
 impl GeneralDescriptor {
     pub fn inputs(&self) -> TVec<&str> {
-        match self {
-            GeneralDescriptor::Append(ref gds) => gds.iter().fold(tvec!(), |mut acc, gd| {
+        fn union<'a>(gds: &[&'a GeneralDescriptor]) -> TVec<&'a str> {
+            gds.iter().fold(tvec!(), |mut acc, gd| {
                 gd.inputs().iter().for_each(|i| {
                     if !acc.contains(i) {
                         acc.push(i)
                     }
                 });
                 acc
-            }),
+            })
+        }
+        match self {
+            GeneralDescriptor::Append(ref gds) | GeneralDescriptor::Switch(ref gds) => {
+                union(&*gds.iter().collect::<Vec<_>>())
+            }
+            GeneralDescriptor::Const(_, _) => tvec!(),
+            GeneralDescriptor::Failover(ref a, ref b) | GeneralDescriptor::Sum(ref a, ref b) => {
+                union(&[&**a, &**b])
+            }
             GeneralDescriptor::IfDefined(ref gd) => gd.inputs(),
             GeneralDescriptor::Name(ref s) => tvec!(&**s),
             GeneralDescriptor::Offset(ref gd, _) => gd.inputs(),
+            GeneralDescriptor::ReplaceIndex(ref gd, _, _) => gd.inputs(),
+            GeneralDescriptor::Round(ref gd, _) => gd.inputs(),
+            GeneralDescriptor::Scale(_, ref gd) => gd.inputs(),
+        }
+    }
+
+    /// Recognizes the recurrent inputs that are translated to Memory ops:
+    /// a negative time offset on a node, whose value is zero before the
+    /// beginning of the stream.
+    fn as_memory(&self) -> Option<(&str, isize)> {
+        use GeneralDescriptor::*;
+        match self {
+            IfDefined(ref o) => o.as_memory(),
+            Offset(ref n, o) if *o < 0 => {
+                if let Name(n) = &**n {
+                    Some((n, *o))
+                } else {
+                    None
+                }
+            }
+            Failover(ref o, ref fallback) => match &**fallback {
+                Const(value, _) if *value == 0.0 => o.as_memory(),
+                _ => None,
+            },
+            _ => None,
         }
     }
 
@@ -97,31 +138,139 @@ impl GeneralDescriptor {
                     tvec!(InferenceFact::default()),
                 )?;
                 model.add_edge(OutletId::new(id, 0), inlet)?;
+                // constants are repeated for each frame of a non-constant appendee
+                let reference = appendees.iter().find(|a| match a {
+                    Const(_, _) => false,
+                    _ => true,
+                });
                 for (ix, appendee) in appendees.iter().enumerate() {
                     let name = format!("{}-{}", name, ix);
-                    appendee.wire(
-                        InletId::new(id, ix),
-                        &*name,
-                        model,
-                        deferred,
-                        adjust_final_offset,
-                    )?;
-                }
-                return Ok(());
-            }
-            &IfDefined(ref o) => {
-                if let &Offset(ref n, ref o) = &**o {
-                    if let Name(n) = &**n {
-                        let name = format!("{}.memory", name);
-                        model.add_node(
+                    if let Const(value, dim) = appendee {
+                        let reference = reference.with_context(|| {
+                            format!("Append of constants only is not supported: {:?}", self)
+                        })?;
+                        let konst = model.add_node(
                             &*name,
-                            crate::ops::memory::Memory::new(n.to_string(), *o),
+                            crate::ops::time_index::ConstFrames::new(*value, *dim),
                             tvec!(InferenceFact::default()),
                         )?;
-                        deferred.insert(inlet, name);
-                        return Ok(());
+                        model.add_edge(OutletId::new(konst, 0), InletId::new(id, ix))?;
+                        reference.wire(
+                            InletId::new(konst, 0),
+                            &*name,
+                            model,
+                            deferred,
+                            adjust_final_offset,
+                        )?;
+                    } else {
+                        appendee.wire(
+                            InletId::new(id, ix),
+                            &*name,
+                            model,
+                            deferred,
+                            adjust_final_offset,
+                        )?;
+                    }
+                }
+                return Ok(());
+            }
+            &IfDefined(_) | &Failover(_, _) if self.as_memory().is_some() => {
+                let (n, o) = self.as_memory().unwrap();
+                let name = format!("{}.memory", name);
+                model.add_node(
+                    &*name,
+                    crate::ops::memory::Memory::new(n.to_string(), o),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name);
+                return Ok(());
+            }
+            &Failover(ref primary, ref fallback) => {
+                // tract computes "valid" frames only: wherever an output frame is
+                // computed, the primary descriptor is defined. This only holds if
+                // the fallback is the zero padding.
+                match &**fallback {
+                    Const(value, _) if *value == 0.0 => {
+                        return primary.wire(inlet, name, model, deferred, adjust_final_offset)
                     }
+                    _ => bail!("Failover is only supported with a Const(0) fallback: {:?}", self),
+                }
+            }
+            &Offset(ref n, 0) => {
+                return n.wire(inlet, name, model, deferred, adjust_final_offset);
+            }
+            &Sum(ref a, ref b) => {
+                let name = format!("{}.Sum", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Add.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(id, 0), inlet)?;
+                for (ix, term) in [a, b].iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    term.wire(InletId::new(id, ix), &*name, model, deferred, adjust_final_offset)?;
+                }
+                return Ok(());
+            }
+            &Scale(scale, ref n) => {
+                let name = format!("{}.Scale", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_hir::ops::math::Mul.into_hir(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(id, 0), inlet)?;
+                let factor = model.add_const(format!("{}.factor", name), tensor2(&[[*scale]]))?;
+                model.add_edge(factor, InletId::new(id, 1))?;
+                n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &Const(value, dim) => {
+                let konst = tract_ndarray::Array2::from_elem((1, *dim), *value);
+                let konst = model.add_const(format!("{}.Const", name), konst)?;
+                model.add_edge(konst, inlet)?;
+                return Ok(());
+            }
+            &Round(ref n, modulus) => {
+                let name = format!("{}.Round", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::time_index::Round::new(*modulus),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(id, 0), inlet)?;
+                n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &ReplaceIndex(ref n, 'x', 0) => {
+                // tract does not use the x index: it is always 0.
+                return n.wire(inlet, name, model, deferred, adjust_final_offset);
+            }
+            &ReplaceIndex(ref n, 't', t) if *t >= 0 => {
+                let name = format!("{}.ReplaceIndex", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::time_index::ReplaceIndex::new(*t as usize),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(id, 0), inlet)?;
+                n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                return Ok(());
+            }
+            &Switch(cases) => {
+                let name = format!("{}.Switch", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::time_index::Switch::new(cases.len()),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(OutletId::new(id, 0), inlet)?;
+                for (ix, case) in cases.iter().enumerate() {
+                    let name = format!("{}-{}", name, ix);
+                    case.wire(InletId::new(id, ix), &*name, model, deferred, adjust_final_offset)?;
                 }
+                return Ok(());
             }
             &Offset(ref n, o) if *o > 0 => {
                 let name = format!("{}-Delay", name);
@@ -167,6 +316,20 @@ pub struct ParsingContext<'a> {
     pub proto_model: &'a KaldiProtoModel,
 }
 
+impl<'a> ParsingContext<'a> {
+    pub fn component_for_node(&self, name: &str) -> TractResult<&'a Component> {
+        let node = self.proto_model.config_lines.nodes.iter().find(|l| l.0 == name);
+        if let Some((_, NodeLine::Component(line))) = node {
+            self.proto_model
+                .components
+                .get(&line.component)
+                .with_context(|| format!("Could not find component {}", line.component))
+        } else {
+            bail!("Could not find component node {}", name);
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct KaldiOpRegister(
     pub HashMap<String, fn(&ParsingContext, node: &str) -> TractResult<Box<dyn InferenceOp>>>,
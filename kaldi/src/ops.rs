@@ -11,9 +11,12 @@ macro_rules! op_kaldi {
 }
 
 pub(crate) mod affine;
+mod elementwise_product;
 pub(crate) mod lstm_nonlin;
 pub(crate) mod memory;
 mod renorm;
+mod scale_and_offset;
+pub(crate) mod time_index;
 
 pub const AFFINE: &'static [&'static str] =
     &["FixedAffineComponent", "NaturalGradientAffineComponent"];
@@ -22,12 +25,22 @@ pub fn register_all_ops(reg: &mut KaldiOpRegister) {
     for affine in AFFINE {
         reg.insert(affine, affine::affine_component);
     }
-    reg.insert("BackpropTruncationComponent", |_, _| {
-        Ok(Box::new(tract_hir::ops::identity::Identity::default()))
-    });
+    reg.insert("TdnnComponent", affine::tdnn_component);
+    reg.insert("LinearComponent", affine::linear_component);
+    for identity in &["BackpropTruncationComponent", "GeneralDropoutComponent", "NoOpComponent"] {
+        reg.insert(identity, |_, _| Ok(Box::new(tract_hir::ops::identity::Identity::default())));
+    }
+    reg.insert("BatchNormComponent", scale_and_offset::batch_norm);
+    reg.insert("ScaleAndOffsetComponent", scale_and_offset::scale_and_offset);
+    reg.insert("ElementwiseProductComponent", elementwise_product::elementwise_product);
     reg.insert("NormalizeComponent", renorm::renorm);
     reg.insert("LstmNonlinearityComponent", lstm_nonlin::lstm_nonlin);
     reg.insert("RectifiedLinearComponent", |_, _| {
         Ok(expand(tract_hir::ops::activations::Clip::new(Some(0.0), None)))
     });
+    reg.insert("SigmoidComponent", |_, _| Ok(Box::new(tract_hir::ops::nn::sigmoid())));
+    reg.insert("TanhComponent", |_, _| Ok(Box::new(tract_hir::ops::math::tanh())));
+    reg.insert("LogSoftmaxComponent", |_, _| {
+        Ok(expand(tract_hir::ops::nn::LayerLogSoftmax::new(1)))
+    });
 }
@@ -28,6 +28,75 @@ pub fn affine_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn
     }))
 }
 
+pub fn tdnn_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let offsets = component
+        .attributes
+        .get("TimeOffsets")
+        .context("missing attribute TimeOffsets")?
+        .cast_to::<i32>()?
+        .as_slice::<i32>()?
+        .to_vec();
+    if offsets.len() == 0 || offsets.windows(2).any(|pair| pair[1] <= pair[0]) {
+        bail!("TimeOffsets must be a non-empty increasing list, got {:?}", offsets)
+    }
+    let kernel: &Tensor =
+        component.attributes.get("LinearParams").context("missing attribute LinearParams")?;
+    let bias = component.attributes.get("BiasParams");
+    affine_with_offsets(&offsets, kernel, bias)
+}
+
+pub fn linear_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let kernel: &Tensor = component.attributes.get("Params").context("missing attribute Params")?;
+    affine_with_offsets(&[0], kernel, None)
+}
+
+/// Translates a linear transform applied to frames spliced at `offsets` to
+/// a dilated convolution, filling the taps missing from `offsets` with zeros.
+fn affine_with_offsets(
+    offsets: &[i32],
+    kernel: &Tensor,
+    bias: Option<&Arc<Tensor>>,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let o_ti = kernel.to_array_view::<f32>()?.into_dimensionality::<tract_ndarray::Ix2>()?;
+    let output_dim = o_ti.shape()[0];
+    if o_ti.shape()[1] % offsets.len() != 0 {
+        bail!(
+            "Linear params have {} columns, not a multiple of the {} time offsets",
+            o_ti.shape()[1],
+            offsets.len()
+        )
+    }
+    let input_dim = o_ti.shape()[1] / offsets.len();
+    let dilation = offsets.windows(2).map(|pair| (pair[1] - pair[0]) as usize).fold(0, gcd).max(1);
+    let kernel_len = (offsets[offsets.len() - 1] - offsets[0]) as usize / dilation + 1;
+    // O•(offset,I) -> T•I•O = HWIO
+    let mut t_i_o = tract_ndarray::Array3::<f32>::zeros((kernel_len, input_dim, output_dim));
+    for (ix, offset) in offsets.iter().enumerate() {
+        let tap = (offset - offsets[0]) as usize / dilation;
+        let weights = o_ti.slice(tract_ndarray::s![.., ix * input_dim..(ix + 1) * input_dim]);
+        t_i_o.index_axis_mut(tract_ndarray::Axis(0), tap).assign(&weights.t());
+    }
+    // kaldi allows bias-free components, serializing an empty bias vector
+    let bias_params = match bias {
+        Some(bias) if bias.len() == output_dim => Arc::clone(bias),
+        Some(bias) if bias.len() > 0 => {
+            bail!("Expected {} bias params, got {}", output_dim, bias.len())
+        }
+        _ => tract_ndarray::Array1::<f32>::zeros(output_dim).into_arc_tensor(),
+    };
+    Ok(expand(Affine { kernel_len, dilation, linear_params: t_i_o.into_arc_tensor(), bias_params }))
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 #[derive(Clone, Debug, new, Hash)]
 struct Affine {
     kernel_len: usize,
@@ -108,3 +177,71 @@ impl Expansion for Affine {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: &str, input: Tensor) -> TractResult<Arc<Tensor>> {
+        let model = crate::kaldi().model_for_read(&mut model.as_bytes())?.into_optimized()?;
+        let mut outputs = model.into_runnable()?.run(tvec!(input))?;
+        Ok(outputs.remove(0))
+    }
+
+    #[test]
+    fn tdnn() {
+        let model = r#"<Nnet3>
+
+input-node name=input dim=1
+component-node name=tdnn1.affine input=input component=tdnn1.affine
+output-node name=output input=tdnn1.affine
+
+<NumComponents> 1
+<ComponentName> tdnn1.affine <TdnnComponent> <TimeOffsets> [ -1 0 2 ]
+<LinearParams>  [
+  1.0 10.0 100.0 ]
+<BiasParams>  [ 0.5 ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T </TdnnComponent>
+</Nnet3>"#;
+        let input = tensor2(&[[1f32], [2.], [3.], [4.], [5.]]);
+        let output = run(model, input).unwrap();
+        assert_eq!(*output, tensor2(&[[421.5f32], [532.5]]));
+    }
+
+    #[test]
+    fn tdnn_wrong_bias() {
+        let model = r#"<Nnet3>
+
+input-node name=input dim=1
+component-node name=tdnn1.affine input=input component=tdnn1.affine
+output-node name=output input=tdnn1.affine
+
+<NumComponents> 1
+<ComponentName> tdnn1.affine <TdnnComponent> <TimeOffsets> [ -1 0 2 ]
+<LinearParams>  [
+  1.0 10.0 100.0 ]
+<BiasParams>  [ 0.5 0.5 ]
+</TdnnComponent>
+</Nnet3>"#;
+        assert!(crate::kaldi().model_for_read(&mut model.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn linear() {
+        let model = r#"<Nnet3>
+
+input-node name=input dim=1
+component-node name=linear input=input component=linear
+output-node name=output input=linear
+
+<NumComponents> 1
+<ComponentName> linear <LinearComponent> <Params>  [
+  2.0
+  3.0 ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T </LinearComponent>
+</Nnet3>"#;
+        let input = tensor2(&[[1f32], [2.]]);
+        let output = run(model, input).unwrap();
+        assert_eq!(*output, tensor2(&[[2f32, 3.], [4., 6.]]));
+    }
+}
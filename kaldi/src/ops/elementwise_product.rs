@@ -0,0 +1,83 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn elementwise_product(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let input_dim = component.attributes.get("InputDim").context("missing attribute InputDim")?;
+    let output_dim =
+        component.attributes.get("OutputDim").context("missing attribute OutputDim")?;
+    let input_dim = input_dim.cast_to_scalar::<i32>()? as usize;
+    let output_dim = output_dim.cast_to_scalar::<i32>()? as usize;
+    if output_dim == 0 || input_dim % output_dim != 0 {
+        bail!("ElementwiseProduct input dim {} is not a multiple of {}", input_dim, output_dim)
+    }
+    Ok(expand(ElementwiseProduct::new(input_dim, output_dim)))
+}
+
+/// Splits the input channels in input_dim / output_dim blocks and multiplies
+/// them together.
+#[derive(Clone, Debug, new, Hash)]
+struct ElementwiseProduct {
+    input_dim: usize,
+    output_dim: usize,
+}
+
+impl_dyn_hash!(ElementwiseProduct);
+
+impl Expansion for ElementwiseProduct {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ElementwiseProduct".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], self.input_dim.to_dim())?;
+        s.equals(&outputs[0].shape[1], self.output_dim.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let blocks = self.input_dim / self.output_dim;
+        let mut wire = tvec!();
+        for ix in 0..blocks {
+            let part = model.wire_node(
+                format!("{}.part-{}", prefix, ix),
+                tract_hir::ops::array::Slice::new(
+                    1,
+                    ix * self.output_dim,
+                    (ix + 1) * self.output_dim,
+                ),
+                inputs,
+            )?[0];
+            wire = if ix == 0 {
+                tvec!(part)
+            } else {
+                let name = if ix == blocks - 1 {
+                    prefix.to_string()
+                } else {
+                    format!("{}.mul-{}", prefix, ix)
+                };
+                model.wire_node(name, tract_hir::ops::math::mul::bin_typed(), &[wire[0], part])?
+            };
+        }
+        Ok(wire)
+    }
+}
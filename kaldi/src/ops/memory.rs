@@ -4,6 +4,8 @@ use tract_itertools::Itertools;
 
 use tract_hir::internal::*;
 
+use super::time_index;
+
 #[derive(Clone, Debug, new, Hash)]
 pub struct Memory {
     pub name: String,
@@ -98,6 +100,16 @@ fn incorporate_memory_ops_as_scans(
         });
         trace!("Loops still in queue: {:?}. Processing: {:?}", loops, coupled_mem_ops);
 
+        // the scan body sees chunks of frames starting at t=0: ops depending on
+        // the frame index would be wrong.
+        if let Some(node) = time_loop.iter().map(|id| model.node(id)).find(|n| {
+            n.op_is::<time_index::Round>()
+                || n.op_is::<time_index::ReplaceIndex>()
+                || n.op_is::<time_index::Switch>()
+        }) {
+            bail!("{} depends on the frame index, it can not be part of a recurrence", node)
+        }
+
         let scan_inputs: Vec<OutletId> = time_loop
             .iter()
             .flat_map(|node_id| model.node(node_id).inputs.iter())
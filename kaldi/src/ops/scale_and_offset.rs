@@ -0,0 +1,126 @@
+use tract_hir::internal::*;
+
+use crate::model::ParsingContext;
+
+pub fn scale_and_offset(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let dim = component.attributes.get("Dim").context("missing attribute Dim")?;
+    let dim = dim.cast_to_scalar::<i32>()? as usize;
+    let scales = component.attributes.get("Scales").context("missing attribute Scales")?;
+    let offsets = component.attributes.get("Offsets").context("missing attribute Offsets")?;
+    let scales = tile(scales.as_slice::<f32>()?, dim)?;
+    let offsets = tile(offsets.as_slice::<f32>()?, dim)?;
+    Ok(expand(ScaleAndOffset::new(scales, offsets)))
+}
+
+pub fn batch_norm(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = ctx.component_for_node(name)?;
+    let attr = |name: &str| {
+        component.attributes.get(name).with_context(|| format!("missing attribute {}", name))
+    };
+    if !attr("TestMode")?.cast_to_scalar::<bool>()? {
+        bail!("BatchNormComponent {} is not in test mode, its statistics are not final", name)
+    }
+    let dim = attr("Dim")?.cast_to_scalar::<i32>()? as usize;
+    let epsilon = attr("Epsilon")?.cast_to_scalar::<f32>()?;
+    let target_rms = attr("TargetRms")?.cast_to_scalar::<f32>()?;
+    let mean = attr("StatsMean")?.as_slice::<f32>()?;
+    let var = attr("StatsVar")?.as_slice::<f32>()?;
+    // test mode: y = (x - mean) * target_rms / sqrt(var + epsilon)
+    let scales: Vec<f32> = var.iter().map(|v| target_rms * (v + epsilon).powf(-0.5)).collect();
+    let offsets: Vec<f32> = mean.iter().zip(scales.iter()).map(|(m, s)| -m * s).collect();
+    Ok(expand(ScaleAndOffset::new(tile(&scales, dim)?, tile(&offsets, dim)?)))
+}
+
+/// Kaldi stores per-block parameters: repeat them up to the full dimension.
+fn tile(block: &[f32], dim: usize) -> TractResult<Arc<Tensor>> {
+    if block.len() == 0 || dim % block.len() != 0 {
+        bail!("Can not tile {} parameters to dimension {}", block.len(), dim)
+    }
+    let data: Vec<f32> = block.iter().cloned().cycle().take(dim).collect();
+    Ok(tract_ndarray::Array2::from_shape_vec((1, dim), data)?.into_arc_tensor())
+}
+
+#[derive(Clone, Debug, new, Hash)]
+struct ScaleAndOffset {
+    scales: Arc<Tensor>,
+    offsets: Arc<Tensor>,
+}
+
+impl_dyn_hash!(ScaleAndOffset);
+
+impl Expansion for ScaleAndOffset {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "ScaleAndOffset".into()
+    }
+
+    op_kaldi!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[1], self.scales.shape()[1].to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let scaled = model.wire_node(
+            prefix.to_string() + ".scale",
+            tract_hir::ops::math::mul::unary(self.scales.clone()),
+            inputs,
+        )?;
+        model.wire_node(prefix, tract_hir::ops::math::add::unary(self.offsets.clone()), &scaled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch_norm_model(test_mode: &str) -> String {
+        format!(
+            r#"<Nnet3>
+
+input-node name=input dim=2
+component-node name=bn input=input component=bn
+output-node name=output input=bn
+
+<NumComponents> 1
+<ComponentName> bn <BatchNormComponent> <Dim> 2 <BlockDim> 2 <Epsilon> 0 <TargetRms> 1 <TestMode> {} <Count> 10 <StatsMean> [ 1 2 ]
+<StatsVar> [ 4 1 ]
+</BatchNormComponent>
+</Nnet3>"#,
+            test_mode
+        )
+    }
+
+    #[test]
+    fn batch_norm() {
+        let model = batch_norm_model("T");
+        let model =
+            crate::kaldi().model_for_read(&mut model.as_bytes()).unwrap().into_optimized().unwrap();
+        let input = tensor2(&[[3f32, 3.], [5., 0.]]);
+        let output = model.into_runnable().unwrap().run(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[1f32, 1.], [2., -2.]]));
+    }
+
+    #[test]
+    fn batch_norm_in_training_mode() {
+        let model = batch_norm_model("F");
+        assert!(crate::kaldi().model_for_read(&mut model.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,535 @@
+//! Descriptors depending on the frame index (`Round`, `ReplaceIndex`,
+//! `Switch` and `Const` in an `Append`). They operate on the time axis (axis 0) and assume the first
+//! frame of the tensor is at t=0.
+//!
+//! When pulsified, pulses must be aligned on the descriptor period, so the
+//! pulsifiers add the extra delay required to align the stream.
+
+use tract_hir::internal::*;
+use tract_pulse::internal::{PulsedFact, PulsedModel, PulsedOp};
+use tract_pulse::tract_pulse_opl::ops::Delay;
+use tract_pulse::{pulsed_op_to_typed_op, submit_op_pulsifier};
+
+/// `Round(input, modulus)`: frame t is frame `modulus * floor(t / modulus)`.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Round {
+    pub modulus: usize,
+}
+
+impl_dyn_hash!(Round);
+
+impl Op for Round {
+    fn name(&self) -> Cow<str> {
+        "Round".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("modulus: {}", self.modulus)])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Round {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output = input.as_ref().clone();
+        for t in 0..input.shape()[0] {
+            let src = t - t % self.modulus;
+            output.assign_slice(t..t + 1, &input, src..src + 1, 0)?;
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Round {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Round {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+impl PulsedOp for Round {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+fn pulsify_round(
+    op: &Round,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let inputs = align_inputs(node, target, mapping, op.modulus)?;
+    target.wire_node(&*node.name, op.clone(), &inputs)
+}
+
+/// `ReplaceIndex(input, t, index)`: all frames are copies of frame `index`.
+#[derive(Clone, Debug, new, Hash)]
+pub struct ReplaceIndex {
+    pub index: usize,
+}
+
+impl_dyn_hash!(ReplaceIndex);
+
+impl Op for ReplaceIndex {
+    fn name(&self) -> Cow<str> {
+        "ReplaceIndex".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("t: {}", self.index)])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ReplaceIndex {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        if input.shape()[0] <= self.index {
+            bail!(
+                "ReplaceIndex on frame {}, but input is only {} frames",
+                self.index,
+                input.shape()[0]
+            )
+        }
+        let mut output = input.as_ref().clone();
+        for t in 0..input.shape()[0] {
+            output.assign_slice(t..t + 1, &input, self.index..self.index + 1, 0)?;
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ReplaceIndex {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for ReplaceIndex {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+fn pulsify_replace_index(
+    op: &ReplaceIndex,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?;
+    if fact.axis != 0 {
+        bail!("ReplaceIndex can only be pulsified along the first axis")
+    }
+    let op = PulsedReplaceIndex { index: op.index, input_delay: fact.delay };
+    target.wire_node(&*node.name, op, &[input])
+}
+
+/// Streaming version of ReplaceIndex: holds the replacement frame once it
+/// has gone through.
+#[derive(Clone, Debug, Hash)]
+pub struct PulsedReplaceIndex {
+    pub index: usize,
+    pub input_delay: usize,
+}
+
+impl_dyn_hash!(PulsedReplaceIndex);
+
+impl Op for PulsedReplaceIndex {
+    fn name(&self) -> Cow<str> {
+        "PulsedReplaceIndex".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("t: {} input delay: {}", self.index, self.input_delay)])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedReplaceIndex {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(ReplaceIndexState::default())))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct ReplaceIndexState {
+    position: usize,
+    frame: Option<Tensor>,
+}
+
+impl OpState for ReplaceIndexState {
+    fn eval(
+        &mut self,
+        _session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let op = op.downcast_ref::<PulsedReplaceIndex>().context("Wrong op")?;
+        let pulse = input.shape()[0];
+        let wanted = op.index + op.input_delay;
+        if self.frame.is_none() && wanted >= self.position && wanted < self.position + pulse {
+            let ix = wanted - self.position;
+            self.frame = Some(input.slice(0, ix, ix + 1)?);
+        }
+        self.position += pulse;
+        let mut output = input.as_ref().clone();
+        // before the frame is seen, the output is in the pulse delay zone
+        if let Some(frame) = &self.frame {
+            for t in 0..pulse {
+                output.assign_slice(t..t + 1, frame, .., 0)?;
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.position as i64));
+        tensors.extend(self.frame.clone());
+        Ok(tensors)
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let mut tensors = tensors.into_iter();
+        let pos = tensors.next().ok_or_else(|| format_err!("Empty ReplaceIndex state"))?;
+        self.position = *pos.to_scalar::<i64>()? as usize;
+        self.frame = tensors.next();
+        Ok(())
+    }
+}
+
+impl TypedOp for PulsedReplaceIndex {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+impl PulsedOp for PulsedReplaceIndex {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.delay += self.index;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// `Switch(input0, input1, ...)`: frame t is taken from input `t % n`.
+#[derive(Clone, Debug, new, Hash)]
+pub struct Switch {
+    pub cases: usize,
+}
+
+impl_dyn_hash!(Switch);
+
+impl Op for Switch {
+    fn name(&self) -> Cow<str> {
+        "Switch".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("cases: {}", self.cases)])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for Switch {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut output = inputs[0].as_ref().clone();
+        for t in 0..output.shape()[0] {
+            output.assign_slice(t..t + 1, &inputs[t % self.cases], t..t + 1, 0)?;
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Switch {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, self.cases)?;
+        check_output_arity(&outputs, 1)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+            s.equals(&input.shape, &outputs[0].shape)?;
+        }
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Switch {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+impl PulsedOp for Switch {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+fn pulsify_switch(
+    op: &Switch,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let inputs = align_inputs(node, target, mapping, op.cases)?;
+    target.wire_node(&*node.name, op.clone(), &inputs)
+}
+
+/// `Const(value, dim)` in an `Append`: one constant frame of `dim` values for
+/// each frame of the (reference) input.
+#[derive(Clone, Debug, new, Educe)]
+#[educe(Hash)]
+pub struct ConstFrames {
+    #[educe(Hash(method = "hash_f32"))]
+    pub value: f32,
+    pub dim: usize,
+}
+
+impl_dyn_hash!(ConstFrames);
+
+impl Op for ConstFrames {
+    fn name(&self) -> Cow<str> {
+        "ConstFrames".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("value: {} dim: {}", self.value, self.dim)])
+    }
+
+    op_kaldi!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for ConstFrames {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = tract_ndarray::Array2::from_elem((input.shape()[0], self.dim), self.value);
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ConstFrames {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], self.dim.to_dim())?;
+        Ok(())
+    }
+
+    as_op!();
+    to_typed!();
+}
+
+impl TypedOp for ConstFrames {
+    as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(
+            DatumType::F32,
+            &[inputs[0].shape[0].clone(), self.dim.to_dim()]
+        )))
+    }
+}
+
+impl PulsedOp for ConstFrames {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.datum_type = DatumType::F32;
+        fact.shape = tvec!(fact.shape[0].clone(), self.dim.to_dim());
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+fn pulsify_const_frames(
+    op: &ConstFrames,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    if target.outlet_fact(input)?.axis != 0 {
+        bail!("ConstFrames can only be pulsified along the first axis")
+    }
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+/// Delays the inputs so they are synchronized, and so that the pulses
+/// start on a multiple of `period` in the original time frame.
+fn align_inputs(
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    period: usize,
+) -> TractResult<TVec<OutletId>> {
+    let facts = node
+        .inputs
+        .iter()
+        .map(|i| target.outlet_fact(mapping[i]).map(|f| f.clone()))
+        .collect::<TractResult<TVec<_>>>()?;
+    if facts.iter().any(|f| f.axis != 0 || f.base_pulse() % period != 0) {
+        bail!("{} requires pulsing on the time axis with a pulse multiple of {}", node, period)
+    }
+    let delay = facts.iter().map(|f| f.delay).max().unwrap();
+    let delay = (delay + period - 1) / period * period;
+    let mut inputs = tvec!();
+    for (input, fact) in node.inputs.iter().zip(facts.into_iter()) {
+        let mut input = mapping[input];
+        if fact.delay < delay {
+            input = target.wire_node(
+                format!("{}.Delay-{}", &*node.name, input.node),
+                Delay::new(fact.axis, &(&fact).into(), delay - fact.delay, 0),
+                &[input],
+            )?[0];
+        }
+        inputs.push(input);
+    }
+    Ok(inputs)
+}
+
+submit_op_pulsifier!(Round, pulsify_round);
+submit_op_pulsifier!(ReplaceIndex, pulsify_replace_index);
+submit_op_pulsifier!(Switch, pulsify_switch);
+submit_op_pulsifier!(ConstFrames, pulsify_const_frames);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round() {
+        let input = tensor2(&[[0f32], [1.], [2.], [3.], [4.]]).into_arc_tensor();
+        let output = Round::new(2).eval(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0f32], [0.], [2.], [2.], [4.]]));
+    }
+
+    #[test]
+    fn switch() {
+        let a = tensor2(&[[0f32], [1.], [2.], [3.]]).into_arc_tensor();
+        let b = tensor2(&[[10f32], [11.], [12.], [13.]]).into_arc_tensor();
+        let output = Switch::new(2).eval(tvec!(a, b)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0f32], [11.], [2.], [13.]]));
+    }
+
+    #[test]
+    fn replace_index() {
+        let input = tensor2(&[[0f32], [1.], [2.]]).into_arc_tensor();
+        let output = ReplaceIndex::new(1).eval(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[1f32], [1.], [1.]]));
+    }
+
+    #[test]
+    fn const_frames() {
+        let input = tensor2(&[[0f32], [1.], [2.]]).into_arc_tensor();
+        let output = ConstFrames::new(0.5, 2).eval(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor2(&[[0.5f32, 0.5], [0.5, 0.5], [0.5, 0.5]]));
+    }
+}
@@ -10,15 +10,19 @@ use nom::{
     bytes::complete::*,
     combinator::*,
     multi::many_m_n,
-    number::complete::{le_f32, le_f64},
+    number::complete::{le_f32, le_f64, le_i32},
     sequence::*,
     IResult,
 };
 
 pub enum KaldiAttributeKind {
     Bool,
+    Flag,
     Int,
+    IntPair,
+    IntVector,
     Float,
+    FloatPair,
     FloatVector,
     FloatMatrix,
 }
@@ -30,8 +34,16 @@ impl KaldiAttributeKind {
                 map(tag("F"), |_| Tensor::from(false)),
                 map(tag("T"), |_| Tensor::from(true)),
             ))(i),
+            Flag => Ok((i, Tensor::from(true))),
             Int => map(super::integer(true), Tensor::from)(i),
+            IntPair => {
+                map(pair(super::integer(true), super::integer(true)), |(a, b)| tensor1(&[a, b]))(i)
+            }
+            IntVector => Self::parse_int_vector(i),
             Float => map(Self::parse_float_value, Tensor::from)(i),
+            FloatPair => map(pair(Self::parse_float_value, Self::parse_float_value), |(a, b)| {
+                tensor1(&[a, b])
+            })(i),
             FloatVector => preceded(multispaced(tag("FV")), Self::parse_float_vector)(i),
             FloatMatrix => preceded(multispaced(tag("FM")), Self::parse_float_matrix)(i),
         }
@@ -41,11 +53,21 @@ impl KaldiAttributeKind {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
 
+    fn parse_int_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = preceded(tag([4]), le_i32)(i)?;
+        // FIXME pending merge of https://github.com/Geal/nom/pull/995
+        if len == 0 {
+            Ok((i, tensor1::<i32>(&[])))
+        } else {
+            map(many_m_n(len as usize, len as usize, le_i32), |data| tensor1(&*data))(i)
+        }
+    }
+
     fn parse_float_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
         let (i, len) = super::integer(true)(i)?;
         // FIXME pending merge of https://github.com/Geal/nom/pull/995
         if len == 0 {
-            Ok((i, tensor1(&[0.0f32])))
+            Ok((i, tensor1::<f32>(&[])))
         } else {
             map(many_m_n(len as usize, len as usize, le_f32), |data| tensor1(&*data))(i)
         }
@@ -71,6 +93,34 @@ impl KaldiAttributeKind {
 
 use KaldiAttributeKind::*;
 
+fn nonlinear() -> HashMap<&'static str, KaldiAttributeKind> {
+    hashmap! {
+        "Dim" => Int,
+        "BlockDim" => Int,
+        "ValueAvg" => FloatVector,
+        "DerivAvg" => FloatVector,
+        "OderivRms" => FloatVector,
+        "Count" => Float,
+        "OderivCount" => Float,
+        "NumDimsSelfRepaired" => Float,
+        "NumDimsProcessed" => Float,
+        "SelfRepairLowerThreshold" => Float,
+        "SelfRepairUpperThreshold" => Float,
+        "SelfRepairScale" => Float,
+    }
+}
+
+fn updatable(
+    mut attributes: HashMap<&'static str, KaldiAttributeKind>,
+) -> HashMap<&'static str, KaldiAttributeKind> {
+    attributes.insert("LearningRateFactor", Float);
+    attributes.insert("IsGradient", Bool);
+    attributes.insert("MaxChange", Float);
+    attributes.insert("L2Regularize", Float);
+    attributes.insert("LearningRate", Float);
+    attributes
+}
+
 lazy_static::lazy_static! {
     pub static ref COMPONENTS: HashMap<&'static str, HashMap<&'static str, KaldiAttributeKind>> = hashmap! {
         "FixedAffineComponent" => hashmap! {
@@ -123,22 +173,58 @@ lazy_static::lazy_static! {
             "NumElementsProcessed" => Float,
             "NumZeroingBoundaries" => Float,
         },
-        "LogSoftmaxComponent" => hashmap!{
+        "LogSoftmaxComponent" => nonlinear(),
+        "RectifiedLinearComponent" => nonlinear(),
+        "SigmoidComponent" => nonlinear(),
+        "TanhComponent" => nonlinear(),
+        "NoOpComponent" => nonlinear(),
+        "TdnnComponent" => updatable(hashmap!{
+            "TimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumSamplesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        }),
+        "LinearComponent" => updatable(hashmap!{
+            "Params" => FloatMatrix,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "RankInOut" => IntPair,
+            "Alpha" => Float,
+            "NumSamplesHistory" => Float,
+            "UpdatePeriod" => Int,
+        }),
+        "BatchNormComponent" => hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Int,
-            "NumDimsSelfRepaired" => Int,
-            "NumDimsProcessed" => Int,
+            "BlockDim" => Int,
+            "Epsilon" => Float,
+            "TargetRms" => Float,
+            "TestMode" => Bool,
+            "Count" => Float,
+            "StatsMean" => FloatVector,
+            "StatsVar" => FloatVector,
         },
-        "RectifiedLinearComponent" => hashmap!{
+        "ScaleAndOffsetComponent" => updatable(hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Float,
-            "NumDimsSelfRepaired" => Float,
-            "NumDimsProcessed" => Float,
-            "SelfRepairScale" => Float,
+            "Scales" => FloatVector,
+            "Offsets" => FloatVector,
+            "UseNaturalGradient" => Bool,
+            "Rank" => Int,
+        }),
+        "GeneralDropoutComponent" => hashmap!{
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "TimePeriod" => Int,
+            "DropoutProportion" => Float,
+            "Continuous" => Flag,
+            "TestMode" => Flag,
+        },
+        "ElementwiseProductComponent" => hashmap!{
+            "InputDim" => Int,
+            "OutputDim" => Int,
         }
     };
 }
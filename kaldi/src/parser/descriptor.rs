@@ -1,46 +1,130 @@
 use nom::IResult;
 use nom::{
-    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list, sequence::*,
+    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list,
+    number::complete::float, sequence::*,
 };
 
 use crate::model::GeneralDescriptor;
+use crate::parser::config_lines::uinteger;
 use crate::parser::spaced;
 
 pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
     spaced(nom::branch::alt((
+        map(preceded(function("Append"), cut(list_of_descriptors)), GeneralDescriptor::Append),
         map(
             preceded(
-                tag("Append"),
+                function("Offset"),
                 cut(delimited(
                     spaced(tag("(")),
-                    separated_list(spaced(tag(",")), parse_general),
+                    tuple((
+                        parse_general,
+                        preceded(spaced(tag(",")), integer),
+                        opt(preceded(spaced(tag(",")), verify(integer, |x: &i32| *x == 0))),
+                    )),
                     spaced(tag(")")),
                 )),
             ),
-            GeneralDescriptor::Append,
+            |(inner, offset, _x)| GeneralDescriptor::Offset(Box::new(inner), offset as isize),
         ),
         map(
             preceded(
-                tag("Offset"),
+                function("IfDefined"),
+                cut(delimited(spaced(tag("(")), parse_general, spaced(tag(")")))),
+            ),
+            |inner| GeneralDescriptor::IfDefined(Box::new(inner)),
+        ),
+        map(
+            preceded(
+                function("Sum"),
                 cut(delimited(
                     spaced(tag("(")),
-                    separated_pair(parse_general, spaced(tag(",")), integer),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
                     spaced(tag(")")),
                 )),
             ),
-            |(inner, offset)| GeneralDescriptor::Offset(Box::new(inner), offset as isize),
+            |(a, b)| GeneralDescriptor::Sum(Box::new(a), Box::new(b)),
         ),
         map(
             preceded(
-                tag("IfDefined"),
-                cut(delimited(spaced(tag("(")), parse_general, spaced(tag(")")))),
+                function("Failover"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(parse_general, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
             ),
-            |inner| GeneralDescriptor::IfDefined(Box::new(inner)),
+            |(a, b)| GeneralDescriptor::Failover(Box::new(a), Box::new(b)),
+        ),
+        map(
+            preceded(
+                function("Scale"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(float, spaced(tag(",")), parse_general),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(scale, inner)| GeneralDescriptor::Scale(scale, Box::new(inner)),
+        ),
+        map(
+            preceded(
+                function("Const"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(float, spaced(tag(",")), uinteger),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(value, dim)| GeneralDescriptor::Const(value, dim),
+        ),
+        map(
+            preceded(
+                function("Round"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    separated_pair(
+                        parse_general,
+                        spaced(tag(",")),
+                        verify(uinteger, |m: &usize| *m > 0),
+                    ),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, modulus)| GeneralDescriptor::Round(Box::new(inner), modulus),
+        ),
+        map(
+            preceded(
+                function("ReplaceIndex"),
+                cut(delimited(
+                    spaced(tag("(")),
+                    tuple((
+                        parse_general,
+                        preceded(spaced(tag(",")), one_of("tx")),
+                        preceded(spaced(tag(",")), integer),
+                    )),
+                    spaced(tag(")")),
+                )),
+            ),
+            |(inner, variable, value)| {
+                GeneralDescriptor::ReplaceIndex(Box::new(inner), variable, value as isize)
+            },
         ),
+        map(preceded(function("Switch"), cut(list_of_descriptors)), GeneralDescriptor::Switch),
         map(super::config_lines::identifier, |i| GeneralDescriptor::Name(i.to_string())),
     )))(i)
 }
 
+/// Matches a descriptor function name, only if it is followed by an opening parenthesis.
+fn function<'a>(name: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    terminated(tag(name), peek(spaced(tag("("))))
+}
+
+fn list_of_descriptors(i: &str) -> IResult<&str, Vec<GeneralDescriptor>> {
+    delimited(spaced(tag("(")), separated_list(spaced(tag(",")), parse_general), spaced(tag(")")))(
+        i,
+    )
+}
+
 pub fn integer(i: &str) -> IResult<&str, i32> {
     map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>())(i)
 }
@@ -71,6 +155,55 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_offset_with_x() {
+        assert_eq!(
+            parse_general("Offset(input, -1, 0)").unwrap().1,
+            Offset(name("input").into(), -1)
+        )
+    }
+
+    #[test]
+    fn test_sum_scale_const() {
+        assert_eq!(
+            parse_general("Sum(Scale(0.66, tdnn2.dropout), Const(1.5, 12))").unwrap().1,
+            Sum(Scale(0.66, name("tdnn2.dropout").into()).into(), Const(1.5, 12).into())
+        )
+    }
+
+    #[test]
+    fn test_replace_index_round() {
+        assert_eq!(
+            parse_general("Append(input, ReplaceIndex(ivector, t, 0), Round(lda, 3))").unwrap().1,
+            Append(vec!(
+                name("input"),
+                ReplaceIndex(name("ivector").into(), 't', 0),
+                Round(name("lda").into(), 3)
+            ))
+        )
+    }
+
+    #[test]
+    fn test_round_zero() {
+        assert!(parse_general("Round(lda, 0)").is_err())
+    }
+
+    #[test]
+    fn test_switch_failover() {
+        assert_eq!(
+            parse_general("Switch(a, Failover(Offset(b, -1), c))").unwrap().1,
+            Switch(vec!(
+                name("a"),
+                Failover(Offset(name("b").into(), -1).into(), name("c").into())
+            ))
+        )
+    }
+
+    #[test]
+    fn test_name_starting_with_function() {
+        assert_eq!(parse_general("Scale_1").unwrap().1, name("Scale_1"))
+    }
+
     #[test]
     fn test_lstm() {
         assert_eq!(
@@ -9,14 +9,19 @@ use nom::{
 use super::{integer, multispaced, open_any, spaced};
 
 pub fn attributes(i: &[u8]) -> IResult<&[u8], HashMap<String, Arc<Tensor>>> {
-    let (i, attributes) = nom::multi::many0(map(pair(open_any, tensor), |(k, v)| {
-        (k.to_string(), v.into_arc_tensor())
+    let (i, attributes) = nom::multi::many0(map(pair(open_any, opt(tensor)), |(k, v)| {
+        // value-less tokens (like <TestMode> in GeneralDropoutComponent) are flags
+        (k.to_string(), v.unwrap_or_else(|| Tensor::from(true)).into_arc_tensor())
     }))(i)?;
     Ok((i, attributes.into_iter().collect()))
 }
 
 pub fn tensor(i: &[u8]) -> IResult<&[u8], Tensor> {
-    nom::branch::alt((scalar, vector, matrix))(i)
+    nom::branch::alt((scalar_pair, scalar, vector, matrix))(i)
+}
+
+pub fn scalar_pair(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map(separated_pair(float, space1, float), |(a, b)| tensor1(&[a, b]))(i)
 }
 
 pub fn scalar(i: &[u8]) -> IResult<&[u8], Tensor> {
@@ -72,12 +77,49 @@ output-node name=output input=fixed1
         nnet3(slice.as_bytes()).unwrap();
     }
 
+    #[test]
+    fn test_nnet3_tdnn() {
+        let slice = r#"<Nnet3>
+
+input-node name=input dim=2
+component-node name=tdnn1.affine input=input component=tdnn1.affine
+output-node name=output input=tdnn1.affine
+
+<NumComponents> 1
+<ComponentName> tdnn1.affine <TdnnComponent> <MaxChange> 0.75 <L2Regularize> 0.01 <LearningRate> 0.001 <TimeOffsets> [ -1 0 1 ]
+<LinearParams>  [
+  1.0 2.0 3.0 4.0 5.0 6.0 ]
+<BiasParams>  [ ]
+<OrthonormalConstraint> 0 <UseNaturalGradient> T <NumSamplesHistory> 2000 <AlphaInOut> 4 4 <RankInOut> 20 80 </TdnnComponent>
+</Nnet3>"#;
+        let model = nnet3(slice.as_bytes()).unwrap();
+        let tdnn = &model.components["tdnn1.affine"];
+        assert_eq!(*tdnn.attributes["TimeOffsets"], tensor1(&[-1.0f32, 0.0, 1.0]));
+        assert_eq!(*tdnn.attributes["AlphaInOut"], tensor1(&[4.0f32, 4.0]));
+        assert_eq!(tdnn.attributes["BiasParams"].len(), 0);
+    }
+
     #[test]
     fn test_vector() {
         let slice = r#"[ 7.0 8.0 ]"#;
         assert_eq!(tensor(slice.as_bytes()).unwrap().1, tensor1(&[7.0f32, 8.0]));
     }
 
+    #[test]
+    fn test_scalar_pair() {
+        let slice = r#"4 4"#;
+        assert_eq!(tensor(slice.as_bytes()).unwrap().1, tensor1(&[4.0f32, 4.0]));
+    }
+
+    #[test]
+    fn test_flags() {
+        let slice = r#"<Dim> 12 <Continuous> <TestMode> </GeneralDropoutComponent>"#;
+        let attributes = attributes(slice.as_bytes()).unwrap().1;
+        assert_eq!(*attributes["Dim"], tensor0(12.0f32));
+        assert_eq!(*attributes["Continuous"], tensor0(true));
+        assert_eq!(*attributes["TestMode"], tensor0(true));
+    }
+
     #[test]
     fn test_matrix() {
         let slice = r#"[
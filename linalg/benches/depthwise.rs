@@ -0,0 +1,54 @@
+#[macro_use]
+extern crate criterion;
+extern crate tract_linalg;
+use criterion::Criterion;
+
+// 3x3 taps over a 16 pixels wide image
+const OFFSETS: [isize; 9] = [0, 1, 2, 16, 17, 18, 32, 33, 34];
+
+fn hwc_f32(c: &mut Criterion, channels: usize) {
+    c.bench_function(&format!("depthwise_hwc_f32_{}", channels), move |be| {
+        let offsets: Vec<isize> = OFFSETS.iter().map(|o| o * channels as isize).collect();
+        let kernel = vec![1.0f32; 9 * channels];
+        let input = vec![1.0f32; 48 * channels];
+        let mut acc = vec![0.0f32; channels];
+        let ref op = (tract_linalg::ops().depthwise_f32)();
+        be.iter(|| unsafe { op.run_hwc(&mut acc, &kernel, input.as_ptr(), &offsets) });
+    });
+}
+
+fn chw_f32(c: &mut Criterion, len: usize) {
+    c.bench_function(&format!("depthwise_chw_f32_{}", len), move |be| {
+        let offsets: Vec<isize> = OFFSETS.iter().map(|o| o / 16 * len as isize + o % 16).collect();
+        let kernel = vec![1.0f32; 9];
+        let input = vec![1.0f32; 3 * len + 2];
+        let mut acc = vec![0.0f32; len];
+        let ref op = (tract_linalg::ops().depthwise_f32)();
+        be.iter(|| unsafe { op.run_chw(&mut acc, &kernel, input.as_ptr(), &offsets) });
+    });
+}
+
+fn hwc_i8(c: &mut Criterion, channels: usize) {
+    c.bench_function(&format!("depthwise_hwc_i8_{}", channels), move |be| {
+        let offsets: Vec<isize> = OFFSETS.iter().map(|o| o * channels as isize).collect();
+        let kernel = vec![1i8; 9 * channels];
+        let input = vec![1i8; 48 * channels];
+        let mut acc = vec![0i32; channels];
+        let ref op = (tract_linalg::ops().depthwise_i8_i32)();
+        be.iter(|| unsafe { op.run_hwc(&mut acc, &kernel, input.as_ptr(), &offsets) });
+    });
+}
+
+fn bs(c: &mut Criterion) {
+    hwc_f32(c, 32);
+    hwc_f32(c, 128);
+    hwc_f32(c, 512);
+    chw_f32(c, 16);
+    chw_f32(c, 112);
+    hwc_i8(c, 32);
+    hwc_i8(c, 128);
+    hwc_i8(c, 512);
+}
+
+criterion_group!(benches, bs);
+criterion_main!(benches);
@@ -1,30 +1,36 @@
 use criterion::*;
 use tract_data::internal::*;
+use tract_linalg::mmm::MatMatMul;
 
-use DatumType::F32;
+fn run(be: &mut Bencher, mut mm: Box<dyn MatMatMul>, m: usize, k: usize) {
+    unsafe {
+        let pa =
+            Tensor::uninitialized_aligned::<f32>(&[mm.a_pack().len(m)], mm.a_pack().alignment())
+                .unwrap();
+        let b = tensor1(&vec![0.0; k]);
+        let mut c = Tensor::zero::<f32>(&[m]).unwrap();
+        mm.b_vec_from_data();
+        be.iter(move || mm.run(&pa.view(), &b.view(), &mut c.view_mut(), &[]));
+    }
+}
 
 fn mat_vec_mul(c: &mut Criterion) {
     let mut group = c.benchmark_group("mat_vec_mul");
-    unsafe {
-        for (m, k) in [(64usize, 64usize)].iter() {
-            group.throughput(Throughput::Elements((m * k) as u64));
+    let ops = tract_linalg::ops();
+    for &(m, k) in [(64usize, 64usize), (256, 256), (1024, 256), (1024, 1024)].iter() {
+        group.throughput(Throughput::Elements((m * k) as u64));
+        for imp in &ops.mmm_f32_impls {
             group.bench_with_input(
-                BenchmarkId::from_parameter(format!("{}x{}", m, k)),
+                BenchmarkId::new(&imp.name, format!("{}x{}", m, k)),
                 &(m, k),
-                |be, (&m, &k)| {
-                    let mut mm = tract_linalg::ops().mmm(F32, F32, F32, m, k, 1).unwrap();
-                    let pa = Tensor::uninitialized_aligned::<f32>(
-                        &[mm.a_pack().len(m)],
-                        mm.a_pack().alignment(),
-                    )
-                    .unwrap();
-                    let b = tensor1(&vec![0.0; k]);
-                    let mut c = Tensor::zero::<f32>(&[m]).unwrap();
-                    mm.b_vec_from_data();
-                    be.iter(move || mm.run(&pa.view(), &b.view(), &mut c.view_mut(), &[]));
-                },
+                |be, &(m, k)| run(be, (imp.constructor)(m, k, 1), m, k),
             );
         }
+        group.bench_with_input(
+            BenchmarkId::new("auto", format!("{}x{}", m, k)),
+            &(m, k),
+            |be, &(m, k)| run(be, ops.mmm_f32(m, k, 1), m, k),
+        );
     }
     group.finish();
 }
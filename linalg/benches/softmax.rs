@@ -0,0 +1,22 @@
+#[macro_use]
+extern crate criterion;
+extern crate tract_linalg;
+use criterion::Criterion;
+
+fn ssoftmax(c: &mut Criterion, n: usize) {
+    c.bench_function(&format!("ssoftmax_{}", n), move |be| {
+        let mut s = (0..n).map(|i| i as f32 / 10.0).collect::<Vec<f32>>();
+        let ref op = (tract_linalg::ops().softmax_f32)();
+        be.iter(|| op.run(&mut s));
+    });
+}
+
+fn bs(c: &mut Criterion) {
+    ssoftmax(c, 8);
+    ssoftmax(c, 128);
+    ssoftmax(c, 1000);
+    ssoftmax(c, 4096);
+}
+
+criterion_group!(benches, bs);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+#[macro_use]
+extern crate criterion;
+extern crate tract_linalg;
+use criterion::Criterion;
+use tract_data::internal::*;
+use tract_linalg::sparse::BlockSparseMatrix;
+
+// one stored column every `every` columns, in every row block
+fn sparse_f32(c: &mut Criterion, m: usize, k: usize, n: usize, every: usize) {
+    c.bench_function(&format!("sparse_f32_{}x{}x{}_1_in_{}", m, k, n, every), move |be| unsafe {
+        let mut op = (tract_linalg::ops().sparse_mmm_f32)(m, k, n);
+        let a =
+            tensor1(&*(0..m * k).map(|i| (i % k % every == 0) as usize as f32).collect::<Vec<_>>())
+                .into_shape(&[m, k])
+                .unwrap();
+        let a = BlockSparseMatrix::<f32>::from_view(&a.view(), 0, 1, op.mr()).unwrap();
+        let pb =
+            Tensor::zero_aligned::<f32>(&[op.b_pack().len(n)], op.b_pack().alignment()).unwrap();
+        let mut c = Tensor::zero::<f32>(&[m, n]).unwrap();
+        op.c_from_data_and_strides(n as isize, 1);
+        be.iter(|| op.run(&a, &pb.view(), &mut c.view_mut(), None).unwrap());
+    });
+}
+
+fn bs(c: &mut Criterion) {
+    for &every in &[2, 5, 10] {
+        sparse_f32(c, 256, 256, 64, every);
+    }
+}
+
+criterion_group!(benches, bs);
+criterion_main!(benches);
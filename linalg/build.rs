@@ -58,8 +58,16 @@ fn main() {
                         // clang at least (dunno about gcc) outputs .asm files in the
                         // root directory that we need to clean up so we don't pollute
                         // the build output/working directory
+                        let _ = fs::remove_file("fma_dw_f32_8.asm");
+                        let _ = fs::remove_file("fma_dw_i8_8.asm");
                         let _ = fs::remove_file("fma_mmm_f32_16x6.asm");
+                        let _ = fs::remove_file("fma_mmm_f32_64x1.asm");
+                        let _ = fs::remove_file("fma_mmm_f64_8x4.asm");
                         let _ = fs::remove_file("fma_mmm_i8_8x8.asm");
+                        let _ = fs::remove_file("fma_sigmoid_f32_8n.asm");
+                        let _ = fs::remove_file("fma_softmax_f32_8n.asm");
+                        let _ = fs::remove_file("fma_sparse_f32_4x16.asm");
+                        let _ = fs::remove_file("fma_tanh_f32_8n.asm");
                     }
                 }
                 "macos" => {
@@ -5,7 +5,7 @@ use crate::frame::MatMatMulImpl;
 use crate::frame::SigmoidImpl;
 use crate::frame::TanhImpl;
 
-use crate::Ops;
+use crate::{MMMImpl, Ops};
 
 fn has_neon_cpuinfo() -> std::io::Result<bool> {
     let cpu_info = fs::read_to_string("/proc/cpuinfo")?;
@@ -24,9 +24,7 @@ fn has_neon() -> bool {
 pub fn plug(ops: &mut Ops) {
     if has_neon() {
         log::info!("armv7neon activated (smmm, ssigmoid), stanh)");
-        ops.mmm_f32 = Box::new(|m, k, n| {
-            Box::new(MatMatMulImpl::<armv7neon::MatMatMulF32x8x4, f32, f32, f32, f32>::new(m, k, n))
-        });
+        ops.mmm_f32_impls = vec![MMMImpl::f32::<armv7neon::MatMatMulF32x8x4>()];
         ops.qmmm_i8_i8 = Box::new(|m, k, n| {
             Box::new(MatMatMulImpl::<armv7neon::MatMatMulI8x8x4, i8, i8, i8, i32>::new(m, k, n))
         });
@@ -40,9 +38,7 @@ pub fn plug(ops: &mut Ops) {
         ops.tanh_f32 = Box::new(|| Box::new(TanhImpl::<armv7neon::TanhF32x4n, f32>::new()));
     } else {
         log::info!("armvfpv2 activated for smmm");
-        ops.mmm_f32 = Box::new(|m, k, n| {
-            Box::new(MatMatMulImpl::<armvfpv2::MatMatMulF32x4x4, f32, f32, f32, f32>::new(m, k, n))
-        });
+        ops.mmm_f32_impls = vec![MMMImpl::f32::<armvfpv2::MatMatMulF32x4x4>()];
     }
 }
 
@@ -1,9 +1,12 @@
 mod arm64simd;
 
-use crate::Ops;
+use crate::{MMMImpl, Ops};
 
+use crate::frame::DepthWiseImpl;
 use crate::frame::MatMatMulImpl;
 use crate::frame::SigmoidImpl;
+use crate::frame::SoftmaxImpl;
+use crate::frame::SparseMatMulImpl;
 use crate::frame::TanhImpl;
 
 fn is_cortex_a5x() -> std::io::Result<bool> {
@@ -17,17 +20,20 @@ fn is_cortex_a5x() -> std::io::Result<bool> {
 pub fn plug(ops: &mut Ops) {
     if is_cortex_a5x().unwrap_or(false) {
         log::info!("arm64simd activated for smmm (cortex A53/A55 variant)");
-        ops.mmm_f32 = Box::new(|m, k, n| {
-            Box::new(MatMatMulImpl::<arm64simd::MatMatMulF32x8x8A5x, f32, f32, f32, f32>::new(
-                m, k, n,
-            ))
-        });
+        ops.mmm_f32_impls = vec![
+            MMMImpl::f32::<arm64simd::MatMatMulF32x8x8A5x>(),
+            MMMImpl::f32::<arm64simd::MatMatMulF32x64x1>(),
+        ];
     } else {
         log::info!("arm64simd activated for smmm (generic variant)");
-        ops.mmm_f32 = Box::new(|m, k, n| {
-            Box::new(MatMatMulImpl::<arm64simd::MatMatMulF32x8x8, f32, f32, f32, f32>::new(m, k, n))
-        })
+        ops.mmm_f32_impls = vec![
+            MMMImpl::f32::<arm64simd::MatMatMulF32x8x8>(),
+            MMMImpl::f32::<arm64simd::MatMatMulF32x64x1>(),
+        ];
     }
+    ops.mmm_f64 = Box::new(|m, k, n| {
+        Box::new(MatMatMulImpl::<arm64simd::MatMatMulF64x8x4, f64, f64, f64, f64>::new(m, k, n))
+    });
     ops.qmmm_i8_i8 = Box::new(|m, k, n| {
         Box::new(MatMatMulImpl::<arm64simd::MatMatMulI8x8x8, i8, i8, i8, i32>::new(m, k, n))
     });
@@ -36,4 +42,12 @@ pub fn plug(ops: &mut Ops) {
     });
     ops.sigmoid_f32 = Box::new(|| Box::new(SigmoidImpl::<arm64simd::SigmoidF32x4n, f32>::new()));
     ops.tanh_f32 = Box::new(|| Box::new(TanhImpl::<arm64simd::TanhF32x4n, f32>::new()));
+    ops.softmax_f32 = Box::new(|| Box::new(SoftmaxImpl::<arm64simd::SoftmaxF32x4n, f32>::new()));
+    ops.depthwise_f32 =
+        Box::new(|| Box::new(DepthWiseImpl::<arm64simd::DepthWiseF32x4, f32, f32>::new()));
+    ops.depthwise_i8_i32 =
+        Box::new(|| Box::new(DepthWiseImpl::<arm64simd::DepthWiseI8x8, i8, i32>::new()));
+    ops.sparse_mmm_f32 = Box::new(|m, k, n| {
+        Box::new(SparseMatMulImpl::<arm64simd::SparseF32x4x8, f32>::new(m, k, n))
+    });
 }
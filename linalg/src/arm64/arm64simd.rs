@@ -1,12 +1,21 @@
+use crate::frame::depthwise::*;
 use crate::frame::mmm::*;
 use crate::frame::sigmoid::*;
+use crate::frame::softmax::*;
+use crate::frame::sparse::*;
 use crate::frame::tanh::*;
 
 extern "C" {
+    fn arm64simd_dw_f32_4(spec: *const DepthWiseKerSpec<f32, f32>) -> isize;
+    fn arm64simd_dw_i8_8(spec: *const DepthWiseKerSpec<i8, i32>) -> isize;
     fn arm64simd_mmm_f32_8x8_a5x(op: *const MatMatMulKerSpec<f32>) -> isize;
     fn arm64simd_mmm_f32_8x8_gen(op: *const MatMatMulKerSpec<f32>) -> isize;
+    fn arm64simd_mmm_f32_64x1(op: *const MatMatMulKerSpec<f32>) -> isize;
+    fn arm64simd_mmm_f64_8x4(op: *const MatMatMulKerSpec<f64>) -> isize;
     fn arm64simd_mmm_i8_8x8(op: *const MatMatMulKerSpec<i32>) -> isize;
     fn arm64simd_sigmoid_f32_4n(ptr: *mut f32, count: usize);
+    fn arm64simd_softmax_f32_4n(ptr: *mut f32, count: usize, max: f32) -> f32;
+    fn arm64simd_sparse_f32_4x8(spec: *const SparseKerSpec<f32>) -> isize;
     fn arm64simd_tanh_f32_4n(ptr: *mut f32, count: usize);
 }
 
@@ -78,6 +87,74 @@ impl MatMatMulKer<f32> for MatMatMulF32x8x8 {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct MatMatMulF32x64x1;
+
+impl MatMatMulKer<f32> for MatMatMulF32x64x1 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        64
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        1
+    }
+    fn alignment_bytes_packed_a() -> usize {
+        16
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        4
+    }
+    fn end_padding_packed_a() -> usize {
+        0
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(op: &MatMatMulKerSpec<f32>) -> isize {
+        unsafe { arm64simd_mmm_f32_64x1(op) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MatMatMulF64x8x4;
+
+impl MatMatMulKer<f64> for MatMatMulF64x8x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_packed_a() -> usize {
+        16
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        16
+    }
+    fn end_padding_packed_a() -> usize {
+        0
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(op: &MatMatMulKerSpec<f64>) -> isize {
+        unsafe { arm64simd_mmm_f64_8x4(op) }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct MatMatMulI8x8x8;
 
@@ -168,6 +245,28 @@ impl SigmoidKer<f32> for SigmoidF32x4n {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct SoftmaxF32x4n;
+
+impl SoftmaxKer<f32> for SoftmaxF32x4n {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn alignment_bytes() -> usize {
+        16
+    }
+    #[inline(never)]
+    fn run(buf: &mut [f32], max: f32) -> f32 {
+        unsafe { arm64simd_softmax_f32_4n(buf.as_mut_ptr(), buf.len(), max) }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct TanhF32x4n;
 
@@ -190,8 +289,74 @@ impl TanhKer<f32> for TanhF32x4n {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct DepthWiseF32x4;
+
+impl DepthWiseKer<f32, f32> for DepthWiseF32x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(never)]
+    fn kernel(spec: &DepthWiseKerSpec<f32, f32>) -> isize {
+        unsafe { arm64simd_dw_f32_4(spec) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DepthWiseI8x8;
+
+impl DepthWiseKer<i8, i32> for DepthWiseI8x8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(never)]
+    fn kernel(spec: &DepthWiseKerSpec<i8, i32>) -> isize {
+        unsafe { arm64simd_dw_i8_8(spec) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SparseF32x4x8;
+
+impl SparseMatMulKer<f32> for SparseF32x4x8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        16
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(spec: &SparseKerSpec<f32>) -> isize {
+        unsafe { arm64simd_sparse_f32_4x8(spec) }
+    }
+}
+
 test_mmm_kernel_f32!(crate::arm64::arm64simd::MatMatMulF32x8x8A5x, test_MatMatMulF32x8x8a5x, true);
 test_mmm_kernel_f32!(crate::arm64::arm64simd::MatMatMulF32x8x8, test_MatMatMulF32x8x8, true);
+test_mmm_kernel_f32!(crate::arm64::arm64simd::MatMatMulF32x64x1, test_MatMatMulF32x64x1, true);
+test_mmm_kernel_f64!(crate::arm64::arm64simd::MatMatMulF64x8x4, test_MatMatMulF64x8x4, true);
 test_mmm_kernel_i8!(crate::arm64::arm64simd::MatMatMulI8x8x8, test_MatMatMulI8x8x8, true);
 test_mmm_kernel_i8_i32!(
     crate::arm64::arm64simd::MatMatMulI8xI32x8x8,
@@ -202,5 +367,16 @@ test_mmm_kernel_i8_i32!(
 #[cfg(test)]
 mod test_simd {
     sigmoid_frame_tests!(true, crate::arm64::arm64simd::SigmoidF32x4n);
+    softmax_frame_tests!(true, crate::arm64::arm64simd::SoftmaxF32x4n);
     tanh_frame_tests!(true, crate::arm64::arm64simd::TanhF32x4n);
+
+    mod depthwise_f32_f32 {
+        depthwise_frame_tests!(true, crate::arm64::arm64simd::DepthWiseF32x4, f32, f32);
+    }
+    mod depthwise_i8_i32 {
+        depthwise_frame_tests!(true, crate::arm64::arm64simd::DepthWiseI8x8, i8, i32);
+    }
+    mod sparse_f32 {
+        sparse_mmm_frame_tests!(true, crate::arm64::arm64simd::SparseF32x4x8);
+    }
 }
@@ -1,4 +1,6 @@
 #[macro_use]
+pub mod depthwise;
+#[macro_use]
 pub mod lut;
 #[macro_use]
 pub mod mmm;
@@ -6,11 +8,18 @@ pub mod pack;
 #[macro_use]
 pub mod sigmoid;
 #[macro_use]
+pub mod softmax;
+#[macro_use]
+pub mod sparse;
+#[macro_use]
 pub mod tanh;
 
 pub use pack::Packer;
 
+pub use self::depthwise::DepthWiseImpl;
 pub use self::mmm::{MatMatMul, MatMatMulImpl};
 
 pub use self::sigmoid::SigmoidImpl;
+pub use self::softmax::SoftmaxImpl;
+pub use self::sparse::SparseMatMulImpl;
 pub use self::tanh::TanhImpl;
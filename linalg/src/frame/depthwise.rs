@@ -0,0 +1,314 @@
+use num_traits::AsPrimitive;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+
+/// Arguments for a depthwise kernel invocation.
+///
+/// Kernels process `blocks` consecutive blocks of `nr` lanes. For each lane
+/// `i` of the block and each tap `t`:
+///
+/// `acc[i] += kernel[t * kernel_tap_stride + i] * input[offsets[t] + i]`
+///
+/// or, when `broadcast` is non zero, `kernel[t]` is used for every lane.
+/// Strides and offsets are counted in elements. Kernel, input and
+/// accumulator pointers are all moved forward by `nr` between two blocks
+/// (the kernel pointer stays put in broadcast mode).
+#[repr(C)]
+#[derive(Debug)]
+pub struct DepthWiseKerSpec<TA, TI>
+where
+    TA: Copy,
+    TI: Copy,
+{
+    pub acc: *mut TI,
+    pub kernel: *const TA,
+    pub kernel_tap_stride: isize,
+    pub input: *const TA,
+    pub offsets: *const isize,
+    pub taps: usize,
+    pub blocks: usize,
+    pub broadcast: usize,
+}
+
+pub trait DepthWiseKer<TA, TI>: Send + Sync + Debug + dyn_clone::DynClone + Clone
+where
+    TA: Copy,
+    TI: Copy,
+{
+    fn name() -> &'static str;
+    fn nr() -> usize;
+    fn kernel(spec: &DepthWiseKerSpec<TA, TI>) -> isize;
+}
+
+pub trait DepthWise<TA, TI>: Send + Sync + Debug + dyn_clone::DynClone
+where
+    TA: Copy,
+    TI: Copy,
+{
+    /// Channels-last accumulation for one output point.
+    ///
+    /// `acc[c] += sum_t kernel[t * acc.len() + c] * input[offsets[t] + c]`
+    ///
+    /// Safety: every `input + offsets[t]` must be valid for `acc.len()` reads.
+    unsafe fn run_hwc(&self, acc: &mut [TI], kernel: &[TA], input: *const TA, offsets: &[isize]);
+
+    /// Channels-first accumulation for a run of contiguous output points of
+    /// a single channel (unit stride).
+    ///
+    /// `acc[x] += sum_t kernel[t] * input[offsets[t] + x]`
+    ///
+    /// Safety: every `input + offsets[t]` must be valid for `acc.len()` reads.
+    unsafe fn run_chw(&self, acc: &mut [TI], kernel: &[TA], input: *const TA, offsets: &[isize]);
+}
+
+dyn_clone::clone_trait_object!(<TA, TI> DepthWise<TA, TI> where TA: Copy, TI: Copy);
+
+#[derive(Debug, Clone, new)]
+pub struct DepthWiseImpl<K, TA, TI>
+where
+    TA: Copy,
+    TI: Copy,
+    K: DepthWiseKer<TA, TI>,
+{
+    phantom: PhantomData<(K, TA, TI)>,
+}
+
+impl<K, TA, TI> DepthWiseImpl<K, TA, TI>
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync + 'static,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync + 'static,
+    K: DepthWiseKer<TA, TI>,
+{
+    unsafe fn run(
+        &self,
+        acc: &mut [TI],
+        kernel: &[TA],
+        kernel_tap_stride: usize,
+        input: *const TA,
+        offsets: &[isize],
+        broadcast: bool,
+    ) {
+        if offsets.len() == 0 {
+            return;
+        }
+        let blocks = acc.len() / K::nr();
+        if blocks > 0 {
+            K::kernel(&DepthWiseKerSpec {
+                acc: acc.as_mut_ptr(),
+                kernel: kernel.as_ptr(),
+                kernel_tap_stride: kernel_tap_stride as isize,
+                input,
+                offsets: offsets.as_ptr(),
+                taps: offsets.len(),
+                blocks,
+                broadcast: broadcast as usize,
+            });
+        }
+        for i in blocks * K::nr()..acc.len() {
+            let mut sum = *acc.get_unchecked(i);
+            for (t, &o) in offsets.iter().enumerate() {
+                let k = if broadcast {
+                    kernel[t]
+                } else {
+                    *kernel.get_unchecked(t * kernel_tap_stride + i)
+                };
+                sum = sum + k.as_() * (*input.offset(o + i as isize)).as_();
+            }
+            *acc.get_unchecked_mut(i) = sum;
+        }
+    }
+}
+
+impl<K, TA, TI> DepthWise<TA, TI> for DepthWiseImpl<K, TA, TI>
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync + 'static,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync + 'static,
+    K: DepthWiseKer<TA, TI>,
+{
+    unsafe fn run_hwc(&self, acc: &mut [TI], kernel: &[TA], input: *const TA, offsets: &[isize]) {
+        assert!(kernel.len() >= offsets.len() * acc.len());
+        self.run(acc, kernel, acc.len(), input, offsets, false)
+    }
+
+    unsafe fn run_chw(&self, acc: &mut [TI], kernel: &[TA], input: *const TA, offsets: &[isize]) {
+        assert!(kernel.len() >= offsets.len());
+        self.run(acc, kernel, 0, input, offsets, true)
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[macro_export]
+    macro_rules! depthwise_frame_tests {
+        ($cond:expr, $ker:ty, $ta:ty, $ti:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn depthwise_hwc(pb in proptest::prelude::any::<crate::frame::depthwise::test::DepthWiseProblem<$ta>>()) {
+                    if $cond {
+                        proptest::prop_assert_eq!(pb.run_hwc::<$ker, $ti>(), pb.reference_hwc::<$ti>())
+                    }
+                }
+
+                #[test]
+                fn depthwise_chw(pb in proptest::prelude::any::<crate::frame::depthwise::test::DepthWiseProblem<$ta>>()) {
+                    if $cond {
+                        proptest::prop_assert_eq!(pb.run_chw::<$ker, $ti>(), pb.reference_chw::<$ti>())
+                    }
+                }
+            }
+
+            #[test]
+            fn depthwise_hwc_one_block() {
+                if $cond {
+                    let pb = crate::frame::depthwise::test::DepthWiseProblem::<$ta>::ones(
+                        <$ker as crate::frame::depthwise::DepthWiseKer<$ta, $ti>>::nr(),
+                        3,
+                    );
+                    assert_eq!(pb.run_hwc::<$ker, $ti>(), pb.reference_hwc::<$ti>())
+                }
+            }
+
+            #[test]
+            fn depthwise_chw_two_blocks_and_tail() {
+                if $cond {
+                    let pb = crate::frame::depthwise::test::DepthWiseProblem::<$ta>::ones(
+                        2 * <$ker as crate::frame::depthwise::DepthWiseKer<$ta, $ti>>::nr() + 1,
+                        2,
+                    );
+                    assert_eq!(pb.run_chw::<$ker, $ti>(), pb.reference_chw::<$ti>())
+                }
+            }
+        };
+    }
+
+    /// Small integer values, so that f32 computations are exact whatever
+    /// the order of the accumulation.
+    pub trait DepthWiseTestValue: Copy + Debug + 'static {
+        fn from_i8(x: i8) -> Self;
+    }
+
+    impl DepthWiseTestValue for f32 {
+        fn from_i8(x: i8) -> f32 {
+            x as f32
+        }
+    }
+
+    impl DepthWiseTestValue for i8 {
+        fn from_i8(x: i8) -> i8 {
+            x
+        }
+    }
+
+    impl DepthWiseTestValue for i32 {
+        fn from_i8(x: i8) -> i32 {
+            x as i32
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct DepthWiseProblem<TA: DepthWiseTestValue> {
+        pub channels: usize,
+        pub kernel: Vec<TA>,
+        pub input: Vec<TA>,
+        pub offsets: Vec<isize>,
+    }
+
+    impl<TA: DepthWiseTestValue> Arbitrary for DepthWiseProblem<TA> {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<DepthWiseProblem<TA>>;
+
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (1usize..40, 1usize..5, 0usize..20)
+                .prop_flat_map(|(channels, taps, extra)| {
+                    let input_len = channels + extra;
+                    (
+                        Just(channels),
+                        proptest::collection::vec(-10i8..10, channels * taps),
+                        proptest::collection::vec(-10i8..10, input_len),
+                        proptest::collection::vec(0..=extra as isize, taps),
+                    )
+                })
+                .prop_map(|(channels, kernel, input, offsets)| DepthWiseProblem {
+                    channels,
+                    kernel: kernel.into_iter().map(TA::from_i8).collect(),
+                    input: input.into_iter().map(TA::from_i8).collect(),
+                    offsets,
+                })
+                .boxed()
+        }
+    }
+
+    impl<TA: DepthWiseTestValue> DepthWiseProblem<TA> {
+        pub fn ones(channels: usize, taps: usize) -> DepthWiseProblem<TA> {
+            DepthWiseProblem {
+                channels,
+                kernel: vec![TA::from_i8(1); channels * taps],
+                input: (0..channels + taps).map(|i| TA::from_i8(i as i8)).collect(),
+                offsets: (0..taps as isize).collect(),
+            }
+        }
+
+        fn acc<TI: DepthWiseTestValue>(&self) -> Vec<TI> {
+            (0..self.channels).map(|i| TI::from_i8((i % 7) as i8 - 3)).collect()
+        }
+
+        pub fn run_hwc<K, TI>(&self) -> Vec<TI>
+        where
+            K: DepthWiseKer<TA, TI>,
+            TA: AsPrimitive<TI> + Send + Sync,
+            TI: DepthWiseTestValue + Add<Output = TI> + Mul<Output = TI> + Send + Sync,
+        {
+            let mut acc = self.acc();
+            let op = DepthWiseImpl::<K, TA, TI>::new();
+            unsafe { op.run_hwc(&mut acc, &self.kernel, self.input.as_ptr(), &self.offsets) };
+            acc
+        }
+
+        pub fn run_chw<K, TI>(&self) -> Vec<TI>
+        where
+            K: DepthWiseKer<TA, TI>,
+            TA: AsPrimitive<TI> + Send + Sync,
+            TI: DepthWiseTestValue + Add<Output = TI> + Mul<Output = TI> + Send + Sync,
+        {
+            let mut acc = self.acc();
+            let op = DepthWiseImpl::<K, TA, TI>::new();
+            unsafe { op.run_chw(&mut acc, &self.kernel, self.input.as_ptr(), &self.offsets) };
+            acc
+        }
+
+        pub fn reference_hwc<TI>(&self) -> Vec<TI>
+        where
+            TA: AsPrimitive<TI>,
+            TI: DepthWiseTestValue + Add<Output = TI> + Mul<Output = TI>,
+        {
+            let mut acc = self.acc();
+            for (t, &o) in self.offsets.iter().enumerate() {
+                for c in 0..self.channels {
+                    acc[c] = acc[c]
+                        + self.kernel[t * self.channels + c].as_()
+                            * self.input[o as usize + c].as_();
+                }
+            }
+            acc
+        }
+
+        pub fn reference_chw<TI>(&self) -> Vec<TI>
+        where
+            TA: AsPrimitive<TI>,
+            TI: DepthWiseTestValue + Add<Output = TI> + Mul<Output = TI>,
+        {
+            let mut acc = self.acc();
+            for (t, &o) in self.offsets.iter().enumerate() {
+                for x in 0..self.channels {
+                    acc[x] = acc[x] + self.kernel[t].as_() * self.input[o as usize + x].as_();
+                }
+            }
+            acc
+        }
+    }
+}
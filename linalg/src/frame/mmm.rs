@@ -5,6 +5,7 @@ pub(crate) mod kernel;
 #[macro_use]
 pub(crate) mod mmm;
 mod storage;
+mod weights;
 #[cfg(test)]
 #[macro_use]
 pub mod tests;
@@ -13,6 +14,7 @@ pub use fuse::*;
 pub use kernel::*;
 pub use mmm::*;
 pub use storage::*;
+pub use weights::*;
 
 #[cfg(test)]
 pub use tests::*;
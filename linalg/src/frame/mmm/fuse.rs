@@ -19,6 +19,88 @@ pub enum FusedSpec {
     ScalarAdd(Tensor),
     QTowardsEven(Tensor, usize),
     QTowardsPlusInf(Tensor, usize),
+    /// Per-row QTowardsPlusInf: one multiplier per row, sharing the shift.
+    /// Only for i32 internal type.
+    PerRowQTowardsPlusInf(Tensor, usize),
+    Activation(Activation),
+}
+
+/// Fixed point multiplier and shift for a QTowardsEven or QTowardsPlusInf
+/// by `factor`.
+pub fn q_multiplier(factor: f32) -> (i32, usize) {
+    // https://github.com/microsoft/onnxruntime/blob/master/onnxruntime/core/util/gemmlowp_common.h#L16
+    let factor_bits = factor.to_bits();
+    let current_exponent = factor_bits >> 23;
+    let bumped_multi = f32::from_bits(factor_bits & 0x007fffff | 0x3f000000);
+    let int_multi = (bumped_multi * (1i64 << 31) as f32).round() as i32;
+    let shift = 126 - current_exponent;
+    (int_multi, shift as usize)
+}
+
+/// Per-row multipliers for a PerRowQTowardsPlusInf by `factors`. They share
+/// the shift of the biggest factor.
+pub fn q_per_row_multipliers(factors: &[f32]) -> (Vec<i32>, usize) {
+    let max = factors.iter().fold(0f32, |m, f| m.max(*f));
+    let (_, shift) = q_multiplier(max);
+    let unit = 2f64.powi(31 + shift as i32);
+    (factors.iter().map(|f| (*f as f64 * unit).round() as i32).collect(), shift)
+}
+
+/// Element-wise activation over a f32 output, applied once the products and
+/// all the other fused operations are done. Activations must come last in
+/// the fused operations list.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Activation {
+    Sigmoid,
+    Tanh,
+    LeakyRelu(f32),
+    HardSwish,
+    /// GELU, tanh approximation
+    Gelu,
+}
+
+impl std::hash::Hash for Activation {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: std::hash::Hasher,
+    {
+        std::mem::discriminant(self).hash(state);
+        if let Activation::LeakyRelu(alpha) = self {
+            alpha.to_bits().hash(state)
+        }
+    }
+}
+
+impl Activation {
+    pub fn apply(&self, xs: &mut [f32]) {
+        match self {
+            Activation::Sigmoid => (crate::ops().sigmoid_f32)().run(xs),
+            Activation::Tanh => (crate::ops().tanh_f32)().run(xs),
+            Activation::LeakyRelu(alpha) => xs.iter_mut().for_each(|x| {
+                if *x < 0.0 {
+                    *x *= alpha
+                }
+            }),
+            Activation::HardSwish => {
+                xs.iter_mut().for_each(|x| *x = *x * (*x + 3.0).max(0.0).min(6.0) / 6.0)
+            }
+            Activation::Gelu => {
+                const SQRT_2_OVER_PI: f32 = 0.7978845608;
+                let tanh = (crate::ops().tanh_f32)();
+                let mut buffer = [0f32; 64];
+                for chunk in xs.chunks_mut(buffer.len()) {
+                    let inner = &mut buffer[..chunk.len()];
+                    for (t, x) in inner.iter_mut().zip(chunk.iter()) {
+                        *t = SQRT_2_OVER_PI * (x + 0.044715 * x * x * x);
+                    }
+                    tanh.run(inner);
+                    for (x, t) in chunk.iter_mut().zip(inner.iter()) {
+                        *x = 0.5 * *x * (1.0 + t);
+                    }
+                }
+            }
+        }
+    }
 }
 
 /*
@@ -93,6 +175,7 @@ pub enum FusedKerSpec<TI: Copy> {
     ScalarAdd(TI),
     QTowardsEven(TI, usize),
     QTowardsPlusInf(TI, usize),
+    PerRowQTowardsPlusInf(*const TI, usize),
 }
 
 pub struct ScratchSpaceFusedNonLinear<TI: Copy> {
@@ -214,6 +297,23 @@ impl<TI: Copy> ScratchSpaceFusedNonLinear<TI> {
                 FusedSpec::QTowardsPlusInf(m, s) => {
                     FusedKerSpec::QTowardsPlusInf(*m.to_scalar_unchecked(), *s)
                 }
+                FusedSpec::PerRowQTowardsPlusInf(v, s) => {
+                    debug_assert_eq!(v.datum_type(), TI::datum_type());
+                    let have = v.len() - down * K::mr();
+                    let ptr = if have < K::mr() {
+                        let mut buf = vec![TI::zero(); K::mr()];
+                        buf[..have]
+                            .copy_from_slice(&v.as_slice_unchecked()[down * K::mr()..][..have]);
+                        let ptr = buf.as_ptr();
+                        self.non_linear_buffers.push(buf);
+                        ptr
+                    } else {
+                        v.as_ptr_unchecked::<TI>().add(down * K::mr())
+                    };
+                    FusedKerSpec::PerRowQTowardsPlusInf(ptr, *s)
+                }
+                // applied by MatMatMulImpl on the output, not by the kernels
+                FusedSpec::Activation(_) => continue,
             };
             self.uspecs.push(s);
         }
@@ -388,6 +488,13 @@ pub mod test {
                     }
                 }
 
+                #[test]
+                fn return_per_row_q_towards_plusinf() {
+                    if $cond {
+                        test::return_per_row_q_towards_plusinf::<$ker, $ta, $tb, $tc, $ti>()
+                    }
+                }
+
                 proptest::proptest! {
                     #[test]
                     fn return_q_towards_plusinf_prop(pb in any::<QTowardsPlusInfProblem<$ker, $ta, $tb, $tc, $ti>>()) {
@@ -768,6 +875,43 @@ pub mod test {
         }));
     }
 
+    pub fn return_per_row_q_towards_plusinf<K, TA, TB, TC, TI>()
+    where
+        K: MatMatMulKer<TI>,
+        TA: Copy,
+        TB: Copy,
+        TC: Copy + PartialEq + 'static + Debug,
+        TI: Copy + Debug + 'static,
+        usize: AsPrimitive<TC> + AsPrimitive<TI>,
+        i64: AsPrimitive<TC>,
+        i32: AsPrimitive<TI>,
+    {
+        // even inputs, so that arm rounding doubling products are exact
+        let len = K::mr() * K::nr();
+        let input: Vec<i64> = (0..len).map(|i| (i as i64 % 21 - 10) * 2).collect();
+        let c: Vec<TC> = input.iter().map(|x| x.as_()).collect();
+        // even rows are divided by 4, odd rows by 8
+        let mults: Vec<TI> = (0..K::mr())
+            .map(|r| if r % 2 == 0 { (1 << 30).as_() } else { (1 << 29).as_() })
+            .collect();
+        let found = fused_ops::<K, TA, TB, TC, TI>(
+            &*c,
+            &[
+                FusedKerSpec::ScalarMul(2usize.as_()),
+                FusedKerSpec::PerRowQTowardsPlusInf(mults.as_ptr(), 2),
+            ],
+        );
+        let expected: Vec<TC> = input
+            .iter()
+            .enumerate()
+            .map(|(ix, x)| {
+                let shift = if ix / K::nr() % 2 == 0 { 1 } else { 2 };
+                (((x >> shift) + 1) >> 1).as_()
+            })
+            .collect();
+        assert_eq!(found, expected);
+    }
+
     #[derive(Debug, new)]
     pub struct QTowardsPlusInfProblem<K, TA, TB, TC, TI>
     where
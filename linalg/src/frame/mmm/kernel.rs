@@ -52,6 +52,39 @@ macro_rules! test_mmm_kernel_f32 {
             mmm_frame_tests!($cond, $k, f32, f32, f32, f32);
             mmm_kernel_fuse_tests!($cond, $k, f32, f32, f32, f32);
             mmm_s_frame_tests!($cond, $k, f32, f32, f32, f32);
+            mmm_activation_tests!($cond, $k);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_mmm_kernel_f64 {
+    ($k: ty, $id: ident, $cond: expr) => {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod $id {
+            mmm_kernel_tests!($cond, $k, f64, f64, f64, f64);
+            mmm_frame_tests!($cond, $k, f64, f64, f64, f64);
+            mmm_kernel_fuse_tests!($cond, $k, f64, f64, f64, f64);
+            mmm_s_frame_tests!($cond, $k, f64, f64, f64, f64);
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! test_mmm_kernel_f16_f32 {
+    ($k: ty, $id: ident, $cond: expr) => {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod $id {
+            mmm_kernel_tests!(
+                $cond,
+                $k,
+                tract_data::prelude::f16,
+                tract_data::prelude::f16,
+                f32,
+                f32
+            );
         }
     };
 }
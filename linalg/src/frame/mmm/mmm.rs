@@ -14,6 +14,7 @@ pub trait MatMatMul:
 {
     fn a_pack(&self) -> Packer;
     fn b_pack(&self) -> Packer;
+    fn a_weights_pack(&self, quant: WeightQuant) -> WeightPacker;
 
     fn a_storage(&self) -> &MatrixStoreSpec;
     fn b_storage(&self) -> &MatrixStoreSpec;
@@ -26,6 +27,12 @@ pub trait MatMatMul:
     unsafe fn set_zero_point_c(&mut self, value: Tensor);
 
     unsafe fn set_scale_factor(&mut self, factor: f32);
+    /// One scale factor per row of C.
+    unsafe fn set_scale_factors(&mut self, factors: &[f32]);
+
+    /// A is weight-only quantized and packed by `a_weights_pack`. Panels
+    /// are expanded to f32 when they are used.
+    unsafe fn a_from_quantized_weights(&mut self, quant: WeightQuant);
 
     unsafe fn b_from_data_and_offsets(&mut self, rows_offsets: &[isize], cols_offsets: &[isize]);
 
@@ -70,6 +77,7 @@ where
 
     pub zero_point_c: Option<Tensor>,
     pub scale_factor: Option<(TI, usize)>,
+    pub scale_factors: Option<(Vec<TI>, usize)>,
 
     phantom: PhantomData<(K, TA, TB, TC, TI)>,
 }
@@ -119,6 +127,7 @@ where
             zero_point_b: None,
             zero_point_c: None,
             scale_factor: None,
+            scale_factors: None,
             phantom: PhantomData,
         }
     }
@@ -142,6 +151,10 @@ where
         Packer::new(self.k, K::nr(), K::alignment_bytes_packed_b(), K::end_padding_packed_b())
     }
 
+    fn a_weights_pack(&self, quant: WeightQuant) -> WeightPacker {
+        WeightPacker { quant, k: self.k, mr: K::mr() }
+    }
+
     fn internal_type(&self) -> DatumType {
         TI::datum_type()
     }
@@ -156,6 +169,10 @@ where
         &self.c_storage
     }
 
+    unsafe fn a_from_quantized_weights(&mut self, quant: WeightQuant) {
+        self.a_storage = MatrixStoreSpec::PackedWeights { quant }
+    }
+
     unsafe fn b_from_data_and_offsets(&mut self, rows_offsets: &[isize], cols_offsets: &[isize]) {
         debug_assert!(rows_offsets.len() > 0);
         debug_assert!(cols_offsets.len() > 0);
@@ -223,7 +240,18 @@ where
     ) -> anyhow::Result<()> {
         let mr = K::mr();
         let nr = K::nr();
-        debug_assert_eq!(a.datum_type(), TA::datum_type());
+        let weights = if let MatrixStoreSpec::PackedWeights { quant } = &self.a_storage {
+            if TA::datum_type() != f32::datum_type()
+                || self.zero_point_a.is_some()
+                || self.zero_point_b.is_some()
+            {
+                anyhow::bail!("Weight-only quantized A requires a plain f32 product");
+            }
+            Some(*quant)
+        } else {
+            debug_assert_eq!(a.datum_type(), TA::datum_type());
+            None
+        };
         debug_assert_eq!(b.datum_type(), TB::datum_type());
         debug_assert_eq!(c.datum_type(), TC::datum_type());
         let m = self.m;
@@ -237,13 +265,39 @@ where
             mr,
             nr,
         };
-        let ref mut tmp_tile = tmp_c_storage.wrap(tmpc.as_ptr());
+        let ref mut tmp_tile = tmp_c_storage.wrap(tmpc.as_ptr())?;
 
         let a = a.as_ptr_unchecked::<TA>();
         let b = b.as_ptr_unchecked::<TB>();
         let c = c.as_ptr_mut_unchecked::<TC>();
+        let c_ptr = c;
         let ref linear = LinearSpec::k(self.k);
-        let mut non_linear = non_linear.to_vec();
+        let activations: Vec<Activation> = non_linear
+            .iter()
+            .filter_map(|spec| if let FusedSpec::Activation(a) = spec { Some(*a) } else { None })
+            .collect();
+        if activations.len() > 0 {
+            if TC::datum_type() != f32::datum_type() {
+                anyhow::bail!("Activations can only be fused in f32 output products");
+            }
+            if non_linear[non_linear.len() - activations.len()..].iter().any(|spec| {
+                if let FusedSpec::Activation(_) = spec {
+                    false
+                } else {
+                    true
+                }
+            }) {
+                anyhow::bail!("Activations must come last in fused operations");
+            }
+            match &self.c_storage {
+                MatrixStoreSpec::Strides { .. } | MatrixStoreSpec::VecStride { .. } => (),
+                storage => {
+                    anyhow::bail!("Storage {:?} for C not supported for fused activations", storage)
+                }
+            }
+        }
+        let mut activation_buffer = vec![];
+        let mut non_linear = non_linear[..non_linear.len() - activations.len()].to_vec();
         if let Some(ref a0) = self.zero_point_a {
             let mut sum_b_over_k = self.sum_b_over_k(b);
             for n in 0..self.n {
@@ -290,6 +344,9 @@ where
         if let Some(scale) = self.scale_factor {
             non_linear.push(FusedSpec::QTowardsPlusInf(tensor0(scale.0), scale.1));
         }
+        if let Some((multis, shift)) = &self.scale_factors {
+            non_linear.push(FusedSpec::PerRowQTowardsPlusInf(tensor1(&multis), *shift));
+        }
         if let Some(c0) = &self.zero_point_c {
             non_linear.push(FusedSpec::ScalarAdd(c0.cast_to::<TI>().unwrap().into_owned()));
         }
@@ -297,6 +354,7 @@ where
         //        if TC::datum_type().size_of() < TI::datum_type().size_of() && self.scale_factor.is_some() {
         if TC::datum_type().size_of() < TI::datum_type().size_of()
             && (self.scale_factor.is_some()
+                || self.scale_factors.is_some()
                 || self.zero_point_a.is_some()
                 || self.zero_point_b.is_some()
                 || self.zero_point_c.is_some()
@@ -305,9 +363,22 @@ where
             non_linear.push(FusedSpec::Min(tensor0(TC::max_value().as_())));
             non_linear.push(FusedSpec::Max(tensor0(TC::min_value().as_())));
         }
-        let a = self.a_storage.wrap(a);
-        let b = self.b_storage.wrap(b);
-        let mut c = self.c_storage.wrap(c);
+        let mut a_buffer = None;
+        let a = if let Some(quant) = weights {
+            let buffer = a_buffer.get_or_insert(Tensor::zero_aligned::<f32>(
+                &[self.a_pack().len(mr)],
+                K::alignment_bytes_packed_a(),
+            )?);
+            MatrixStore::PackedWeights {
+                ptr: a as *const u8,
+                packer: self.a_weights_pack(quant),
+                buffer: buffer.as_ptr_mut_unchecked::<TA>(),
+            }
+        } else {
+            self.a_storage.wrap(a)?
+        };
+        let b = self.b_storage.wrap(b)?;
+        let mut c = self.c_storage.wrap(c)?;
         for ia in 0..m / mr {
             let ref a = a.panel_a(ia);
             for ib in 0..n / nr {
@@ -322,6 +393,7 @@ where
                     non_linear,
                 });
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
+                self.apply_activations(c_ptr, ia, ib, &activations, &mut activation_buffer)?;
             }
             if n % nr != 0 {
                 let ref b = b.panel_b(nr, n / nr, n % nr);
@@ -335,6 +407,7 @@ where
                     non_linear,
                 });
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
+                Self::apply_activations_to_tmp(&mut tmpc, &activations);
                 c.set_from_tile(ia, n / nr, mr, n % nr, &*tmpc);
             }
         }
@@ -352,6 +425,7 @@ where
                     non_linear,
                 });
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
+                Self::apply_activations_to_tmp(&mut tmpc, &activations);
                 c.set_from_tile(m / mr, ib, m % mr, nr, &*tmpc);
             }
             if n % nr != 0 {
@@ -365,6 +439,7 @@ where
                     non_linear,
                 });
                 debug_assert_eq!(err, 0, "Kernel return error {}", err);
+                Self::apply_activations_to_tmp(&mut tmpc, &activations);
                 c.set_from_tile(m / mr, n / nr, m % mr, n % nr, &*tmpc);
             }
         }
@@ -384,13 +459,15 @@ where
     }
 
     unsafe fn set_scale_factor(&mut self, factor: f32) {
-        // https://github.com/microsoft/onnxruntime/blob/master/onnxruntime/core/util/gemmlowp_common.h#L16
-        let factor_bits = factor.to_bits();
-        let current_exponent = factor_bits >> 23;
-        let bumped_multi = f32::from_bits(factor_bits & 0x007fffff | 0x3f000000);
-        let int_multi = (bumped_multi * (1i64 << 31) as f32).round() as i32;
-        let shift = 126 - current_exponent;
-        self.scale_factor = Some((int_multi.as_(), shift as usize));
+        let (int_multi, shift) = q_multiplier(factor);
+        self.scale_factor = Some((int_multi.as_(), shift));
+        self.scale_factors = None;
+    }
+
+    unsafe fn set_scale_factors(&mut self, factors: &[f32]) {
+        let (int_multis, shift) = q_per_row_multipliers(factors);
+        self.scale_factors = Some((int_multis.iter().map(|m| m.as_()).collect(), shift));
+        self.scale_factor = None;
     }
 }
 
@@ -403,6 +480,66 @@ where
     K: MatMatMulKer<TI> + 'static,
     i32: AsPrimitive<TI>,
 {
+    /// Applies the fused activations to the tile of C the kernel just wrote.
+    unsafe fn apply_activations(
+        &self,
+        c: *mut TC,
+        down: usize,
+        right: usize,
+        activations: &[Activation],
+        buffer: &mut Vec<f32>,
+    ) -> anyhow::Result<()> {
+        if activations.len() == 0 {
+            return Ok(());
+        }
+        let (mr, nr) = (K::mr(), K::nr());
+        let (row_byte_stride, col_byte_stride, cols) = match &self.c_storage {
+            MatrixStoreSpec::Strides { row_byte_stride, col_byte_stride, .. } => {
+                (*row_byte_stride, *col_byte_stride, nr)
+            }
+            // C is a single column: the kernel only wrote the first column of the tile
+            MatrixStoreSpec::VecStride { byte_stride, .. } => (*byte_stride, 0, 1),
+            storage => {
+                anyhow::bail!("Storage {:?} for C not supported for fused activations", storage)
+            }
+        };
+        let ptr = |y: usize, x: usize| {
+            (c as *mut u8).offset(
+                (down * mr + y) as isize * row_byte_stride
+                    + (right * nr + x) as isize * col_byte_stride,
+            ) as *mut f32
+        };
+        if cols > 1 && col_byte_stride == std::mem::size_of::<f32>() as isize {
+            for y in 0..mr {
+                let row = std::slice::from_raw_parts_mut(ptr(y, 0), nr);
+                activations.iter().for_each(|a| a.apply(row));
+            }
+            return Ok(());
+        }
+        buffer.clear();
+        for y in 0..mr {
+            for x in 0..cols {
+                buffer.push(*ptr(y, x));
+            }
+        }
+        activations.iter().for_each(|a| a.apply(buffer));
+        let mut values = buffer.iter();
+        for y in 0..mr {
+            for x in 0..cols {
+                *ptr(y, x) = *values.next().unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the fused activations to a partial tile, before it is copied to C.
+    unsafe fn apply_activations_to_tmp(tmpc: &mut [TC], activations: &[Activation]) {
+        if activations.len() > 0 {
+            let tile = std::slice::from_raw_parts_mut(tmpc.as_mut_ptr() as *mut f32, tmpc.len());
+            activations.iter().for_each(|a| a.apply(tile));
+        }
+    }
+
     fn sum_a_over_k(&self, mut a: *const TA) -> Vec<TI> {
         match &self.a_storage {
             MatrixStoreSpec::Packed { .. } => {
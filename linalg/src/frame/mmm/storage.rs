@@ -1,21 +1,27 @@
+use super::weights::{WeightPacker, WeightQuant};
 use std::ffi::c_void;
 use std::fmt;
 use std::fmt::Debug;
+use tract_data::anyhow;
 
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub enum MatrixStoreSpec {
     Packed { panel_len: usize },
+    PackedWeights { quant: WeightQuant },
     Strides { row_byte_stride: isize, col_byte_stride: isize, mr: usize, nr: usize },
     OffsetsAndPtrs { row_byte_offsets: Vec<isize>, col_byte_offsets: Vec<isize>, nr: usize },
     VecStride { byte_stride: isize, mr: usize, nr: usize },
 }
 
 impl MatrixStoreSpec {
-    pub unsafe fn wrap<T: Copy + Debug>(&self, ptr: *const T) -> MatrixStore<T> {
-        match self {
+    pub unsafe fn wrap<T: Copy + Debug>(&self, ptr: *const T) -> anyhow::Result<MatrixStore<T>> {
+        let store = match self {
             MatrixStoreSpec::Packed { panel_len } => {
                 MatrixStore::Packed { ptr, panel_len: *panel_len }
             }
+            MatrixStoreSpec::PackedWeights { .. } => {
+                anyhow::bail!("Packed weights need their packer and a buffer to be wrapped")
+            }
             MatrixStoreSpec::Strides { row_byte_stride, col_byte_stride, mr, nr } => {
                 MatrixStore::Strides {
                     ptr,
@@ -33,7 +39,8 @@ impl MatrixStoreSpec {
                     col_byte_offsets.iter().map(|&i| (ptr as *const u8).offset(i) as _).collect();
                 MatrixStore::OffsetsAndPtrs { col_ptrs, row_byte_offsets, nr: *nr }
             }
-        }
+        };
+        Ok(store)
     }
 }
 
@@ -41,6 +48,7 @@ impl fmt::Display for MatrixStoreSpec {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         match self {
             MatrixStoreSpec::Packed { .. } => write!(fmt, "Packed"),
+            MatrixStoreSpec::PackedWeights { quant } => write!(fmt, "PackedWeights ({})", quant),
             MatrixStoreSpec::Strides { .. } => write!(fmt, "Strides"),
             MatrixStoreSpec::OffsetsAndPtrs { .. } => write!(fmt, "OffsetsAndPtrs"),
             MatrixStoreSpec::VecStride { .. } => write!(fmt, "VecStrides"),
@@ -52,6 +60,7 @@ impl fmt::Display for MatrixStoreSpec {
 pub enum MatrixStore<'a, T: Copy> {
     Strides { ptr: *const T, row_byte_stride: isize, col_byte_stride: isize, mr: usize, nr: usize },
     Packed { ptr: *const T, panel_len: usize },
+    PackedWeights { ptr: *const u8, packer: WeightPacker, buffer: *mut T },
     OffsetsAndPtrs { row_byte_offsets: &'a [isize], col_ptrs: Vec<*const T>, nr: usize },
     VecStride { ptr: *const T, byte_stride: isize, mr: usize, nr: usize },
 }
@@ -62,6 +71,10 @@ impl<'a, T: Copy> MatrixStore<'a, T> {
             MatrixStore::Packed { ptr, panel_len } => {
                 PanelStore::Packed { ptr: ptr.offset((panel_len * i) as isize) as _ }
             }
+            MatrixStore::PackedWeights { ptr, packer, buffer } => {
+                packer.unpack_panel(ptr.add(packer.panel_bytes() * i), *buffer as *mut f32);
+                PanelStore::Packed { ptr: *buffer as _ }
+            }
             _ => unimplemented!(),
         }
     }
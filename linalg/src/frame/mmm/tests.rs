@@ -193,6 +193,58 @@ macro_rules! mmm_s_frame_tests {
     };
 }
 
+#[macro_export]
+macro_rules! mmm_activation_tests {
+    ($cond:expr, $ker:ty) => {
+        mod activation {
+            use $crate::frame::mmm::tests::*;
+            use $crate::frame::mmm::Activation;
+
+            #[test]
+            fn sigmoid_13_1_7() {
+                if $cond {
+                    unsafe { activation::<$ker>(13, 1, 7, Activation::Sigmoid).unwrap() }
+                }
+            }
+
+            #[test]
+            fn tanh_13_1_7() {
+                if $cond {
+                    unsafe { activation::<$ker>(13, 1, 7, Activation::Tanh).unwrap() }
+                }
+            }
+
+            #[test]
+            fn leaky_relu_13_1_7() {
+                if $cond {
+                    unsafe { activation::<$ker>(13, 1, 7, Activation::LeakyRelu(0.1)).unwrap() }
+                }
+            }
+
+            #[test]
+            fn hard_swish_13_1_7() {
+                if $cond {
+                    unsafe { activation::<$ker>(13, 1, 7, Activation::HardSwish).unwrap() }
+                }
+            }
+
+            #[test]
+            fn gelu_13_1_7() {
+                if $cond {
+                    unsafe { activation::<$ker>(13, 1, 7, Activation::Gelu).unwrap() }
+                }
+            }
+
+            #[test]
+            fn tanh_33_5_21() {
+                if $cond {
+                    unsafe { activation::<$ker>(33, 5, 21, Activation::Tanh).unwrap() }
+                }
+            }
+        }
+    };
+}
+
 pub fn strat_mat_mat_mul<TA: LADatum, TB: LADatum>(
 ) -> BoxedStrategy<(usize, usize, usize, Tensor, Tensor)> {
     (1usize..5, 1usize..5, 1usize..5)
@@ -466,6 +518,38 @@ where
     })
 }
 
+pub unsafe fn activation<K: MatMatMulKer<f32> + 'static>(
+    m: usize,
+    k: usize,
+    n: usize,
+    activation: Activation,
+) -> proptest::test_runner::TestCaseResult {
+    let bias = (0..m).map(|i| i as f32 - 6.0).collect::<Vec<f32>>();
+    let spec = [FusedSpec::PerRowAdd(tensor1(&*bias)), FusedSpec::Activation(activation)];
+    fused_op::<K, f32, f32, f32, f32, _>(m, k, n, &spec, |exp| {
+        for x in 0..n {
+            for y in 0..m {
+                let v = exp[x + y * n] + bias[y];
+                exp[x + y * n] = match activation {
+                    Activation::Sigmoid => 1.0 / (1.0 + (-v).exp()),
+                    Activation::Tanh => v.tanh(),
+                    Activation::LeakyRelu(alpha) => {
+                        if v < 0.0 {
+                            alpha * v
+                        } else {
+                            v
+                        }
+                    }
+                    Activation::HardSwish => v * (v + 3.0).max(0.0).min(6.0) / 6.0,
+                    Activation::Gelu => {
+                        0.5 * v * (1.0 + (0.7978845608 * (v + 0.044715 * v * v * v)).tanh())
+                    }
+                }
+            }
+        }
+    })
+}
+
 pub unsafe fn max<K: MatMatMulKer<TI>, TA, TB, TC, TI>(
     m: usize,
     k: usize,
@@ -0,0 +1,384 @@
+use std::fmt;
+use tract_data::anyhow;
+use tract_data::internal::*;
+
+/// Storage type of weight-only quantized values.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum WeightFormat {
+    I8,
+    /// Signed 4-bit values, packed two per byte in packed panels.
+    I4,
+}
+
+impl WeightFormat {
+    pub fn bits(&self) -> usize {
+        match self {
+            WeightFormat::I8 => 8,
+            WeightFormat::I4 => 4,
+        }
+    }
+
+    /// Largest magnitude a value can take (quantization is symmetric).
+    pub fn max(&self) -> i8 {
+        match self {
+            WeightFormat::I8 => 127,
+            WeightFormat::I4 => 7,
+        }
+    }
+}
+
+impl fmt::Display for WeightFormat {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightFormat::I8 => write!(fmt, "i8"),
+            WeightFormat::I4 => write!(fmt, "i4"),
+        }
+    }
+}
+
+/// Weight-only quantization: a constant f32 matrix is stored as small
+/// integers, with one f32 scale per row (output channel) and per group of
+/// `group_size` consecutive values along k.
+///
+/// A `group_size` of at least k gives per-channel scales.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct WeightQuant {
+    pub format: WeightFormat,
+    pub group_size: usize,
+}
+
+impl WeightQuant {
+    pub fn per_channel(format: WeightFormat) -> WeightQuant {
+        WeightQuant { format, group_size: std::usize::MAX }
+    }
+
+    pub fn per_group(format: WeightFormat, group_size: usize) -> WeightQuant {
+        WeightQuant { format, group_size }
+    }
+
+    /// Effective group size for a given k.
+    pub fn group_size(&self, k: usize) -> usize {
+        self.group_size.min(k).max(1)
+    }
+
+    pub fn groups(&self, k: usize) -> usize {
+        let gs = self.group_size(k);
+        (k + gs - 1) / gs
+    }
+
+    /// Symmetric quantization of a `m` x `k` f32 matrix.
+    ///
+    /// Returns the values as a i8 tensor with the shape of `a`, and the
+    /// scales as a `[m, groups]` f32 tensor.
+    pub fn quantize(
+        &self,
+        a: &TensorView,
+        m_axis: usize,
+        k_axis: usize,
+    ) -> anyhow::Result<(Tensor, Tensor)> {
+        let (m, k) = (a.shape()[m_axis], a.shape()[k_axis]);
+        let (rsa, csa) = (a.strides()[m_axis], a.strides()[k_axis]);
+        let gs = self.group_size(k);
+        let groups = self.groups(k);
+        let max = self.format.max() as f32;
+        let src = a.as_ptr::<f32>()?;
+        let mut values = Tensor::zero::<i8>(a.shape())?;
+        let mut scales = Tensor::zero::<f32>(&[m, groups])?;
+        {
+            let dst = values.as_slice_mut::<i8>()?.as_mut_ptr();
+            let scales = scales.as_slice_mut::<f32>()?;
+            for row in 0..m {
+                for g in 0..groups {
+                    let cols = g * gs..((g + 1) * gs).min(k);
+                    let at = |col: usize| row as isize * rsa + col as isize * csa;
+                    let amax = cols
+                        .clone()
+                        .map(|col| unsafe { *src.offset(at(col)) }.abs())
+                        .fold(0.0, f32::max);
+                    let scale = amax / max;
+                    scales[row * groups + g] = scale;
+                    if scale == 0.0 {
+                        continue;
+                    }
+                    for col in cols {
+                        unsafe {
+                            *dst.offset(at(col)) =
+                                (*src.offset(at(col)) / scale).round().max(-max).min(max) as i8
+                        }
+                    }
+                }
+            }
+        }
+        Ok((values, scales))
+    }
+
+    /// Reverse of `quantize`.
+    pub fn dequantize(
+        &self,
+        values: &TensorView,
+        scales: &TensorView,
+        m_axis: usize,
+        k_axis: usize,
+    ) -> anyhow::Result<Tensor> {
+        let k = values.shape()[k_axis];
+        let (rsa, csa) = (values.strides()[m_axis], values.strides()[k_axis]);
+        let gs = self.group_size(k);
+        let groups = self.groups(k);
+        let src = values.as_ptr::<i8>()?;
+        let scales = scales.as_slice::<f32>()?;
+        let mut a = Tensor::zero::<f32>(values.shape())?;
+        {
+            let dst = a.as_slice_mut::<f32>()?.as_mut_ptr();
+            for row in 0..values.shape()[m_axis] {
+                for col in 0..k {
+                    let offset = row as isize * rsa + col as isize * csa;
+                    unsafe {
+                        *dst.offset(offset) =
+                            *src.offset(offset) as f32 * scales[row * groups + col / gs];
+                    }
+                }
+            }
+        }
+        Ok(a)
+    }
+}
+
+impl fmt::Display for WeightQuant {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.group_size == std::usize::MAX {
+            write!(fmt, "{} per channel", self.format)
+        } else {
+            write!(fmt, "{} by groups of {}", self.format, self.group_size)
+        }
+    }
+}
+
+/// Packs weight-only quantized A matrices in panels of `mr` rows.
+///
+/// Each panel starts with its `groups x mr` f32 scales, followed by the
+/// `k x mr` values in the same order as a regular packed A panel (one byte
+/// per value for i8, two values per byte, low nibble first, for i4). Panels
+/// are padded to 4 bytes.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WeightPacker {
+    pub quant: WeightQuant,
+    pub k: usize,
+    pub mr: usize,
+}
+
+impl WeightPacker {
+    pub fn alignment(&self) -> usize {
+        std::mem::size_of::<f32>()
+    }
+
+    pub fn panel_bytes(&self) -> usize {
+        let scales = self.quant.groups(self.k) * self.mr * std::mem::size_of::<f32>();
+        let values = (self.k * self.mr * self.quant.format.bits() + 7) / 8;
+        (scales + values + 3) / 4 * 4
+    }
+
+    /// Packed size in bytes for `m` rows.
+    pub fn len(&self, m: usize) -> usize {
+        (m + self.mr - 1) / self.mr * self.panel_bytes()
+    }
+
+    /// Pack `values` (i8) and `scales` (f32, `[m, groups]`) as computed by
+    /// `WeightQuant::quantize` into a u8 tensor of `len(m)` bytes.
+    pub unsafe fn pack(
+        &self,
+        pa: &mut TensorView,
+        values: &TensorView,
+        scales: &TensorView,
+        k_axis: usize,
+        mn_axis: usize,
+    ) {
+        let m = values.shape()[mn_axis];
+        let (rsa, csa) = (values.strides()[mn_axis], values.strides()[k_axis]);
+        let groups = self.quant.groups(self.k);
+        let values = values.as_ptr_unchecked::<i8>();
+        let scales = scales.as_slice_unchecked::<f32>();
+        let pa = pa.as_ptr_mut_unchecked::<u8>();
+        let mr = self.mr;
+        for panel in 0..(m + mr - 1) / mr {
+            let ptr = pa.add(panel * self.panel_bytes());
+            std::ptr::write_bytes(ptr, 0, self.panel_bytes());
+            let panel_scales = ptr as *mut f32;
+            for g in 0..groups {
+                for r in 0..mr.min(m - panel * mr) {
+                    *panel_scales.add(g * mr + r) = scales[(panel * mr + r) * groups + g];
+                }
+            }
+            let panel_values = ptr.add(groups * mr * std::mem::size_of::<f32>());
+            for kk in 0..self.k {
+                for r in 0..mr.min(m - panel * mr) {
+                    let v = *values.offset((panel * mr + r) as isize * rsa + kk as isize * csa);
+                    let j = kk * mr + r;
+                    match self.quant.format {
+                        WeightFormat::I8 => *(panel_values.add(j) as *mut i8) = v,
+                        WeightFormat::I4 => {
+                            *panel_values.add(j / 2) |= ((v as u8) & 0x0F) << (4 * (j % 2))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expand the panel at `panel` into a regular f32 packed A panel.
+    pub(crate) unsafe fn unpack_panel(&self, panel: *const u8, output: *mut f32) {
+        let mr = self.mr;
+        let gs = self.quant.group_size(self.k);
+        let groups = self.quant.groups(self.k);
+        let scales = panel as *const f32;
+        let values = panel.add(groups * mr * std::mem::size_of::<f32>());
+        match self.quant.format {
+            WeightFormat::I8 => {
+                let values = values as *const i8;
+                for kk in 0..self.k {
+                    let scales = scales.add(kk / gs * mr);
+                    for r in 0..mr {
+                        let j = kk * mr + r;
+                        *output.add(j) = *values.add(j) as f32 * *scales.add(r);
+                    }
+                }
+            }
+            WeightFormat::I4 => {
+                for kk in 0..self.k {
+                    let scales = scales.add(kk / gs * mr);
+                    for r in 0..mr {
+                        let j = kk * mr + r;
+                        // shift the nibble to the top, then back with sign extension
+                        let byte = *values.add(j / 2) << (4 * (1 - j % 2));
+                        *output.add(j) = ((byte as i8) >> 4) as f32 * *scales.add(r);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::mmm::MatMatMul;
+    use proptest::prelude::*;
+
+    #[derive(Debug)]
+    struct WeightProblem {
+        quant: WeightQuant,
+        m: usize,
+        k: usize,
+        n: usize,
+        a: Vec<f32>,
+        b: Vec<f32>,
+    }
+
+    impl Arbitrary for WeightProblem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<WeightProblem>;
+
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (
+                prop_oneof!(Just(WeightFormat::I8), Just(WeightFormat::I4)),
+                prop_oneof!(Just(std::usize::MAX), 1usize..8),
+                1usize..20,
+                1usize..20,
+                1usize..20,
+            )
+                .prop_flat_map(|(format, group_size, m, k, n)| {
+                    (
+                        Just((WeightQuant { format, group_size }, m, k, n)),
+                        proptest::collection::vec(-10f32..10f32, m * k),
+                        proptest::collection::vec(-10f32..10f32, k * n),
+                    )
+                })
+                .prop_map(|((quant, m, k, n), a, b)| WeightProblem { quant, m, k, n, a, b })
+                .boxed()
+        }
+    }
+
+    impl WeightProblem {
+        fn quantized(&self) -> (Tensor, Tensor) {
+            let a = tensor1(&self.a).into_shape(&[self.m, self.k]).unwrap();
+            self.quant.quantize(&a.view(), 0, 1).unwrap()
+        }
+
+        fn run(&self, mut mmm: Box<dyn MatMatMul>, weights: bool) -> Vec<f32> {
+            let (m, k, n) = (self.m, self.k, self.n);
+            let (values, scales) = self.quantized();
+            unsafe {
+                let pa = if weights {
+                    mmm.a_from_quantized_weights(self.quant);
+                    let packer = mmm.a_weights_pack(self.quant);
+                    let mut pa =
+                        Tensor::uninitialized_aligned::<u8>(&[packer.len(m)], packer.alignment())
+                            .unwrap();
+                    packer.pack(&mut pa.view_mut(), &values.view(), &scales.view(), 1, 0);
+                    pa
+                } else {
+                    let a = self.quant.dequantize(&values.view(), &scales.view(), 0, 1).unwrap();
+                    let mut pa = Tensor::uninitialized_aligned::<f32>(
+                        &[mmm.a_pack().len(m)],
+                        mmm.a_pack().alignment(),
+                    )
+                    .unwrap();
+                    mmm.a_pack().pack(&mut pa.view_mut(), &a.view(), 1, 0);
+                    pa
+                };
+                let b = tensor1(&self.b).into_shape(&[k, n]).unwrap();
+                let mut pb = Tensor::uninitialized_aligned::<f32>(
+                    &[mmm.b_pack().len(n)],
+                    mmm.b_pack().alignment(),
+                )
+                .unwrap();
+                mmm.b_pack().pack(&mut pb.view_mut(), &b.view(), 0, 1);
+                let mut c = Tensor::zero::<f32>(&[m, n]).unwrap();
+                mmm.run(&pa.view(), &pb.view(), &mut c.view_mut(), &[]).unwrap();
+                c.as_slice::<f32>().unwrap().to_vec()
+            }
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn quantize_error_bound(pb in any::<WeightProblem>()) {
+            let (values, scales) = pb.quantized();
+            let a = pb.quant.dequantize(&values.view(), &scales.view(), 0, 1).unwrap();
+            let groups = pb.quant.groups(pb.k);
+            let gs = pb.quant.group_size(pb.k);
+            let scales = scales.as_slice::<f32>().unwrap();
+            for (ix, (x, y)) in pb.a.iter().zip(a.as_slice::<f32>().unwrap()).enumerate() {
+                let scale = scales[ix / pb.k * groups + ix % pb.k / gs];
+                prop_assert!((x - y).abs() <= scale / 2.0 + 1e-5);
+            }
+        }
+
+        #[test]
+        fn weights_mmm_generic(pb in any::<WeightProblem>()) {
+            let mmm = || crate::generic().mmm_f32(pb.m, pb.k, pb.n);
+            prop_assert_eq!(pb.run(mmm(), true), pb.run(mmm(), false));
+        }
+
+        #[test]
+        fn weights_mmm_best(pb in any::<WeightProblem>()) {
+            let mmm = || crate::ops().mmm_f32(pb.m, pb.k, pb.n);
+            prop_assert_eq!(pb.run(mmm(), true), pb.run(mmm(), false));
+        }
+    }
+
+    #[test]
+    fn i4_nibbles() {
+        let quant = WeightQuant::per_channel(WeightFormat::I4);
+        let values = tensor2(&[[-8i8, 7, -1, 3]]);
+        let scales = tensor2(&[[1f32]]);
+        let packer = WeightPacker { quant, k: 4, mr: 1 };
+        let mut pa = Tensor::zero::<u8>(&[packer.len(1)]).unwrap();
+        let mut unpacked = vec![0f32; 4];
+        unsafe {
+            packer.pack(&mut pa.view_mut(), &values.view(), &scales.view(), 1, 0);
+            packer.unpack_panel(pa.as_ptr::<u8>().unwrap(), unpacked.as_mut_ptr());
+        }
+        assert_eq!(pa.as_slice::<u8>().unwrap()[4..], [0x78, 0x3F]);
+        assert_eq!(unpacked, vec![-8.0, 7.0, -1.0, 3.0]);
+    }
+}
@@ -0,0 +1,209 @@
+use num_traits::Float;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait ExpFunc {
+    fn exp_approx(self) -> Self;
+}
+
+impl ExpFunc for f32 {
+    fn exp_approx(self) -> f32 {
+        crate::generic::softmax::sexp(self)
+    }
+}
+
+pub trait Softmax<T>: Send + Sync + Debug + dyn_clone::DynClone
+where
+    T: Copy + Debug + PartialEq + Send + Sync + ExpFunc,
+{
+    /// Element-wise exponential, in place.
+    fn exp(&self, vec: &mut [T]);
+    /// Softmax over the whole slice, in place.
+    fn run(&self, vec: &mut [T]);
+    /// Log-softmax over the whole slice, in place. `scratch` is a working
+    /// buffer that callers can reuse from one call to the next.
+    fn run_log(&self, vec: &mut [T], scratch: &mut Vec<T>);
+}
+
+dyn_clone::clone_trait_object!(<T> Softmax<T> where T: Copy);
+
+#[derive(Debug, Clone, new)]
+pub struct SoftmaxImpl<K, T>
+where
+    T: Copy + Debug + PartialEq + Send + Sync + ExpFunc,
+    K: SoftmaxKer<T> + Clone,
+{
+    phantom: PhantomData<(K, T)>,
+}
+
+impl<K, T> SoftmaxImpl<K, T>
+where
+    T: Float + Debug + Send + Sync + ExpFunc,
+    K: SoftmaxKer<T> + Clone,
+{
+    /// exp(x - max) in place, returns the sum of the results
+    fn exp_sum(&self, vec: &mut [T], max: T) -> T {
+        let alignment = K::alignment_bytes();
+        let mut offset = 0;
+        let mut sum = T::zero();
+        unsafe {
+            while offset < vec.len() && &vec[offset] as *const T as usize % alignment != 0 {
+                let y = (*vec.get_unchecked(offset) - max).exp_approx();
+                *vec.get_unchecked_mut(offset) = y;
+                sum = sum + y;
+                offset += 1;
+            }
+            let len = (vec.len() - offset) / K::nr() * K::nr();
+            if len > 0 {
+                sum = sum + K::run(&mut vec[offset..][..len], max);
+            }
+            for i in (len + offset)..vec.len() {
+                let y = (*vec.get_unchecked(i) - max).exp_approx();
+                *vec.get_unchecked_mut(i) = y;
+                sum = sum + y;
+            }
+        }
+        sum
+    }
+
+    fn max(vec: &[T]) -> T {
+        vec.iter().fold(T::neg_infinity(), |acc, &x| acc.max(x))
+    }
+}
+
+impl<K, T> Softmax<T> for SoftmaxImpl<K, T>
+where
+    T: Float + Debug + Send + Sync + ExpFunc,
+    K: SoftmaxKer<T> + Clone,
+{
+    fn exp(&self, vec: &mut [T]) {
+        self.exp_sum(vec, T::zero());
+    }
+
+    fn run(&self, vec: &mut [T]) {
+        if vec.len() == 0 {
+            return;
+        }
+        let max = Self::max(vec);
+        let recip = self.exp_sum(vec, max).recip();
+        vec.iter_mut().for_each(|x| *x = *x * recip);
+    }
+
+    fn run_log(&self, vec: &mut [T], scratch: &mut Vec<T>) {
+        if vec.len() == 0 {
+            return;
+        }
+        let max = Self::max(vec);
+        scratch.clear();
+        scratch.extend_from_slice(vec);
+        let shift = max + self.exp_sum(scratch, max).ln();
+        vec.iter_mut().for_each(|x| *x = *x - shift);
+    }
+}
+
+/// Kernel for the exponential pass of softmax: computes `exp(x - max)` in
+/// place and returns the sum of the results.
+pub trait SoftmaxKer<T>: Send + Sync + Debug + dyn_clone::DynClone + Clone
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+{
+    fn name() -> &'static str;
+    fn alignment_bytes() -> usize;
+    fn nr() -> usize;
+    fn run(vec: &mut [T], max: T) -> T;
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use super::SoftmaxKer;
+    use proptest::test_runner::TestCaseResult;
+
+    #[macro_export]
+    macro_rules! softmax_frame_tests {
+        ($cond:expr, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn exp(xs in proptest::collection::vec(-25f32..5.0, 0..100)) {
+                    if $cond {
+                        crate::frame::softmax::test::test_exp::<$ker>(&*xs).unwrap()
+                    }
+                }
+
+                #[test]
+                fn softmax(xs in proptest::collection::vec(-25f32..25.0, 1..100)) {
+                    if $cond {
+                        crate::frame::softmax::test::test_softmax::<$ker>(&*xs).unwrap()
+                    }
+                }
+
+                #[test]
+                fn log_softmax(xs in proptest::collection::vec(-25f32..25.0, 1..100)) {
+                    if $cond {
+                        crate::frame::softmax::test::test_log_softmax::<$ker>(&*xs).unwrap()
+                    }
+                }
+            }
+
+            #[test]
+            fn exp_magic() {
+                if $cond {
+                    crate::frame::softmax::test::test_exp::<$ker>(&[0f32, -100.0, 1.0, -1.0])
+                        .unwrap()
+                }
+            }
+
+            #[test]
+            fn softmax_18_zeros() {
+                if $cond {
+                    crate::frame::softmax::test::test_softmax::<$ker>(&[0.0; 18]).unwrap();
+                }
+            }
+
+            #[test]
+            fn softmax_large_range() {
+                if $cond {
+                    let mut xs = vec![-1000f32; 19];
+                    xs[7] = 1000.0;
+                    crate::frame::softmax::test::test_softmax::<$ker>(&xs).unwrap();
+                    crate::frame::softmax::test::test_log_softmax::<$ker>(&xs).unwrap();
+                }
+            }
+        };
+    }
+
+    fn reference(values: &[f32]) -> (f32, f32) {
+        let max = values.iter().fold(std::f32::NEG_INFINITY, |acc, &x| acc.max(x));
+        let sum = values.iter().map(|x| (x - max).exp()).sum::<f32>();
+        (max, sum)
+    }
+
+    pub fn test_exp<K: SoftmaxKer<f32>>(values: &[f32]) -> TestCaseResult {
+        use crate::frame::softmax::Softmax;
+        let op = crate::frame::softmax::SoftmaxImpl::<K, f32>::new();
+        let mut found = values.to_vec();
+        op.exp(&mut found);
+        let expected = values.iter().map(|x| x.exp()).collect::<Vec<_>>();
+        crate::test::check_close(&*found, &*expected)
+    }
+
+    pub fn test_softmax<K: SoftmaxKer<f32>>(values: &[f32]) -> TestCaseResult {
+        use crate::frame::softmax::Softmax;
+        let op = crate::frame::softmax::SoftmaxImpl::<K, f32>::new();
+        let mut found = values.to_vec();
+        op.run(&mut found);
+        let (max, sum) = reference(values);
+        let expected = values.iter().map(|x| (x - max).exp() / sum).collect::<Vec<_>>();
+        crate::test::check_close(&*found, &*expected)
+    }
+
+    pub fn test_log_softmax<K: SoftmaxKer<f32>>(values: &[f32]) -> TestCaseResult {
+        use crate::frame::softmax::Softmax;
+        let op = crate::frame::softmax::SoftmaxImpl::<K, f32>::new();
+        let mut found = values.to_vec();
+        op.run_log(&mut found, &mut vec![]);
+        let (max, sum) = reference(values);
+        let expected = values.iter().map(|x| x - max - sum.ln()).collect::<Vec<_>>();
+        crate::test::check_close(&*found, &*expected)
+    }
+}
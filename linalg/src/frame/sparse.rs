@@ -0,0 +1,441 @@
+use crate::frame::Packer;
+use num_traits::Zero;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+use tract_data::anyhow;
+use tract_data::internal::*;
+
+/// A matrix stored in a block compressed sparse row format.
+///
+/// Rows are grouped by blocks of `mr` rows (the height of the kernel that
+/// will consume the matrix). In each row block, only the columns holding
+/// at least one non-zero value are stored, as `mr` consecutive values.
+/// The last row block is padded with zeros.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockSparseMatrix<T: Copy> {
+    m: usize,
+    k: usize,
+    mr: usize,
+    /// for each row block, index of its first stored column in `cols`
+    row_blocks: Vec<usize>,
+    /// column index (in k) of each stored column
+    cols: Vec<usize>,
+    /// `mr` values for each stored column
+    values: Vec<T>,
+}
+
+impl<T: Datum + Copy + Zero> BlockSparseMatrix<T> {
+    /// Compress a dense `m` x `k` view, `m_axis` and `k_axis` being the
+    /// view axes for rows and columns.
+    pub fn from_view(
+        a: &TensorView,
+        m_axis: usize,
+        k_axis: usize,
+        mr: usize,
+    ) -> anyhow::Result<BlockSparseMatrix<T>> {
+        let m = a.shape()[m_axis];
+        let k = a.shape()[k_axis];
+        let rsa = a.strides()[m_axis];
+        let csa = a.strides()[k_axis];
+        let ptr = a.as_ptr::<T>()?;
+        let mut row_blocks = vec![0];
+        let mut cols = vec![];
+        let mut values = vec![];
+        for rb in 0..(m + mr - 1) / mr {
+            let rows = rb * mr..((rb + 1) * mr).min(m);
+            for col in 0..k {
+                let column = rows
+                    .clone()
+                    .map(|row| unsafe { *ptr.offset(row as isize * rsa + col as isize * csa) });
+                if column.clone().any(|v| !v.is_zero()) {
+                    cols.push(col);
+                    values.extend(column);
+                    values.extend(std::iter::repeat(T::zero()).take(mr - rows.len()));
+                }
+            }
+            row_blocks.push(cols.len());
+        }
+        Ok(BlockSparseMatrix { m, k, mr, row_blocks, cols, values })
+    }
+}
+
+impl<T: Copy> BlockSparseMatrix<T> {
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn mr(&self) -> usize {
+        self.mr
+    }
+
+    /// Number of stored columns, across all row blocks.
+    pub fn stored_columns(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Ratio of stored blocks over the dense block count.
+    pub fn density(&self) -> f32 {
+        let blocks = (self.row_blocks.len() - 1) * self.k;
+        if blocks == 0 {
+            1.0
+        } else {
+            self.cols.len() as f32 / blocks as f32
+        }
+    }
+}
+
+impl<T: Copy> Hash for BlockSparseMatrix<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.m.hash(state);
+        self.k.hash(state);
+        self.mr.hash(state);
+        self.row_blocks.hash(state);
+        self.cols.hash(state);
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                self.values.as_ptr() as *const u8,
+                self.values.len() * std::mem::size_of::<T>(),
+            )
+        };
+        bytes.hash(state);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct SparseKerSpec<T: Copy> {
+    /// `mr` values per stored column
+    pub a: *const T,
+    /// column index of each stored column
+    pub cols: *const usize,
+    /// stored column count
+    pub blocks: usize,
+    /// packed B panel: row `k` starts at `b + k * nr`
+    pub b: *const T,
+    /// `mr` x `nr` row-major output tile, overwritten
+    pub c: *mut T,
+}
+
+pub trait SparseMatMulKer<T>: Copy + Clone + Debug + Send + Sync + 'static
+where
+    T: Copy + Debug,
+{
+    fn name() -> &'static str;
+    fn kernel(spec: &SparseKerSpec<T>) -> isize;
+    fn mr() -> usize;
+    fn nr() -> usize;
+    fn alignment_bytes_packed_b() -> usize;
+    fn end_padding_packed_b() -> usize;
+}
+
+/// Multiplication of a block sparse matrix by a dense packed B.
+pub trait SparseMatMul<T>: Debug + fmt::Display + dyn_clone::DynClone + Send + Sync
+where
+    T: Copy,
+{
+    /// Row block height expected in the sparse A.
+    fn mr(&self) -> usize;
+    fn b_pack(&self) -> Packer;
+
+    unsafe fn c_from_data_and_strides(&mut self, row_stride: isize, col_stride: isize);
+
+    /// `c = a.b (+ bias)`, bias having one value per row of A.
+    unsafe fn run(
+        &self,
+        a: &BlockSparseMatrix<T>,
+        b: &TensorView,
+        c: &mut TensorView,
+        bias: Option<&[T]>,
+    ) -> anyhow::Result<()>;
+}
+
+dyn_clone::clone_trait_object!(<T> SparseMatMul<T> where T: Copy);
+
+#[derive(Debug, Clone)]
+pub struct SparseMatMulImpl<K, T>
+where
+    T: Copy + Debug,
+    K: SparseMatMulKer<T>,
+{
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+    pub c_row_stride: isize,
+    pub c_col_stride: isize,
+    phantom: PhantomData<(K, T)>,
+}
+
+unsafe impl<K, T> Send for SparseMatMulImpl<K, T>
+where
+    T: Copy + Debug,
+    K: SparseMatMulKer<T>,
+{
+}
+
+unsafe impl<K, T> Sync for SparseMatMulImpl<K, T>
+where
+    T: Copy + Debug,
+    K: SparseMatMulKer<T>,
+{
+}
+
+impl<K, T> SparseMatMulImpl<K, T>
+where
+    T: Copy + Debug,
+    K: SparseMatMulKer<T>,
+{
+    pub fn new(m: usize, k: usize, n: usize) -> SparseMatMulImpl<K, T> {
+        SparseMatMulImpl {
+            m,
+            k,
+            n,
+            c_row_stride: n as isize,
+            c_col_stride: 1,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, T> SparseMatMul<T> for SparseMatMulImpl<K, T>
+where
+    T: Datum + Copy + Zero + Add<Output = T> + Mul<Output = T> + Debug,
+    K: SparseMatMulKer<T>,
+{
+    fn mr(&self) -> usize {
+        K::mr()
+    }
+
+    fn b_pack(&self) -> Packer {
+        Packer::new(self.k, K::nr(), K::alignment_bytes_packed_b(), K::end_padding_packed_b())
+    }
+
+    unsafe fn c_from_data_and_strides(&mut self, row_stride: isize, col_stride: isize) {
+        self.c_row_stride = row_stride;
+        self.c_col_stride = col_stride;
+    }
+
+    unsafe fn run(
+        &self,
+        a: &BlockSparseMatrix<T>,
+        b: &TensorView,
+        c: &mut TensorView,
+        bias: Option<&[T]>,
+    ) -> anyhow::Result<()> {
+        let (mr, nr) = (K::mr(), K::nr());
+        if a.mr != mr || a.m != self.m || a.k != self.k {
+            anyhow::bail!(
+                "Sparse A is {}x{} by blocks of {}, multiplier expects {}x{} by blocks of {}",
+                a.m,
+                a.k,
+                a.mr,
+                self.m,
+                self.k,
+                mr
+            );
+        }
+        let b = b.as_ptr::<T>()?;
+        let c = c.as_ptr_mut::<T>()?;
+        let mut tile = vec![T::zero(); mr * nr];
+        for rb in 0..a.row_blocks.len() - 1 {
+            let (start, end) = (a.row_blocks[rb], a.row_blocks[rb + 1]);
+            let rows = mr.min(self.m - rb * mr);
+            for panel in 0..(self.n + nr - 1) / nr {
+                K::kernel(&SparseKerSpec {
+                    a: a.values.as_ptr().add(start * mr),
+                    cols: a.cols.as_ptr().add(start),
+                    blocks: end - start,
+                    b: b.add(panel * self.k * nr),
+                    c: tile.as_mut_ptr(),
+                });
+                let cols = nr.min(self.n - panel * nr);
+                for r in 0..rows {
+                    let row = rb * mr + r;
+                    let bias = bias.map(|b| b[row]).unwrap_or(T::zero());
+                    let c = c.offset(row as isize * self.c_row_stride);
+                    for x in 0..cols {
+                        *c.offset((panel * nr + x) as isize * self.c_col_stride) =
+                            *tile.get_unchecked(r * nr + x) + bias;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K, T> fmt::Display for SparseMatMulImpl<K, T>
+where
+    T: Copy + Debug,
+    K: SparseMatMulKer<T>,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Sparse (m:{}, k:{}, n:{}) ({} {}x{})",
+            self.m,
+            self.k,
+            self.n,
+            K::name(),
+            K::mr(),
+            K::nr()
+        )
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[macro_export]
+    macro_rules! sparse_mmm_frame_tests {
+        ($cond:expr, $ker:ty) => {
+            proptest::proptest! {
+                #[test]
+                fn sparse_prop(pb in proptest::prelude::any::<crate::frame::sparse::test::SparseProblem>()) {
+                    if $cond {
+                        let found = pb.run::<$ker>();
+                        proptest::prop_assert_eq!(found, pb.reference())
+                    }
+                }
+            }
+
+            #[test]
+            fn sparse_empty_row_block() {
+                if $cond {
+                    let mr = <$ker as crate::frame::sparse::SparseMatMulKer<f32>>::mr();
+                    let nr = <$ker as crate::frame::sparse::SparseMatMulKer<f32>>::nr();
+                    let m = 2 * mr + 1;
+                    let pb = crate::frame::sparse::test::SparseProblem {
+                        m,
+                        k: 3,
+                        n: nr + 1,
+                        a: (0..m * 3).map(|i| if i / 3 < mr { 0.0 } else { i as f32 }).collect(),
+                        b: (0..3 * (nr + 1)).map(|i| i as f32).collect(),
+                        bias: Some((0..m).map(|i| i as f32).collect()),
+                        c_trans: false,
+                    };
+                    assert_eq!(pb.run::<$ker>(), pb.reference())
+                }
+            }
+
+            #[test]
+            fn sparse_c_trans() {
+                if $cond {
+                    let mr = <$ker as crate::frame::sparse::SparseMatMulKer<f32>>::mr();
+                    let pb = crate::frame::sparse::test::SparseProblem {
+                        m: mr + 1,
+                        k: 2,
+                        n: 3,
+                        a: (0..2 * (mr + 1)).map(|i| (i % 3) as f32).collect(),
+                        b: (0..6).map(|i| i as f32 - 2.0).collect(),
+                        bias: None,
+                        c_trans: true,
+                    };
+                    assert_eq!(pb.run::<$ker>(), pb.reference())
+                }
+            }
+        };
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SparseProblem {
+        pub m: usize,
+        pub k: usize,
+        pub n: usize,
+        /// row-major m x k, with small integer values
+        pub a: Vec<f32>,
+        /// row-major k x n
+        pub b: Vec<f32>,
+        pub bias: Option<Vec<f32>>,
+        pub c_trans: bool,
+    }
+
+    impl Arbitrary for SparseProblem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<SparseProblem>;
+
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (1usize..40, 1usize..20, 1usize..40, any::<bool>())
+                .prop_flat_map(|(m, k, n, c_trans)| {
+                    (
+                        Just((m, k, n, c_trans)),
+                        proptest::collection::vec(prop_oneof!(3 => Just(0i8), 1 => -5i8..5), m * k),
+                        proptest::collection::vec(-5i8..5, k * n),
+                        proptest::option::of(proptest::collection::vec(-5i8..5, m)),
+                    )
+                })
+                .prop_map(|((m, k, n, c_trans), a, b, bias)| SparseProblem {
+                    m,
+                    k,
+                    n,
+                    a: a.into_iter().map(|x| x as f32).collect(),
+                    b: b.into_iter().map(|x| x as f32).collect(),
+                    bias: bias.map(|b| b.into_iter().map(|x| x as f32).collect()),
+                    c_trans,
+                })
+                .boxed()
+        }
+    }
+
+    impl SparseProblem {
+        pub fn reference(&self) -> Vec<f32> {
+            let mut c = vec![0.0; self.m * self.n];
+            for row in 0..self.m {
+                for col in 0..self.n {
+                    let mut sum = self.bias.as_ref().map(|b| b[row]).unwrap_or(0.0);
+                    for i in 0..self.k {
+                        sum += self.a[row * self.k + i] * self.b[i * self.n + col];
+                    }
+                    c[row * self.n + col] = sum;
+                }
+            }
+            c
+        }
+
+        pub fn run<K: SparseMatMulKer<f32>>(&self) -> Vec<f32> {
+            unsafe {
+                let a = tensor1(&self.a).into_shape(&[self.m, self.k]).unwrap();
+                let a = BlockSparseMatrix::<f32>::from_view(&a.view(), 0, 1, K::mr()).unwrap();
+                let mut op = SparseMatMulImpl::<K, f32>::new(self.m, self.k, self.n);
+                let b = tensor1(&self.b).into_shape(&[self.k, self.n]).unwrap();
+                let mut packed_b = Tensor::uninitialized_aligned::<f32>(
+                    &[op.b_pack().len(self.n)],
+                    op.b_pack().alignment(),
+                )
+                .unwrap();
+                op.b_pack().pack(packed_b.view_mut(), b.view(), 0, 1);
+                // with c_trans, c is stored column-major
+                let mut c = Tensor::zero::<f32>(&[self.m * self.n]).unwrap();
+                if self.c_trans {
+                    op.c_from_data_and_strides(1, self.m as isize);
+                }
+                op.run(&a, &packed_b.view(), &mut c.view_mut(), self.bias.as_deref()).unwrap();
+                let c = c.as_slice::<f32>().unwrap();
+                if self.c_trans {
+                    (0..self.m * self.n).map(|i| c[(i % self.n) * self.m + i / self.n]).collect()
+                } else {
+                    c.to_vec()
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn block_sparse_from_dense() {
+        let a = tensor2(&[[0f32, 1.0, 0.0], [0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        let sparse = BlockSparseMatrix::<f32>::from_view(&a.view(), 0, 1, 2).unwrap();
+        assert_eq!(sparse.row_blocks, vec![0, 1, 2]);
+        assert_eq!(sparse.cols, vec![1, 0]);
+        assert_eq!(sparse.values, vec![1.0, 0.0, 2.0, 0.0]);
+        assert_eq!(sparse.density(), 2.0 / 6.0);
+    }
+}
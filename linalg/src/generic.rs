@@ -1,9 +1,15 @@
+pub mod depthwise;
 pub mod lut;
 pub mod mmm;
 pub mod sigmoid;
+pub mod softmax;
+pub mod sparse;
 pub mod tanh;
 
+pub use self::depthwise::GenericDepthWise4;
 pub use self::lut::GenericLut8;
-pub use self::mmm::GenericMmm4x4;
+pub use self::mmm::{GenericMmm4x1, GenericMmm4x4};
 pub use self::sigmoid::SSigmoid4;
+pub use self::softmax::SSoftmax4;
+pub use self::sparse::GenericSparse4x4;
 pub use self::tanh::STanh4;
@@ -0,0 +1,82 @@
+use crate::frame::depthwise::{DepthWiseKer, DepthWiseKerSpec};
+use num_traits::AsPrimitive;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+
+#[derive(Copy, Clone, Debug)]
+pub struct GenericDepthWise4<TA, TI>(PhantomData<(TA, TI)>)
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync;
+
+unsafe impl<TA, TI> Send for GenericDepthWise4<TA, TI>
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync,
+{
+}
+
+unsafe impl<TA, TI> Sync for GenericDepthWise4<TA, TI>
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync,
+{
+}
+
+impl<TA, TI> DepthWiseKer<TA, TI> for GenericDepthWise4<TA, TI>
+where
+    TA: Copy + Debug + AsPrimitive<TI> + Send + Sync + 'static,
+    TI: Copy + Debug + Add<Output = TI> + Mul<Output = TI> + Send + Sync + 'static,
+{
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(never)]
+    fn kernel(spec: &DepthWiseKerSpec<TA, TI>) -> isize {
+        unsafe {
+            let mut acc = spec.acc;
+            let mut kernel = spec.kernel;
+            let mut input = spec.input;
+            for _ in 0..spec.blocks {
+                let mut sums = [*acc, *acc.add(1), *acc.add(2), *acc.add(3)];
+                for t in 0..spec.taps {
+                    let offset = *spec.offsets.add(t);
+                    let i = input.offset(offset);
+                    for lane in 0..4 {
+                        let k = if spec.broadcast != 0 {
+                            *kernel.add(t)
+                        } else {
+                            *kernel.offset(t as isize * spec.kernel_tap_stride + lane as isize)
+                        };
+                        sums[lane] = sums[lane] + k.as_() * (*i.add(lane)).as_();
+                    }
+                }
+                for lane in 0..4 {
+                    *acc.add(lane) = sums[lane];
+                }
+                acc = acc.add(4);
+                input = input.add(4);
+                if spec.broadcast == 0 {
+                    kernel = kernel.add(4);
+                }
+            }
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod f32_f32 {
+        depthwise_frame_tests!(true, crate::generic::GenericDepthWise4<f32, f32>, f32, f32);
+    }
+    mod i8_i32 {
+        depthwise_frame_tests!(true, crate::generic::GenericDepthWise4<i8, i32>, i8, i32);
+    }
+}
@@ -40,6 +40,15 @@ impl PseudoRightShift for f32 {
     }
 }
 
+impl PseudoRightShift for f64 {
+    fn q_even(self, mult: Self, shift: usize) -> Self {
+        self * mult * 2f64.powi(-(shift as i32))
+    }
+    fn q_to_plus_inf(self, mult: Self, shift: usize) -> Self {
+        self * mult * 2f64.powi(-(shift as i32))
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct GenericMmm4x4<TA, TB, TC, TI>(PhantomData<(TA, TB, TC, TI)>)
 where
@@ -331,6 +340,13 @@ where
                             }
                         }
                     }
+                    FusedKerSpec::PerRowQTowardsPlusInf(mults, shift) => {
+                        for i in 0..4 {
+                            for j in 0..4 {
+                                ab[i][j] = ab[i][j].q_to_plus_inf(*mults.offset(i as isize), shift);
+                            }
+                        }
+                    }
                 }
                 pnl = pnl.add(1);
             }
@@ -372,6 +388,247 @@ where
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct GenericMmm4x1<TA, TB, TC, TI>(PhantomData<(TA, TB, TC, TI)>)
+where
+    TA: Copy + fmt::Debug + AsPrimitive<TI>,
+    TB: Copy + fmt::Debug + AsPrimitive<TI>,
+    TC: Copy + fmt::Debug + AsPrimitive<TI> + 'static,
+    TI: Copy
+        + ops::AddAssign
+        + ops::Mul<Output = TI>
+        + ops::MulAssign
+        + PseudoRightShift
+        + PartialOrd
+        + Zero
+        + fmt::Debug
+        + fmt::Display
+        + AsPrimitive<TC>
+        + 'static;
+
+unsafe impl<TA, TB, TC, TI> Send for GenericMmm4x1<TA, TB, TC, TI>
+where
+    TA: Copy + fmt::Debug + AsPrimitive<TI>,
+    TB: Copy + fmt::Debug + AsPrimitive<TI>,
+    TC: Copy + fmt::Debug + AsPrimitive<TI> + 'static,
+    TI: Copy
+        + ops::AddAssign
+        + ops::Mul<Output = TI>
+        + ops::MulAssign
+        + PseudoRightShift
+        + PartialOrd
+        + Zero
+        + fmt::Debug
+        + fmt::Display
+        + AsPrimitive<TC>
+        + 'static,
+{
+}
+
+unsafe impl<TA, TB, TC, TI> Sync for GenericMmm4x1<TA, TB, TC, TI>
+where
+    TA: Copy + fmt::Debug + AsPrimitive<TI>,
+    TB: Copy + fmt::Debug + AsPrimitive<TI>,
+    TC: Copy + fmt::Debug + AsPrimitive<TI> + 'static,
+    TI: Copy
+        + ops::AddAssign
+        + ops::Mul<Output = TI>
+        + ops::MulAssign
+        + PseudoRightShift
+        + PartialOrd
+        + Zero
+        + fmt::Debug
+        + fmt::Display
+        + AsPrimitive<TC>
+        + 'static,
+{
+}
+
+impl<TA, TB, TC, TI> MatMatMulKer<TI> for GenericMmm4x1<TA, TB, TC, TI>
+where
+    TA: Copy + fmt::Debug + AsPrimitive<TI>,
+    TB: Copy + fmt::Debug + AsPrimitive<TI>,
+    TC: Copy + fmt::Debug + AsPrimitive<TI> + 'static + Bounded,
+    TI: Copy
+        + ops::AddAssign
+        + ops::Mul<Output = TI>
+        + ops::MulAssign
+        + PseudoRightShift
+        + PartialOrd
+        + Zero
+        + Signed
+        + fmt::Debug
+        + fmt::Display
+        + AsPrimitive<TC>
+        + 'static,
+    usize: AsPrimitive<TI>,
+{
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        1
+    }
+    fn end_padding_packed_a() -> usize {
+        0
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(always)]
+    fn alignment_bytes_packed_a() -> usize {
+        std::mem::size_of::<TA>()
+    }
+    #[inline(always)]
+    fn alignment_bytes_packed_b() -> usize {
+        std::mem::size_of::<TB>()
+    }
+    #[inline(never)]
+    fn kernel(spec: &MatMatMulKerSpec<TI>) -> isize {
+        unsafe {
+            let mut ab = [TI::zero(); 4];
+            match (*spec.a, *spec.b, *spec.linear) {
+                (Packed { ptr: a }, Packed { ptr: b }, Mul { k }) => {
+                    let a = a as *const TA;
+                    let b = b as *const TB;
+                    for i in 0..k {
+                        let a = std::slice::from_raw_parts(a.offset(4 * i as isize), 4);
+                        let b = *b.offset(i as isize);
+                        for r in 0..4 {
+                            ab[r] += a[r].as_() * b.as_();
+                        }
+                    }
+                }
+                (Packed { ptr: a }, OffsetsAndPtrs { row_byte_offsets, col_ptrs }, Mul { k }) => {
+                    let a = a as *const TA;
+                    let pb = *(col_ptrs as *const *const TB);
+                    for i in 0..k {
+                        let a = std::slice::from_raw_parts(a.offset(4 * i as isize), 4);
+                        let offset = *row_byte_offsets.offset(i as isize)
+                            / std::mem::size_of::<TB>() as isize;
+                        let b = *(pb.offset(offset));
+                        for r in 0..4 {
+                            ab[r] += a[r].as_() * b.as_();
+                        }
+                    }
+                }
+                (Packed { ptr: a }, VecStride { ptr: b, byte_stride, .. }, Mul { k }) => {
+                    let a = a as *const TA;
+                    let b = b as *const TB;
+                    for i in 0..k {
+                        let a = std::slice::from_raw_parts(a.offset(4 * i as isize), 4);
+                        let b = *b
+                            .offset(i as isize * byte_stride / std::mem::size_of::<TB>() as isize);
+                        for r in 0..4 {
+                            ab[r] += a[r].as_() * b.as_();
+                        }
+                    }
+                }
+                _ => return 1,
+            }
+            let mut pnl = spec.non_linear;
+            loop {
+                if pnl.is_null() {
+                    break;
+                }
+                match *pnl {
+                    FusedKerSpec::Done => break,
+                    FusedKerSpec::AddC => {
+                        let (c, row_byte_stride) = match *spec.c {
+                            Strides { ptr, row_byte_stride, .. } => {
+                                (ptr as *const TC, row_byte_stride)
+                            }
+                            VecStride { ptr, byte_stride, .. } => (ptr as *const TC, byte_stride),
+                            _ => return 1,
+                        };
+                        let rsc = row_byte_stride / std::mem::size_of::<TC>() as isize;
+                        for r in 0..4 {
+                            ab[r] += (*c.offset(r as isize * rsc)).as_();
+                        }
+                    }
+                    FusedKerSpec::PerRowMul(bias) => {
+                        for r in 0..4 {
+                            ab[r] *= *bias.offset(r as isize);
+                        }
+                    }
+                    FusedKerSpec::PerRowAdd(bias) => {
+                        for r in 0..4 {
+                            ab[r] += *bias.offset(r as isize);
+                        }
+                    }
+                    FusedKerSpec::PerColMul(bias) => {
+                        for r in 0..4 {
+                            ab[r] *= *bias;
+                        }
+                    }
+                    FusedKerSpec::PerColAdd(bias) => {
+                        for r in 0..4 {
+                            ab[r] += *bias;
+                        }
+                    }
+                    FusedKerSpec::Min(m) => {
+                        for r in 0..4 {
+                            ab[r] = if m < ab[r] { m } else { ab[r] }
+                        }
+                    }
+                    FusedKerSpec::Max(m) => {
+                        for r in 0..4 {
+                            ab[r] = if m > ab[r] { m } else { ab[r] }
+                        }
+                    }
+                    FusedKerSpec::AddRowColProducts(rows, cols) => {
+                        for r in 0..4 {
+                            ab[r] += *rows.offset(r as isize) * *cols;
+                        }
+                    }
+                    FusedKerSpec::ScalarAdd(a) => {
+                        for r in 0..4 {
+                            ab[r] += a;
+                        }
+                    }
+                    FusedKerSpec::ScalarMul(a) => {
+                        for r in 0..4 {
+                            ab[r] *= a;
+                        }
+                    }
+                    FusedKerSpec::QTowardsEven(mult, shift) => {
+                        for r in 0..4 {
+                            ab[r] = ab[r].q_even(mult, shift);
+                        }
+                    }
+                    FusedKerSpec::QTowardsPlusInf(mult, shift) => {
+                        for r in 0..4 {
+                            ab[r] = ab[r].q_to_plus_inf(mult, shift);
+                        }
+                    }
+                    FusedKerSpec::PerRowQTowardsPlusInf(mults, shift) => {
+                        for r in 0..4 {
+                            ab[r] = ab[r].q_to_plus_inf(*mults.offset(r as isize), shift);
+                        }
+                    }
+                }
+                pnl = pnl.add(1);
+            }
+            let (c, row_byte_stride) = match *spec.c {
+                Strides { ptr, row_byte_stride, .. } => (ptr as *mut TC, row_byte_stride),
+                VecStride { ptr, byte_stride, .. } => (ptr as *mut TC, byte_stride),
+                _ => return 1,
+            };
+            let rsc = row_byte_stride / std::mem::size_of::<TC>() as isize;
+            for r in 0..4 {
+                *c.offset(r as isize * rsc) = ab[r].as_();
+            }
+        }
+        return 0;
+    }
+}
+
 #[cfg(test)]
 #[derive(Copy, Clone, Debug)]
 pub struct GenericMmmTest3x2<TA, TB, TC, TI>(PhantomData<(TA, TB, TC, TI)>)
@@ -623,6 +880,13 @@ where
                             }
                         }
                     }
+                    FusedKerSpec::PerRowQTowardsPlusInf(mults, shift) => {
+                        for i in 0..3 {
+                            for j in 0..2 {
+                                ab[i][j] = ab[i][j].q_to_plus_inf(*mults.offset(i as isize), shift);
+                            }
+                        }
+                    }
                 }
                 pnl = pnl.add(1);
             }
@@ -654,11 +918,20 @@ where
 }
 
 test_mmm_kernel_f32!(crate::generic::mmm::GenericMmm4x4<f32, f32, f32, f32>, test_GenericMmm4x4_f32, true);
+test_mmm_kernel_f64!(crate::generic::mmm::GenericMmm4x4<f64, f64, f64, f64>, test_GenericMmm4x4_f64, true);
+test_mmm_kernel_f16_f32!(
+    crate::generic::mmm::GenericMmm4x4<tract_data::prelude::f16, tract_data::prelude::f16, f32, f32>,
+    test_GenericMmm4x4_f16_f32,
+    true
+);
 test_mmm_kernel_i8!(crate::generic::mmm::GenericMmm4x4<i8, i8, i8, i32>, test_GenericMmm4x4_i8, true);
 test_mmm_kernel_u8!(crate::generic::mmm::GenericMmm4x4<u8, u8, u8, i32>, test_GenericMmm4x4_u8, true);
 test_mmm_kernel_i8_i32!(crate::generic::mmm::GenericMmm4x4<i8, i8, i32, i32>, test_GenericMmm4x4_i8_i32, true);
 test_mmm_kernel_i8_u8_i32!(crate::generic::mmm::GenericMmm4x4<i8, u8, i32, i32>, test_GenericMmm4x4_i8_u8_i32, true);
 
+test_mmm_kernel_f32!(crate::generic::mmm::GenericMmm4x1<f32, f32, f32, f32>, test_GenericMmm4x1_f32, true);
+test_mmm_kernel_i8!(crate::generic::mmm::GenericMmm4x1<i8, i8, i8, i32>, test_GenericMmm4x1_i8, true);
+
 test_mmm_kernel_f32!(crate::generic::mmm::GenericMmmTest3x2<f32, f32, f32, f32>, test_GenericMmmTest3x2_f32, true);
 test_mmm_kernel_i8!(crate::generic::mmm::GenericMmmTest3x2<i8, i8, i8, i32>, test_GenericMmmTest3x2_i8, true);
 test_mmm_kernel_u8!(crate::generic::mmm::GenericMmmTest3x2<u8, u8, u8, i32>, test_GenericMmmTest3x2_u8, true);
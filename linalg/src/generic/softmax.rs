@@ -0,0 +1,68 @@
+use crate::frame::softmax::SoftmaxKer;
+
+const LOW: f32 = -88.37626;
+const HIGH: f32 = 88.0;
+const LOG2_E: f32 = 1.442695;
+const LN_2_HI: f32 = 0.693359375;
+const LN_2_LO: f32 = -2.12194440e-4;
+const P0: f32 = 1.9875691500e-4;
+const P1: f32 = 1.3981999507e-3;
+const P2: f32 = 8.3334519073e-3;
+const P3: f32 = 4.1665795894e-2;
+const P4: f32 = 1.6666665459e-1;
+const P5: f32 = 5.0000001201e-1;
+
+/// exp(x), computed as 2^n * exp(r) with x = n * ln(2) + r and a polynomial
+/// approximation of exp(r) on [-ln(2)/2, ln(2)/2].
+pub fn sexp(x: f32) -> f32 {
+    let x = x.max(LOW).min(HIGH);
+
+    let n = (x * LOG2_E + 0.5).floor();
+    let r = x - n * LN_2_HI;
+    let r = r - n * LN_2_LO;
+    let r2 = r * r;
+
+    let p = P0;
+    let p = r * p + P1;
+    let p = r * p + P2;
+    let p = r * p + P3;
+    let p = r * p + P4;
+    let p = r * p + P5;
+    let p = r2 * p + r + 1.0;
+
+    p * f32::from_bits(((n as i32 + 127) as u32) << 23)
+}
+
+#[derive(Clone, Debug)]
+pub struct SSoftmax4;
+
+impl SoftmaxKer<f32> for SSoftmax4 {
+    fn name() -> &'static str {
+        "generic"
+    }
+
+    fn alignment_bytes() -> usize {
+        16
+    }
+
+    fn nr() -> usize {
+        4
+    }
+
+    fn run(x: &mut [f32], max: f32) -> f32 {
+        debug_assert!(x.len() % Self::nr() == 0);
+        debug_assert!(x.as_ptr() as usize % Self::alignment_bytes() == 0);
+        let mut sum = 0.0;
+        x.iter_mut().for_each(|px| {
+            *px = sexp(*px - max);
+            sum += *px;
+        });
+        sum
+    }
+}
+
+#[cfg(test)]
+#[macro_use]
+pub mod test {
+    softmax_frame_tests!(true, crate::generic::softmax::SSoftmax4);
+}
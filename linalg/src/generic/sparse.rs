@@ -0,0 +1,70 @@
+use crate::frame::sparse::{SparseKerSpec, SparseMatMulKer};
+use num_traits::Zero;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul};
+
+#[derive(Copy, Clone, Debug)]
+pub struct GenericSparse4x4<T>(PhantomData<T>)
+where
+    T: Copy + Debug + Add<Output = T> + Mul<Output = T> + Zero;
+
+unsafe impl<T> Send for GenericSparse4x4<T> where
+    T: Copy + Debug + Add<Output = T> + Mul<Output = T> + Zero
+{
+}
+
+unsafe impl<T> Sync for GenericSparse4x4<T> where
+    T: Copy + Debug + Add<Output = T> + Mul<Output = T> + Zero
+{
+}
+
+impl<T> SparseMatMulKer<T> for GenericSparse4x4<T>
+where
+    T: Copy + Debug + Add<Output = T> + Mul<Output = T> + Zero + 'static,
+{
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        std::mem::size_of::<T>()
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(spec: &SparseKerSpec<T>) -> isize {
+        unsafe {
+            let mut ab = [[T::zero(); 4]; 4];
+            for block in 0..spec.blocks {
+                let a = spec.a.add(4 * block);
+                let b = spec.b.add(4 * *spec.cols.add(block));
+                for i in 0..4 {
+                    for j in 0..4 {
+                        ab[i][j] = ab[i][j] + *a.add(i) * *b.add(j);
+                    }
+                }
+            }
+            for i in 0..4 {
+                for j in 0..4 {
+                    *spec.c.add(4 * i + j) = ab[i][j];
+                }
+            }
+        }
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    sparse_mmm_frame_tests!(true, crate::generic::GenericSparse4x4<f32>);
+}
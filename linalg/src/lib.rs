@@ -22,15 +22,53 @@ pub mod arm64;
 #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
 pub mod arm32;
 
+pub use self::frame::depthwise;
 pub use self::frame::lut;
 pub use self::frame::mmm;
 pub use self::frame::sigmoid;
+pub use self::frame::softmax;
+pub use self::frame::sparse;
 pub use self::frame::tanh;
 
 use tract_data::prelude::*;
 
+const MMM_TILE_OVERHEAD: usize = 4;
+
+pub struct MMMImpl {
+    pub name: String,
+    pub mr: usize,
+    pub nr: usize,
+    pub constructor: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+}
+
+impl MMMImpl {
+    pub fn f32<K: mmm::MatMatMulKer<f32>>() -> MMMImpl {
+        MMMImpl {
+            name: format!("{}_{}x{}", K::name(), K::mr(), K::nr()),
+            mr: K::mr(),
+            nr: K::nr(),
+            constructor: Box::new(|m, k, n| {
+                Box::new(mmm::MatMatMulImpl::<K, f32, f32, f32, f32>::new(m, k, n))
+            }),
+        }
+    }
+
+    /// Estimated work for a m x k x n product: each (padded) tile pays for
+    /// its products, its a and b loads, and a fixed store overhead.
+    pub fn cost(&self, m: usize, k: usize, n: usize) -> usize {
+        let tiles = ((m + self.mr - 1) / self.mr) * ((n + self.nr - 1) / self.nr);
+        tiles
+            * (k * (self.mr * self.nr + self.mr + self.nr) + self.mr * self.nr * MMM_TILE_OVERHEAD)
+    }
+}
+
 pub struct Ops {
-    pub mmm_f32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    pub mmm_f32_impls: Vec<MMMImpl>,
+    pub mmm_f32_override: Option<String>,
+    pub mmm_f64: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    pub mmm_f16_f16_f16: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    pub mmm_f16_f16_f32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
+    pub mmm_f16_f32_f32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
     pub qmmm_i8_i32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
     pub qmmm_u8_i32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
     pub qmmm_u8_u8: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
@@ -38,10 +76,28 @@ pub struct Ops {
     pub qmmm_i8_u8_i32: Box<dyn Fn(usize, usize, usize) -> Box<dyn mmm::MatMatMul> + Send + Sync>,
     pub sigmoid_f32: Box<dyn Fn() -> Box<dyn sigmoid::Sigmoid<f32>> + Send + Sync>,
     pub tanh_f32: Box<dyn Fn() -> Box<dyn tanh::Tanh<f32>> + Send + Sync>,
+    pub softmax_f32: Box<dyn Fn() -> Box<dyn softmax::Softmax<f32>> + Send + Sync>,
     pub lut_u8: Box<dyn Fn(&[u8]) -> Box<dyn lut::Lut> + Send + Sync>,
+    pub depthwise_f32: Box<dyn Fn() -> Box<dyn depthwise::DepthWise<f32, f32>> + Send + Sync>,
+    pub depthwise_i8_i32: Box<dyn Fn() -> Box<dyn depthwise::DepthWise<i8, i32>> + Send + Sync>,
+    pub sparse_mmm_f32:
+        Box<dyn Fn(usize, usize, usize) -> Box<dyn sparse::SparseMatMul<f32>> + Send + Sync>,
 }
 
 impl Ops {
+    pub fn mmm_f32_impl(&self, m: usize, k: usize, n: usize) -> &MMMImpl {
+        if let Some(forced) = &self.mmm_f32_override {
+            if let Some(imp) = self.mmm_f32_impls.iter().find(|imp| &imp.name == forced) {
+                return imp;
+            }
+        }
+        self.mmm_f32_impls.iter().min_by_key(|imp| imp.cost(m, k, n)).unwrap()
+    }
+
+    pub fn mmm_f32(&self, m: usize, k: usize, n: usize) -> Box<dyn mmm::MatMatMul> {
+        (self.mmm_f32_impl(m, k, n).constructor)(m, k, n)
+    }
+
     pub fn mmm(
         &self,
         a: DatumType,
@@ -53,7 +109,11 @@ impl Ops {
     ) -> Option<Box<dyn mmm::MatMatMul>> {
         use DatumType::*;
         match (a, b, c) {
-            (F32, F32, F32) => Some((self.mmm_f32)(m, k, n)),
+            (F32, F32, F32) => Some(self.mmm_f32(m, k, n)),
+            (F64, F64, F64) => Some((self.mmm_f64)(m, k, n)),
+            (F16, F16, F16) => Some((self.mmm_f16_f16_f16)(m, k, n)),
+            (F16, F16, F32) => Some((self.mmm_f16_f16_f32)(m, k, n)),
+            (F16, F32, F32) => Some((self.mmm_f16_f32_f32)(m, k, n)),
             (I8, I8, I32) => Some((self.qmmm_i8_i32)(m, k, n)),
             (U8, U8, I32) => Some((self.qmmm_u8_i32)(m, k, n)),
             (I8, I8, I8) => Some((self.qmmm_i8_i8)(m, k, n)),
@@ -66,10 +126,42 @@ impl Ops {
 
 pub fn generic() -> Ops {
     Ops {
-        mmm_f32: Box::new(|m, k, n| {
+        mmm_f32_impls: vec![
+            MMMImpl::f32::<generic::GenericMmm4x4<f32, f32, f32, f32>>(),
+            MMMImpl::f32::<generic::GenericMmm4x1<f32, f32, f32, f32>>(),
+        ],
+        mmm_f32_override: None,
+        mmm_f64: Box::new(|m, k, n| {
+            Box::new(mmm::MatMatMulImpl::<
+                generic::GenericMmm4x4<f64, f64, f64, f64>,
+                f64,
+                f64,
+                f64,
+                f64,
+            >::new(m, k, n))
+        }),
+        mmm_f16_f16_f16: Box::new(|m, k, n| {
             Box::new(mmm::MatMatMulImpl::<
-                generic::GenericMmm4x4<f32, f32, f32, f32>,
+                generic::GenericMmm4x4<f16, f16, f16, f32>,
+                f16,
+                f16,
+                f16,
                 f32,
+            >::new(m, k, n))
+        }),
+        mmm_f16_f16_f32: Box::new(|m, k, n| {
+            Box::new(mmm::MatMatMulImpl::<
+                generic::GenericMmm4x4<f16, f16, f32, f32>,
+                f16,
+                f16,
+                f32,
+                f32,
+            >::new(m, k, n))
+        }),
+        mmm_f16_f32_f32: Box::new(|m, k, n| {
+            Box::new(mmm::MatMatMulImpl::<
+                generic::GenericMmm4x4<f16, f32, f32, f32>,
+                f16,
                 f32,
                 f32,
                 f32,
@@ -118,7 +210,21 @@ pub fn generic() -> Ops {
         }),
         sigmoid_f32: Box::new(|| Box::new(sigmoid::SigmoidImpl::<generic::SSigmoid4, f32>::new())),
         tanh_f32: Box::new(|| Box::new(tanh::TanhImpl::<generic::STanh4, f32>::new())),
+        softmax_f32: Box::new(|| {
+            Box::new(softmax::SoftmaxImpl::<generic::SSoftmax4, f32>::new())
+        }),
         lut_u8: Box::new(|table: &[u8]| Box::new(lut::LutImpl::<generic::GenericLut8>::new(table))),
+        depthwise_f32: Box::new(|| {
+            Box::new(
+                depthwise::DepthWiseImpl::<generic::GenericDepthWise4<f32, f32>, f32, f32>::new(),
+            )
+        }),
+        depthwise_i8_i32: Box::new(|| {
+            Box::new(depthwise::DepthWiseImpl::<generic::GenericDepthWise4<i8, i32>, i8, i32>::new())
+        }),
+        sparse_mmm_f32: Box::new(|m, k, n| {
+            Box::new(sparse::SparseMatMulImpl::<generic::GenericSparse4x4<f32>, f32>::new(m, k, n))
+        }),
     }
 }
 
@@ -128,14 +234,23 @@ pub fn best() -> Ops {
     #[cfg(target_arch = "x86_64")]
     {
         if is_x86_feature_detected!("fma") {
-            ops.mmm_f32 = Box::new(|m, k, n| {
+            ops.mmm_f32_impls = vec![
+                MMMImpl::f32::<x86_64_fma::mmm::MatMatMulF32x16x6>(),
+                MMMImpl::f32::<x86_64_fma::mmm::MatMatMulF32x64x1>(),
+            ];
+            ops.mmm_f64 = Box::new(|m, k, n| {
                 Box::new(
-                    mmm::MatMatMulImpl::<x86_64_fma::mmm::MatMatMulF32x16x6, f32, f32, f32, f32>::new(
+                    mmm::MatMatMulImpl::<x86_64_fma::mmm::MatMatMulF64x8x4, f64, f64, f64, f64>::new(
                         m, k, n,
-                        ),
-                        )
+                    ),
+                )
+            });
+            ops.sparse_mmm_f32 = Box::new(|m, k, n| {
+                Box::new(sparse::SparseMatMulImpl::<x86_64_fma::sparse::SparseF32x4x16, f32>::new(
+                    m, k, n,
+                ))
             });
-            log::info!("mmm_f32 x86_64/fma activated");
+            log::info!("mmm_f32, mmm_f64 and sparse_mmm_f32 x86_64/fma activated");
         }
         if is_x86_feature_detected!("avx2") {
             ops.qmmm_i8_i8 = Box::new(|m, k, n| {
@@ -154,13 +269,48 @@ pub fn best() -> Ops {
                     i32,
                 >::new(m, k, n))
             });
-            log::info!("mmm_i8_i8 and mmm_i8_i32 x86_64/fma activated");
+            ops.depthwise_i8_i32 = Box::new(|| {
+                Box::new(depthwise::DepthWiseImpl::<
+                    x86_64_fma::depthwise::DepthWiseI8x8,
+                    i8,
+                    i32,
+                >::new())
+            });
+            log::info!("mmm_i8_i8, mmm_i8_i32 and depthwise_i8_i32 x86_64/fma activated");
+        }
+        if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma") {
+            ops.sigmoid_f32 = Box::new(|| {
+                Box::new(sigmoid::SigmoidImpl::<x86_64_fma::sigmoid::SigmoidF32x8n, f32>::new())
+            });
+            ops.tanh_f32 = Box::new(|| {
+                Box::new(tanh::TanhImpl::<x86_64_fma::tanh::TanhF32x8n, f32>::new())
+            });
+            ops.softmax_f32 = Box::new(|| {
+                Box::new(softmax::SoftmaxImpl::<x86_64_fma::softmax::SoftmaxF32x8n, f32>::new())
+            });
+            ops.depthwise_f32 = Box::new(|| {
+                Box::new(depthwise::DepthWiseImpl::<
+                    x86_64_fma::depthwise::DepthWiseF32x8,
+                    f32,
+                    f32,
+                >::new())
+            });
+            log::info!("sigmoid_f32, tanh_f32, softmax_f32 and depthwise_f32 x86_64/fma activated");
         }
     }
     #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
     arm32::plug(&mut ops);
     #[cfg(target_arch = "aarch64")]
     arm64::plug(&mut ops);
+    if let Ok(name) = std::env::var("TRACT_MMM_F32") {
+        if ops.mmm_f32_impls.iter().any(|imp| imp.name == name) {
+            log::info!("mmm_f32 forced to {}", name);
+            ops.mmm_f32_override = Some(name);
+        } else {
+            let names: Vec<&str> = ops.mmm_f32_impls.iter().map(|imp| &*imp.name).collect();
+            log::warn!("TRACT_MMM_F32={} ignored, expected one of {:?}", name, names);
+        }
+    }
     return ops;
 }
 
@@ -211,6 +361,15 @@ mod test {
         }
     }
 
+    impl LADatum for f64 {
+        fn strat() -> BoxedStrategy<Self> {
+            (-1000isize..1000).prop_map(|i| i as f64 / 1000.0).boxed()
+        }
+        fn close(&self, other: &Self) -> bool {
+            (self - other).abs() < 0.001
+        }
+    }
+
     impl LADatum for u8 {
         fn strat() -> BoxedStrategy<Self> {
             any::<u8>().boxed()
@@ -238,6 +397,62 @@ mod test {
         }
     }
 
+    #[test]
+    fn mmm_f32_selection_prefers_mat_vec() {
+        let ops = crate::generic();
+        assert_eq!(ops.mmm_f32_impl(64, 256, 1).nr, 1);
+        assert_eq!(ops.mmm_f32_impl(64, 256, 64).nr, 4);
+    }
+
+    #[test]
+    fn mmm_f16_inputs_accumulate_in_f32() {
+        use tract_data::prelude::*;
+        let (m, k, n) = (3, 5, 2);
+        let a32 = tensor1(&*(0..m * k).map(|i| i as f32 / 8.0).collect::<Vec<_>>())
+            .into_shape(&[m, k])
+            .unwrap();
+        let b32 = tensor1(&*(0..k * n).map(|i| 1.0 - i as f32 / 4.0).collect::<Vec<_>>())
+            .into_shape(&[k, n])
+            .unwrap();
+        let run = |a: &Tensor, b: &Tensor, c_dt: DatumType| unsafe {
+            let ops = crate::generic();
+            let mut mm = ops.mmm(a.datum_type(), b.datum_type(), c_dt, m, k, n).unwrap();
+            let mut pa = Tensor::uninitialized_aligned_dt(
+                a.datum_type(),
+                &[mm.a_pack().len(m)],
+                mm.a_pack().alignment(),
+            )
+            .unwrap();
+            mm.a_pack().pack(&mut pa.view_mut(), &a.view(), 1, 0);
+            let mut pb = Tensor::uninitialized_aligned_dt(
+                b.datum_type(),
+                &[mm.b_pack().len(n)],
+                mm.b_pack().alignment(),
+            )
+            .unwrap();
+            mm.b_pack().pack(&mut pb.view_mut(), &b.view(), 0, 1);
+            mm.c_from_data_and_strides(n as isize, 1);
+            let mut c = Tensor::zero_dt(c_dt, &[m, n]).unwrap();
+            mm.run(&pa.view(), &pb.view(), &mut c.view_mut(), &[]).unwrap();
+            c.cast_to::<f32>().unwrap().into_owned()
+        };
+        let expected = run(&a32, &b32, DatumType::F32);
+        let a16 = a32.cast_to::<f16>().unwrap().into_owned();
+        let b16 = b32.cast_to::<f16>().unwrap().into_owned();
+        assert_eq!(run(&a16, &b16, DatumType::F32), expected);
+        assert_eq!(run(&a16, &b32, DatumType::F32), expected);
+        assert_eq!(run(&a16, &b16, DatumType::F16), expected);
+    }
+
+    #[test]
+    fn mmm_f32_override() {
+        let mut ops = crate::generic();
+        ops.mmm_f32_override = Some("generic_4x1".to_string());
+        assert_eq!(ops.mmm_f32_impl(64, 256, 64).name, "generic_4x1");
+        ops.mmm_f32_override = Some("no_such_kernel".to_string());
+        assert_eq!(ops.mmm_f32_impl(64, 256, 64).name, "generic_4x4");
+    }
+
     pub(crate) fn check_close<T: LADatum>(
         found: &[T],
         expected: &[T],
@@ -1 +1,6 @@
+pub mod depthwise;
 pub mod mmm;
+pub mod sigmoid;
+pub mod softmax;
+pub mod sparse;
+pub mod tanh;
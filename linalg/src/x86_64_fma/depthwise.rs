@@ -0,0 +1,62 @@
+use crate::frame::depthwise::*;
+
+extern "C" {
+    fn fma_dw_f32_8(spec: *const DepthWiseKerSpec<f32, f32>) -> isize;
+    fn fma_dw_i8_8(spec: *const DepthWiseKerSpec<i8, i32>) -> isize;
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DepthWiseF32x8;
+
+impl DepthWiseKer<f32, f32> for DepthWiseF32x8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(never)]
+    fn kernel(spec: &DepthWiseKerSpec<f32, f32>) -> isize {
+        unsafe { fma_dw_f32_8(spec) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct DepthWiseI8x8;
+
+impl DepthWiseKer<i8, i32> for DepthWiseI8x8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "avx2"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(never)]
+    fn kernel(spec: &DepthWiseKerSpec<i8, i32>) -> isize {
+        unsafe { fma_dw_i8_8(spec) }
+    }
+}
+
+#[cfg(test)]
+mod test_simd {
+    mod f32_f32 {
+        depthwise_frame_tests!(
+            is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma"),
+            crate::x86_64_fma::depthwise::DepthWiseF32x8,
+            f32,
+            f32
+        );
+    }
+    mod i8_i32 {
+        depthwise_frame_tests!(
+            is_x86_feature_detected!("avx2"),
+            crate::x86_64_fma::depthwise::DepthWiseI8x8,
+            i8,
+            i32
+        );
+    }
+}
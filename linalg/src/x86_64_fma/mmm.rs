@@ -2,6 +2,8 @@ use crate::frame::mmm::*;
 
 extern "C" {
     fn fma_mmm_f32_16x6(op: *const MatMatMulKerSpec<f32>) -> isize;
+    fn fma_mmm_f32_64x1(op: *const MatMatMulKerSpec<f32>) -> isize;
+    fn fma_mmm_f64_8x4(op: *const MatMatMulKerSpec<f64>) -> isize;
     fn fma_mmm_i8_8x8(op: *const MatMatMulKerSpec<i32>) -> isize;
 }
 
@@ -39,6 +41,74 @@ impl MatMatMulKer<f32> for MatMatMulF32x16x6 {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct MatMatMulF32x64x1;
+
+impl MatMatMulKer<f32> for MatMatMulF32x64x1 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        64
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        1
+    }
+    fn alignment_bytes_packed_a() -> usize {
+        32
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        4
+    }
+    fn end_padding_packed_a() -> usize {
+        0
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(spec: &MatMatMulKerSpec<f32>) -> isize {
+        unsafe { fma_mmm_f32_64x1(spec) }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct MatMatMulF64x8x4;
+
+impl MatMatMulKer<f64> for MatMatMulF64x8x4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    fn alignment_bytes_packed_a() -> usize {
+        32
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        8
+    }
+    fn end_padding_packed_a() -> usize {
+        0
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(spec: &MatMatMulKerSpec<f64>) -> isize {
+        unsafe { fma_mmm_f64_8x4(spec) }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct MatMatMulI8x8x8;
 
@@ -113,6 +183,18 @@ test_mmm_kernel_f32!(
     is_x86_feature_detected!("fma")
 );
 
+test_mmm_kernel_f32!(
+    crate::x86_64_fma::mmm::MatMatMulF32x64x1,
+    test_MatMatMulF32x64x1,
+    is_x86_feature_detected!("fma")
+);
+
+test_mmm_kernel_f64!(
+    crate::x86_64_fma::mmm::MatMatMulF64x8x4,
+    test_MatMatMulF64x8x4,
+    is_x86_feature_detected!("fma")
+);
+
 test_mmm_kernel_i8!(
     crate::x86_64_fma::mmm::MatMatMulI8x8x8,
     test_MatMatMulI8x8x8,
@@ -0,0 +1,35 @@
+use crate::frame::softmax::*;
+
+extern "C" {
+    fn fma_softmax_f32_8n(ptr: *mut f32, count: usize, max: f32) -> f32;
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SoftmaxF32x8n;
+
+impl SoftmaxKer<f32> for SoftmaxF32x8n {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn alignment_bytes() -> usize {
+        32
+    }
+    #[inline(never)]
+    fn run(buf: &mut [f32], max: f32) -> f32 {
+        unsafe { fma_softmax_f32_8n(buf.as_mut_ptr(), buf.len(), max) }
+    }
+}
+
+#[cfg(test)]
+mod test_simd {
+    softmax_frame_tests!(
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma"),
+        crate::x86_64_fma::softmax::SoftmaxF32x8n
+    );
+}
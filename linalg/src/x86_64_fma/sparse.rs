@@ -0,0 +1,41 @@
+use crate::frame::sparse::*;
+
+extern "C" {
+    fn fma_sparse_f32_4x16(spec: *const SparseKerSpec<f32>) -> isize;
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SparseF32x4x16;
+
+impl SparseMatMulKer<f32> for SparseF32x4x16 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn mr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        16
+    }
+    fn alignment_bytes_packed_b() -> usize {
+        32
+    }
+    fn end_padding_packed_b() -> usize {
+        0
+    }
+    #[inline(never)]
+    fn kernel(spec: &SparseKerSpec<f32>) -> isize {
+        unsafe { fma_sparse_f32_4x16(spec) }
+    }
+}
+
+#[cfg(test)]
+mod test_simd {
+    sparse_mmm_frame_tests!(
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma"),
+        crate::x86_64_fma::sparse::SparseF32x4x16
+    );
+}
@@ -0,0 +1,35 @@
+use crate::frame::tanh::*;
+
+extern "C" {
+    fn fma_tanh_f32_8n(ptr: *mut f32, count: usize);
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TanhF32x8n;
+
+impl TanhKer<f32> for TanhF32x8n {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn alignment_bytes() -> usize {
+        32
+    }
+    #[inline(never)]
+    fn run(buf: &mut [f32]) {
+        unsafe { fma_tanh_f32_8n(buf.as_mut_ptr(), buf.len()) }
+    }
+}
+
+#[cfg(test)]
+mod test_simd {
+    tanh_frame_tests!(
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma"),
+        crate::x86_64_fma::tanh::TanhF32x8n
+    );
+}
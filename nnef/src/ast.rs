@@ -7,6 +7,80 @@ pub mod parse;
 pub struct ProtoModel {
     pub doc: Document,
     pub tensors: Vec<(String, Arc<Tensor>)>,
+    pub quantization: Option<HashMap<String, QuantFormat>>,
+}
+
+/// Quantization of a tensor, as described in a `graph.quant` file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QuantFormat {
+    /// `real = scale * (quantized - zero_point)`
+    Linear { zero_point: i32, scale: f32, bits: usize, signed: bool },
+}
+
+impl QuantFormat {
+    pub fn linear(datum_type: DatumType, zero_point: i32, scale: f32) -> TractResult<QuantFormat> {
+        let format = QuantFormat::Linear {
+            zero_point,
+            scale,
+            bits: datum_type.size_of() * 8,
+            signed: datum_type.is_signed(),
+        };
+        if format.datum_type()? != datum_type {
+            bail!("Unsupported quantized type {:?}", datum_type)
+        }
+        Ok(format)
+    }
+
+    /// Quantization carried by a quantized datum type.
+    pub fn from_datum_type(datum_type: DatumType) -> Option<QuantFormat> {
+        let zps = datum_type.zp_scale()?;
+        Some(QuantFormat::Linear {
+            zero_point: zps.zero_point,
+            scale: zps.scale,
+            bits: 8,
+            signed: datum_type.unquantized().is_signed(),
+        })
+    }
+
+    pub fn datum_type(&self) -> TractResult<DatumType> {
+        match self {
+            QuantFormat::Linear { bits: 8, signed: true, .. } => Ok(DatumType::I8),
+            QuantFormat::Linear { bits: 8, signed: false, .. } => Ok(DatumType::U8),
+            QuantFormat::Linear { bits: 32, signed: true, .. } => Ok(DatumType::I32),
+            _ => bail!("Unsupported quantization {:?}", self),
+        }
+    }
+
+    /// Type of the tensors carrying this quantization: 8-bit integers get a
+    /// quantized type, wider ones keep their storage type.
+    pub fn quantized_datum_type(&self) -> TractResult<DatumType> {
+        let zps = ZpScale::new(self.zero_point(), self.scale());
+        match self.datum_type()? {
+            DatumType::I8 => Ok(DatumType::QI8(zps)),
+            DatumType::U8 => Ok(DatumType::QU8(zps)),
+            dt => Ok(dt),
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        match self {
+            QuantFormat::Linear { scale, .. } => *scale,
+        }
+    }
+
+    pub fn zero_point(&self) -> i32 {
+        match self {
+            QuantFormat::Linear { zero_point, .. } => *zero_point,
+        }
+    }
+
+    /// Zero point as a scalar of the quantized type.
+    pub fn zero_point_tensor(&self) -> TractResult<Arc<Tensor>> {
+        Ok(tensor0(self.zero_point())
+            .cast_to_dt(self.datum_type()?)?
+            .into_owned()
+            .into_arc_tensor())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -35,6 +35,20 @@ impl<'a> Dumper<'a> {
         Ok(())
     }
 
+    pub fn quantization(&mut self, quantization: &HashMap<String, QuantFormat>) -> TractResult<()> {
+        for (id, format) in quantization.iter().sorted_by_key(|(id, _)| id.as_str()) {
+            write!(self.w, "{:?}: ", id)?;
+            match format {
+                QuantFormat::Linear { zero_point, scale, bits, signed } => writeln!(
+                    self.w,
+                    "zero_point_linear_quantize(zero_point = {}, scale = {:?}, bits = {}, signed = {}, symmetric = false);",
+                    zero_point, scale, bits, signed
+                )?,
+            }
+        }
+        Ok(())
+    }
+
     pub fn fragments(&mut self, defs: &[FragmentDef]) -> TractResult<()> {
         for fragment_def in defs.iter().sorted_by_key(|frag| &frag.decl.id) {
             self.fragment_def(&fragment_def)?
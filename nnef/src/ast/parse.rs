@@ -31,6 +31,21 @@ pub fn parse_parameters(doc: &str) -> TractResult<Vec<Parameter>> {
     all_consuming(parameter_list)(doc).map(|pair| pair.1).map_err(translate_error)
 }
 
+#[inline(never)]
+pub fn parse_quantization(doc: &str) -> TractResult<Vec<(String, QuantFormat)>> {
+    let entries = all_consuming(terminated(many0(quantization), space_and_comments))(doc)
+        .map(|pair| pair.1)
+        .map_err(translate_error)?;
+    entries
+        .into_iter()
+        .map(|(id, inv)| {
+            let format = quant_format(&inv)
+                .with_context(|| format!("Interpreting quantization for {:?}", id))?;
+            Ok((id, format))
+        })
+        .collect()
+}
+
 // <document> ::= <version> <extension>* <fragmentdefinition>* <graph-definition>
 fn document(i: &str) -> IResult<&str, Document> {
     map(
@@ -344,6 +359,65 @@ fn loop_iters(i: &str) -> IResult<&str, Vec<(String, RValue)>> {
     separated_list(stag(","), separated_pair(identifier, stag("in"), rvalue))(i)
 }
 
+// QUANTIZATION
+
+// <quantization> ::= <string-literal> ":" <invocation> ";"
+fn quantization(i: &str) -> IResult<&str, (String, Invocation)> {
+    spaced(pair(terminated(spaced(string_literal), stag(":")), terminated(invocation, stag(";"))))(
+        i,
+    )
+}
+
+fn quant_format(inv: &Invocation) -> TractResult<QuantFormat> {
+    fn literal_arg<'i>(inv: &'i Invocation, name: &str) -> TractResult<&'i Literal> {
+        match inv.arguments.iter().find(|arg| arg.id.as_deref() == Some(name)) {
+            Some(Argument { rvalue: RValue::Literal(lit), .. }) => Ok(lit),
+            _ => bail!("Expected a literal argument `{}' in {:?}", name, inv),
+        }
+    }
+    fn numeric_arg(inv: &Invocation, name: &str) -> TractResult<f64> {
+        match literal_arg(inv, name)? {
+            Literal::Numeric(n) => Ok(n.parse::<f64>()?),
+            lit => bail!("Expected a numeric value for `{}', got {:?}", name, lit),
+        }
+    }
+    fn logical_arg(inv: &Invocation, name: &str) -> TractResult<bool> {
+        match literal_arg(inv, name)? {
+            Literal::Logical(b) => Ok(*b),
+            lit => bail!("Expected a logical value for `{}', got {:?}", name, lit),
+        }
+    }
+    let bits = numeric_arg(inv, "bits")? as usize;
+    match &*inv.id {
+        "zero_point_linear_quantize" => Ok(QuantFormat::Linear {
+            zero_point: numeric_arg(inv, "zero_point")? as i32,
+            scale: numeric_arg(inv, "scale")? as f32,
+            bits,
+            signed: logical_arg(inv, "signed")?,
+        }),
+        "linear_quantize" | "min_max_linear_quantize" => {
+            let (signed, symmetric) = if inv.id == "linear_quantize" {
+                (false, false)
+            } else {
+                (logical_arg(inv, "signed")?, logical_arg(inv, "symmetric")?)
+            };
+            let min = numeric_arg(inv, "min")?;
+            let max = numeric_arg(inv, "max")?;
+            let range = (1u64 << bits) as f64 - 1.0 - (signed && symmetric) as usize as f64;
+            let offset =
+                if signed { (1u64 << (bits - 1)) as f64 - symmetric as usize as f64 } else { 0.0 };
+            let scale = (max - min) / range;
+            Ok(QuantFormat::Linear {
+                zero_point: ((-min / scale).round() - offset) as i32,
+                scale: scale as f32,
+                bits,
+                signed,
+            })
+        }
+        _ => bail!("Unsupported quantization {}", inv.id),
+    }
+}
+
 // TERMINALS
 
 // identifier: identifiers must consist of the following ASCII characters: _, [a-z], [A-Z], [0-9].
@@ -802,6 +876,27 @@ graph y( x, s, bias ) -> ( y ) {
         );
     }
 
+    #[test]
+    fn test_quantization() {
+        let quant = parse_quantization(
+            "\"input\": zero_point_linear_quantize(zero_point = -3, scale = 0.25, bits = 8, signed = true, symmetric = false);\n\
+             # comment\n\
+             'weights': linear_quantize(min = -1.0, max = 1.0, bits = 8);\n",
+        )
+        .unwrap();
+        assert_eq!(
+            quant[0],
+            (
+                "input".to_string(),
+                QuantFormat::Linear { zero_point: -3, scale: 0.25, bits: 8, signed: true }
+            )
+        );
+        assert_eq!(quant[1].0, "weights");
+        assert_eq!(quant[1].1.datum_type().unwrap(), DatumType::U8);
+        assert_eq!(quant[1].1.zero_point(), 128);
+        assert!((quant[1].1.scale() - 2.0 / 255.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_fragments() {
         p(
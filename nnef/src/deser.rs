@@ -138,6 +138,14 @@ impl<'mb> ModelBuilder<'mb> {
         ))
     }
 
+    /// Quantization annotation of the graph-level tensor being wired, if any.
+    pub fn output_quantization(&self) -> Option<QuantFormat> {
+        if self.naming_scopes.len() != 1 {
+            return None;
+        }
+        self.proto_model.quantization.as_ref()?.get(&self.naming_scopes[0]).cloned()
+    }
+
     pub fn wire(
         &mut self,
         op: impl Into<Box<dyn TypedOp>>,
@@ -6,6 +6,7 @@ mod cast;
 mod downsample;
 mod gather;
 mod one_hot;
+mod quant;
 mod reduce;
 mod scan;
 mod source;
@@ -24,6 +25,9 @@ pub fn register(registry: &mut Registry) {
 
     registry.register_unit_element_wise("tract_core_round_even", &ops::math::RoundHalfToEven {});
 
+    registry.register_unit_element_wise("tract_core_hard_swish", &ops::nn::HardSwish {});
+    registry.register_unit_element_wise("tract_core_gelu", &ops::nn::Gelu {});
+
     registry.register_binary("tract_core_xor", &ops::logic::Xor {});
 
     broadcast::register(registry);
@@ -31,6 +35,7 @@ pub fn register(registry: &mut Registry) {
     downsample::register(registry);
     gather::register(registry);
     one_hot::register(registry);
+    quant::register(registry);
     reduce::register(registry);
     scan::register(registry);
     source::register(registry);
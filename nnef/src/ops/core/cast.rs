@@ -1,3 +1,4 @@
+use crate::ast::QuantFormat;
 use crate::internal::*;
 use crate::ser::*;
 use tract_core::ops::cast::Cast;
@@ -13,25 +14,45 @@ pub fn register(registry: &mut Registry) {
     );
 }
 
-fn cast_parameters() -> Vec<Parameter> {
+pub fn cast_parameters() -> Vec<Parameter> {
     vec![TypeName::Scalar.tensor().named("input"), TypeName::String.named("to")]
 }
 
-fn cast_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
-    let op = node.op_as::<ElementWiseOp>().unwrap().0.downcast_ref::<Cast>().unwrap();
+pub fn cast_invocation(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    to: DatumType,
+    quantization: Option<QuantFormat>,
+) -> TractResult<Option<Arc<RValue>>> {
     let input = ast.mapping[&node.inputs[0]].clone();
-    Ok(Some(invocation(
+    let cast = invocation(
         "tract_core_cast",
         &[input],
-        &[("to", string(format!("{:?}", op.to).to_lowercase()))],
-    )))
+        &[("to", string(format!("{:?}", to.unquantized()).to_lowercase()))],
+    );
+    if let Some(quantization) = quantization {
+        let cast = ast.force_assign(&node.name, &cast);
+        ast.quantize(&cast, quantization)?;
+        return Ok(Some(cast));
+    }
+    Ok(Some(cast))
+}
+
+fn cast_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ElementWiseOp>().unwrap().0.downcast_ref::<Cast>().unwrap();
+    cast_invocation(ast, node, op.to, QuantFormat::from_datum_type(op.to))
 }
 
-fn cast_load(
+pub fn cast_load(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
     let input = invocation.named_arg_as(builder, "input")?;
-    let to = invocation.named_arg_as::<String>(builder, "to")?.parse()?;
+    let mut to: DatumType = invocation.named_arg_as::<String>(builder, "to")?.parse()?;
+    if let Some(q) = builder.output_quantization() {
+        if q.datum_type()? == to {
+            to = q.quantized_datum_type()?;
+        }
+    }
     builder.wire(ElementWiseOp(Box::new(Cast { to })), &[input])
 }
@@ -0,0 +1,77 @@
+use super::cast::{cast_invocation, cast_load, cast_parameters};
+use crate::ast::QuantFormat;
+use crate::internal::*;
+use crate::ser::*;
+use tract_core::ops::cast::cast;
+use tract_core::ops::element_wise::ElementWiseOp;
+use tract_core::ops::quant::{DequantizeLinearF32, QuantizeLinearI8, QuantizeLinearU8};
+
+pub fn register(registry: &mut Registry) {
+    // quantization is dumped as a cast to a storage type, annotated in graph.quant
+    registry.register_element_wise(
+        "tract_core_cast",
+        TypeId::of::<QuantizeLinearI8>(),
+        quantize_dump,
+        cast_parameters(),
+        cast_load,
+    );
+    registry.register_element_wise(
+        "tract_core_cast",
+        TypeId::of::<QuantizeLinearU8>(),
+        quantize_dump,
+        cast_parameters(),
+        cast_load,
+    );
+    registry.register_dumper(TypeId::of::<DequantizeLinearF32>(), dequantize_dump);
+    registry.register_primitive(
+        "tract_core_dequantize_linear",
+        &dequantize_parameters(),
+        dequantize_load,
+    );
+}
+
+fn quantize_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ElementWiseOp>().unwrap();
+    let (scale, zero_point, dt) = if let Some(op) = op.0.downcast_ref::<QuantizeLinearI8>() {
+        (op.scale, op.zero_point as i32, i8::datum_type())
+    } else if let Some(op) = op.0.downcast_ref::<QuantizeLinearU8>() {
+        (op.scale, op.zero_point as i32, u8::datum_type())
+    } else {
+        return Ok(None);
+    };
+    let quantization = QuantFormat::linear(dt, zero_point, scale.recip())?;
+    cast_invocation(ast, node, dt, Some(quantization))
+}
+
+fn dequantize_parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::Integer.tensor().named("input"),
+        TypeName::Scalar.named("scale"),
+        TypeName::Integer.named("zero_point"),
+    ]
+}
+
+fn dequantize_dump(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<DequantizeLinearF32>().unwrap();
+    let input = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation(
+        "tract_core_dequantize_linear",
+        &[input],
+        &[("scale", numeric(op.scale)), ("zero_point", numeric(op.zero_point))],
+    )))
+}
+
+fn dequantize_load(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let mut input = invocation.named_arg_as(builder, "input")?;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    let zero_point: i64 = invocation.named_arg_as(builder, "zero_point")?;
+    // the scale and zero point of the op win over the ones of a quantized input type
+    let input_dt = builder.model.outlet_fact(input)?.datum_type;
+    if input_dt.is_quantized() {
+        input = builder.wire(cast(input_dt.unquantized()), &[input])?[0];
+    }
+    builder.wire(DequantizeLinearF32::new(scale, zero_point as i32), &[input])
+}
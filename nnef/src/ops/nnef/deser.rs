@@ -13,7 +13,9 @@ pub fn external(
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
     let type_name = invocation.invocation.generic_type_name.unwrap_or(TypeName::Scalar);
-    let dt = if type_name == TypeName::Scalar {
+    let dt = if let Some(q) = builder.output_quantization() {
+        q.quantized_datum_type()?
+    } else if type_name == TypeName::Scalar {
         f32::datum_type()
     } else if type_name == TypeName::Logical {
         bool::datum_type()
@@ -47,10 +49,15 @@ pub fn variable(
             shape
         );
     }
-    let tensor = if tensor.datum_type() == f32::datum_type() {
+    let dt = if let Some(q) = builder.output_quantization() {
+        q.quantized_datum_type()?
+    } else {
+        f32::datum_type()
+    };
+    let tensor = if tensor.datum_type() == dt {
         tensor.clone()
     } else {
-        tensor.cast_to::<f32>()?.into_owned().into_arc_tensor()
+        tensor.cast_to_dt(dt)?.into_owned().into_arc_tensor()
     };
     builder.wire(tract_core::ops::konst::Const::new(tensor), &[])
 }
@@ -205,7 +212,13 @@ pub fn conv(
     use ops::cnn::{PaddingSpec, PoolSpec};
     use ops::nn::DataFormat;
     let input: OutletId = invocation.named_arg_as(builder, "input")?;
-    let kernel: Arc<Tensor> = invocation.named_arg_as(builder, "filter")?;
+    let filter: OutletId = invocation.named_arg_as(builder, "filter")?;
+    let kernel = builder
+        .model
+        .outlet_fact(filter)?
+        .konst
+        .clone()
+        .ok_or_else(|| format_err!("Convolution filter must be a constant"))?;
     let input_fact = builder.model.outlet_fact(input)?.clone();
     if input_fact.rank() != kernel.rank() {
         bail!(
@@ -255,7 +268,135 @@ pub fn conv(
 
     let border: String = invocation.named_arg_as(builder, "border")?;
     assert_eq!(border, "constant");
-    let op = ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel.clone(), group, bias, None);
+    let q_params = quantization_params(builder, filter, input)?;
+    if let (Some(_), Some(bias)) = (&q_params, &bias) {
+        if bias.datum_type() != i32::datum_type() {
+            bail!("Quantized convolution expects a bias quantized to 32-bit integers");
+        }
+    }
+    let op = ConvUnary::new(pool_spec, KernelFormat::OIHW, kernel.clone(), group, bias, q_params);
+    builder.wire(op, &[input])
+}
+
+/*
+fragment deconv( input: tensor<scalar>, filter: tensor<scalar>,
+bias: tensor<scalar> = 0.0, border: string = 'constant',
+padding: (integer,integer)[] = [], stride: integer[] = [],
+dilation: integer[] = [], output_shape: integer[] = [], groups: integer = 1 )
+-> ( output: tensor<scalar> );
+*/
+
+pub fn deconv(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    use ops::cnn::{DeconvUnary, PaddingSpec, PoolSpec};
+    use ops::nn::DataFormat;
+    let input: OutletId = invocation.named_arg_as(builder, "input")?;
+    let kernel: Arc<Tensor> = invocation
+        .named_arg_as(builder, "filter")
+        .context("Deconvolution filter must be a constant")?;
+    let input_fact = builder.model.outlet_fact(input)?.clone();
+    if input_fact.rank() != kernel.rank() {
+        bail!(
+            "Deconvolution input expected as NCHW, filter as IOHW. Got {:?} and {:?}.",
+            input_fact,
+            kernel
+        );
+    }
+    if input_fact.shape[1] != kernel.shape()[0].to_dim() {
+        bail!(
+            "Deconvolution input and kernel channels (first axis of the kernel) must match. Got {:?} and {:?}.",
+            input_fact,
+            kernel
+        );
+    }
+    let mut group = invocation.named_arg_as(builder, "groups")?;
+    if group == 0 {
+        group = kernel.shape()[0]
+    }
+    let spatial_rank = kernel.rank() - 2;
+    let dilation: TVec<usize> = invocation.named_arg_as(builder, "dilation")?;
+    if dilation.len() != 0 && dilation.len() != spatial_rank {
+        bail!("Deconvolution dilation only apply to spatial dimensions, so it should be of rank {}. Got {:?}", spatial_rank, dilation)
+    }
+    let stride: TVec<usize> = invocation.named_arg_as(builder, "stride")?;
+    if stride.len() != 0 && stride.len() != spatial_rank {
+        bail!("Deconvolution stride only apply to spatial dimensions, so it should be of rank {}. Got {:?}", spatial_rank, stride)
+    }
+    let padding: TVec<TVec<usize>> = invocation.named_arg_as(builder, "padding")?;
+    let output_shape: TVec<usize> = invocation.named_arg_as(builder, "output_shape")?;
+    let (padding, adjustments) = if output_shape.len() == 0 {
+        let padding = if padding.len() == 0 {
+            PaddingSpec::SameUpper
+        } else {
+            let before = padding.iter().map(|p| p[0]).collect();
+            let after = padding.iter().map(|p| p[1]).collect();
+            PaddingSpec::Explicit(before, after, false)
+        };
+        (padding, tvec!())
+    } else {
+        // translate output_shape into explicit padding and adjustments
+        if output_shape.len() != kernel.rank() {
+            bail!(
+                "Deconvolution output_shape must be of rank {}. Got {:?}",
+                kernel.rank(),
+                output_shape
+            )
+        }
+        let input_shape = input_fact
+            .shape
+            .as_concrete()
+            .context("Deconvolution with an explicit output_shape requires a known input shape")?;
+        let mut before = tvec!();
+        let mut after = tvec!();
+        let mut adjustments = tvec!();
+        for axis in 0..spatial_rank {
+            let field =
+                (kernel.shape()[axis + 2] - 1) * dilation.get(axis).cloned().unwrap_or(1) + 1;
+            let full = (input_shape[axis + 2] - 1) * stride.get(axis).cloned().unwrap_or(1) + field;
+            let output = output_shape[axis + 2];
+            let (bef, aft) = if padding.len() == 0 {
+                let total = full.saturating_sub(output);
+                (total / 2, total - total / 2)
+            } else {
+                (padding[axis][0], padding[axis][1])
+            };
+            let cropped = full.saturating_sub(bef + aft);
+            if output < cropped {
+                bail!("Deconvolution output_shape {:?} inconsistent with padding", output_shape)
+            }
+            before.push(bef);
+            after.push(aft);
+            adjustments.push(output - cropped);
+        }
+        (PaddingSpec::Explicit(before, after, false), adjustments)
+    };
+    let pool_spec = PoolSpec::new(
+        DataFormat::NCHW,
+        kernel.shape()[2..].into(),
+        padding,
+        if dilation.len() > 0 { Some(dilation) } else { None },
+        if stride.len() > 0 { Some(stride) } else { None },
+        None,
+    );
+    let output_channels = kernel.shape()[1] * group;
+    let bias: Arc<Tensor> = invocation.named_arg_as(builder, "bias")?;
+    let bias: Option<Arc<Tensor>> = if bias.is_uniform()? {
+        let bias = bias.cast_to_scalar::<f32>()?;
+        if bias == 0.0 {
+            None
+        } else {
+            Some(tensor1(&vec![bias; output_channels]).into_arc_tensor())
+        }
+    } else {
+        Some(bias.into_tensor().into_shape(&[output_channels])?.into_arc_tensor())
+    };
+    let border: String = invocation.named_arg_as(builder, "border")?;
+    if border != "constant" {
+        bail!("Deconvolution only supports constant border, got {}", border)
+    }
+    let op = DeconvUnary::new(pool_spec, kernel, bias, adjustments, group);
     builder.wire(op, &[input])
 }
 
@@ -360,6 +501,7 @@ pub fn sum_pool(
  *   fragment max_reduce( input: tensor<scalar>, axes: integer[] ) -> ( output: tensor<scalar> );
  *   and also min, argmax, armmin, any, all
  */
+
 pub fn reduce(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
@@ -373,14 +515,24 @@ pub fn reduce(
         "max" => ops::nn::Reducer::Max,
         "argmin" => ops::nn::Reducer::ArgMin(false),
         "argmax" => ops::nn::Reducer::ArgMax(false),
+        "any" => ops::nn::Reducer::Any,
+        "all" => ops::nn::Reducer::All,
         _ => bail!("unsupported reducer: {}", invocation.invocation.id),
     };
     let wire = builder.wire(ops::nn::Reduce::new(axes.clone(), reducer), &[input])?;
     if reducer_name != "sum" || !invocation.named_arg_as(builder, "normalize")? {
         return Ok(wire);
     }
+    Ok(tvec!(normalize_sum(builder, input, wire[0], &axes)?))
+}
 
-    let fact = builder.model.outlet_fact(wire[0])?;
+fn normalize_sum(
+    builder: &mut ModelBuilder,
+    input: OutletId,
+    sum: OutletId,
+    axes: &[usize],
+) -> TractResult<OutletId> {
+    let fact = builder.model.outlet_fact(sum)?;
     let input_shape = &builder.model.outlet_fact(input)?.shape;
     let cardinality: TDim = axes.iter().map(|ax| &input_shape[*ax]).maybe_product()?;
     if let Ok(c) = cardinality.to_isize() {
@@ -389,24 +541,219 @@ pub fn reduce(
                 .cast_to_dt(fact.datum_type)?
                 .into_owned()
                 .broadcast_into_rank(input_shape.rank())?;
-            return builder.wire(ops::math::mul::unary(cardinality.into_arc_tensor()), &wire);
+            return Ok(
+                builder.wire(ops::math::mul::unary(cardinality.into_arc_tensor()), &[sum])?[0]
+            );
         }
     }
     bail!("Normalization only works with float items and known dimensions");
 }
 
+fn mean_reduce(
+    builder: &mut ModelBuilder,
+    input: OutletId,
+    axes: &[usize],
+) -> TractResult<OutletId> {
+    let sum = builder.wire(ops::nn::Reduce::new(axes.into(), ops::nn::Reducer::Sum), &[input])?;
+    normalize_sum(builder, input, sum[0], axes)
+}
+
+fn wire_binary(
+    builder: &mut ModelBuilder,
+    op: ops::binary::TypedBinOp,
+    a: OutletId,
+    b: OutletId,
+) -> TractResult<OutletId> {
+    let inputs = crate::registry::multicast(builder, &[a, b])?;
+    Ok(builder.wire(op, &inputs)?[0])
+}
+
+/*
+ * fragment moments( input: tensor<scalar>, axes: integer[] )
+ * -> ( mean: tensor<scalar>, variance: tensor<scalar> )
+ */
+
+pub fn moments(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let axes: TVec<usize> = invocation.named_arg_as(builder, "axes")?;
+    let mean = mean_reduce(builder, input, &axes)?;
+    let centered = builder.wire(ops::math::sub::bin_typed(), &[input, mean])?;
+    let square = builder.wire(ops::math::square(), &centered)?;
+    let variance = mean_reduce(builder, square[0], &axes)?;
+    Ok(tvec!(mean, variance))
+}
+
+/*
+ * fragment softmax( x: tensor<scalar>, axes: integer[] = [1] ) -> ( y: tensor<scalar> )
+ */
+
+pub fn softmax(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let x = invocation.named_arg_as(builder, "x")?;
+    let axes: TVec<usize> = invocation.named_arg_as(builder, "axes")?;
+    builder.wire(ops::nn::Softmax::new(axes, false), &[x])
+}
+
+// fragment leaky_relu( x: tensor<scalar>, alpha: scalar ) -> ( y: tensor<scalar> )
+pub fn leaky_relu(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let x = invocation.named_arg_as(builder, "x")?;
+    let alpha = invocation.named_arg_as(builder, "alpha")?;
+    builder.wire(ops::nn::leaky_relu(alpha), &[x])
+}
+
+/*
+ * fragment batch_normalization( input: tensor<scalar>, mean: tensor<scalar>,
+ *     variance: tensor<scalar>, offset: tensor<scalar>, scale: tensor<scalar>, epsilon: scalar )
+ * -> ( output: tensor<scalar> )
+ *
+ * Rewritten as input * factor + shift so that constant statistics fold into
+ * two element-wise operations.
+ */
+
+pub fn batch_normalization(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    use ops::math;
+    let input = invocation.named_arg_as(builder, "input")?;
+    let mean = invocation.named_arg_as(builder, "mean")?;
+    let variance = invocation.named_arg_as(builder, "variance")?;
+    let offset = invocation.named_arg_as(builder, "offset")?;
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    let epsilon = invocation.named_arg_as(builder, "epsilon")?;
+    let variance = wire_binary(builder, math::add::bin_typed(), variance, epsilon)?;
+    let std = builder.wire(math::sqrt(), &[variance])?[0];
+    let factor = wire_binary(builder, math::div::bin_typed(), scale, std)?;
+    let shift = wire_binary(builder, math::mul::bin_typed(), mean, factor)?;
+    let shift = wire_binary(builder, math::sub::bin_typed(), offset, shift)?;
+    let scaled = wire_binary(builder, math::mul::bin_typed(), input, factor)?;
+    Ok(tvec!(wire_binary(builder, math::add::bin_typed(), scaled, shift)?))
+}
+
+/*
+ * fragment split<?>( value: tensor<?>, axis: integer, ratios: integer[] )
+ * -> ( values: tensor<?>[] );
+ */
+
+pub fn split(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let value = invocation.named_arg_as(builder, "value")?;
+    let axis: usize = invocation.named_arg_as(builder, "axis")?;
+    let ratios: TVec<usize> = invocation.named_arg_as(builder, "ratios")?;
+    let total: usize = ratios.iter().sum();
+    if total == 0 {
+        bail!("split ratios must not all be zero, got {:?}", ratios)
+    }
+    let dim = builder.model.outlet_fact(value)?.shape[axis].clone();
+    let mut start = 0;
+    let mut outputs = tvec!();
+    for ratio in ratios {
+        let end = start + ratio;
+        let slice =
+            ops::array::Slice::new(axis, dim.clone() * start / total, dim.clone() * end / total);
+        outputs.push(builder.wire(slice, &[value])?[0]);
+        start = end;
+    }
+    Ok(outputs)
+}
+
+/*
+ * fragment stack<?>( values: tensor<?>[], axis: integer ) -> ( value: tensor<?> );
+ */
+
+pub fn stack(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let values: TVec<OutletId> = invocation.named_arg_as(builder, "values")?;
+    let axis: usize = invocation.named_arg_as(builder, "axis")?;
+    let mut expanded = tvec!();
+    for value in values {
+        expanded.push(builder.wire(AxisOp::Add(axis), &[value])?[0]);
+    }
+    builder.wire(ops::array::TypedConcat::concat_vars(axis, expanded.len()), &expanded)
+}
+
+/*
+ * fragment unstack<?>( value: tensor<?>, axis: integer ) -> ( values: tensor<?>[] );
+ */
+
+pub fn unstack(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let value = invocation.named_arg_as(builder, "value")?;
+    let axis: usize = invocation.named_arg_as(builder, "axis")?;
+    let dim = builder.model.outlet_fact(value)?.shape[axis]
+        .to_usize()
+        .context("unstack requires a known dimension")?;
+    let mut outputs = tvec!();
+    for i in 0..dim {
+        let slice = builder.wire(ops::array::Slice::new(axis, i, i + 1), &[value])?;
+        outputs.push(builder.wire(AxisOp::Rm(axis), &slice)?[0]);
+    }
+    Ok(outputs)
+}
+
 /*
  * fragment matmul( A: tensor<scalar>, B: tensor<scalar>, transposeA: logical = false, transposeB: logical = false ) -> ( C: tensor<scalar> );
  */
+
 pub fn matmul(
     builder: &mut ModelBuilder,
     invocation: &ResolvedInvocation,
 ) -> TractResult<TVec<OutletId>> {
     let a = invocation.named_arg_as(builder, "A")?;
     let b = invocation.named_arg_as(builder, "B")?;
-    let a_trans = invocation.named_arg_as(builder, "transposeA")?;
-    let b_trans = invocation.named_arg_as(builder, "transposeB")?;
-    builder.wire(ops::matmul::MatMul { a_trans, b_trans, c_trans: false, q_params: None }, &[a, b])
+    let a_trans: bool = invocation.named_arg_as(builder, "transposeA")?;
+    let b_trans: bool = invocation.named_arg_as(builder, "transposeB")?;
+    let a_konst = builder.model.outlet_fact(a)?.konst.clone();
+    let b_konst = builder.model.outlet_fact(b)?.konst.clone();
+    match (a_konst, b_konst, quantization_params(builder, a, b)?) {
+        (_, _, None) => builder.wire(
+            ops::matmul::MatMul { a_trans, b_trans, c_trans: false, q_params: None },
+            &[a, b],
+        ),
+        (Some(a_konst), None, q_params) => {
+            let op = ops::matmul::MatMulUnary::new(a_konst, a_trans, b_trans, false, q_params);
+            builder.wire(op, &[b])
+        }
+        (None, Some(b_konst), q_params) => {
+            // C = A.B is computed as Ct = Bt.At
+            let op = ops::matmul::MatMulUnary::new(b_konst, !b_trans, !a_trans, true, q_params);
+            builder.wire(op, &[a])
+        }
+        _ => bail!("Quantized products expect exactly one constant input"),
+    }
+}
+
+/// Builds the QParams for a product of `a` and `b`: inputs with quantized
+/// types must be multiplied to the type of the `graph.quant` annotation of
+/// the output being wired.
+fn quantization_params(
+    builder: &ModelBuilder,
+    a: OutletId,
+    b: OutletId,
+) -> TractResult<Option<ops::quant::QParams>> {
+    let a_dt = builder.model.outlet_fact(a)?.datum_type;
+    let b_dt = builder.model.outlet_fact(b)?.datum_type;
+    match (a_dt.is_quantized(), b_dt.is_quantized(), builder.output_quantization()) {
+        (false, false, None) => Ok(None),
+        (true, true, Some(c_quant)) => {
+            Ok(Some(ops::quant::QParams::new(c_quant.quantized_datum_type()?)))
+        }
+        _ => bail!("Quantized products expect quantization for both inputs and the output"),
+    }
 }
 
 /*
@@ -427,3 +774,190 @@ pub fn select(
     let inputs = crate::registry::multicast(builder, &[cond, true_value, false_value])?;
     builder.wire(ops::logic::Iff {}, &inputs)
 }
+
+/*
+ * fragment area_downsample( input: tensor<scalar>, factor: integer[] ) -> ( output: tensor<scalar> )
+ */
+
+pub fn area_downsample(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    use ops::cnn::{PaddingSpec, PoolSpec, SumPool};
+    use ops::nn::DataFormat;
+    let input = invocation.named_arg_as(builder, "input")?;
+    let factor: TVec<usize> = invocation.named_arg_as(builder, "factor")?;
+    let pool_spec = PoolSpec::new(
+        DataFormat::NCHW,
+        factor.clone(),
+        PaddingSpec::Valid,
+        None,
+        Some(factor),
+        None,
+    );
+    builder.wire(SumPool::new(pool_spec, false, true), &[input])
+}
+
+/*
+ * fragment nearest_upsample( input: tensor<scalar>, factor: integer[] ) -> ( output: tensor<scalar> )
+ * fragment multilinear_upsample( input: tensor<scalar>, factor: integer[],
+ *     method: string = 'symmetric', border: string = 'replicate' ) -> ( output: tensor<scalar> );
+ */
+
+pub fn upsample(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    use ops::cnn::{Upsample, UpsampleMethod};
+    let input = invocation.named_arg_as(builder, "input")?;
+    let factor: TVec<usize> = invocation.named_arg_as(builder, "factor")?;
+    let mut factors = tvec!(1, 1);
+    factors.extend(factor.into_iter());
+    let op = if invocation.invocation.id == "nearest_upsample" {
+        Upsample::new(factors, UpsampleMethod::Nearest, true)
+    } else {
+        let method: String = invocation.named_arg_as(builder, "method")?;
+        let method = match &*method {
+            "symmetric" => UpsampleMethod::LinearSymmetric,
+            "asymmetric" => UpsampleMethod::LinearAsymmetric,
+            "aligned" => UpsampleMethod::LinearAligned,
+            _ => bail!("Unsupported multilinear_upsample method: {}", method),
+        };
+        let border: String = invocation.named_arg_as(builder, "border")?;
+        let replicate_border = match &*border {
+            "replicate" => true,
+            "constant" => false,
+            _ => bail!("Unsupported multilinear_upsample border: {}", border),
+        };
+        Upsample::new(factors, method, replicate_border)
+    };
+    builder.wire(op, &[input])
+}
+
+/*
+ * fragment local_response_normalization( input: tensor<scalar>, size: integer[],
+ *     alpha: scalar = 1.0, beta: scalar = 0.5, bias: scalar = 1.0 ) -> ( output: tensor<scalar> )
+ *
+ * Normalization across channels maps to Lrn, other windows use the fragment body.
+ */
+
+pub fn local_response_normalization(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let size: TVec<usize> = invocation.named_arg_as(builder, "size")?;
+    if size.len() >= 2 && size.iter().enumerate().all(|(axis, &s)| axis == 1 || s == 1) {
+        let alpha = invocation.named_arg_as(builder, "alpha")?;
+        let beta = invocation.named_arg_as(builder, "beta")?;
+        let bias = invocation.named_arg_as(builder, "bias")?;
+        return builder.wire(ops::nn::Lrn { alpha, beta, bias, size: size[1] }, &[input]);
+    }
+    let framework = builder.framework;
+    let fragment = framework
+        .stdlib
+        .iter()
+        .find(|f| f.decl.id == "local_response_normalization")
+        .context("local_response_normalization fragment not found")?;
+    builder
+        .wire_fragment_invocation(invocation, &fragment.decl, fragment.body.as_deref().unwrap())?
+        .to(builder)
+}
+
+/*
+ * fragment avg_roi_pool( input: tensor<scalar>, rois: tensor<scalar>,
+ *     batch_index: tensor<integer>, output_size: integer[] ) -> ( output: tensor<scalar> );
+ * fragment max_roi_pool( input: tensor<scalar>, rois: tensor<scalar>,
+ *     batch_index: tensor<integer>, output_size: integer[] ) -> ( output: tensor<scalar> );
+ */
+
+pub fn roi_pool(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let input = invocation.named_arg_as(builder, "input")?;
+    let rois = invocation.named_arg_as(builder, "rois")?;
+    let batch_index = invocation.named_arg_as(builder, "batch_index")?;
+    let output_size = invocation.named_arg_as(builder, "output_size")?;
+    let average = invocation.invocation.id == "avg_roi_pool";
+    builder.wire(ops::cnn::RoiPool::new(output_size, average), &[input, rois, batch_index])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(graph: &str, tensors: Vec<(&str, Tensor)>, inputs: TVec<Tensor>) -> TVec<Arc<Tensor>> {
+        let proto = ProtoModel {
+            doc: crate::ast::parse::parse_document(graph).unwrap(),
+            tensors: tensors
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.into_arc_tensor()))
+                .collect(),
+            quantization: None,
+        };
+        let model = crate::nnef().model_for_proto_model(&proto).unwrap();
+        model.into_runnable().unwrap().run(inputs).unwrap()
+    }
+
+    #[test]
+    fn split_stack_unstack() {
+        let graph = r#"
+version 1.0;
+
+graph net(input) -> (a, b, s)
+{
+    input = external(shape = [2, 4]);
+    [a, b] = split(input, axis = 1, ratios = [1, 3]);
+    [c, d] = unstack(input, axis = 0);
+    s = stack([d, c], axis = 0);
+}
+"#;
+        let input = tensor2(&[[1f32, 2., 3., 4.], [5., 6., 7., 8.]]);
+        let outputs = run(graph, vec![], tvec!(input));
+        assert_eq!(*outputs[0], tensor2(&[[1f32], [5.]]));
+        assert_eq!(*outputs[1], tensor2(&[[2f32, 3., 4.], [6., 7., 8.]]));
+        assert_eq!(*outputs[2], tensor2(&[[5f32, 6., 7., 8.], [1., 2., 3., 4.]]));
+    }
+
+    #[test]
+    fn area_downsample() {
+        let graph = r#"
+version 1.0;
+
+graph net(input) -> (output)
+{
+    input = external(shape = [1, 1, 2, 4]);
+    output = area_downsample(input, factor = [2, 2]);
+}
+"#;
+        let input = tensor4(&[[[[1f32, 2., 3., 4.], [5., 6., 7., 8.]]]]);
+        let outputs = run(graph, vec![], tvec!(input));
+        assert_eq!(*outputs[0], tensor4(&[[[[3.5f32, 5.5]]]]));
+    }
+
+    #[test]
+    fn batch_normalization() {
+        let graph = r#"
+version 1.0;
+
+graph net(input) -> (output)
+{
+    input = external(shape = [1, 2]);
+    mean = variable(shape = [1, 2], label = 'mean');
+    variance = variable(shape = [1, 2], label = 'variance');
+    offset = variable(shape = [1, 2], label = 'offset');
+    scale = variable(shape = [1, 2], label = 'scale');
+    output = batch_normalization(input, mean, variance, offset, scale, epsilon = 0.0);
+}
+"#;
+        let tensors = vec![
+            ("mean", tensor2(&[[1f32, 2.]])),
+            ("variance", tensor2(&[[4f32, 1.]])),
+            ("offset", tensor2(&[[0.5f32, 0.]])),
+            ("scale", tensor2(&[[1f32, 2.]])),
+        ];
+        let outputs = run(graph, tensors, tvec!(tensor2(&[[3f32, 3.]])));
+        assert_eq!(*outputs[0], tensor2(&[[1.5f32, 2.]]));
+    }
+}
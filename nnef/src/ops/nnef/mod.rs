@@ -69,6 +69,13 @@ pub fn tract_nnef() -> Registry {
 
     registry.register_unit_element_wise("tanh", &ops::math::Tanh {});
     registry.register_unit_element_wise("sigmoid", &ops::nn::Sigmoid {});
+    registry.register_element_wise(
+        "leaky_relu",
+        TypeId::of::<ops::nn::LeakyRelu>(),
+        ser::leaky_relu,
+        vec![TypeName::Scalar.tensor().named("x"), TypeName::Scalar.named("alpha")],
+        deser::leaky_relu,
+    );
 
     registry.register_unit_element_wise("not", &ops::logic::Not {});
 
@@ -97,18 +104,42 @@ pub fn tract_nnef() -> Registry {
 
     primitive(&mut registry, "conv", deser::conv);
     dumper!(ops::cnn::ConvUnary, ser::conv);
+    primitive(&mut registry, "deconv", deser::deconv);
+    dumper!(ops::cnn::DeconvUnary, ser::deconv);
 
     primitive(&mut registry, "sum_reduce", deser::reduce);
     primitive(&mut registry, "max_reduce", deser::reduce);
     primitive(&mut registry, "min_reduce", deser::reduce);
     primitive(&mut registry, "argmax_reduce", deser::reduce);
     primitive(&mut registry, "argmin_reduce", deser::reduce);
+    primitive(&mut registry, "any_reduce", deser::reduce);
+    primitive(&mut registry, "all_reduce", deser::reduce);
     dumper!(ops::nn::Reduce, ser::reduce);
+    primitive(&mut registry, "moments", deser::moments);
+    primitive(&mut registry, "softmax", deser::softmax);
+    dumper!(ops::nn::Softmax, ser::softmax);
 
     primitive(&mut registry, "max_pool_with_index", deser::max_pool_with_index);
     dumper!(ops::cnn::MaxPool, ser::max_pool);
     primitive(&mut registry, "box", deser::sum_pool);
     dumper!(ops::cnn::SumPool, ser::sum_pool);
+    primitive(&mut registry, "area_downsample", deser::area_downsample);
+
+    primitive(&mut registry, "nearest_upsample", deser::upsample);
+    primitive(&mut registry, "multilinear_upsample", deser::upsample);
+    dumper!(ops::cnn::Upsample, ser::upsample);
+
+    primitive(&mut registry, "local_response_normalization", deser::local_response_normalization);
+    dumper!(ops::nn::Lrn, ser::lrn);
+    primitive(&mut registry, "batch_normalization", deser::batch_normalization);
+
+    primitive(&mut registry, "avg_roi_pool", deser::roi_pool);
+    primitive(&mut registry, "max_roi_pool", deser::roi_pool);
+    dumper!(ops::cnn::RoiPool, ser::roi_pool);
+
+    primitive(&mut registry, "split", deser::split);
+    primitive(&mut registry, "stack", deser::stack);
+    primitive(&mut registry, "unstack", deser::unstack);
 
     for frag in stdlib {
         if frag.body.is_some() {
@@ -1,3 +1,4 @@
+use crate::ast::QuantFormat;
 use crate::internal::*;
 use crate::ser::*;
 use tract_core::ops;
@@ -8,7 +9,8 @@ pub fn source(
     _node: &TypedNode,
     op: &ops::source::TypedSource,
 ) -> TractResult<Option<Arc<RValue>>> {
-    if op.fact.datum_type == DatumType::F32 {
+    // quantized inputs are declared as scalar and typed by their quantization
+    if op.fact.datum_type == DatumType::F32 || op.fact.datum_type.is_quantized() {
         Ok(Some(invocation(
             "external",
             &[],
@@ -126,17 +128,22 @@ fn data_from_ncwh(data_format: DataFormat, geo_rank: usize, mut wire: Arc<RValue
     wire
 }
 
-fn conv_fragment<'a>(ast: &'a mut IntoAst, data_format: DataFormat, geo_rank: usize) -> String {
+fn conv_fragment<'a>(
+    ast: &'a mut IntoAst,
+    op_name: &str,
+    data_format: DataFormat,
+    geo_rank: usize,
+) -> String {
     if data_format == DataFormat::NCHW {
-        return "conv".into();
+        return op_name.into();
     }
-    let fragment_name = format!("tract_conv_{:?}_{}D", data_format, geo_rank).to_lowercase();
+    let fragment_name = format!("tract_{}_{:?}_{}D", op_name, data_format, geo_rank).to_lowercase();
     if ast.fragments.contains_key(&fragment_name) {
         return fragment_name;
     }
 
     let mut body = vec![];
-    let mut fragment = ast.framework.stdlib.iter().find(|f| f.decl.id == "conv").unwrap().clone();
+    let mut fragment = ast.framework.stdlib.iter().find(|f| f.decl.id == op_name).unwrap().clone();
     fragment.decl.id = fragment_name.clone();
 
     let mut wire = ident("input").into();
@@ -144,7 +151,7 @@ fn conv_fragment<'a>(ast: &'a mut IntoAst, data_format: DataFormat, geo_rank: us
 
     body.push(assignment("nchw", wire));
     wire = invocation(
-        "conv",
+        op_name,
         &[ident("nchw").into(), ident("filter").into(), ident("bias").into()],
         &*fragment
             .decl
@@ -178,14 +185,29 @@ pub fn conv(
         .to_usize()?;
     let co =
         op.pool_spec.data_format.shape(&node.outputs[0].fact.shape.to_tvec())?.c().to_usize()?;
-    let mut wire = ast.mapping[&node.inputs[0]].clone();
+    let input = ast.mapping[&node.inputs[0]].clone();
     let mut kernel_shape = tvec!(co, ci / op.group);
     kernel_shape.extend(op.pool_spec.kernel_shape.iter().copied());
     let mut weights = op.kernel_as_group_o_ihw()?.into_tensor();
     weights.set_shape(&*kernel_shape)?;
     let weigths = ast.konst_variable(format!("{}_weigths", node.name), &weights.into_arc_tensor());
-    wire = ast.force_assign(format!("{}_input", node.name), &wire);
-    let conv_fragment = conv_fragment(ast, op.pool_spec.data_format, op.pool_spec.rank());
+    let mut wire = ast.force_assign(format!("{}_input", node.name), &input);
+    let quantization = if let Some(q_params) = &op.q_params {
+        if op.pool_spec.data_format != DataFormat::NCHW {
+            bail!("Quantized convolutions can only be serialized in NCHW data format");
+        }
+        let input_dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+        Some(quantize_inputs(
+            ast,
+            q_params,
+            (&weigths, &weigths, op.kernel.datum_type()),
+            (&input, &wire, input_dt),
+            true,
+        )?)
+    } else {
+        None
+    };
+    let conv_fragment = conv_fragment(ast, "conv", op.pool_spec.data_format, op.pool_spec.rank());
     let padding = match &op.pool_spec.padding {
         PaddingSpec::Explicit(bef, after, _) => array(
             &bef.iter()
@@ -201,8 +223,14 @@ pub fn conv(
     };
     let mut inputs = tvec![wire, weigths];
     if let Some(bias) = op.bias.as_ref() {
-        let bias = ast.konst(format!("{}_bias", node.name), bias);
-        inputs.push(bias)
+        if let Some((a_scale, b_scale)) = quantization {
+            let bias = ast.konst_variable(format!("{}_bias", node.name), bias);
+            ast.quantize(&bias, QuantFormat::linear(i32::datum_type(), 0, a_scale * b_scale)?)?;
+            inputs.push(bias)
+        } else {
+            let bias = ast.konst(format!("{}_bias", node.name), bias);
+            inputs.push(bias)
+        }
     }
     wire = invocation(
         &conv_fragment,
@@ -216,6 +244,79 @@ pub fn conv(
         ],
     );
     wire = ast.force_assign(&node.name, &wire);
+    if let (Some(q_params), Some(scales)) = (&op.q_params, quantization) {
+        quantize_output(ast, q_params, scales, &wire)?;
+    }
+    Ok(Some(wire))
+}
+
+pub fn deconv(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::cnn::DeconvUnary,
+) -> TractResult<Option<Arc<RValue>>> {
+    use tract_core::ops::cnn::PaddingSpec;
+    let mut wire = ast.mapping[&node.inputs[0]].clone();
+    wire = ast.force_assign(format!("{}_input", node.name), &wire);
+    let weights = ast.konst_variable(format!("{}_weights", node.name), &op.kernel);
+    let deconv_fragment =
+        conv_fragment(ast, "deconv", op.pool_spec.data_format, op.pool_spec.rank());
+    let adjusted = op.adjustments.iter().any(|&a| a != 0);
+    let explicit = |before: &[usize], after: &[usize]| {
+        array(
+            &before
+                .iter()
+                .zip(after.iter())
+                .map(|(a, b)| tuple_2(numeric(a), numeric(b)))
+                .collect::<Vec<_>>(),
+        )
+    };
+    let padding = match &op.pool_spec.padding {
+        PaddingSpec::Explicit(bef, after, _) => explicit(bef, after),
+        PaddingSpec::SameUpper if !adjusted => array(&[]),
+        PaddingSpec::SameUpper | PaddingSpec::SameLower => {
+            // NNEF auto padding accounts for output_shape, so spell it out
+            let (before, after): (TVec<usize>, TVec<usize>) = (0..op.pool_spec.rank())
+                .map(|axis| {
+                    let field =
+                        (op.pool_spec.kernel_shape[axis] - 1) * op.pool_spec.dilation(axis) + 1;
+                    let total = field.saturating_sub(op.pool_spec.stride(axis));
+                    let before = if op.pool_spec.padding == PaddingSpec::SameUpper {
+                        total / 2
+                    } else {
+                        total - total / 2
+                    };
+                    (before, total - before)
+                })
+                .unzip();
+            explicit(&before, &after)
+        }
+        PaddingSpec::Valid => array(
+            (0..op.pool_spec.rank()).map(|_| tuple_2(numeric(0), numeric(0))).collect::<Vec<_>>(),
+        ),
+    };
+    let mut inputs = tvec![wire, weights];
+    if let Some(bias) = op.bias.as_ref() {
+        inputs.push(ast.konst(format!("{}_bias", node.name), bias));
+    }
+    let mut params = vec![
+        ("dilation", ints(&op.pool_spec.dilations())),
+        ("stride", ints(&op.pool_spec.strides())),
+        ("border", string("constant")),
+        ("groups", numeric(op.group)),
+        ("padding", padding),
+    ];
+    if adjusted {
+        let shape = node.outputs[0].fact.shape.as_concrete().context(
+            "Deconvolution adjustments can only be serialized with a known output shape",
+        )?;
+        let shape = op.pool_spec.data_format.shape(shape)?;
+        let mut output_shape = tvec!(*shape.n().unwrap_or(&1), *shape.c());
+        output_shape.extend(shape.hw_dims().iter().cloned());
+        params.push(("output_shape", ints(&output_shape)));
+    }
+    wire = invocation(&deconv_fragment, &inputs, &params);
+    wire = ast.force_assign(&node.name, &wire);
     Ok(Some(wire))
 }
 
@@ -367,18 +468,51 @@ pub fn reduce(
         ops::nn::Reducer::Sum => "sum_reduce",
         ops::nn::Reducer::Max => "max_reduce",
         ops::nn::Reducer::Min => "min_reduce",
+        ops::nn::Reducer::Any => "any_reduce",
+        ops::nn::Reducer::All => "all_reduce",
         _ => return Ok(None),
     };
     Ok(Some(invocation(oper, &[wire], &[("axes", ints(&*op.axes))])))
 }
 
+pub fn softmax(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::nn::Softmax,
+) -> TractResult<Option<Arc<RValue>>> {
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let softmax = invocation("softmax", &[wire], &[("axes", ints(&*op.axes))]);
+    if op.log {
+        let softmax = ast.force_assign(format!("{}_softmax", node.name), &softmax);
+        Ok(Some(invocation("log", &[softmax], &[])))
+    } else {
+        Ok(Some(softmax))
+    }
+}
+
+pub fn leaky_relu(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<ops::element_wise::ElementWiseOp>().unwrap();
+    let op = op.0.downcast_ref::<ops::nn::LeakyRelu>().unwrap();
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    Ok(Some(invocation("leaky_relu", &[wire], &[("alpha", numeric(op.alpha))])))
+}
+
 pub fn matmul(
     ast: &mut IntoAst,
     node: &TypedNode,
     op: &ops::matmul::MatMul,
 ) -> TractResult<Option<Arc<RValue>>> {
-    let a = ast.force_assign(format!("{}_a", node.name), &ast.mapping[&node.inputs[0]].clone());
-    let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[1]].clone());
+    let a_input = ast.mapping[&node.inputs[0]].clone();
+    let b_input = ast.mapping[&node.inputs[1]].clone();
+    let a = ast.force_assign(format!("{}_a", node.name), &a_input);
+    let b = ast.force_assign(format!("{}_b", node.name), &b_input);
+    let scales = if let Some(q_params) = &op.q_params {
+        let a_dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+        let b_dt = ast.model.outlet_fact(node.inputs[1])?.datum_type;
+        Some(quantize_inputs(ast, q_params, (&a_input, &a, a_dt), (&b_input, &b, b_dt), false)?)
+    } else {
+        None
+    };
     let c = if op.c_trans {
         invocation(
             "matmul",
@@ -392,7 +526,11 @@ pub fn matmul(
             &[("transposeA", logical(op.a_trans)), ("transposeB", logical(op.b_trans))],
         )
     };
-    Ok(Some(ast.force_assign(&node.name, &c)))
+    let c = ast.force_assign(&node.name, &c);
+    if let (Some(q_params), Some(scales)) = (&op.q_params, scales) {
+        quantize_output(ast, q_params, scales, &c)?;
+    }
+    Ok(Some(c))
 }
 
 pub fn matmul_unary(
@@ -400,8 +538,22 @@ pub fn matmul_unary(
     node: &TypedNode,
     op: &ops::matmul::MatMulUnary,
 ) -> TractResult<Option<Arc<RValue>>> {
-    let a = ast.konst(format!("{}_a", node.name), &op.a);
-    let b = ast.force_assign(format!("{}_b", node.name), &ast.mapping[&node.inputs[0]].clone());
+    let b_input = ast.mapping[&node.inputs[0]].clone();
+    let b = ast.force_assign(format!("{}_b", node.name), &b_input);
+    let (a, scales) = if let Some(q_params) = &op.q_params {
+        let a = ast.konst_variable(format!("{}_a", node.name), &op.a);
+        let b_dt = ast.model.outlet_fact(node.inputs[0])?.datum_type;
+        let scales = quantize_inputs(
+            ast,
+            q_params,
+            (&a, &a, op.a.datum_type()),
+            (&b_input, &b, b_dt),
+            true,
+        )?;
+        (a, Some(scales))
+    } else {
+        (ast.konst(format!("{}_a", node.name), &op.a), None)
+    };
     let c = if op.c_trans {
         invocation(
             "matmul",
@@ -415,7 +567,78 @@ pub fn matmul_unary(
             &[("transposeA", logical(op.a_trans)), ("transposeB", logical(op.b_trans))],
         )
     };
-    Ok(Some(ast.force_assign(&node.name, &c)))
+    let c = ast.force_assign(&node.name, &c);
+    if let (Some(q_params), Some(scales)) = (&op.q_params, scales) {
+        quantize_output(ast, q_params, scales, &c)?;
+    }
+    Ok(Some(c))
+}
+
+fn scalar_zero_point(zero_point: &Option<Arc<Tensor>>) -> TractResult<i32> {
+    match zero_point {
+        None => Ok(0),
+        Some(zp) if zp.rank() == 0 => zp.cast_to_scalar::<i32>(),
+        Some(zp) => bail!("Only scalar zero points can be serialized, got {:?}", zp),
+    }
+}
+
+/// Scale of an input of an integer product: given by its quantized type, or
+/// by the annotation of the tensor it is read from.
+fn input_scale(ast: &IntoAst, input: &RValue, dt: DatumType) -> TractResult<f32> {
+    if let Some(zps) = dt.zp_scale() {
+        return Ok(zps.scale);
+    }
+    match input {
+        RValue::Identifier(id) if ast.quantization.contains_key(id) => {
+            Ok(ast.quantization[id].scale())
+        }
+        _ => bail!("Can not serialize quantized product: scale of {:?} is unknown", input),
+    }
+}
+
+/// Annotates the inputs of an integer product, returning their scales. Each
+/// input is given as the tensor it is read from, the tensor the product
+/// uses, and its type. Constant integer weights that are not otherwise
+/// quantized are taken at face value, with a scale of 1.
+fn quantize_inputs(
+    ast: &mut IntoAst,
+    q_params: &ops::quant::QParams,
+    (a_input, a, a_dt): (&RValue, &RValue, DatumType),
+    (b_input, b, b_dt): (&RValue, &RValue, DatumType),
+    a_is_const: bool,
+) -> TractResult<(f32, f32)> {
+    if q_params.inputs_kind.is_some() {
+        bail!("Quantization parameters given as inputs can not be serialized");
+    }
+    let explicit = q_params.with_storage_types(a_dt, b_dt)?;
+    let a_scale =
+        if a_is_const && !a_dt.is_quantized() { 1.0 } else { input_scale(ast, a_input, a_dt)? };
+    let b_scale = input_scale(ast, b_input, b_dt)?;
+    let a_zero_point = scalar_zero_point(&explicit.zero_point_a)?;
+    let b_zero_point = scalar_zero_point(&explicit.zero_point_b)?;
+    ast.quantize(a, QuantFormat::linear(a_dt.unquantized(), a_zero_point, a_scale)?)?;
+    ast.quantize(b, QuantFormat::linear(b_dt.unquantized(), b_zero_point, b_scale)?)?;
+    Ok((a_scale, b_scale))
+}
+
+fn quantize_output(
+    ast: &mut IntoAst,
+    q_params: &ops::quant::QParams,
+    (a_scale, b_scale): (f32, f32),
+    c: &RValue,
+) -> TractResult<()> {
+    if q_params.scale_factors.is_some() {
+        bail!("Per channel quantization scales can not be serialized");
+    }
+    if let Some(format) = QuantFormat::from_datum_type(q_params.c_datum_type) {
+        if q_params.scale_factor.is_some() || q_params.zero_point_c.is_some() {
+            bail!("Quantized output type can not be serialized with extra {:?}", q_params);
+        }
+        return ast.quantize(c, format);
+    }
+    let c_scale = a_scale * b_scale / q_params.scale_factor.unwrap_or(1.0);
+    let zero_point = scalar_zero_point(&q_params.zero_point_c)?;
+    ast.quantize(c, QuantFormat::linear(q_params.c_datum_type, zero_point, c_scale)?)
 }
 
 pub fn select(
@@ -429,3 +652,61 @@ pub fn select(
         &[],
     )))
 }
+
+pub fn upsample(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::cnn::Upsample,
+) -> TractResult<Option<Arc<RValue>>> {
+    use tract_core::ops::cnn::UpsampleMethod;
+    // NNEF only resamples spatial axes of NCHW tensors
+    if op.factors.len() < 2 || op.factors[0] != 1 || op.factors[1] != 1 {
+        return Ok(None);
+    }
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let factor = ints(&op.factors[2..]);
+    let method = match op.method {
+        UpsampleMethod::Nearest => {
+            return Ok(Some(invocation("nearest_upsample", &[wire], &[("factor", factor)])))
+        }
+        UpsampleMethod::LinearSymmetric => "symmetric",
+        UpsampleMethod::LinearAsymmetric => "asymmetric",
+        UpsampleMethod::LinearAligned => "aligned",
+    };
+    let border = if op.replicate_border { "replicate" } else { "constant" };
+    Ok(Some(invocation(
+        "multilinear_upsample",
+        &[wire],
+        &[("factor", factor), ("method", string(method)), ("border", string(border))],
+    )))
+}
+
+pub fn lrn(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::nn::Lrn,
+) -> TractResult<Option<Arc<RValue>>> {
+    let wire = ast.mapping[&node.inputs[0]].clone();
+    let mut size = tvec!(1; node.outputs[0].fact.rank());
+    size[1] = op.size;
+    Ok(Some(invocation(
+        "local_response_normalization",
+        &[wire],
+        &[
+            ("size", ints(&size)),
+            ("alpha", numeric(op.alpha)),
+            ("beta", numeric(op.beta)),
+            ("bias", numeric(op.bias)),
+        ],
+    )))
+}
+
+pub fn roi_pool(
+    ast: &mut IntoAst,
+    node: &TypedNode,
+    op: &ops::cnn::RoiPool,
+) -> TractResult<Option<Arc<RValue>>> {
+    let inputs: TVec<Arc<RValue>> = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect();
+    let name = if op.average { "avg_roi_pool" } else { "max_roi_pool" };
+    Ok(Some(invocation(name, &inputs, &[("output_size", ints(&op.output_size))])))
+}
@@ -28,6 +28,7 @@ pub struct IntoAst<'a> {
     pub results: Vec<String>,
     pub mapping: HashMap<OutletId, Arc<RValue>>,
     pub tensors: Vec<(String, Arc<Tensor>)>,
+    pub quantization: HashMap<String, QuantFormat>,
     pub fragments: HashMap<String, FragmentDef>,
     pub body: Vec<Assignment>,
 }
@@ -50,6 +51,7 @@ impl<'a> IntoAst<'a> {
             results: vec![],
             mapping: Default::default(),
             tensors: Default::default(),
+            quantization: Default::default(),
             fragments: Default::default(),
             body: vec![],
             parent: None,
@@ -160,7 +162,15 @@ impl<'a> IntoAst<'a> {
                 .clone(),
         ));
         let properties: Assignment = assignment("properties", Arc::new(array(properties)));
-        let IntoAst { prefix, mut fragments, body, tensors, parameters, results, .. } = self;
+        for (input, id) in self.model.input_outlets()?.iter().zip(self.parameters.iter()) {
+            let dt = self.model.outlet_fact(*input)?.datum_type;
+            if let Some(q) = QuantFormat::from_datum_type(dt) {
+                self.quantization.insert(id.clone(), q);
+            }
+        }
+        let IntoAst {
+            prefix, mut fragments, body, tensors, parameters, results, quantization, ..
+        } = self;
         let mut id = prefix
             .map(|p| p.trim_end_matches(&['-', '/', '.'][..]).replace(&['-', '/', '.'][..], "_"))
             .unwrap_or("network".into());
@@ -193,7 +203,8 @@ impl<'a> IntoAst<'a> {
             fragments: fragments.into_iter().map(|(_, v)| v).collect(),
             graph_def: GraphDef { id, parameters, results, body },
         };
-        Ok(ProtoModel { doc, tensors })
+        let quantization = if quantization.len() > 0 { Some(quantization) } else { None };
+        Ok(ProtoModel { doc, tensors, quantization })
     }
 
     fn node(&mut self, node: &TypedNode) -> TractResult<TVec<Arc<RValue>>> {
@@ -228,6 +239,22 @@ impl<'a> IntoAst<'a> {
         bail!("No serializer found for node {}", node);
     }
 
+    /// Records the quantization of a named tensor, to be dumped in `graph.quant`.
+    pub fn quantize(&mut self, tensor: &RValue, format: QuantFormat) -> TractResult<()> {
+        let id = if let RValue::Identifier(id) = tensor {
+            id
+        } else {
+            bail!("Only named tensors can be quantized, got {:?}", tensor)
+        };
+        if let Some(previous) = self.quantization.get(id) {
+            if *previous != format {
+                bail!("Conflicting quantization for {}: {:?} and {:?}", id, previous, format)
+            }
+        }
+        self.quantization.insert(id.clone(), format);
+        Ok(())
+    }
+
     pub fn scoped_id(&self, name: impl Into<String>) -> String {
         let mut name = name.into();
         if let Some(p) = &self.prefix {
@@ -270,7 +297,8 @@ impl<'a> IntoAst<'a> {
         tensor: &Arc<Tensor>,
         force_variable: bool,
     ) -> Arc<RValue> {
-        if !force_variable && tensor.is_uniform().unwrap() {
+        let quantization = QuantFormat::from_datum_type(tensor.datum_type());
+        if !force_variable && quantization.is_none() && tensor.is_uniform().unwrap() {
             if tensor.datum_type() == String::datum_type() {
                 string(tensor.to_scalar::<String>().unwrap()).into()
             } else {
@@ -292,6 +320,9 @@ impl<'a> IntoAst<'a> {
                 })
                 .into(),
             );
+            if let Some(q) = quantization {
+                self.quantization.insert(id.clone(), q);
+            }
             ident(id).into()
         }
     }
@@ -81,13 +81,15 @@ pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResul
         for d in 0..tensor.rank() {
             header.dims[d] = tensor.shape()[d] as u32;
         }
-        header.data_size_bytes = (tensor.len() * tensor.datum_type().size_of()) as u32;
-        header.bits_per_item = (tensor.datum_type().size_of() * 8) as u32;
-        header.item_type = if tensor.datum_type().is_float() {
+        // quantization goes to graph.quant, the tensor file has the storage type
+        let dt = tensor.datum_type().unquantized();
+        header.data_size_bytes = (tensor.len() * dt.size_of()) as u32;
+        header.bits_per_item = (dt.size_of() * 8) as u32;
+        header.item_type = if dt.is_float() {
             0
-        } else if tensor.datum_type().is_signed() {
+        } else if dt.is_signed() {
             0x100
-        } else if tensor.datum_type().is_unsigned() {
+        } else if dt.is_unsigned() {
             1
         } else {
             bail!("Don't know how to serialize {:?}", tensor.datum_type())
@@ -99,6 +101,25 @@ pub fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResul
     }
 }
 
+/// Write a state snapshot as a tensor count followed by the tensors.
+pub fn write_snapshot<W: std::io::Write>(w: &mut W, snapshot: &StateSnapshot) -> TractResult<()> {
+    let tensors = snapshot.to_tensors()?;
+    w.write_all(&(tensors.len() as u32).to_le_bytes())?;
+    for tensor in &tensors {
+        write_tensor(w, tensor)?;
+    }
+    Ok(())
+}
+
+pub fn read_snapshot<R: std::io::Read>(mut reader: R) -> TractResult<StateSnapshot> {
+    let mut count = [0u8; 4];
+    reader.read_exact(&mut count)?;
+    let tensors = (0..u32::from_le_bytes(count))
+        .map(|_| read_tensor(&mut reader))
+        .collect::<TractResult<Vec<_>>>()?;
+    StateSnapshot::from_tensors(tensors)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
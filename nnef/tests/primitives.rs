@@ -0,0 +1,35 @@
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::cnn::*;
+use tract_nnef::tract_core::ops::nn::DataFormat;
+
+fn round_trip(model: TypedModel, input: Tensor) {
+    let nnef = tract_nnef::nnef();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer).unwrap();
+    let reloaded = nnef.model_for_read(&mut &*buffer).unwrap();
+    let expected = model.into_runnable().unwrap().run(tvec!(input.clone())).unwrap();
+    let found = reloaded.into_runnable().unwrap().run(tvec!(input)).unwrap();
+    assert_eq!(expected, found);
+}
+
+#[test]
+fn deconv_and_upsample_round_trip() {
+    let mut model = TypedModel::default();
+    let input =
+        model.add_source("input", TypedFact::dt_shape(f32::datum_type(), &[1, 2, 3])).unwrap();
+    let pool_spec = PoolSpec::new(
+        DataFormat::NCHW,
+        tvec!(3),
+        PaddingSpec::Explicit(tvec!(1), tvec!(0), false),
+        None,
+        Some(tvec!(2)),
+        None,
+    );
+    let kernel = tensor3(&[[[1f32, 0., -1.]], [[0.5, 2., 1.]]]).into_arc_tensor();
+    let deconv = DeconvUnary::new(pool_spec, kernel, Some(rctensor1(&[0.5f32])), tvec!(1), 1);
+    let deconv = model.wire_node("deconv", deconv, &[input]).unwrap();
+    let upsample = Upsample::new(tvec!(1, 1, 2), UpsampleMethod::LinearAligned, true);
+    let upsample = model.wire_node("upsample", upsample, &deconv).unwrap();
+    model.set_output_outlets(&upsample).unwrap();
+    round_trip(model, tensor3(&[[[1f32, 2., 3.], [-1., 0., 4.]]]));
+}
@@ -0,0 +1,180 @@
+use tract_nnef::ast::QuantFormat;
+use tract_nnef::internal::*;
+use tract_nnef::tract_core::ops::cnn::{ConvUnary, KernelFormat, PaddingSpec, PoolSpec};
+use tract_nnef::tract_core::ops::matmul::MatMulUnary;
+use tract_nnef::tract_core::ops::nn::DataFormat;
+use tract_nnef::tract_core::ops::quant::QParams;
+
+fn round_trip(model: &TypedModel) -> TypedModel {
+    let nnef = tract_nnef::nnef();
+    let mut buffer = vec![];
+    nnef.write_to_tar(model, &mut buffer).unwrap();
+    let proto = nnef.proto_model_for_read(&mut &*buffer).unwrap();
+    assert!(proto.quantization.is_some());
+    nnef.model_for_proto_model(&proto).unwrap()
+}
+
+#[test]
+fn quantized_matmul_round_trip() {
+    let b_dt = DatumType::QI8(ZpScale::new(-2, 0.5));
+    let c_dt = DatumType::QI8(ZpScale::new(3, 0.25));
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(b_dt, &[4, 2])).unwrap();
+    let a = tensor2(&[[1i8, 2, 3, 4], [-1, 0, 1, 2], [5, -5, 3, -3]])
+        .cast_to_dt(DatumType::QI8(ZpScale::new(1, 0.125)))
+        .unwrap()
+        .into_owned()
+        .into_arc_tensor();
+    let mm = model
+        .wire_node(
+            "mm",
+            MatMulUnary::new(a, false, false, false, Some(QParams::new(c_dt))),
+            &[input],
+        )
+        .unwrap();
+    model.set_output_outlets(&mm).unwrap();
+
+    let reloaded = round_trip(&model);
+    assert_eq!(
+        reloaded.outlet_fact(reloaded.output_outlets().unwrap()[0]).unwrap().datum_type,
+        c_dt
+    );
+
+    let input =
+        tensor2(&[[1i8, -3], [7, 2], [0, -1], [4, 5]]).cast_to_dt(b_dt).unwrap().into_owned();
+    let expected = model.into_runnable().unwrap().run(tvec!(input.clone())).unwrap();
+    let found = reloaded.into_runnable().unwrap().run(tvec!(input)).unwrap();
+    assert_eq!(expected, found);
+}
+
+#[test]
+fn quantized_conv_round_trip() {
+    let x_dt = DatumType::QU8(ZpScale::new(128, 0.5));
+    let c_dt = DatumType::QU8(ZpScale::new(100, 2.0));
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(x_dt, &[1, 2, 3])).unwrap();
+    let kernel = tensor3(&[[[3i8], [-7]]])
+        .cast_to_dt(DatumType::QI8(ZpScale::new(0, 0.25)))
+        .unwrap()
+        .into_owned()
+        .into_arc_tensor();
+    let pool_spec =
+        PoolSpec::new(DataFormat::NCHW, tvec!(1), PaddingSpec::Valid, None, None, Some(1));
+    let conv = ConvUnary::new(
+        pool_spec,
+        KernelFormat::OIHW,
+        kernel,
+        1,
+        Some(rctensor1(&[12i32])),
+        Some(QParams::new(c_dt)),
+    );
+    let conv = model.wire_node("conv", conv, &[input]).unwrap();
+    model.set_output_outlets(&conv).unwrap();
+
+    let reloaded = round_trip(&model);
+    assert_eq!(
+        reloaded.outlet_fact(reloaded.output_outlets().unwrap()[0]).unwrap().datum_type,
+        c_dt
+    );
+
+    let input =
+        tensor3(&[[[0u8, 130, 255], [128, 20, 200]]]).cast_to_dt(x_dt).unwrap().into_owned();
+    let expected = model.into_runnable().unwrap().run(tvec!(input.clone())).unwrap();
+    let found = reloaded.into_runnable().unwrap().run(tvec!(input)).unwrap();
+    assert_eq!(expected, found);
+}
+
+#[test]
+fn unknown_scales_are_not_serialized() {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(i8::datum_type(), &[4, 2])).unwrap();
+    let a = tensor2(&[[1i8, 2, 3, 4]]).into_arc_tensor();
+    let q_params = QParams::new(i8::datum_type()).with_zero_point_a(&rctensor0(1i8));
+    let mm = model
+        .wire_node("mm", MatMulUnary::new(a, false, false, false, Some(q_params)), &[input])
+        .unwrap();
+    model.set_output_outlets(&mm).unwrap();
+    let mut buffer = vec![];
+    assert!(tract_nnef::nnef().with_tract_core().write_to_tar(&model, &mut buffer).is_err());
+}
+
+#[test]
+fn plain_integer_inputs_are_not_annotated() {
+    let mut model = TypedModel::default();
+    let input = model.add_source("input", TypedFact::dt_shape(i8::datum_type(), &[4, 2])).unwrap();
+    model.set_output_outlets(&[input]).unwrap();
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer).unwrap();
+    let proto = nnef.proto_model_for_read(&mut &*buffer).unwrap();
+    assert!(proto.quantization.is_none());
+    let reloaded = nnef.model_for_proto_model(&proto).unwrap();
+    let input = reloaded.input_outlets().unwrap()[0];
+    assert_eq!(reloaded.outlet_fact(input).unwrap().datum_type, i8::datum_type());
+}
+
+#[test]
+fn quantized_types_are_annotated() {
+    let x_dt = DatumType::QI8(ZpScale::new(-3, 0.25));
+    let c_dt = DatumType::QU8(ZpScale::new(128, 0.5));
+    let mut model = TypedModel::default();
+    let x = model.add_source("x", TypedFact::dt_shape(x_dt, &[2])).unwrap();
+    let c = tensor1(&[1u8, 200]).cast_to_dt(c_dt).unwrap().into_owned();
+    let c = model.add_const("c", c).unwrap();
+    model.set_output_outlets(&[x, c]).unwrap();
+
+    let nnef = tract_nnef::nnef();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&model, &mut buffer).unwrap();
+    let proto = nnef.proto_model_for_read(&mut &*buffer).unwrap();
+    let quantization = proto.quantization.unwrap();
+    let mut formats = quantization.values().cloned().collect::<Vec<_>>();
+    formats.sort_by_key(|q| q.zero_point());
+    assert_eq!(
+        formats,
+        vec![
+            QuantFormat::Linear { zero_point: -3, scale: 0.25, bits: 8, signed: true },
+            QuantFormat::Linear { zero_point: 128, scale: 0.5, bits: 8, signed: false },
+        ]
+    );
+    let (_, tensor) = proto.tensors.iter().find(|(_, t)| t.len() == 2).unwrap();
+    assert_eq!(tensor.datum_type(), u8::datum_type());
+    assert_eq!(tensor.as_slice::<u8>().unwrap(), &[1, 200]);
+}
+
+#[test]
+fn calibrated_model_round_trip() {
+    use tract_nnef::tract_core::ops::quant::calibration::{Calibration, CalibrationMethod};
+    let data = |shape: &[usize], factor: f32| {
+        let len = shape.iter().product::<usize>();
+        tensor1(&(0..len).map(|i| ((i * 7 % 13) as f32 - 6.0) / 3.0 * factor).collect::<Vec<_>>())
+            .into_shape(shape)
+            .unwrap()
+    };
+    let mut model = TypedModel::default();
+    let mut wire = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), &[7, 3])).unwrap();
+    for (ix, a) in [data(&[5, 7], 1.0), data(&[4, 5], -0.7)].iter().enumerate() {
+        let op = MatMulUnary::new(a.clone().into_arc_tensor(), false, false, false, None);
+        wire = model.wire_node(format!("mm{}", ix), op, &[wire]).unwrap()[0];
+    }
+    model.set_output_outlets(&[wire]).unwrap();
+    let samples = [1.0, -0.5, 0.8].iter().map(|&f| tvec!(data(&[7, 3], f)));
+    let calibration = Calibration::collect(&model, samples).unwrap();
+    let quantized = calibration.quantize(&model, CalibrationMethod::MinMax).unwrap();
+
+    let nnef = tract_nnef::nnef().with_tract_core();
+    let mut buffer = vec![];
+    nnef.write_to_tar(&quantized, &mut buffer).unwrap();
+    let reloaded = nnef.model_for_read(&mut &*buffer).unwrap();
+
+    let input = data(&[7, 3], 0.9);
+    let expected = quantized.into_runnable().unwrap().run(tvec!(input.clone())).unwrap();
+    let found = reloaded.into_runnable().unwrap().run(tvec!(input)).unwrap();
+    // scales are serialized as their inverse: allow for a quantization step
+    let expected = expected[0].as_slice::<f32>().unwrap();
+    let absmax = expected.iter().fold(0f32, |m, x| m.max(x.abs()));
+    for (e, f) in expected.iter().zip(found[0].as_slice::<f32>().unwrap()) {
+        assert!((e - f).abs() <= 0.02 * absmax, "expected:{} found:{}", e, f);
+    }
+}
@@ -49,6 +49,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("GlobalMaxPool", |_, _| Ok((expand(ops::nn::GlobalMaxPool), vec![])));
     reg.insert("Hardmax", layer_hard_max);
     reg.insert("HardSigmoid", hard_sigmoid);
+    reg.insert("HardSwish", |_, _| Ok((Box::new(ops::nn::hard_swish()), vec![])));
     reg.insert("InstanceNormalization", instance_norm::instance_normalization);
     reg.insert("LeakyRelu", leaky_relu);
     reg.insert("LogSoftmax", layer_log_soft_max);
@@ -14,7 +14,8 @@ fn quantize_linear(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let op = QuantizeLinear::new(Some(2).filter(|_| node.input.len() == 3));
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1);
+    let op = QuantizeLinear::new(Some(2).filter(|_| node.input.len() == 3), axis);
     Ok((expand(op), vec![]))
 }
 
@@ -22,7 +23,8 @@ fn dequantize_linear(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let op = DequantizeLinear::new(Some(2).filter(|_| node.input.len() == 3));
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1);
+    let op = DequantizeLinear::new(Some(2).filter(|_| node.input.len() == 3), axis);
     Ok((expand(op), vec![]))
 }
 
@@ -37,6 +39,7 @@ fn dynamic_quantize_linear(
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct QuantizeLinear {
     optional_zero_point_input: Option<usize>,
+    axis: i64,
 }
 
 impl_dyn_hash!(QuantizeLinear);
@@ -75,13 +78,8 @@ impl Expansion for QuantizeLinear {
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
         use tract_hir::ops::quant::*;
-        let scale = target
-            .outlet_fact(inputs[1])?
-            .konst
-            .as_ref()
-            .context("y_scale must be a const")?
-            .as_slice::<f32>()?[0]
-            .recip();
+        let scale =
+            target.outlet_fact(inputs[1])?.konst.clone().context("y_scale must be a const")?;
         let zero_point = if self.optional_zero_point_input.is_some() {
             target
                 .outlet_fact(inputs[2])?
@@ -92,6 +90,17 @@ impl Expansion for QuantizeLinear {
         } else {
             rctensor0(0u8)
         };
+        if scale.len() > 1 || zero_point.len() > 1 {
+            return wire_quantize_per_axis(
+                prefix,
+                target,
+                inputs[0],
+                self.axis,
+                &scale,
+                &zero_point,
+            );
+        }
+        let scale = scale.as_slice::<f32>()?[0].recip();
         let op: Box<dyn TypedOp> = if zero_point.datum_type() == u8::datum_type() {
             Box::new(quantize_linear_u8(scale, zero_point.as_slice::<u8>()?[0]))
         } else {
@@ -104,6 +113,7 @@ impl Expansion for QuantizeLinear {
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct DequantizeLinear {
     optional_zero_point_input: Option<usize>,
+    axis: i64,
 }
 
 impl_dyn_hash!(DequantizeLinear);
@@ -140,12 +150,8 @@ impl Expansion for DequantizeLinear {
         target: &mut TypedModel,
         inputs: &[OutletId],
     ) -> TractResult<TVec<OutletId>> {
-        let scale = target
-            .outlet_fact(inputs[1])?
-            .konst
-            .as_ref()
-            .context("y_scale must be a const")?
-            .as_slice::<f32>()?[0];
+        let scale =
+            target.outlet_fact(inputs[1])?.konst.clone().context("y_scale must be a const")?;
         let zero_point = if self.optional_zero_point_input.is_some() {
             target
                 .outlet_fact(inputs[2])?
@@ -156,6 +162,17 @@ impl Expansion for DequantizeLinear {
         } else {
             rctensor0(0u8)
         };
+        if scale.len() > 1 || zero_point.len() > 1 {
+            return wire_dequantize_per_axis(
+                prefix,
+                target,
+                inputs[0],
+                self.axis,
+                &scale,
+                &zero_point,
+            );
+        }
+        let scale = scale.as_slice::<f32>()?[0];
         let op: Box<dyn TypedOp> = if zero_point.datum_type() == u8::datum_type() {
             Box::new(DequantizeLinearF32::new(scale, zero_point.as_slice::<u8>()?[0] as i32))
         } else if zero_point.datum_type() == i8::datum_type() {
@@ -167,6 +184,80 @@ impl Expansion for DequantizeLinear {
     }
 }
 
+/// Shape a per-axis scale or zero point so that it broadcasts against an
+/// input of the given rank.
+fn per_axis(param: &Tensor, rank: usize, axis: i64) -> TractResult<Arc<Tensor>> {
+    let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+    if axis >= rank {
+        bail!("Invalid quantization axis {} for rank {}", axis, rank)
+    }
+    let mut shape = tvec!(1; rank);
+    shape[axis] = param.len();
+    Ok(param.cast_to::<f32>()?.into_owned().into_shape(&shape)?.into_arc_tensor())
+}
+
+fn wire_quantize_per_axis(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    axis: i64,
+    scale: &Tensor,
+    zero_point: &Tensor,
+) -> TractResult<TVec<OutletId>> {
+    use tract_hir::ops::math;
+    let rank = target.outlet_fact(input)?.rank();
+    let dt = zero_point.datum_type();
+    let (min, max) = match dt {
+        DatumType::U8 => (0f32, 255f32),
+        DatumType::I8 => (-128f32, 127f32),
+        _ => bail!("Unsupported zero point type {:?}", dt),
+    };
+    let mut inv_scale = scale.cast_to::<f32>()?.into_owned();
+    inv_scale.as_slice_mut::<f32>()?.iter_mut().for_each(|s| *s = s.recip());
+    let mut wire = target.wire_node(
+        format!("{}.scale", prefix),
+        math::mul::unary(per_axis(&inv_scale, rank, axis)?),
+        &[input],
+    )?;
+    wire = target.wire_node(format!("{}.round", prefix), math::round_half_to_even(), &wire)?;
+    wire = target.wire_node(
+        format!("{}.zero_point", prefix),
+        math::add::unary(per_axis(zero_point, rank, axis)?),
+        &wire,
+    )?;
+    let min = tensor0(min).broadcast_into_rank(rank)?.into_arc_tensor();
+    wire = target.wire_node(format!("{}.min", prefix), math::max::unary(min), &wire)?;
+    let max = tensor0(max).broadcast_into_rank(rank)?.into_arc_tensor();
+    wire = target.wire_node(format!("{}.max", prefix), math::min::unary(max), &wire)?;
+    target.wire_node(prefix, tract_hir::ops::cast::cast(dt), &wire)
+}
+
+fn wire_dequantize_per_axis(
+    prefix: &str,
+    target: &mut TypedModel,
+    input: OutletId,
+    axis: i64,
+    scale: &Tensor,
+    zero_point: &Tensor,
+) -> TractResult<TVec<OutletId>> {
+    use tract_hir::ops::math;
+    let rank = target.outlet_fact(input)?.rank();
+    let scale = per_axis(scale, rank, axis)?;
+    let mut offset = per_axis(zero_point, rank, axis)?.into_tensor();
+    offset.as_slice_mut::<f32>()?.iter_mut().for_each(|zp| *zp = -*zp);
+    let mut wire = target.wire_node(
+        format!("{}.cast", prefix),
+        tract_hir::ops::cast::cast(f32::datum_type()),
+        &[input],
+    )?;
+    wire = target.wire_node(
+        format!("{}.zero_point", prefix),
+        math::add::unary(offset.into_arc_tensor()),
+        &wire,
+    )?;
+    target.wire_node(prefix, math::mul::unary(scale), &wire)
+}
+
 #[derive(Debug, Clone, new, Default, Hash)]
 pub struct DynamicQuantizeLinear {}
 
@@ -0,0 +1,270 @@
+use crate::model::{optional_inputs, OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::signal;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert("DFT", dft);
+    reg.insert("MelWeightMatrix", mel_weight_matrix);
+    reg.insert("STFT", stft);
+}
+
+fn dft(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(1);
+    let inverse = node.get_attr_opt::<i64>("inverse")?.unwrap_or(0) != 0;
+    let onesided = node.get_attr_opt::<i64>("onesided")?.unwrap_or(0) != 0;
+    let length_input = optional_inputs(node).skip(1).next().unwrap();
+    Ok((expand(Dft::new(axis, inverse, onesided, length_input)), vec![]))
+}
+
+fn stft(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let onesided = node.get_attr_opt::<i64>("onesided")?.unwrap_or(1) != 0;
+    let mut options = optional_inputs(node).skip(2);
+    Ok((expand(Stft::new(onesided, options.next().unwrap(), options.next().unwrap())), vec![]))
+}
+
+fn mel_weight_matrix(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let datum_type = node.get_attr_opt("output_datatype")?.unwrap_or(DatumType::F32);
+    Ok((expand(MelWeightMatrix::new(datum_type)), vec![]))
+}
+
+fn const_input<'a>(model: &'a TypedModel, input: OutletId, name: &str) -> TractResult<&'a Tensor> {
+    model
+        .outlet_fact(input)?
+        .konst
+        .as_deref()
+        .with_context(|| format!("{} input must be a constant", name))
+}
+
+#[derive(Debug, Clone, new, Hash)]
+struct Dft {
+    axis: i64,
+    inverse: bool,
+    onesided: bool,
+    length_input: Option<usize>,
+}
+
+impl_dyn_hash!(Dft);
+
+impl Expansion for Dft {
+    fn name(&self) -> Cow<str> {
+        "DFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1 + self.length_input.is_some() as usize)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            let axis = if self.axis < 0 { self.axis + rank } else { self.axis } as usize;
+            for ix in 0..rank as usize - 1 {
+                if ix != axis {
+                    s.equals(&inputs[0].shape[ix], &outputs[0].shape[ix])?;
+                }
+            }
+            s.equals(&outputs[0].shape[rank as usize - 1], 2.to_dim())?;
+            let onesided = self.onesided;
+            if let Some(length) = self.length_input {
+                s.given(&inputs[length].value, move |s, length| {
+                    let n = length.cast_to_scalar::<i64>()? as usize;
+                    let n = if onesided { n / 2 + 1 } else { n };
+                    s.equals(&outputs[0].shape[axis], n.to_dim())
+                })
+            } else {
+                s.given(&inputs[0].shape[axis], move |s, n| {
+                    let n = if onesided { n / 2 + 1 } else { n };
+                    s.equals(&outputs[0].shape[axis], n)
+                })
+            }
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fact = model.outlet_fact(inputs[0])?.clone();
+        let axis = if self.axis < 0 { self.axis + fact.rank() as i64 } else { self.axis } as usize;
+        let mut wire = inputs[0];
+        if let Some(length) = self.length_input {
+            let length =
+                const_input(model, inputs[length], "dft_length")?.cast_to_scalar::<i64>()? as usize;
+            let current = fact.shape[axis].to_usize()?;
+            if length < current {
+                wire = model.wire_node(
+                    format!("{}.truncate", prefix),
+                    tract_hir::ops::array::Slice::new(axis, 0, length),
+                    &[wire],
+                )?[0];
+            } else if length > current {
+                let mut pads = vec![(0, 0); fact.rank()];
+                pads[axis] = (0, length - current);
+                let zero = Tensor::zero_dt(fact.datum_type, &[])?;
+                wire = model.wire_node(
+                    format!("{}.pad", prefix),
+                    tract_hir::ops::array::Pad::new(
+                        pads,
+                        tract_hir::ops::array::PadMode::Constant(zero.into_arc_tensor()),
+                    ),
+                    &[wire],
+                )?[0];
+            }
+        }
+        model.wire_node(prefix, signal::Dft::new(axis, self.inverse, self.onesided), &[wire])
+    }
+}
+
+#[derive(Debug, Clone, new, Hash)]
+struct Stft {
+    onesided: bool,
+    window_input: Option<usize>,
+    frame_length_input: Option<usize>,
+}
+
+impl_dyn_hash!(Stft);
+
+impl Expansion for Stft {
+    fn name(&self) -> Cow<str> {
+        "STFT".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(
+            &inputs,
+            2 + self.window_input.is_some() as usize + self.frame_length_input.is_some() as usize,
+        )?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[3], 2.to_dim())?;
+        if let Some(window) = self.window_input {
+            s.equals(&inputs[window].rank, 1)?;
+            s.equals(&inputs[window].datum_type, &inputs[0].datum_type)?;
+        }
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let frame_step =
+            const_input(model, inputs[1], "frame_step")?.cast_to_scalar::<i64>()? as usize;
+        let window = if let Some(window) = self.window_input {
+            Some(const_input(model, inputs[window], "window")?.clone())
+        } else {
+            None
+        };
+        let frame_length = if let Some(length) = self.frame_length_input {
+            const_input(model, inputs[length], "frame_length")?.cast_to_scalar::<i64>()? as usize
+        } else if let Some(window) = &window {
+            window.len()
+        } else {
+            bail!("STFT requires either a window or a frame_length")
+        };
+        let mut wire = model.wire_node(
+            format!("{}.frame", prefix),
+            signal::Frame::new(1, frame_length, frame_step),
+            &[inputs[0]],
+        )?;
+        if let Some(window) = window {
+            if window.len() != frame_length {
+                bail!("STFT window length must be frame_length ({})", frame_length)
+            }
+            let window = window.into_shape(&[1, 1, frame_length, 1])?;
+            wire = model.wire_node(
+                format!("{}.window", prefix),
+                tract_hir::ops::math::mul::unary(window.into_arc_tensor()),
+                &wire,
+            )?;
+        }
+        model.wire_node(prefix, signal::Dft::new(2, false, self.onesided), &wire)
+    }
+}
+
+#[derive(Debug, Clone, new, Hash)]
+struct MelWeightMatrix {
+    datum_type: DatumType,
+}
+
+impl_dyn_hash!(MelWeightMatrix);
+
+impl Expansion for MelWeightMatrix {
+    fn name(&self) -> Cow<str> {
+        "MelWeightMatrix".into()
+    }
+
+    op_onnx!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 5)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.datum_type)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.given(&inputs[0].value, move |s, bins| {
+            s.equals(&outputs[0].shape[1], (bins.cast_to_scalar::<i64>()? as usize).to_dim())
+        })?;
+        s.given(&inputs[1].value, move |s, dft_length| {
+            let dft_length = dft_length.cast_to_scalar::<i64>()? as usize;
+            s.equals(&outputs[0].shape[0], (dft_length / 2 + 1).to_dim())
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let int = |ix: usize, name: &str| -> TractResult<usize> {
+            Ok(const_input(model, inputs[ix], name)?.cast_to_scalar::<i64>()? as usize)
+        };
+        let float = |ix: usize, name: &str| -> TractResult<f64> {
+            Ok(const_input(model, inputs[ix], name)?.cast_to_scalar::<f64>()?)
+        };
+        let matrix = signal::mel::onnx_mel_weight_matrix(
+            int(0, "num_mel_bins")?,
+            int(1, "dft_length")?,
+            int(2, "sample_rate")?,
+            float(3, "lower_edge_hertz")?,
+            float(4, "upper_edge_hertz")?,
+        )?;
+        let matrix = matrix.cast_to_dt(self.datum_type)?.into_owned();
+        Ok(tvec!(model.add_const(prefix, matrix)?))
+    }
+}
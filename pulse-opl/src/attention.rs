@@ -0,0 +1,250 @@
+use tract_core::ops::nn::{AttentionWindow, LocalAttention};
+use tract_nnef::internal::*;
+use tract_nnef::ser::string;
+
+pub fn register(registry: &mut Registry) {
+    registry.register_dumper(TypeId::of::<LocalAttention>(), ser_attention);
+    registry.register_primitive(
+        "tract_pulse_local_attention",
+        &attention_parameters(),
+        de_attention,
+    );
+    registry.register_dumper(TypeId::of::<PulsedLocalAttention>(), ser_pulsed_attention);
+    let mut parameters = attention_parameters();
+    parameters.push(TypeName::Integer.named("q_delay"));
+    parameters.push(TypeName::Integer.named("kv_delay"));
+    parameters.push(TypeName::String.named("stream_len"));
+    registry.register_primitive(
+        "tract_pulse_pulsed_local_attention",
+        &parameters,
+        de_pulsed_attention,
+    );
+}
+
+fn attention_parameters() -> Vec<Parameter> {
+    vec![
+        TypeName::Scalar.tensor().named("q"),
+        TypeName::Scalar.tensor().named("k"),
+        TypeName::Scalar.tensor().named("v"),
+        TypeName::String.named("window"),
+        TypeName::Integer.named("left"),
+        TypeName::Integer.named("right").default(0i64),
+        TypeName::Integer.named("size").default(1i64),
+        TypeName::Scalar.named("scale"),
+    ]
+}
+
+fn attention_arguments(op: &LocalAttention) -> Vec<(&'static str, RValue)> {
+    let mut arguments = match op.window {
+        AttentionWindow::Band { left, right } => {
+            vec![("window", string("band")), ("left", numeric(left)), ("right", numeric(right))]
+        }
+        AttentionWindow::Chunked { size, left } => {
+            vec![("window", string("chunked")), ("left", numeric(left)), ("size", numeric(size))]
+        }
+    };
+    arguments.push(("scale", numeric(op.scale)));
+    arguments
+}
+
+fn ser_attention(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<LocalAttention>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<Vec<_>>();
+    Ok(Some(invocation("tract_pulse_local_attention", &inputs, &attention_arguments(op))))
+}
+
+fn ser_pulsed_attention(ast: &mut IntoAst, node: &TypedNode) -> TractResult<Option<Arc<RValue>>> {
+    let op = node.op_as::<PulsedLocalAttention>().unwrap();
+    let inputs = node.inputs.iter().map(|i| ast.mapping[i].clone()).collect::<Vec<_>>();
+    let mut arguments = attention_arguments(&op.op);
+    arguments.push(("q_delay", numeric(op.q_delay)));
+    arguments.push(("kv_delay", numeric(op.kv_delay)));
+    arguments.push(("stream_len", string(op.stream_len.to_string())));
+    Ok(Some(invocation("tract_pulse_pulsed_local_attention", &inputs, &arguments)))
+}
+
+fn attention_op(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<LocalAttention> {
+    let left = invocation.named_arg_as::<i64>(builder, "left")? as usize;
+    let window = match &*invocation.named_arg_as::<String>(builder, "window")? {
+        "band" => {
+            let right = invocation.named_arg_as::<i64>(builder, "right")? as usize;
+            AttentionWindow::Band { left, right }
+        }
+        "chunked" => {
+            let size = invocation.named_arg_as::<i64>(builder, "size")? as usize;
+            if size == 0 {
+                bail!("Chunked attention window must have a non-zero size");
+            }
+            AttentionWindow::Chunked { size, left }
+        }
+        window => bail!("Unsupported attention window: {}", window),
+    };
+    let scale = invocation.named_arg_as(builder, "scale")?;
+    Ok(LocalAttention { window, scale })
+}
+
+fn de_attention(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let q = invocation.named_arg_as(builder, "q")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let v = invocation.named_arg_as(builder, "v")?;
+    let op = attention_op(builder, invocation)?;
+    builder.wire(op, &[q, k, v])
+}
+
+fn de_pulsed_attention(
+    builder: &mut ModelBuilder,
+    invocation: &ResolvedInvocation,
+) -> TractResult<TVec<OutletId>> {
+    let q = invocation.named_arg_as(builder, "q")?;
+    let k = invocation.named_arg_as(builder, "k")?;
+    let v = invocation.named_arg_as(builder, "v")?;
+    let op = attention_op(builder, invocation)?;
+    let q_delay = invocation.named_arg_as::<i64>(builder, "q_delay")? as usize;
+    let kv_delay = invocation.named_arg_as::<i64>(builder, "kv_delay")? as usize;
+    let stream_len = invocation.named_arg_as::<String>(builder, "stream_len")?;
+    let stream_len = parse_dim(&stream_len)
+        .with_context(|| format!("Parsing stream length {:?}", stream_len))?;
+    builder.wire(PulsedLocalAttention { op, q_delay, kv_delay, stream_len }, &[q, k, v])
+}
+
+/// Parses back a dimension from its display form: sums of integers, symbols,
+/// products by an integer (`2.S`) and divisions by an integer (`(S+1)/2`).
+fn parse_dim(s: &str) -> TractResult<TDim> {
+    fn expr(s: &str) -> TractResult<(TDim, &str)> {
+        let (mut dim, mut rest) = term(s)?;
+        while rest.starts_with('+') {
+            let (t, r) = term(&rest[1..])?;
+            dim = dim + t;
+            rest = r;
+        }
+        Ok((dim, rest))
+    }
+    fn integer(s: &str) -> Option<(i64, &str)> {
+        let sign = if s.starts_with('-') { 1 } else { 0 };
+        let len = sign + s[sign..].chars().take_while(|c| c.is_ascii_digit()).count();
+        s[..len].parse().ok().map(|i| (i, &s[len..]))
+    }
+    fn term(s: &str) -> TractResult<(TDim, &str)> {
+        if s.starts_with('(') {
+            let (dim, rest) = expr(&s[1..])?;
+            if !rest.starts_with(")/") {
+                bail!("Expected a division at {:?}", rest);
+            }
+            let (divisor, rest) = integer(&rest[2..])
+                .ok_or_else(|| format_err!("Expected a divisor at {:?}", rest))?;
+            Ok((dim / divisor as u64, rest))
+        } else if let Some((i, rest)) = integer(s) {
+            if rest.starts_with('.') {
+                let (dim, rest) = term(&rest[1..])?;
+                Ok((dim * i, rest))
+            } else {
+                Ok((i.into(), rest))
+            }
+        } else if let Some(c) = s.chars().next().filter(|c| c.is_alphabetic()) {
+            Ok((Symbol::from(c).into(), &s[c.len_utf8()..]))
+        } else {
+            bail!("Unexpected {:?}", s)
+        }
+    }
+    let (dim, rest) = expr(s)?;
+    if !rest.is_empty() {
+        bail!("Unexpected {:?}", rest);
+    }
+    Ok(dim)
+}
+
+#[derive(Debug, Clone, Default, Hash)]
+struct PulsedLocalAttentionState {
+    current_pos: usize,
+}
+
+impl OpState for PulsedLocalAttentionState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (q, k, v) = args_3!(inputs);
+        let op = op
+            .downcast_ref::<PulsedLocalAttention>()
+            .ok_or_else(|| format_err!("Wrong Op type"))?;
+        let q_pos = self.current_pos as isize - op.q_delay as isize;
+        let kv_pos = self.current_pos as isize - op.kv_delay as isize;
+        self.current_pos += q.shape()[q.rank() - 2];
+        let len =
+            op.stream_len.eval(&session.resolved_symbols).to_isize().unwrap_or(std::isize::MAX);
+        Ok(tvec!(op.op.eval_at(&q, q_pos, &k, &v, kv_pos, len)?.into_arc_tensor()))
+    }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        self.current_pos = *args_1!(tensors).to_scalar::<i64>()? as usize;
+        Ok(())
+    }
+}
+
+/// LocalAttention over a stream.
+///
+/// Queries come late by the window lookahead, so the frames they can see are
+/// already there. Keys and values come with enough past frames to cover the
+/// lookback: they are the output of a Delay with overlap, which acts as the
+/// key/value cache.
+#[derive(Debug, Clone, Hash)]
+pub struct PulsedLocalAttention {
+    pub op: LocalAttention,
+    pub q_delay: usize,
+    pub kv_delay: usize,
+    pub stream_len: TDim,
+}
+
+impl_dyn_hash!(PulsedLocalAttention);
+
+impl Op for PulsedLocalAttention {
+    fn name(&self) -> Cow<str> {
+        "PulsedLocalAttention".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let mut info = self.op.info()?;
+        info.push(format!("queries delay: {}, keys/values delay: {}", self.q_delay, self.kv_delay));
+        Ok(info)
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for PulsedLocalAttention {
+    fn is_stateless(&self) -> bool {
+        false
+    }
+
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulsedLocalAttentionState::default())))
+    }
+}
+
+impl TypedOp for PulsedLocalAttention {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let rank = inputs[0].rank();
+        let mut shape = inputs[0].shape.to_tvec();
+        shape[rank - 1] = inputs[2].shape[rank - 1].clone();
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &*shape)))
+    }
+
+    as_op!();
+}
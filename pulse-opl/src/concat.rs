@@ -89,6 +89,15 @@ impl OpState for PulsedSameAxisConcatState {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        self.current_pos = *args_1!(tensors).to_scalar::<i64>()? as usize;
+        Ok(())
+    }
 }
 
 unsafe fn overwrite_part_of_pulse<T: Datum>(
@@ -79,6 +79,15 @@ impl OpState for DelayState {
             Ok(tvec!(output))
         }
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(self.buffer.clone()))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        self.buffer = args_1!(tensors);
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Hash)]
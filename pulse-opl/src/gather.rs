@@ -0,0 +1,69 @@
+use tract_core::ops::array::Gather;
+use tract_nnef::internal::*;
+
+/// A Gather with either its data or its indices embedded as a constant.
+///
+/// Pulsed networks do not carry constant nodes, so the constant operand of
+/// a Gather is folded in the op when the other one is streaming.
+#[derive(Debug, Clone, Hash)]
+pub struct GatherUnary {
+    pub axis: usize,
+    pub konst: Arc<Tensor>,
+    pub konst_is_data: bool,
+}
+
+impl_dyn_hash!(GatherUnary);
+
+impl GatherUnary {
+    pub fn gather(&self) -> Gather {
+        Gather::new(self.axis)
+    }
+
+    fn data_and_indices<'a, T>(&self, konst: &'a T, input: &'a T) -> (&'a T, &'a T) {
+        if self.konst_is_data {
+            (konst, input)
+        } else {
+            (input, konst)
+        }
+    }
+}
+
+impl Op for GatherUnary {
+    fn name(&self) -> Cow<str> {
+        "GatherUnary".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis: {}, constant {}: {:?}",
+            self.axis,
+            if self.konst_is_data { "data" } else { "indices" },
+            self.konst
+        )])
+    }
+
+    op_pulse!();
+    op_as_typed_op!();
+}
+
+impl EvalOp for GatherUnary {
+    fn is_stateless(&self) -> bool {
+        true
+    }
+
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let (data, indices) = self.data_and_indices(&self.konst, &input);
+        self.gather().eval(tvec!(data.clone(), indices.clone()))
+    }
+}
+
+impl TypedOp for GatherUnary {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let konst = TypedFact::from(self.konst.clone());
+        let (data, indices) = self.data_and_indices(&konst, inputs[0]);
+        self.gather().output_facts(&[data, indices])
+    }
+
+    as_op!();
+}
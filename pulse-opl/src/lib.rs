@@ -3,8 +3,10 @@ use tract_nnef::internal::*;
 #[macro_use]
 mod macros;
 
+mod attention;
 mod concat;
 mod delay;
+mod gather;
 mod pad;
 
 pub use tract_nnef;
@@ -16,7 +18,9 @@ pub mod prelude {
 }
 
 pub mod ops {
+    pub use super::attention::PulsedLocalAttention;
     pub use super::delay::Delay;
+    pub use super::gather::GatherUnary;
     pub use super::pad::PulsePad;
 }
 
@@ -34,6 +38,7 @@ impl WithPulse for tract_nnef::framework::Nnef {
 
 pub fn tract_nnef_registry() -> Registry {
     let mut reg = Registry::new("pulse");
+    attention::register(&mut reg);
     delay::register(&mut reg);
     reg
 }
@@ -20,6 +20,20 @@ impl OpState for PulsePadOpState {
         let tensor = self.pad(session, op, input)?;
         Ok(tvec!(tensor.into_arc_tensor()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.current_pos as i64));
+        tensors.extend(self.last_valid_frame.clone());
+        Ok(tensors)
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let mut tensors = tensors.into_iter();
+        let pos = tensors.next().ok_or_else(|| format_err!("Empty PulsePad state"))?;
+        self.current_pos = *pos.to_scalar::<i64>()? as usize;
+        self.last_valid_frame = tensors.next();
+        Ok(())
+    }
 }
 
 impl PulsePadOpState {
@@ -58,16 +72,17 @@ impl PulsePadOpState {
         op: &PulsePad,
         mut input: Tensor,
     ) -> TractResult<Tensor> {
+        let pulse = input.shape()[op.axis];
         let pulse_begin = self.current_pos;
-        let pulse_end = self.current_pos + op.pulse;
-        self.current_pos += op.pulse;
+        let pulse_end = self.current_pos + pulse;
+        self.current_pos += pulse;
         let end_input =
             op.end_input.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
         let after = op.after.eval(&session.resolved_symbols).to_usize().unwrap_or(std::usize::MAX);
 
         if let PadMode::Edge = op.mode {
             if after != 0 && pulse_begin < end_input {
-                let latest_valid_frame = (end_input - pulse_begin).min(op.pulse) - 1;
+                let latest_valid_frame = (end_input - pulse_begin).min(pulse) - 1;
                 unsafe {
                     dispatch_copy_by_size!(Self::save_frame(input.datum_type())(
                         self,
@@ -90,7 +105,7 @@ impl PulsePadOpState {
         }
 
         if pulse_begin < op.begin_input {
-            let fill_up_to = (op.begin_input - pulse_begin).min(op.pulse);
+            let fill_up_to = (op.begin_input - pulse_begin).min(pulse);
             match &op.mode {
                 PadMode::Constant(c) => unsafe {
                     dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
@@ -115,14 +130,14 @@ impl PulsePadOpState {
             }
         }
         if pulse_end > end_input && after > 0 {
-            let fill_from = op.pulse - (pulse_end - end_input).min(op.pulse);
+            let fill_from = pulse - (pulse_end - end_input).min(pulse);
             match &op.mode {
                 PadMode::Constant(c) => unsafe {
                     dispatch_copy_by_size!(Self::fill_slice_constant(input.datum_type())(
                         &mut input,
                         c,
                         op.axis,
-                        fill_from..op.pulse
+                        fill_from..pulse
                     ))
                 },
                 PadMode::Edge => {
@@ -132,7 +147,7 @@ impl PulsePadOpState {
                             &mut input,
                             op.axis,
                             last_frame,
-                            fill_from..op.pulse
+                            fill_from..pulse
                         ))
                     }
                 }
@@ -147,7 +162,6 @@ impl PulsePadOpState {
 #[derive(Debug, Clone, Default, Hash)]
 pub struct PulsePad {
     pub axis: usize,
-    pub pulse: usize,
     pub before: usize,
     pub after: TDim,
     pub begin_input: usize,
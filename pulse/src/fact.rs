@@ -1,7 +1,9 @@
 use crate::internal::*;
 
 lazy_static::lazy_static! {
-    static ref S: Symbol = Symbol::new('S');
+    // shared with symbols parsed back by name, like the ones of NNEF pulsed ops
+    static ref S: Symbol = Symbol::from('S');
+    static ref P: Symbol = Symbol::new('P');
 }
 
 pub fn stream_symbol() -> Symbol {
@@ -12,6 +14,11 @@ pub fn stream_dim() -> TDim {
     (*S).into()
 }
 
+/// Number of base pulses fed by a run of a symbolic pulse model.
+pub fn pulse_symbol() -> Symbol {
+    *P
+}
+
 pub trait StreamFact {
     fn stream_info(&self) -> Option<(usize, &TDim)>;
 }
@@ -43,19 +50,32 @@ pub struct PulsedFact {
 impl_dyn_hash!(PulsedFact);
 
 impl PulsedFact {
-    pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: usize) -> TractResult<PulsedFact> {
+    /// `pulse` applies to the stream symbol: a tensor streaming at another
+    /// rate (like `S/4` or `2*S`) gets a pulse scaled accordingly.
+    pub fn from_tensor_fact_pulse(tf: &TypedFact, pulse: &TDim) -> TractResult<PulsedFact> {
         let datum_type = tf.datum_type;
         let (axis, len) = tf
             .shape
             .stream_info()
             .ok_or_else(|| format_err!("Can not pulse a tensor with no streaming dim"))?;
+        let (num, den) = len.slope(stream_symbol());
+        if num <= 0 || pulse.gcd() * num as u64 % den != 0 {
+            bail!("Can not pulse a stream of length {} with a pulse of {}", len, pulse)
+        }
         let mut shape: TVec<TDim> = tf.shape.iter().collect();
-        shape[axis] = pulse.into();
+        shape[axis] = pulse.clone() * num / den;
         Ok(PulsedFact { datum_type, shape, axis, dim: len.clone(), delay: 0 })
     }
 
-    pub fn pulse(&self) -> usize {
-        self.shape[self.axis].to_usize().expect("Pulse should be an integer. This is a tract bug.")
+    /// Concrete pulse, or None if the pulse is symbolic.
+    pub fn pulse(&self) -> Option<usize> {
+        self.shape[self.axis].to_usize().ok()
+    }
+
+    /// Largest size all pulses are a multiple of: the pulse itself when it
+    /// is concrete, the base pulse when it is symbolic.
+    pub fn base_pulse(&self) -> usize {
+        self.shape[self.axis].gcd() as usize
     }
 
     pub fn to_pulse_fact(&self) -> TypedFact {
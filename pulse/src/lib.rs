@@ -4,6 +4,7 @@ pub mod macros;
 pub mod fact;
 pub mod model;
 pub mod ops;
+pub mod stream;
 
 pub mod internal {
     pub use std::fmt;
@@ -12,15 +13,17 @@ pub mod internal {
 
     pub use downcast_rs::Downcast;
 
-    pub use crate::fact::{stream_dim, stream_symbol, PulsedFact};
+    pub use crate::fact::{pulse_symbol, stream_dim, stream_symbol, PulsedFact};
     pub use crate::model::{PulsedModel, PulsedModelExt};
     pub use crate::ops::{OpPulsifier, PulsedOp};
+    pub use crate::stream::PulsedStream;
     pub use tract_pulse_opl::op_pulse;
 }
 
 use internal::*;
 
 pub use ops::PulsedOp;
+pub use tract_pulse_opl;
 
 pub trait WithPulse {
     fn with_pulse(self) -> Self;
@@ -94,4 +97,58 @@ mod tests {
             TypedFact::dt_shape(DatumType::F32, &[4, 2, 3])
         );
     }
+
+    #[test]
+    fn test_streams_at_different_rates() -> TractResult<()> {
+        use tract_core::ops::array::{Pad, PadMode, Slice, TypedConcat};
+        let mut model = TypedModel::default();
+        let a = model.add_source(
+            "audio",
+            TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref()),
+        )?;
+        let v = model.add_source(
+            "video",
+            TypedFact::dt_shape(f32::datum_type(), [stream_dim().div_ceil(4), 3.to_dim()].as_ref()),
+        )?;
+        // delay audio by 3 frames, which is not a multiple of the downsampling
+        let pad = Pad::new(vec![(3, 0), (0, 0)], PadMode::Constant(rctensor0(0f32)));
+        let a = model.wire_node("pad", pad, &[a])?;
+        let a = model.wire_node("slice", Slice::new(0, 3, stream_dim() + 3), &a)?;
+        let a = model.wire_node("down", tract_core::ops::Downsample::new(0, 4, 0), &a)?;
+        let concat = model.wire_node("concat", TypedConcat::concat_vars(1, 2), &[a[0], v])?;
+        model.set_output_outlets(&concat)?;
+
+        let audio = tensor1(&(0..32).map(|i| i as f32).collect::<Vec<_>>()).into_shape(&[16, 2])?;
+        let video = tensor1(&(0..12).map(|i| -i as f32).collect::<Vec<_>>()).into_shape(&[4, 3])?;
+        let offline = model
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 16))?
+            .into_runnable()?
+            .run(tvec!(audio.clone(), video.clone()))?;
+
+        let pulsed = PulsedModel::new(&model, 8)?;
+        assert_eq!(pulsed.input_fact(0)?.pulse(), Some(8));
+        assert_eq!(pulsed.input_fact(1)?.pulse(), Some(2));
+        let output_fact = pulsed.output_fact(0)?.clone();
+        assert_eq!(output_fact.pulse(), Some(2));
+        assert_eq!(output_fact.delay, 1);
+
+        let typed = pulsed.into_typed()?;
+        assert_eq!(*typed.properties["pulse.input_pulses"], tensor1(&[8i64, 2]));
+        assert_eq!(*typed.properties["pulse.output_pulses"], tensor1(&[2i64]));
+        assert_eq!(*typed.properties["pulse.delay"], tensor1(&[1i64]));
+
+        let plan = typed.into_runnable()?;
+        let mut state = SimpleState::new(&plan)?;
+        let audio = Tensor::stack_tensors(0, &[&audio, &Tensor::zero::<f32>(&[8, 2])?])?;
+        let video = Tensor::stack_tensors(0, &[&video, &Tensor::zero::<f32>(&[2, 3])?])?;
+        let mut got = vec![];
+        for i in 0..3 {
+            let a = audio.slice(0, 8 * i, 8 * (i + 1))?;
+            let v = video.slice(0, 2 * i, 2 * (i + 1))?;
+            got.push(state.run(tvec!(a, v))?.remove(0).into_tensor());
+        }
+        let got = Tensor::stack_tensors(0, &got)?.slice(0, 1, 5)?;
+        assert_eq!(got, *offline[0]);
+        Ok(())
+    }
 }
@@ -7,16 +7,22 @@ macro_rules! pulsed_op_to_typed_op {
     };
 }
 
+#[macro_export]
 macro_rules! submit_op_pulsifier {
     ($op: ty, $func: expr) => {
-        inventory::submit!(OpPulsifier {
+        inventory::submit!($crate::internal::OpPulsifier {
             type_id: std::any::TypeId::of::<$op>(),
-            func: |source: &TypedModel,
-                   node: &TypedNode,
-                   target: &mut PulsedModel,
-                   mapping: &HashMap<OutletId, OutletId>,
-                   pulse: usize|
-             -> TractResult<TVec<OutletId>> {
+            func: |source: &$crate::internal::TypedModel,
+                   node: &$crate::internal::TypedNode,
+                   target: &mut $crate::internal::PulsedModel,
+                   mapping: &$crate::internal::HashMap<
+                $crate::internal::OutletId,
+                $crate::internal::OutletId,
+            >,
+                   pulse: &$crate::internal::TDim|
+             -> $crate::internal::TractResult<
+                $crate::internal::TVec<$crate::internal::OutletId>,
+            > {
                 let op = node.op_as::<$op>().unwrap();
                 ($func)(op, source, node, target, mapping, pulse)
             },
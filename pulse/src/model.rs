@@ -7,11 +7,18 @@ pub type PulsedNode = Node<PulsedFact, Box<dyn PulsedOp>>;
 pub trait PulsedModelExt {
     fn new(source: &TypedModel, pulse: usize) -> TractResult<PulsedModel>;
 
+    /// Pulsify with a pulse of `base * P`: the resulting plan accepts any
+    /// multiple of `base` frames on each run.
+    fn new_with_symbolic_pulse(source: &TypedModel, base: usize) -> TractResult<PulsedModel>;
+
     fn new_with_mapping(
         source: &TypedModel,
-        pulse: usize,
+        pulse: impl Into<TDim>,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)>;
 
+    /// Streaming metadata goes to the model properties, one value per input
+    /// or output: `pulse.{input,output}_axes`, `pulse.{input,output}_pulses`
+    /// (the base pulse if the pulse is symbolic) and `pulse.delay` for outputs.
     fn into_typed(self) -> TractResult<TypedModel>;
 }
 
@@ -20,23 +27,35 @@ impl PulsedModelExt for PulsedModel {
         Ok(PulsedModel::new_with_mapping(source, pulse)?.0)
     }
 
+    fn new_with_symbolic_pulse(source: &TypedModel, base: usize) -> TractResult<PulsedModel> {
+        Ok(PulsedModel::new_with_mapping(source, TDim::from(pulse_symbol()) * base)?.0)
+    }
+
     fn new_with_mapping(
         source: &TypedModel,
-        pulse: usize,
+        pulse: impl Into<TDim>,
     ) -> TractResult<(PulsedModel, HashMap<OutletId, OutletId>)> {
-        Pulsifier(pulse).translate_model_with_mappings(source)
+        Pulsifier(pulse.into()).translate_model_with_mappings(source)
     }
 
     fn into_typed(self) -> TractResult<TypedModel> {
         let mut typed = tract_core::model::translator::IntoTranslator.translate_model(&self)?;
-        let delays = tensor1(
-            &self
-                .output_outlets()?
+        let inputs = self.input_outlets()?.to_vec();
+        let outputs = self.output_outlets()?.to_vec();
+        let properties: &[(&str, &[OutletId], fn(&PulsedFact) -> usize)] = &[
+            ("pulse.delay", &outputs, |f| f.delay),
+            ("pulse.input_axes", &inputs, |f| f.axis),
+            ("pulse.input_pulses", &inputs, |f| f.base_pulse()),
+            ("pulse.output_axes", &outputs, |f| f.axis),
+            ("pulse.output_pulses", &outputs, |f| f.base_pulse()),
+        ];
+        for (name, outlets, value) in properties {
+            let values = outlets
                 .iter()
-                .map(|oo| Ok(self.outlet_fact(*oo)?.delay as _))
-                .collect::<TractResult<TVec<i64>>>()?,
-        );
-        typed.properties.insert("pulse.delay".to_string(), delays.into_arc_tensor());
+                .map(|o| Ok(value(self.outlet_fact(*o)?) as i64))
+                .collect::<TractResult<Vec<i64>>>()?;
+            typed.properties.insert(name.to_string(), tensor1(&values).into_arc_tensor());
+        }
         Ok(typed)
     }
 }
@@ -76,7 +95,7 @@ impl SpecialOps<PulsedFact, Box<dyn PulsedOp>> for PulsedModel {
 }
 
 #[derive(Debug)]
-struct Pulsifier(usize);
+struct Pulsifier(TDim);
 
 impl
     tract_core::model::translator::Translate<
@@ -96,7 +115,7 @@ impl
         if let Some(pulsifier) =
             inventory::iter::<crate::ops::OpPulsifier>().find(|p| p.type_id == node.op.type_id())
         {
-            (pulsifier.func)(source, node, target, mapping, self.0)
+            (pulsifier.func)(source, node, target, mapping, &self.0)
         } else {
             bail!("No pulsifier for {}", node);
         }
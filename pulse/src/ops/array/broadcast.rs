@@ -0,0 +1,35 @@
+use crate::internal::*;
+use tract_core::ops::array::MultiBroadcastTo;
+
+submit_op_pulsifier!(MultiBroadcastTo, pulsify);
+
+fn pulsify(
+    op: &MultiBroadcastTo,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?;
+    let axis = fact.axis + op.shape.len() - fact.shape.len();
+    if op.shape[axis] != fact.dim {
+        bail!("Can not pulsify MultiBroadcastTo along the streaming axis");
+    }
+    let mut shape = op.shape.clone();
+    shape[axis] = fact.shape[fact.axis].clone();
+    target.wire_node(&*node.name, MultiBroadcastTo::new(shape), &[input])
+}
+
+impl PulsedOp for MultiBroadcastTo {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.axis += self.shape.len() - fact.shape.len();
+        fact.shape = self.shape.clone();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
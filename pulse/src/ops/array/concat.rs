@@ -12,19 +12,18 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
-    if node.inputs.len() > 1 {
-        bail!("Pulsification not implemented for more than one input to Concat")
-    }
-
     let input = mapping[&node.inputs[0]];
     let fact = target.outlet_fact(input)?;
 
     if fact.axis == op.axis {
         pulsify_along_concat_axis(op, source, node, target, mapping)
+    } else if op.slices.iter().all(|s| s.is_var()) {
+        let inputs = crate::ops::binary::sync_inputs(node, target, mapping)?;
+        target.wire_node(&*node.name, op.clone(), &inputs)
     } else {
-        bail!("Pulsify for Concat on a separate axis is not implemented (but possible)");
+        bail!("Pulsify for Concat with constants on a separate axis is not implemented");
     }
 }
 
@@ -85,6 +84,17 @@ impl PulsedOp for PulsedSameAxisConcat {
     pulsed_op_to_typed_op!();
 }
 
+impl PulsedOp for TypedConcat {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[self.axis] = inputs.iter().map(|f| f.shape[self.axis].clone()).sum();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
 /// Concat with pulse along concat axis
 #[derive(Debug, Clone, Hash)]
 pub struct PulsedSameAxisConcat {
@@ -173,6 +183,15 @@ impl OpState for PulsedSameAxisConcatState {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        self.current_pos = *args_1!(tensors).to_scalar::<i64>()? as usize;
+        Ok(())
+    }
 }
 
 pub fn overwrite_part_of_pulse<T: Datum>(
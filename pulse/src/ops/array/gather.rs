@@ -0,0 +1,50 @@
+use crate::internal::*;
+use tract_core::ops::array::Gather;
+use tract_pulse_opl::ops::GatherUnary;
+
+submit_op_pulsifier!(Gather, pulsify);
+
+fn pulsify(
+    op: &Gather,
+    source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let (input, op) = if let Some(data) = source.outlet_fact(node.inputs[0])?.konst.clone() {
+        (node.inputs[1], GatherUnary { axis: op.axis, konst: data, konst_is_data: true })
+    } else if let Some(indices) = source.outlet_fact(node.inputs[1])?.konst.clone() {
+        (node.inputs[0], GatherUnary { axis: op.axis, konst: indices, konst_is_data: false })
+    } else {
+        bail!("Can not pulsify Gather with both data and indices streaming")
+    };
+    let input = mapping[&input];
+    if !op.konst_is_data && target.outlet_fact(input)?.axis == op.axis {
+        bail!("Can not pulsify Gather along the gathered axis");
+    }
+    target.wire_node(&*node.name, op, &[input])
+}
+
+impl PulsedOp for GatherUnary {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let konst_shape: TVec<TDim> = self.konst.shape().iter().map(|d| d.to_dim()).collect();
+        let shape = if self.konst_is_data {
+            self.gather().compute_output_shape(&*konst_shape, &*fact.shape)?
+        } else {
+            self.gather().compute_output_shape(&*fact.shape, &*konst_shape)?
+        };
+        if self.konst_is_data {
+            fact.datum_type = self.konst.datum_type();
+            fact.axis += self.axis;
+        } else if fact.axis > self.axis {
+            fact.axis = fact.axis + self.konst.rank() - 1;
+        }
+        fact.shape = shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -1,3 +1,7 @@
+mod broadcast;
 mod concat;
+mod gather;
+mod one_hot;
 mod pad;
 mod slice;
+mod tile;
@@ -0,0 +1,31 @@
+use crate::internal::*;
+use tract_core::ops::array::OneHot;
+
+submit_op_pulsifier!(OneHot, pulsify);
+
+fn pulsify(
+    op: &OneHot,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+impl PulsedOp for OneHot {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.datum_type = self.off.datum_type();
+        fact.shape.insert(self.axis, self.dim.to_dim());
+        if fact.axis >= self.axis {
+            fact.axis += 1;
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -0,0 +1,31 @@
+use crate::internal::*;
+use tract_core::ops::array::Tile;
+
+submit_op_pulsifier!(Tile, pulsify);
+
+fn pulsify(
+    op: &Tile,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    if op.multipliers[target.outlet_fact(input)?.axis] != 1 {
+        bail!("Can not pulsify Tile along the streaming axis");
+    }
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+impl PulsedOp for Tile {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape =
+            fact.shape.iter().zip(self.multipliers.iter()).map(|(d, &m)| d.clone() * m).collect();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -7,11 +7,26 @@ submit_op_pulsifier!(UnaryOp, pulsify_un);
 submit_op_pulsifier!(TypedBinOp, pulsify_bin);
 submit_op_pulsifier!(Iff, pulsify_iff);
 
-fn sync_inputs(
+pub(crate) fn sync_inputs(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
 ) -> TractResult<TVec<OutletId>> {
+    let facts = node
+        .inputs
+        .iter()
+        .map(|input| target.outlet_fact(mapping[input]))
+        .collect::<TractResult<TVec<_>>>()?;
+    for fact in &facts[1..] {
+        if fact.shape[fact.axis] != facts[0].shape[facts[0].axis] {
+            bail!(
+                "{} combines streams with different rates (pulses {} and {})",
+                node,
+                facts[0].shape[facts[0].axis],
+                fact.shape[fact.axis]
+            )
+        }
+    }
     let delay = node
         .inputs
         .iter()
@@ -41,7 +56,7 @@ fn pulsify_bin(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let inputs = &*sync_inputs(node, target, mapping)?;
     target.wire_node(&*node.name, op.clone(), &inputs)
@@ -68,7 +83,7 @@ fn pulsify_un(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let input = mapping[&node.inputs[0]];
     target.wire_node(&*node.name, op.clone(), &[input])
@@ -97,7 +112,7 @@ fn pulsify_iff(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let inputs = &*sync_inputs(node, target, mapping)?;
     target.wire_node(&*node.name, op.clone(), &inputs)
@@ -11,7 +11,7 @@ fn pulsify_max_pool(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     fn min_value<D: Datum + tract_core::num_traits::Bounded>() -> Tensor {
         tensor0(D::min_value())
@@ -28,7 +28,7 @@ fn pulsify_sum_pool(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let (wire, pool_spec) = pulsify(&op.pool_spec, source, node, target, mapping, None)?;
     target.wire_node(&node.name, SumPool { pool_spec, ..op.clone() }, &[wire])
@@ -106,7 +106,7 @@ pub fn pulsify(
 
     let geo_axis = fact.axis - input_shape.h_axis();
     let stride = spec.strides.as_ref().and_then(|v| v.get(geo_axis).cloned()).unwrap_or(1);
-    let pulse = fact.pulse();
+    let pulse = fact.base_pulse();
     if pulse % stride != 0 {
         bail!("Pulsificaton requires pulse to be a stride multiple")
     }
@@ -140,7 +140,6 @@ pub fn pulsify(
         }
         let op = tract_pulse_opl::ops::PulsePad {
             axis: fact.axis,
-            pulse,
             before,
             after: computed_padding.pad_after.clone(),
             begin_input: fact.delay,
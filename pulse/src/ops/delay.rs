@@ -117,4 +117,46 @@ mod test {
             assert_eq!(&output[0].as_slice::<u8>().unwrap()[skip..], &expect[skip..]);
         }
     }
+
+    #[test]
+    fn snapshot_and_restore() {
+        use tract_pulse_opl::tract_nnef::tensors::{read_snapshot, write_snapshot};
+        let pulse = 4usize;
+        let mut model = PulsedModel::default();
+        let fact = PulsedFact {
+            datum_type: u8::datum_type(),
+            shape: tvec![pulse.to_dim()],
+            axis: 0,
+            dim: stream_dim(),
+            delay: 0,
+        };
+        let source = model.add_source("source", fact.clone()).unwrap();
+        model.wire_node("delay", Delay::new(fact.axis, &(&fact).into(), 5, 2), &[source]).unwrap();
+        model.auto_outputs().unwrap();
+
+        let plan = SimplePlan::new(model).unwrap();
+        let mut state = tract_core::plan::SimpleState::new(&plan).unwrap();
+        let input =
+            |i: usize| tensor1(&(pulse * i..pulse * (i + 1)).map(|a| a as u8).collect::<Vec<_>>());
+        for i in 0..2 {
+            state.run(tvec!(input(i))).unwrap();
+        }
+        let snapshot = state.snapshot().unwrap();
+        let expected: Vec<_> = (2..5).map(|i| state.run(tvec!(input(i))).unwrap()).collect();
+
+        // rewind
+        state.restore(&snapshot).unwrap();
+        for (i, expected) in (2..5).zip(&expected) {
+            assert_eq!(&state.run(tvec!(input(i))).unwrap(), expected);
+        }
+
+        // migrate
+        let mut blob = vec![];
+        write_snapshot(&mut blob, &snapshot).unwrap();
+        let mut migrated = tract_core::plan::SimpleState::new(&plan).unwrap();
+        migrated.restore(&read_snapshot(&*blob).unwrap()).unwrap();
+        for (i, expected) in (2..5).zip(&expected) {
+            assert_eq!(&migrated.run(tvec!(input(i))).unwrap(), expected);
+        }
+    }
 }
@@ -1,5 +1,6 @@
 use crate::internal::*;
 use tract_core::ops::Downsample;
+use tract_pulse_opl::ops::Delay;
 
 submit_op_pulsifier!(Downsample, pulsify);
 
@@ -9,26 +10,48 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
-    let input = mapping[&node.inputs[0]];
-    let pulse = target.outlet_fact(input)?.pulse();
+    let mut input = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(input)?.clone();
+    if fact.axis != op.axis {
+        return target.wire_node(&*node.name, op.clone(), &[input]);
+    }
     let stride = if op.stride > 0 {
         op.stride as usize
     } else {
         bail!("Negative strides are not causal, can not pulsify.")
     };
-    if pulse % stride != 0 {
+    if fact.base_pulse() % stride != 0 {
         bail!("Pulsificaton requires pulse to be a stride multiple")
     }
+    if op.modulo >= stride {
+        bail!("Pulsification requires downsample modulo to be smaller than stride")
+    }
+    // kept frames must be at the same place in every pulse
+    let misalignment = fact.delay % stride;
+    if misalignment > 0 {
+        input = target.wire_node(
+            format!("{}.Delay", node.name),
+            Delay::new(fact.axis, &(&fact).into(), stride - misalignment, 0),
+            &[input],
+        )?[0];
+    }
     target.wire_node(&*node.name, op.clone(), &[input])
 }
 
 impl PulsedOp for Downsample {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
         let mut fact = inputs[0].clone();
-        fact.shape[self.axis] /= self.stride as usize;
-        fact.dim = fact.dim.div_ceil(self.stride as _);
+        let stride = self.stride as usize;
+        if self.axis == fact.axis {
+            fact.shape[self.axis] /= stride;
+            fact.dim = (fact.dim - self.modulo).div_ceil(stride as _);
+            fact.delay /= stride;
+        } else {
+            fact.shape[self.axis] =
+                (fact.shape[self.axis].clone() - self.modulo).div_ceil(stride as _);
+        }
         Ok(tvec!(fact))
     }
 
@@ -0,0 +1,27 @@
+use crate::internal::*;
+use tract_core::ops::array::Gather;
+use tract_core::ops::konst::Const;
+
+submit_op_pulsifier!(Const, pulsify);
+
+/// Constants are not wired in a pulsed network: the ops consuming them fold
+/// them in their pulsed form.
+fn pulsify(
+    _op: &Const,
+    source: &TypedModel,
+    node: &TypedNode,
+    _target: &mut PulsedModel,
+    _mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    if source.output_outlets()?.contains(&OutletId::new(node.id, 0)) {
+        bail!("Can not pulsify a constant output");
+    }
+    for succ in &node.outputs[0].successors {
+        let succ = source.node(succ.node);
+        if !succ.op_is::<Gather>() {
+            bail!("Can not pulsify constant {} as an input of {}", node, succ);
+        }
+    }
+    Ok(tvec!())
+}
@@ -1,7 +1,8 @@
 use crate::internal::*;
-use tract_core::ops::matmul::MatMulUnary;
+use tract_core::ops::matmul::{MatMul, MatMulUnary};
 
 submit_op_pulsifier!(MatMulUnary, pulsify);
+submit_op_pulsifier!(MatMul, pulsify_mat_mul);
 
 fn pulsify(
     op: &MatMulUnary,
@@ -9,7 +10,7 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let input = mapping[&node.inputs[0]];
     let fact = target.outlet_fact(input)?;
@@ -37,3 +38,51 @@ impl PulsedOp for MatMulUnary {
     as_op!();
     pulsed_op_to_typed_op!();
 }
+
+fn pulsify_mat_mul(
+    op: &MatMul,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let inputs = super::binary::sync_inputs(node, target, mapping)?;
+    let facts = [target.outlet_fact(inputs[0])?, target.outlet_fact(inputs[1])?];
+    let c_rank = facts[0].shape.len().max(facts[1].shape.len());
+    let c_axes = facts
+        .iter()
+        .map(|fact| {
+            if fact.axis + 2 >= fact.shape.len() {
+                bail!("Can not pulsify MatMul along a matrix axis, only along a broadcast axis");
+            }
+            Ok(fact.axis + c_rank - fact.shape.len())
+        })
+        .collect::<TractResult<TVec<usize>>>()?;
+    if c_axes[0] != c_axes[1] {
+        bail!("Can not pulsify MatMul with operands streaming along different axes");
+    }
+    target.wire_node(&*node.name, op.clone(), &inputs)
+}
+
+impl PulsedOp for MatMul {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let (_m, _k, _n, c_shape) = tract_core::ops::matmul::compute_shape(
+            &inputs[0].shape,
+            &inputs[1].shape,
+            self.a_trans,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        fact.datum_type = self.q_params.as_ref().map(|qp| qp.c_datum_type).unwrap_or_else(|| {
+            tract_core::ops::matmul::output_type(inputs[0].datum_type, inputs[1].datum_type)
+        });
+        fact.axis += c_shape.len() - inputs[0].shape.len();
+        fact.shape = c_shape;
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
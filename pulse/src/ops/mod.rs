@@ -8,10 +8,12 @@ pub mod delay;
 pub mod downsample;
 pub mod dummy;
 pub mod element_wise;
+pub mod konst;
 pub mod matmul;
 pub mod nn;
 pub mod quant;
 pub mod scan;
+pub mod signal;
 pub mod source;
 
 inventory::collect!(OpPulsifier);
@@ -23,7 +25,7 @@ pub struct OpPulsifier {
         &TypedNode,
         &mut PulsedModel,
         &HashMap<OutletId, OutletId>,
-        usize,
+        &TDim,
     ) -> TractResult<TVec<OutletId>>,
 }
 
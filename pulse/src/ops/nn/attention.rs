@@ -0,0 +1,61 @@
+use crate::internal::*;
+use tract_core::ops::nn::LocalAttention;
+use tract_pulse_opl::ops::{Delay, PulsedLocalAttention};
+
+submit_op_pulsifier!(LocalAttention, pulsify);
+
+fn pulsify(
+    op: &LocalAttention,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let mut inputs = crate::ops::binary::sync_inputs(node, target, mapping)?;
+    let fact = target.outlet_fact(inputs[0])?.clone();
+    for input in &inputs {
+        let input_fact = target.outlet_fact(*input)?;
+        if input_fact.axis + 2 != input_fact.shape.len() {
+            bail!("LocalAttention can only be pulsified along its time axis");
+        }
+    }
+    let lookback = op.window.lookback();
+    let lookahead = op.window.lookahead();
+    if lookahead > 0 {
+        inputs[0] = target.wire_node(
+            format!("{}.q.Delay", node.name),
+            Delay::new(fact.axis, &(&fact).into(), lookahead, 0),
+            &[inputs[0]],
+        )?[0];
+    }
+    if lookback + lookahead > 0 {
+        for (ix, name) in [(1, "k"), (2, "v")].iter() {
+            let input_fact = target.outlet_fact(inputs[*ix])?.clone();
+            inputs[*ix] = target.wire_node(
+                format!("{}.{}.Delay", node.name, name),
+                Delay::new(fact.axis, &(&input_fact).into(), 0, lookback + lookahead),
+                &[inputs[*ix]],
+            )?[0];
+        }
+    }
+    let op = PulsedLocalAttention {
+        op: op.clone(),
+        q_delay: fact.delay + lookahead,
+        kv_delay: fact.delay + lookback + lookahead,
+        stream_len: fact.dim.clone(),
+    };
+    target.wire_node(&*node.name, op, &inputs)
+}
+
+impl PulsedOp for PulsedLocalAttention {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        let rank = fact.shape.len();
+        fact.shape[rank - 1] = inputs[2].shape[rank - 1].clone();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
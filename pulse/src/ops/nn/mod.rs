@@ -1 +1,3 @@
+mod attention;
 mod reduce;
+mod softmax;
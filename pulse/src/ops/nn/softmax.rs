@@ -0,0 +1,29 @@
+use crate::internal::*;
+use tract_core::ops::nn::Softmax;
+
+submit_op_pulsifier!(Softmax, pulsify);
+
+fn pulsify(
+    op: &Softmax,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    let axis = target.outlet_fact(input)?.axis;
+    if op.axes.contains(&axis) {
+        bail!("Can not compute softmax over streaming axis");
+    }
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+impl PulsedOp for Softmax {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
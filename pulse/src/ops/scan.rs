@@ -9,7 +9,7 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     mapping: &HashMap<OutletId, OutletId>,
-    _pulse: usize,
+    _pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     for input_id in 0..node.inputs.len() {
         let input = mapping[&node.inputs[input_id]];
@@ -51,15 +51,13 @@ impl PulsedOp for Scan {
             .shape
             .iter()
             .enumerate()
-            .map(
-                |(axis, d)| {
-                    if axis == output_mapping.axis {
-                        inputs[0].pulse().to_dim()
-                    } else {
-                        d
-                    }
-                },
-            )
+            .map(|(axis, d)| {
+                if axis == output_mapping.axis {
+                    inputs[0].shape[inputs[0].axis].clone()
+                } else {
+                    d
+                }
+            })
             .collect();
         let fact = PulsedFact {
             datum_type: output_body_fact.datum_type,
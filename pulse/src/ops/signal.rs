@@ -0,0 +1,78 @@
+use crate::internal::*;
+use tract_core::ops::signal::{Dft, Frame};
+
+submit_op_pulsifier!(Frame, pulsify_frame);
+submit_op_pulsifier!(Dft, pulsify_dft);
+
+fn pulsify_frame(
+    op: &Frame,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let mut wire = mapping[&node.inputs[0]];
+    let fact = target.outlet_fact(wire)?.clone();
+    if fact.axis == op.axis {
+        let pulse = fact.base_pulse();
+        if pulse % op.frame_step != 0 {
+            bail!("Pulsification requires pulse to be a frame step multiple")
+        }
+        let overlap = op.frame_length.saturating_sub(op.frame_step);
+        if overlap > 0 || fact.delay % op.frame_step > 0 {
+            let align_to = (overlap + fact.delay).div_ceil(op.frame_step) * op.frame_step;
+            let delay = align_to - overlap - fact.delay;
+            wire = target.wire_node(
+                format!("{}.delay", node.name),
+                tract_pulse_opl::ops::Delay::new(fact.axis, &(&fact).into(), delay, overlap),
+                &[wire],
+            )?[0];
+        }
+    }
+    target.wire_node(&*node.name, op.clone(), &[wire])
+}
+
+impl PulsedOp for Frame {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[self.axis] = self.frame_count(&fact.shape[self.axis]);
+        fact.shape.insert(self.axis + 1, self.frame_length.to_dim());
+        if fact.axis == self.axis {
+            fact.delay /= self.frame_step;
+            fact.dim = self.frame_count(&fact.dim);
+        } else if fact.axis > self.axis {
+            fact.axis += 1;
+        }
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+fn pulsify_dft(
+    op: &Dft,
+    _source: &TypedModel,
+    node: &TypedNode,
+    target: &mut PulsedModel,
+    mapping: &HashMap<OutletId, OutletId>,
+    _pulse: &TDim,
+) -> TractResult<TVec<OutletId>> {
+    let input = mapping[&node.inputs[0]];
+    if target.outlet_fact(input)?.axis == op.axis {
+        bail!("Can not pulsify Dft along the streaming axis")
+    }
+    target.wire_node(&*node.name, op.clone(), &[input])
+}
+
+impl PulsedOp for Dft {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape = self.output_facts(&[&inputs[0].to_pulse_fact()])?.remove(0).shape.to_tvec();
+        Ok(tvec!(fact))
+    }
+
+    as_op!();
+    pulsed_op_to_typed_op!();
+}
@@ -9,7 +9,7 @@ fn pulsify(
     node: &TypedNode,
     target: &mut PulsedModel,
     _mapping: &HashMap<OutletId, OutletId>,
-    pulse: usize,
+    pulse: &TDim,
 ) -> TractResult<TVec<OutletId>> {
     let pulsed_fact = PulsedFact::from_tensor_fact_pulse(&node.outputs[0].fact, pulse)?;
     let id = target.add_source(node.name.clone(), pulsed_fact)?;
@@ -0,0 +1,387 @@
+use crate::internal::*;
+use std::borrow::Borrow;
+
+#[derive(Debug, Clone)]
+struct OutputStream {
+    axis: usize,
+    delay: usize,
+    len: TDim,
+    /// shape of an empty output
+    shape: TVec<usize>,
+    datum_type: DatumType,
+    /// frames produced by the plan so far, delay included
+    produced: usize,
+}
+
+#[derive(Debug, Clone)]
+struct InputStream {
+    axis: usize,
+    /// frames of this input in one (base) pulse
+    pulse: usize,
+    dim: TDim,
+    pending: Tensor,
+    filler: Tensor,
+    /// frames given to the plan so far
+    fed: usize,
+}
+
+/// Run a pulsed model over a finite stream.
+///
+/// Inputs can be pushed in chunks of any length, one tensor per model input.
+/// The plan runs as soon as a pulse of every input is available. Once the
+/// stream is over, `flush` pads the last pulses with a filler (zero unless set
+/// by `with_filler`) and keeps running the plan until delays, paddings,
+/// convolutions and poolings have emitted their right context, with the
+/// offline model padding semantics. Outputs come without the pulse delay, and
+/// are trimmed to the offline output length.
+///
+/// With a symbolic pulse model (see `PulsedModelExt::new_with_symbolic_pulse`)
+/// each `push` runs the plan once, over as many base pulses as available.
+#[derive(Debug)]
+pub struct PulsedStream<P>
+where
+    P: Borrow<TypedSimplePlan<TypedModel>> + Clone,
+{
+    pub state: TypedSimpleState<TypedModel, P>,
+    symbolic_pulse: bool,
+    finished: bool,
+    inputs: TVec<InputStream>,
+    outputs: TVec<OutputStream>,
+}
+
+impl<P> PulsedStream<P>
+where
+    P: Borrow<TypedSimplePlan<TypedModel>> + Clone,
+{
+    /// `plan` must run `model`, once converted by `into_typed`, and maybe
+    /// optimized.
+    pub fn new(model: &PulsedModel, plan: P) -> TractResult<PulsedStream<P>> {
+        let mut symbolic_pulse = false;
+        let inputs = model
+            .input_outlets()?
+            .iter()
+            .map(|outlet| {
+                let fact = model.outlet_fact(*outlet)?;
+                symbolic_pulse |= fact.shape[fact.axis].to_usize().is_err();
+                Ok(InputStream {
+                    axis: fact.axis,
+                    pulse: fact.base_pulse(),
+                    dim: fact.dim.clone(),
+                    pending: Tensor::zero_dt(
+                        fact.datum_type,
+                        &empty_shape(&fact.shape, fact.axis)?,
+                    )?,
+                    filler: Tensor::zero_dt(fact.datum_type, &[])?,
+                    fed: 0,
+                })
+            })
+            .collect::<TractResult<_>>()?;
+        let outputs = model
+            .output_outlets()?
+            .iter()
+            .map(|outlet| {
+                let fact = model.outlet_fact(*outlet)?;
+                Ok(OutputStream {
+                    axis: fact.axis,
+                    delay: fact.delay,
+                    len: fact.dim.clone(),
+                    shape: empty_shape(&fact.shape, fact.axis)?,
+                    datum_type: fact.datum_type,
+                    produced: 0,
+                })
+            })
+            .collect::<TractResult<_>>()?;
+        Ok(PulsedStream {
+            state: TypedSimpleState::new(plan)?,
+            symbolic_pulse,
+            finished: false,
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Value used to pad the inputs after the end of the stream. Frames
+    /// computed from the padding are never output, so any value must give the
+    /// same result.
+    pub fn with_filler(mut self, filler: Tensor) -> TractResult<PulsedStream<P>> {
+        if filler.rank() != 0 {
+            bail!("Filler must be a scalar, got {:?}", filler);
+        }
+        for input in &mut self.inputs {
+            input.filler = filler.cast_to_dt(input.pending.datum_type())?.into_owned();
+        }
+        Ok(self)
+    }
+
+    /// Feed input frames, one tensor per input, returns the output frames they
+    /// allowed to compute.
+    pub fn push(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Tensor>> {
+        if self.finished {
+            bail!("Stream is already flushed")
+        }
+        if inputs.len() != self.inputs.len() {
+            bail!("Stream expects {} inputs, got {}", self.inputs.len(), inputs.len())
+        }
+        for (stream, input) in self.inputs.iter_mut().zip(inputs) {
+            stream.pending = Tensor::stack_tensors(stream.axis, &[&stream.pending, &input])?;
+        }
+        let pulses = self
+            .inputs
+            .iter()
+            .map(|input| input.pending.shape()[input.axis] / input.pulse)
+            .min()
+            .unwrap_or(0);
+        let mut chunks = vec![vec![]; self.outputs.len()];
+        if pulses == 0 {
+            return self.collect(chunks);
+        }
+        let mut taken = tvec!();
+        for input in &mut self.inputs {
+            let (axis, len) = (input.axis, pulses * input.pulse);
+            taken.push(input.pending.slice(axis, 0, len)?);
+            input.pending = input.pending.slice(axis, len, input.pending.shape()[axis])?;
+            input.fed += len;
+        }
+        if self.symbolic_pulse {
+            self.run_pulse(taken, &mut chunks)?;
+        } else {
+            for p in 0..pulses {
+                let inputs = self
+                    .inputs
+                    .iter()
+                    .zip(&taken)
+                    .map(|(input, t)| t.slice(input.axis, p * input.pulse, (p + 1) * input.pulse))
+                    .collect::<TractResult<_>>()?;
+                self.run_pulse(inputs, &mut chunks)?;
+            }
+        }
+        self.collect(chunks)
+    }
+
+    /// Signal the end of the stream, returns the last output frames.
+    pub fn flush(&mut self) -> TractResult<TVec<Tensor>> {
+        if self.finished {
+            bail!("Stream is already flushed")
+        }
+        self.finished = true;
+        let len = self.stream_len()?;
+        self.state.session_state.resolved_symbols[stream_symbol()] = Some(len as i64);
+        // pending frames may span several pulses if inputs were pushed unevenly
+        let pulses = self
+            .inputs
+            .iter()
+            .map(|input| (input.pending.shape()[input.axis] + input.pulse - 1) / input.pulse)
+            .max()
+            .unwrap_or(0);
+        let padded = self
+            .inputs
+            .iter()
+            .map(|input| {
+                let mut shape: TVec<usize> = input.pending.shape().into();
+                shape[input.axis] = pulses * input.pulse - shape[input.axis];
+                let filler = input.filler.broadcast_scalar_to_shape(&shape)?;
+                Tensor::stack_tensors(input.axis, &[&input.pending, &filler])
+            })
+            .collect::<TractResult<TVec<_>>>()?;
+        let mut chunks = vec![vec![]; self.outputs.len()];
+        let mut p = 0;
+        while !self.outputs_complete()? {
+            let inputs = self
+                .inputs
+                .iter()
+                .zip(&padded)
+                .map(|(input, padded)| {
+                    if p < pulses {
+                        padded.slice(input.axis, p * input.pulse, (p + 1) * input.pulse)
+                    } else {
+                        let mut shape: TVec<usize> = padded.shape().into();
+                        shape[input.axis] = input.pulse;
+                        input.filler.broadcast_scalar_to_shape(&shape)
+                    }
+                })
+                .collect::<TractResult<_>>()?;
+            self.run_pulse(inputs, &mut chunks)?;
+            p += 1;
+        }
+        self.collect(chunks)
+    }
+
+    /// Value of the stream symbol matching the length of all the inputs.
+    fn stream_len(&self) -> TractResult<usize> {
+        let lens: TVec<usize> =
+            self.inputs.iter().map(|input| input.fed + input.pending.shape()[input.axis]).collect();
+        // input lengths may be rounded, look around the exact ratio
+        let (mut lo, mut hi) = (std::usize::MAX, 0);
+        for (input, len) in self.inputs.iter().zip(&lens) {
+            let (num, den) = input.dim.slope(stream_symbol());
+            let estimate = *len * den as usize / num.max(1) as usize;
+            lo = lo.min(estimate.saturating_sub(den as usize));
+            hi = hi.max(estimate + den as usize);
+        }
+        for s in lo..=hi {
+            let values = SymbolValues::default().with(stream_symbol(), s as i64);
+            if self
+                .inputs
+                .iter()
+                .zip(&lens)
+                .all(|(input, len)| input.dim.eval(&values).to_usize().ok() == Some(*len))
+            {
+                return Ok(s);
+            }
+        }
+        bail!("Input lengths {:?} do not match a common stream length", lens)
+    }
+
+    fn outputs_complete(&self) -> TractResult<bool> {
+        let symbols = &self.state.session_state.resolved_symbols;
+        for output in &self.outputs {
+            let len = output.len.eval(symbols).to_isize()?.max(0) as usize;
+            if output.produced < output.delay + len {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn run_pulse(&mut self, inputs: TVec<Tensor>, chunks: &mut [Vec<Tensor>]) -> TractResult<()> {
+        let results = self.state.run(inputs)?;
+        let symbols = &self.state.session_state.resolved_symbols;
+        for ((output, result), chunks) in self.outputs.iter_mut().zip(results).zip(chunks) {
+            let pulse = result.shape()[output.axis];
+            let end = output
+                .len
+                .eval(symbols)
+                .to_isize()
+                .map(|len| output.delay + len.max(0) as usize)
+                .unwrap_or(std::usize::MAX);
+            let valid = output.delay.max(output.produced)..end.min(output.produced + pulse);
+            if valid.start < valid.end {
+                chunks.push(result.slice(
+                    output.axis,
+                    valid.start - output.produced,
+                    valid.end - output.produced,
+                )?);
+            }
+            output.produced += pulse;
+        }
+        Ok(())
+    }
+
+    fn collect(&self, chunks: Vec<Vec<Tensor>>) -> TractResult<TVec<Tensor>> {
+        self.outputs
+            .iter()
+            .zip(chunks)
+            .map(|(output, mut chunks)| {
+                chunks.insert(0, Tensor::zero_dt(output.datum_type, &output.shape)?);
+                Tensor::stack_tensors(output.axis, &chunks)
+            })
+            .collect()
+    }
+}
+
+fn empty_shape(shape: &[TDim], axis: usize) -> TractResult<TVec<usize>> {
+    shape.iter().enumerate().map(|(ix, d)| if ix == axis { Ok(0) } else { d.to_usize() }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tract_core::ops::array::{Pad, PadMode};
+
+    #[test]
+    fn flush_matches_offline() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source("a", TypedFact::dt_shape(f32::datum_type(), [stream_dim()].as_ref()))?;
+        let pad = Pad::new(vec![(2, 3)], PadMode::Constant(rctensor0(-1f32)));
+        let pad = model.wire_node("pad", pad, &[a])?;
+        model.set_output_outlets(&pad)?;
+        let input: Vec<f32> = (0..9).map(|i| i as f32).collect();
+
+        let offline = model
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 9))?
+            .into_runnable()?
+            .run(tvec!(tensor1(&input)))?;
+
+        let pulsed = PulsedModel::new(&model, 4)?;
+        let plan = pulsed.clone().into_typed()?.into_optimized()?.into_runnable()?;
+        let mut stream = PulsedStream::new(&pulsed, &plan)?.with_filler(tensor0(std::f32::NAN))?;
+        let mut got = vec![];
+        for chunk in input.chunks(5) {
+            got.push(stream.push(tvec!(tensor1(chunk)))?.remove(0));
+        }
+        got.push(stream.flush()?.remove(0));
+        assert_eq!(Tensor::stack_tensors(0, &got)?, *offline[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_pulse_matches_offline() -> TractResult<()> {
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source("a", TypedFact::dt_shape(f32::datum_type(), [stream_dim()].as_ref()))?;
+        let pad = Pad::new(vec![(2, 3)], PadMode::Constant(rctensor0(-1f32)));
+        let pad = model.wire_node("pad", pad, &[a])?;
+        model.set_output_outlets(&pad)?;
+        let input: Vec<f32> = (0..13).map(|i| i as f32).collect();
+
+        let offline = model
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 13))?
+            .into_runnable()?
+            .run(tvec!(tensor1(&input)))?;
+
+        let pulsed = PulsedModel::new_with_symbolic_pulse(&model, 2)?;
+        assert_eq!(pulsed.input_fact(0)?.base_pulse(), 2);
+        let plan = pulsed.clone().into_typed()?.into_optimized()?.into_runnable()?;
+        let mut stream = PulsedStream::new(&pulsed, &plan)?;
+        let mut got = vec![];
+        for chunk in [2, 4, 6, 1].iter().scan(0, |pos, &len| {
+            *pos += len;
+            Some(&input[*pos - len..*pos])
+        }) {
+            got.push(stream.push(tvec!(tensor1(chunk)))?.remove(0));
+        }
+        got.push(stream.flush()?.remove(0));
+        assert_eq!(Tensor::stack_tensors(0, &got)?, *offline[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn several_inputs_match_offline() -> TractResult<()> {
+        use tract_core::ops::array::{Slice, TypedConcat};
+        let mut model = TypedModel::default();
+        let a = model.add_source(
+            "audio",
+            TypedFact::dt_shape(f32::datum_type(), [stream_dim(), 2.to_dim()].as_ref()),
+        )?;
+        let v = model.add_source(
+            "video",
+            TypedFact::dt_shape(f32::datum_type(), [stream_dim().div_ceil(4), 3.to_dim()].as_ref()),
+        )?;
+        let pad = Pad::new(vec![(3, 0), (0, 0)], PadMode::Constant(rctensor0(0f32)));
+        let a = model.wire_node("pad", pad, &[a])?;
+        let a = model.wire_node("slice", Slice::new(0, 3, stream_dim() + 3), &a)?;
+        let a = model.wire_node("down", tract_core::ops::Downsample::new(0, 4, 0), &a)?;
+        let concat = model.wire_node("concat", TypedConcat::concat_vars(1, 2), &[a[0], v])?;
+        model.set_output_outlets(&concat)?;
+
+        let audio = tensor1(&(0..28).map(|i| i as f32).collect::<Vec<_>>()).into_shape(&[14, 2])?;
+        let video = tensor1(&(0..12).map(|i| -i as f32).collect::<Vec<_>>()).into_shape(&[4, 3])?;
+        let offline = model
+            .concretize_dims(&SymbolValues::default().with(stream_symbol(), 14))?
+            .into_runnable()?
+            .run(tvec!(audio.clone(), video.clone()))?;
+
+        let pulsed = PulsedModel::new(&model, 8)?;
+        let plan = pulsed.clone().into_typed()?.into_optimized()?.into_runnable()?;
+        let mut stream = PulsedStream::new(&pulsed, &plan)?;
+        assert!(stream.push(tvec!(audio.clone())).is_err());
+        let mut got = vec![];
+        // inputs do not need to be pushed at the same pace
+        got.push(stream.push(tvec!(audio.slice(0, 0, 5)?, video.clone()))?.remove(0));
+        got.push(stream.push(tvec!(audio.slice(0, 5, 14)?, video.slice(0, 4, 4)?))?.remove(0));
+        got.push(stream.flush()?.remove(0));
+        assert_eq!(Tensor::stack_tensors(0, &got)?, *offline[0]);
+        Ok(())
+    }
+}
@@ -84,6 +84,8 @@ impl From<Tensor> for TensorHolder {
             }
             DatumType::String => TensorHolder::String(Self::to_tensor(m.into_array().unwrap())),
             DatumType::Blob => TensorHolder::String(Self::to_tensor(m.into_array().unwrap())),
+            DatumType::QI8(_) => TensorHolder::I8(Self::to_tensor(m.into_array().unwrap())),
+            DatumType::QU8(_) => TensorHolder::U8(Self::to_tensor(m.into_array().unwrap())),
         }
     }
 }
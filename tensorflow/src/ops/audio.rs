@@ -0,0 +1,216 @@
+use tract_hir::internal::*;
+use tract_hir::tract_core::ops::matmul::MatMulUnary;
+use tract_hir::tract_core::ops::nn::{Reduce, Reducer};
+use tract_hir::tract_core::ops::signal;
+
+use crate::model::{ParsingContext, TfOpRegister};
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("AudioSpectrogram", audio_spectrogram);
+    reg.insert("Mfcc", mfcc);
+}
+
+fn audio_spectrogram(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let window_size = pb.get_attr_int("window_size")?;
+    let stride = pb.get_attr_int("stride")?;
+    let magnitude_squared = pb.get_attr_opt_bool("magnitude_squared")?.unwrap_or(false);
+    Ok(expand(AudioSpectrogram::new(window_size, stride, magnitude_squared)))
+}
+
+fn mfcc(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    Ok(expand(Mfcc::new(
+        pb.get_attr_opt_float("upper_frequency_limit")?.unwrap_or(4000.0),
+        pb.get_attr_opt_float("lower_frequency_limit")?.unwrap_or(20.0),
+        pb.get_attr_opt_int("filterbank_channel_count")?.unwrap_or(40),
+        pb.get_attr_opt_int("dct_coefficient_count")?.unwrap_or(13),
+    )))
+}
+
+/// Input is `[samples, channels]`, output is `[channels, frames, bins]`.
+#[derive(Debug, Clone, new, Hash)]
+struct AudioSpectrogram {
+    window_size: usize,
+    stride: usize,
+    magnitude_squared: bool,
+}
+
+impl_dyn_hash!(AudioSpectrogram);
+
+impl Expansion for AudioSpectrogram {
+    fn name(&self) -> Cow<str> {
+        "AudioSpectrogram".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 3)?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[0])?;
+        let bins = self.window_size.next_power_of_two() / 2 + 1;
+        s.equals(&outputs[0].shape[2], bins.to_dim())?;
+        s.given(&inputs[0].shape[0], move |s, samples| {
+            let frames = signal::Frame::new(0, self.window_size, self.stride).frame_count(&samples);
+            s.equals(&outputs[0].shape[1], frames)
+        })
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let fft_length = self.window_size.next_power_of_two();
+        let mut wire = model.wire_node(
+            format!("{}.channels-first", prefix),
+            AxisOp::Move(1, 0),
+            &[inputs[0]],
+        )?;
+        wire = model.wire_node(
+            format!("{}.frame", prefix),
+            signal::Frame::new(1, self.window_size, self.stride),
+            &wire,
+        )?;
+        let window = signal::Window::Hann { periodic: true }
+            .tensor(self.window_size)
+            .into_shape(&[1, 1, self.window_size])?;
+        wire = model.wire_node(
+            format!("{}.window", prefix),
+            tract_hir::ops::math::mul::unary(window.into_arc_tensor()),
+            &wire,
+        )?;
+        if fft_length > self.window_size {
+            wire = model.wire_node(
+                format!("{}.pad", prefix),
+                tract_hir::ops::array::Pad::new(
+                    vec![(0, 0), (0, 0), (0, fft_length - self.window_size)],
+                    tract_hir::ops::array::PadMode::Constant(rctensor0(0f32)),
+                ),
+                &wire,
+            )?;
+        }
+        wire = model.wire_node(format!("{}.complex", prefix), AxisOp::Add(3), &wire)?;
+        wire =
+            model.wire_node(format!("{}.fft", prefix), signal::Dft::new(2, false, true), &wire)?;
+        wire = model.wire_node(format!("{}.sqr", prefix), tract_hir::ops::math::square(), &wire)?;
+        wire = model.wire_node(
+            format!("{}.sum", prefix),
+            Reduce::new(tvec!(3), Reducer::Sum),
+            &wire,
+        )?;
+        if self.magnitude_squared {
+            model.wire_node(prefix, AxisOp::Rm(3), &wire)
+        } else {
+            wire = model.wire_node(format!("{}.power", prefix), AxisOp::Rm(3), &wire)?;
+            model.wire_node(prefix, tract_hir::ops::math::sqrt(), &wire)
+        }
+    }
+}
+
+/// Input is a `[channels, frames, bins]` power spectrogram and the sample
+/// rate, output is `[channels, frames, dct_coefficient_count]`.
+#[derive(Debug, Clone, new, Educe)]
+#[educe(Hash)]
+struct Mfcc {
+    #[educe(Hash(method = "hash_f32"))]
+    upper_frequency_limit: f32,
+    #[educe(Hash(method = "hash_f32"))]
+    lower_frequency_limit: f32,
+    filterbank_channel_count: usize,
+    dct_coefficient_count: usize,
+}
+
+impl_dyn_hash!(Mfcc);
+
+impl Expansion for Mfcc {
+    fn name(&self) -> Cow<str> {
+        "Mfcc".into()
+    }
+
+    op_tf!();
+
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[1].datum_type, i32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 3)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].rank, 3)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        s.equals(&outputs[0].shape[2], self.dct_coefficient_count.to_dim())?;
+        Ok(())
+    }
+
+    fn wire(
+        &self,
+        prefix: &str,
+        model: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        let sample_rate = model
+            .outlet_fact(inputs[1])?
+            .konst
+            .as_ref()
+            .context("Mfcc sample rate must be a constant")?
+            .cast_to_scalar::<i32>()?;
+        let bins = model.outlet_fact(inputs[0])?.shape[2].to_usize()?;
+        let filterbank = signal::mel::tf_mfcc_mel_filterbank(
+            bins,
+            sample_rate as f64,
+            self.filterbank_channel_count,
+            self.lower_frequency_limit as f64,
+            self.upper_frequency_limit as f64,
+        )?;
+        let dct = signal::mel::dct_matrix(
+            self.filterbank_channel_count,
+            self.dct_coefficient_count,
+            false,
+        );
+        let mut wire = model.wire_node(
+            format!("{}.magnitude", prefix),
+            tract_hir::ops::math::sqrt(),
+            &[inputs[0]],
+        )?;
+        wire = model.wire_node(
+            format!("{}.filterbank", prefix),
+            MatMulUnary::new(
+                filterbank.broadcast_into_rank(3)?.into_arc_tensor(),
+                true,
+                true,
+                true,
+                None,
+            ),
+            &wire,
+        )?;
+        wire = model.wire_node(
+            format!("{}.floor", prefix),
+            tract_hir::ops::math::max::unary(rctensor3(&[[[1e-12f32]]])),
+            &wire,
+        )?;
+        wire = model.wire_node(format!("{}.log", prefix), tract_hir::ops::math::ln(), &wire)?;
+        model.wire_node(
+            prefix,
+            MatMulUnary::new(dct.broadcast_into_rank(3)?.into_arc_tensor(), true, true, true, None),
+            &wire,
+        )
+    }
+}
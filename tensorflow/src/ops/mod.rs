@@ -13,6 +13,7 @@ macro_rules! op_tf {
 }
 
 pub mod array;
+pub mod audio;
 pub mod control_flow;
 pub mod logic;
 pub mod math;
@@ -24,6 +25,7 @@ pub mod vars;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     array::register_all_ops(reg);
+    audio::register_all_ops(reg);
     control_flow::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
@@ -64,6 +64,9 @@ impl TryFrom<DatumType> for DataType {
             DatumType::Blob => Ok(DataType::DtString),
             DatumType::String => Ok(DataType::DtString),
             DatumType::TDim => bail!("Dimension is not translatable in protobuf"),
+            DatumType::QI8(_) | DatumType::QU8(_) => {
+                bail!("Quantized types are not translatable in protobuf")
+            }
         }
     }
 }